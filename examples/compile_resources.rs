@@ -0,0 +1,63 @@
+extern crate clap;
+extern crate env_logger;
+extern crate snips_nlu_lib;
+
+use clap::{App, Arg};
+use snips_nlu_lib::{
+    FstGazetteer, GazetteerReader, InternedStemmer, BINARY_STEMS_EXTENSION, FST_GAZETTEER_EXTENSION,
+};
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    env_logger::Builder::from_default_env()
+        .default_format_timestamp_nanos(true)
+        .init();
+
+    let matches = App::new("snips-nlu-compile-resources")
+        .about(
+            "Precompiles the gazetteers and stems of a language resources directory into binary \
+             files, which the engine loads much faster than the text files",
+        )
+        .arg(
+            Arg::with_name("RESOURCES_DIR")
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("path to the language resources directory, e.g. resources/en"),
+        )
+        .get_matches();
+    let resources_dir = Path::new(matches.value_of("RESOURCES_DIR").unwrap());
+
+    for gazetteer_path in text_files(&resources_dir.join("gazetteers")) {
+        let fst_path = gazetteer_path.with_extension(FST_GAZETTEER_EXTENSION);
+        println!("Compiling {:?} into {:?}", gazetteer_path, fst_path);
+        let reader = GazetteerReader::new(File::open(&gazetteer_path).unwrap()).unwrap();
+        let writer = BufWriter::new(File::create(&fst_path).unwrap());
+        FstGazetteer::build(reader, writer).unwrap();
+    }
+
+    for stems_path in text_files(&resources_dir.join("stemming")) {
+        let binary_path = stems_path.with_extension(BINARY_STEMS_EXTENSION);
+        println!("Compiling {:?} into {:?}", stems_path, binary_path);
+        let stemmer = InternedStemmer::from_reader(File::open(&stems_path).unwrap()).unwrap();
+        let writer = BufWriter::new(File::create(&binary_path).unwrap());
+        stemmer.write_binary(writer).unwrap();
+    }
+}
+
+fn text_files(dir: &Path) -> Vec<PathBuf> {
+    if !dir.exists() {
+        return vec![];
+    }
+    fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .map_or(false, |extension| extension == "txt")
+        })
+        .collect()
+}
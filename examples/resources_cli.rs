@@ -0,0 +1,211 @@
+extern crate clap;
+extern crate env_logger;
+extern crate snips_nlu_lib;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use snips_nlu_lib::{
+    CsvDialect, Language, ResourcePackBuilder, ResourcePackManifest, StemmerBackend,
+};
+use std::str::FromStr;
+
+fn main() {
+    env_logger::Builder::from_default_env()
+        .default_format_timestamp_nanos(true)
+        .init();
+
+    let matches = App::new("snips-nlu-resources-cli")
+        .about("Builds and verifies custom language resource packs")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("build")
+                .about(
+                    "Validates raw word lists and lexeme files, and writes them as a resource \
+                     pack along with a manifest listing the hashes of its files",
+                )
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .required(true)
+                        .takes_value(true)
+                        .index(1)
+                        .help("path of the pack directory, or of the zip archive with --zip"),
+                )
+                .arg(
+                    Arg::with_name("name")
+                        .long("--name")
+                        .required(true)
+                        .takes_value(true)
+                        .help("name of the pack"),
+                )
+                .arg(
+                    Arg::with_name("pack_version")
+                        .long("--pack-version")
+                        .required(true)
+                        .takes_value(true)
+                        .help("version of the pack, e.g. 1.2.0"),
+                )
+                .arg(
+                    Arg::with_name("language")
+                        .long("--language")
+                        .required(true)
+                        .takes_value(true)
+                        .help("language of the resources, e.g. en"),
+                )
+                .arg(
+                    Arg::with_name("gazetteer")
+                        .long("--gazetteer")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("word list, with one value per line, given as NAME=PATH"),
+                )
+                .arg(
+                    Arg::with_name("word_clusters")
+                        .long("--word-clusters")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("word clusters, with a word and its cluster per line, as NAME=PATH"),
+                )
+                .arg(
+                    Arg::with_name("stems")
+                        .long("--stems")
+                        .takes_value(true)
+                        .help("lexeme file, in which each line lists a stem and its inflections"),
+                )
+                .arg(
+                    Arg::with_name("stop_words")
+                        .long("--stop-words")
+                        .takes_value(true)
+                        .help("stop words, with one word per line"),
+                )
+                .arg(
+                    Arg::with_name("delimiter")
+                        .long("--delimiter")
+                        .takes_value(true)
+                        .help("delimiter of the lexeme and word clusters files, ',' by default"),
+                )
+                .arg(
+                    Arg::with_name("comment")
+                        .long("--comment")
+                        .takes_value(true)
+                        .help("character starting the comment lines of the input files"),
+                )
+                .arg(
+                    Arg::with_name("snowball")
+                        .long("--snowball")
+                        .help("falls back to a Snowball stemmer for the words without stem"),
+                )
+                .arg(
+                    Arg::with_name("precompile")
+                        .long("--precompile")
+                        .help("stores the gazetteers and stems in their compact binary formats"),
+                )
+                .arg(
+                    Arg::with_name("zip")
+                        .long("--zip")
+                        .help("writes the pack as a compressed zip archive"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Checks the files of a resource pack directory against its manifest")
+                .arg(
+                    Arg::with_name("PACK_DIR")
+                        .required(true)
+                        .takes_value(true)
+                        .index(1)
+                        .help("path to the resource pack directory"),
+                ),
+        )
+        .get_matches();
+
+    match matches.subcommand() {
+        ("build", Some(matches)) => build(matches),
+        ("verify", Some(matches)) => verify(matches),
+        _ => unreachable!(),
+    }
+}
+
+fn build(matches: &ArgMatches) {
+    let language = Language::from_str(matches.value_of("language").unwrap()).unwrap();
+    let dialect = CsvDialect {
+        delimiter: matches
+            .value_of("delimiter")
+            .map(single_byte)
+            .unwrap_or(b','),
+        comment: matches.value_of("comment").map(single_byte),
+        ..CsvDialect::default()
+    };
+    let stemmer = if matches.is_present("snowball") {
+        StemmerBackend::Snowball
+    } else {
+        StemmerBackend::Dictionary
+    };
+    let mut builder = ResourcePackBuilder::new(
+        matches.value_of("name").unwrap(),
+        matches.value_of("pack_version").unwrap(),
+        language,
+    )
+    .dialect(dialect)
+    .stemmer(stemmer)
+    .precompile(matches.is_present("precompile"));
+    for (name, path) in named_paths(matches, "gazetteer") {
+        builder = builder.gazetteer(name, path);
+    }
+    for (name, path) in named_paths(matches, "word_clusters") {
+        builder = builder.word_clusters(name, path);
+    }
+    if let Some(path) = matches.value_of("stems") {
+        builder = builder.stems(path);
+    }
+    if let Some(path) = matches.value_of("stop_words") {
+        builder = builder.stop_words(path);
+    }
+
+    let output = matches.value_of("OUTPUT").unwrap();
+    let manifest = if matches.is_present("zip") {
+        builder.build_zip(output).unwrap()
+    } else {
+        builder.build(output).unwrap()
+    };
+    println!(
+        "Built resource pack '{}' ({}) with {} files in {}",
+        manifest.name,
+        manifest.version,
+        manifest.files.len(),
+        output
+    );
+}
+
+fn verify(matches: &ArgMatches) {
+    let pack_dir = matches.value_of("PACK_DIR").unwrap();
+    let manifest = ResourcePackManifest::from_dir(pack_dir).unwrap();
+    manifest.verify(pack_dir).unwrap();
+    println!(
+        "Resource pack '{}' ({}) is valid",
+        manifest.name, manifest.version
+    );
+}
+
+fn named_paths<'a>(matches: &'a ArgMatches, arg_name: &str) -> Vec<(&'a str, &'a str)> {
+    matches
+        .values_of(arg_name)
+        .map(|values| {
+            values
+                .map(|value| {
+                    let separator = value
+                        .find('=')
+                        .unwrap_or_else(|| panic!("Expected NAME=PATH, found '{}'", value));
+                    (&value[..separator], &value[separator + 1..])
+                })
+                .collect()
+        })
+        .unwrap_or_else(Vec::new)
+}
+
+fn single_byte(value: &str) -> u8 {
+    match value.as_bytes() {
+        [byte] => *byte,
+        _ => panic!("Expected a single ASCII character, found '{}'", value),
+    }
+}
@@ -3,20 +3,56 @@
 extern crate ffi_utils;
 extern crate snips_nlu_ontology_ffi_macros;
 
+use std::cell::RefCell;
 use std::ffi::{CStr, CString};
-use std::io::Cursor;
 use std::slice;
+use std::str::FromStr;
 use std::sync::Mutex;
 
 use failure::{format_err, ResultExt};
 use ffi_utils::*;
-use snips_nlu_lib::SnipsNluEngine;
+use snips_nlu_lib::{Language, SnipsNluEngine, TextRanges};
 use snips_nlu_ontology_ffi_macros::{CIntentClassifierResultArray, CIntentParserResult, CSlotList};
 
+use crate::ownership::{point_to_tracked_string, track, untrack};
+
+mod ownership;
+
 type Result<T> = std::result::Result<T, failure::Error>;
 
 pub struct CSnipsNluEngine(std::sync::Mutex<SnipsNluEngine>);
 
+/// Range of a text fragment in Unicode scalar values, UTF-8 bytes and UTF-16 code units
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CTextRanges {
+    /// Start index in Unicode scalar values, as in the ranges of the slots
+    pub char_start: i32,
+    /// End index in Unicode scalar values
+    pub char_end: i32,
+    /// Start index in bytes of the UTF-8 encoding of the text
+    pub byte_start: i32,
+    /// End index in bytes of the UTF-8 encoding of the text
+    pub byte_end: i32,
+    /// Start index in code units of the UTF-16 encoding of the text
+    pub utf16_start: i32,
+    /// End index in code units of the UTF-16 encoding of the text
+    pub utf16_end: i32,
+}
+
+impl From<TextRanges> for CTextRanges {
+    fn from(ranges: TextRanges) -> Self {
+        Self {
+            char_start: ranges.chars.start as i32,
+            char_end: ranges.chars.end as i32,
+            byte_start: ranges.bytes.start as i32,
+            byte_end: ranges.bytes.end as i32,
+            utf16_start: ranges.utf16.start as i32,
+            utf16_end: ranges.utf16.end as i32,
+        }
+    }
+}
+
 macro_rules! get_nlu_engine {
     ($opaque:ident) => {{
         unsafe { <CSnipsNluEngine as ffi_utils::RawBorrow<CSnipsNluEngine>>::raw_borrow($opaque) }?
@@ -26,8 +62,34 @@ macro_rules! get_nlu_engine {
     }};
 }
 
-generate_error_handling!(snips_nlu_engine_get_last_error);
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Same as the `wrap!` macro of `ffi_utils`, except that the error is recorded in the last
+/// error of this library, which is handed over as a tracked string by
+/// `snips_nlu_engine_get_last_error`
+macro_rules! wrap {
+    ($e:expr) => {
+        match $e {
+            Ok(_) => SNIPS_RESULT::SNIPS_RESULT_OK,
+            Err(e) => {
+                set_last_error(&e);
+                SNIPS_RESULT::SNIPS_RESULT_KO
+            }
+        }
+    };
+}
+
+/// Retrieves the last error which happened in this thread, the error string being owned by
+/// the caller until it is destroyed with `snips_nlu_engine_destroy_string`
+#[no_mangle]
+pub extern "C" fn snips_nlu_engine_get_last_error(error: *mut *const libc::c_char) -> SNIPS_RESULT {
+    wrap!(get_last_error(error))
+}
 
+/// Loads an engine from a directory, the engine being owned by the caller until it is destroyed
+/// with `snips_nlu_engine_destroy_client`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_create_from_dir(
     root_dir: *const libc::c_char,
@@ -36,6 +98,8 @@ pub extern "C" fn snips_nlu_engine_create_from_dir(
     wrap!(create_from_dir(root_dir, client))
 }
 
+/// Loads an engine from a zip archive, the engine being owned by the caller until it is
+/// destroyed with `snips_nlu_engine_destroy_client`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_create_from_zip(
     zip: *const libc::c_uchar,
@@ -45,6 +109,8 @@ pub extern "C" fn snips_nlu_engine_create_from_zip(
     wrap!(create_from_zip(zip, zip_size, client))
 }
 
+/// The result is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_result`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_run_parse(
     client: *const CSnipsNluEngine,
@@ -64,6 +130,8 @@ pub extern "C" fn snips_nlu_engine_run_parse(
     ))
 }
 
+/// The result is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_result`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_run_parse_with_alternatives(
     client: *const CSnipsNluEngine,
@@ -85,6 +153,25 @@ pub extern "C" fn snips_nlu_engine_run_parse_with_alternatives(
     ))
 }
 
+/// The result is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_result`
+#[no_mangle]
+pub extern "C" fn snips_nlu_engine_run_parse_with_filter(
+    client: *const CSnipsNluEngine,
+    input: *const libc::c_char,
+    allowed_intents: *const CStringArray,
+    result: *mut *const CIntentParserResult,
+) -> SNIPS_RESULT {
+    wrap!(run_parse_with_filter(
+        client,
+        input,
+        allowed_intents,
+        result
+    ))
+}
+
+/// The result is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_slots`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_run_get_slots(
     client: *const CSnipsNluEngine,
@@ -95,6 +182,8 @@ pub extern "C" fn snips_nlu_engine_run_get_slots(
     wrap!(run_get_slots(client, input, intent, 0, result))
 }
 
+/// The result is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_slots`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_run_get_slots_with_alternatives(
     client: *const CSnipsNluEngine,
@@ -112,6 +201,8 @@ pub extern "C" fn snips_nlu_engine_run_get_slots_with_alternatives(
     ))
 }
 
+/// The result is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_intent_classifier_results`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_run_get_intents(
     client: *const CSnipsNluEngine,
@@ -121,6 +212,8 @@ pub extern "C" fn snips_nlu_engine_run_get_intents(
     wrap!(run_get_intents(client, input, result))
 }
 
+/// The string is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_string`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_run_parse_into_json(
     client: *const CSnipsNluEngine,
@@ -140,6 +233,8 @@ pub extern "C" fn snips_nlu_engine_run_parse_into_json(
     ))
 }
 
+/// The string is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_string`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_run_parse_with_alternatives_into_json(
     client: *const CSnipsNluEngine,
@@ -161,6 +256,8 @@ pub extern "C" fn snips_nlu_engine_run_parse_with_alternatives_into_json(
     ))
 }
 
+/// The string is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_string`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_run_get_slots_into_json(
     client: *const CSnipsNluEngine,
@@ -177,6 +274,8 @@ pub extern "C" fn snips_nlu_engine_run_get_slots_into_json(
     ))
 }
 
+/// The string is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_string`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_run_get_slots_with_alternatives_into_json(
     client: *const CSnipsNluEngine,
@@ -194,6 +293,8 @@ pub extern "C" fn snips_nlu_engine_run_get_slots_with_alternatives_into_json(
     ))
 }
 
+/// The string is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_string`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_run_get_intents_into_json(
     client: *const CSnipsNluEngine,
@@ -203,35 +304,62 @@ pub extern "C" fn snips_nlu_engine_run_get_intents_into_json(
     wrap!(run_get_intents_into_json(client, input, result_json))
 }
 
+/// Destroys a string returned by any of the `*_into_json` functions or by
+/// `snips_nlu_engine_get_model_version`, which must not be used afterwards
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_destroy_string(string: *mut libc::c_char) -> SNIPS_RESULT {
-    wrap!(unsafe { CString::from_raw_pointer(string) })
+    wrap!(untrack(string).and_then(|_| unsafe { CString::from_raw_pointer(string) }))
 }
 
+/// Destroys an engine created with `snips_nlu_engine_create_from_dir` or
+/// `snips_nlu_engine_create_from_zip`, which must not be used afterwards
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_destroy_client(client: *mut CSnipsNluEngine) -> SNIPS_RESULT {
-    wrap!(unsafe { CSnipsNluEngine::from_raw_pointer(client) })
+    wrap!(untrack(client).and_then(|_| unsafe { CSnipsNluEngine::from_raw_pointer(client) }))
 }
 
+/// Destroys a result returned by `snips_nlu_engine_run_parse`,
+/// `snips_nlu_engine_run_parse_with_alternatives` or `snips_nlu_engine_run_parse_with_filter`,
+/// including the slots and strings it contains
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_destroy_result(
     result: *mut CIntentParserResult,
 ) -> SNIPS_RESULT {
-    wrap!(unsafe { CIntentParserResult::from_raw_pointer(result) })
+    wrap!(untrack(result).and_then(|_| unsafe { CIntentParserResult::from_raw_pointer(result) }))
 }
 
+/// Destroys slots returned by `snips_nlu_engine_run_get_slots` or
+/// `snips_nlu_engine_run_get_slots_with_alternatives`, including the strings they contain
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_destroy_slots(result: *mut CSlotList) -> SNIPS_RESULT {
-    wrap!(unsafe { CSlotList::from_raw_pointer(result) })
+    wrap!(untrack(result).and_then(|_| unsafe { CSlotList::from_raw_pointer(result) }))
 }
 
+/// Destroys intents returned by `snips_nlu_engine_run_get_intents`, including the strings
+/// they contain
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_destroy_intent_classifier_results(
     result: *mut CIntentClassifierResultArray,
 ) -> SNIPS_RESULT {
-    wrap!(unsafe { CIntentClassifierResultArray::from_raw_pointer(result) })
+    wrap!(untrack(result)
+        .and_then(|_| unsafe { CIntentClassifierResultArray::from_raw_pointer(result) }))
+}
+
+/// Converts the range of a slot of the input, e.g. `range_start` and `range_end` of a `CSlot`,
+/// into all the units supported by the engine, the result being written in the struct
+/// provided by the caller
+#[no_mangle]
+pub extern "C" fn snips_nlu_engine_get_text_ranges(
+    input: *const libc::c_char,
+    char_start: i32,
+    char_end: i32,
+    result: *mut CTextRanges,
+) -> SNIPS_RESULT {
+    wrap!(get_text_ranges(input, char_start, char_end, result))
 }
 
+/// The string is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_string`
 #[no_mangle]
 pub extern "C" fn snips_nlu_engine_get_model_version(
     version: *mut *const libc::c_char,
@@ -239,6 +367,42 @@ pub extern "C" fn snips_nlu_engine_get_model_version(
     wrap!(get_model_version(version))
 }
 
+/// The string is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_string`
+#[no_mangle]
+pub extern "C" fn snips_nlu_engine_get_supported_languages_into_json(
+    result_json: *mut *const libc::c_char,
+) -> SNIPS_RESULT {
+    wrap!(get_supported_languages_into_json(result_json))
+}
+
+/// The string is owned by the caller and must be destroyed with
+/// `snips_nlu_engine_destroy_string`
+#[no_mangle]
+pub extern "C" fn snips_nlu_engine_get_supported_builtin_entities_into_json(
+    language: *const libc::c_char,
+    result_json: *mut *const libc::c_char,
+) -> SNIPS_RESULT {
+    wrap!(get_supported_builtin_entities_into_json(
+        language,
+        result_json
+    ))
+}
+
+fn set_last_error(error: &failure::Error) {
+    let message = error
+        .iter_chain()
+        .map(|cause| cause.to_string())
+        .collect::<Vec<_>>()
+        .join("\nCaused by: ");
+    LAST_ERROR.with(|last_error| last_error.replace(Some(message)));
+}
+
+fn get_last_error(error: *mut *const libc::c_char) -> Result<()> {
+    let last_error = LAST_ERROR.with(|last_error| last_error.borrow().clone());
+    point_to_tracked_string(error, last_error.unwrap_or_default())
+}
+
 fn create_from_dir(
     root_dir: *const libc::c_char,
     client: *mut *const CSnipsNluEngine,
@@ -247,7 +411,7 @@ fn create_from_dir(
 
     let nlu_engine = SnipsNluEngine::from_path(root_dir)?;
 
-    let raw_pointer = CSnipsNluEngine(Mutex::new(nlu_engine)).into_raw_pointer();
+    let raw_pointer = track(CSnipsNluEngine(Mutex::new(nlu_engine)).into_raw_pointer());
     unsafe { *client = raw_pointer };
 
     Ok(())
@@ -259,9 +423,8 @@ fn create_from_zip(
     client: *mut *const CSnipsNluEngine,
 ) -> Result<()> {
     let slice = unsafe { slice::from_raw_parts(zip, zip_size as usize) };
-    let reader = Cursor::new(slice.to_owned());
-    let nlu_engine = SnipsNluEngine::from_zip(reader)?;
-    let raw_pointer = CSnipsNluEngine(Mutex::new(nlu_engine)).into_raw_pointer();
+    let nlu_engine = SnipsNluEngine::from_zip_bytes(slice)?;
+    let raw_pointer = track(CSnipsNluEngine(Mutex::new(nlu_engine)).into_raw_pointer());
     unsafe { *client = raw_pointer };
 
     Ok(())
@@ -297,7 +460,29 @@ fn run_parse_with_alternatives(
         intents_alternatives as usize,
         slots_alternatives as usize,
     )?;
-    let raw_pointer = CIntentParserResult::from(results).into_raw_pointer();
+    let raw_pointer = track(CIntentParserResult::from(results).into_raw_pointer());
+
+    unsafe { *result = raw_pointer };
+
+    Ok(())
+}
+
+fn run_parse_with_filter(
+    client: *const CSnipsNluEngine,
+    input: *const libc::c_char,
+    allowed_intents: *const CStringArray,
+    result: *mut *const CIntentParserResult,
+) -> Result<()> {
+    let input = create_rust_string_from!(input);
+    let nlu_engine = get_nlu_engine!(client);
+
+    if allowed_intents.is_null() {
+        return Err(format_err!("allowed intents must not be null"));
+    }
+    let allowed_intents = unsafe { convert_to_rust_vec(allowed_intents)? };
+
+    let results = nlu_engine.parse_with_filter(&input, &allowed_intents)?;
+    let raw_pointer = track(CIntentParserResult::from(results).into_raw_pointer());
 
     unsafe { *result = raw_pointer };
 
@@ -317,7 +502,7 @@ fn run_get_slots(
 
     let slots =
         nlu_engine.get_slots_with_alternatives(&input, &intent, slots_alternatives as usize)?;
-    let raw_pointer = CSlotList::from(slots).into_raw_pointer();
+    let raw_pointer = track(CSlotList::from(slots).into_raw_pointer());
 
     unsafe { *result = raw_pointer };
 
@@ -333,7 +518,7 @@ fn run_get_intents(
     let nlu_engine = get_nlu_engine!(client);
 
     let intents = nlu_engine.get_intents(&input)?;
-    let raw_pointer = CIntentClassifierResultArray::from(intents).into_raw_pointer();
+    let raw_pointer = track(CIntentClassifierResultArray::from(intents).into_raw_pointer());
 
     unsafe { *result = raw_pointer };
 
@@ -370,7 +555,7 @@ fn run_parse_with_alternatives_into_json(
         slots_alternatives as usize,
     )?;
 
-    point_to_string(result_json, serde_json::to_string(&results)?)
+    point_to_tracked_string(result_json, serde_json::to_string(&results)?)
 }
 
 fn run_get_slots_into_json(
@@ -386,7 +571,7 @@ fn run_get_slots_into_json(
 
     let slots =
         nlu_engine.get_slots_with_alternatives(&input, &intent, slots_alternatives as usize)?;
-    point_to_string(result_json, serde_json::to_string(&slots)?)
+    point_to_tracked_string(result_json, serde_json::to_string(&slots)?)
 }
 
 fn run_get_intents_into_json(
@@ -399,11 +584,55 @@ fn run_get_intents_into_json(
 
     let intents = nlu_engine.get_intents(&input)?;
 
-    point_to_string(result_json, serde_json::to_string(&intents)?)
+    point_to_tracked_string(result_json, serde_json::to_string(&intents)?)
+}
+
+fn get_text_ranges(
+    input: *const libc::c_char,
+    char_start: i32,
+    char_end: i32,
+    result: *mut CTextRanges,
+) -> Result<()> {
+    let input = create_rust_string_from!(input);
+    if char_start < 0 || char_end < char_start || char_end as usize > input.chars().count() {
+        return Err(format_err!(
+            "invalid range {}..{} of input {:?}",
+            char_start,
+            char_end,
+            input
+        ));
+    }
+    if result.is_null() {
+        return Err(format_err!("result must not be null"));
+    }
+    let ranges = TextRanges::new(&input, &(char_start as usize..char_end as usize));
+    unsafe { *result = CTextRanges::from(ranges) };
+    Ok(())
 }
 
 fn get_model_version(version: *mut *const libc::c_char) -> Result<()> {
-    point_to_string(version, snips_nlu_lib::MODEL_VERSION.to_string())
+    point_to_tracked_string(version, snips_nlu_lib::MODEL_VERSION.to_string())
+}
+
+fn get_supported_languages_into_json(result_json: *mut *const libc::c_char) -> Result<()> {
+    let languages: Vec<String> = SnipsNluEngine::supported_languages()
+        .iter()
+        .map(|language| language.to_string())
+        .collect();
+    point_to_tracked_string(result_json, serde_json::to_string(&languages)?)
+}
+
+fn get_supported_builtin_entities_into_json(
+    language: *const libc::c_char,
+    result_json: *mut *const libc::c_char,
+) -> Result<()> {
+    let language = create_rust_string_from!(language);
+    let language = Language::from_str(&language)?;
+    let entities: Vec<&str> = SnipsNluEngine::supported_builtin_entities(language)
+        .into_iter()
+        .map(|kind| kind.identifier())
+        .collect();
+    point_to_tracked_string(result_json, serde_json::to_string(&entities)?)
 }
 
 unsafe fn convert_to_rust_vec<'a>(c_array: *const CStringArray) -> Result<Vec<&'a str>> {
@@ -413,3 +642,215 @@ unsafe fn convert_to_rust_vec<'a>(c_array: *const CStringArray) -> Result<Vec<&'
         .map(|&ptr| Ok(CStr::from_ptr(ptr).to_str().map_err(failure::Error::from)?))
         .collect::<Result<Vec<_>>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+    use std::path::Path;
+    use std::ptr;
+
+    use super::*;
+
+    const NB_ITERATIONS: usize = 50;
+
+    /// Allocator recording the number of bytes allocated and not released yet by each thread,
+    /// so that tests can detect memory leaks
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATED_BYTES: Cell<isize> = Cell::new(0);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ =
+                ALLOCATED_BYTES.try_with(|bytes| bytes.set(bytes.get() + layout.size() as isize));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            let _ =
+                ALLOCATED_BYTES.try_with(|bytes| bytes.set(bytes.get() - layout.size() as isize));
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn allocated_bytes() -> isize {
+        ALLOCATED_BYTES.with(|bytes| bytes.get())
+    }
+
+    pub fn is_ok(result: SNIPS_RESULT) -> bool {
+        match result {
+            SNIPS_RESULT::SNIPS_RESULT_OK => true,
+            _ => false,
+        }
+    }
+
+    fn create_engine() -> *const CSnipsNluEngine {
+        let engine_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let engine_dir = CString::new(engine_dir.to_str().unwrap()).unwrap();
+        let mut client: *const CSnipsNluEngine = ptr::null();
+        let result = snips_nlu_engine_create_from_dir(engine_dir.as_ptr(), &mut client);
+        assert!(is_ok(result));
+        client
+    }
+
+    /// Runs all the parsing functions and destroys their results, returning the pointers of
+    /// the results
+    fn parse_and_destroy_results(client: *const CSnipsNluEngine) -> Vec<usize> {
+        let input = CString::new("Make me two hot cups of tea").unwrap();
+        let intent = CString::new("MakeTea").unwrap();
+        let mut parse_result: *const CIntentParserResult = ptr::null();
+        let mut slots: *const CSlotList = ptr::null();
+        let mut intents: *const CIntentClassifierResultArray = ptr::null();
+        let mut json: *const libc::c_char = ptr::null();
+        let results = vec![
+            snips_nlu_engine_run_parse(
+                client,
+                input.as_ptr(),
+                ptr::null(),
+                ptr::null(),
+                &mut parse_result,
+            ),
+            snips_nlu_engine_run_get_slots(client, input.as_ptr(), intent.as_ptr(), &mut slots),
+            snips_nlu_engine_run_get_intents(client, input.as_ptr(), &mut intents),
+            snips_nlu_engine_run_parse_into_json(
+                client,
+                input.as_ptr(),
+                ptr::null(),
+                ptr::null(),
+                &mut json,
+            ),
+        ];
+        assert!(results.into_iter().all(is_ok));
+
+        let destroy_results = vec![
+            snips_nlu_engine_destroy_result(parse_result as *mut _),
+            snips_nlu_engine_destroy_slots(slots as *mut _),
+            snips_nlu_engine_destroy_intent_classifier_results(intents as *mut _),
+            snips_nlu_engine_destroy_string(json as *mut _),
+        ];
+        assert!(destroy_results.into_iter().all(is_ok));
+        vec![
+            parse_result as usize,
+            slots as usize,
+            intents as usize,
+            json as usize,
+        ]
+    }
+
+    #[test]
+    fn test_ffi_results_are_released() {
+        // Given
+        let client = create_engine();
+        // The first parsing fills the caches of the engine, which are not released
+        parse_and_destroy_results(client);
+        let initially_allocated_bytes = allocated_bytes();
+        let mut allocated_pointers: Vec<usize> = Vec::with_capacity(4 * NB_ITERATIONS);
+
+        // When
+        for _ in 0..NB_ITERATIONS {
+            allocated_pointers.extend(parse_and_destroy_results(client));
+        }
+        let leaked_bytes = allocated_bytes() - initially_allocated_bytes;
+        let destroy_client_result = snips_nlu_engine_destroy_client(client as *mut _);
+
+        // Then
+        assert!(is_ok(destroy_client_result));
+        // Only the vector of pointers is expected to remain allocated
+        let expected_bytes =
+            (allocated_pointers.capacity() * std::mem::size_of::<usize>()) as isize;
+        assert!(
+            leaked_bytes <= expected_bytes,
+            "{} bytes were not released",
+            leaked_bytes - expected_bytes
+        );
+        #[cfg(debug_assertions)]
+        {
+            assert!(!ownership::is_live(client));
+            assert!(allocated_pointers
+                .into_iter()
+                .all(|pointer| !ownership::is_live(pointer as *const u8)));
+        }
+    }
+
+    #[test]
+    fn test_ffi_text_ranges() {
+        // Given
+        let input = CString::new("thé au café").unwrap();
+        let mut ranges = CTextRanges::from(TextRanges::new("", &(0..0)));
+
+        // When
+        let result = snips_nlu_engine_get_text_ranges(input.as_ptr(), 7, 11, &mut ranges);
+        let invalid_result = snips_nlu_engine_get_text_ranges(input.as_ptr(), 7, 12, &mut ranges);
+
+        // Then
+        let expected_ranges = CTextRanges {
+            char_start: 7,
+            char_end: 11,
+            byte_start: 8,
+            byte_end: 13,
+            utf16_start: 7,
+            utf16_end: 11,
+        };
+        assert!(is_ok(result));
+        assert!(!is_ok(invalid_result));
+        assert_eq!(expected_ranges, ranges);
+    }
+
+    #[test]
+    fn test_ffi_last_error_can_be_destroyed() {
+        // Given
+        let input = CString::new("thé au café").unwrap();
+        let mut ranges = CTextRanges::from(TextRanges::new("", &(0..0)));
+        let invalid_result = snips_nlu_engine_get_text_ranges(input.as_ptr(), 7, 12, &mut ranges);
+        assert!(!is_ok(invalid_result));
+
+        // When
+        let mut error: *const libc::c_char = ptr::null();
+        let error_result = snips_nlu_engine_get_last_error(&mut error);
+        let error_message = unsafe { CStr::from_ptr(error) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        let destroy_result = snips_nlu_engine_destroy_string(error as *mut _);
+
+        // Then
+        assert!(is_ok(error_result));
+        assert!(!error_message.is_empty());
+        assert!(is_ok(destroy_result));
+    }
+}
+
+#[cfg(all(test, debug_assertions))]
+mod ownership_tests {
+    use std::ptr;
+
+    use super::tests::is_ok;
+    use super::*;
+
+    #[test]
+    fn test_ffi_double_free_is_detected() {
+        // Given
+        let mut version: *const libc::c_char = ptr::null();
+        let version_result = snips_nlu_engine_get_model_version(&mut version);
+        assert!(is_ok(version_result));
+
+        // When
+        let first_destroy_result = snips_nlu_engine_destroy_string(version as *mut _);
+        let second_destroy_result = snips_nlu_engine_destroy_string(version as *mut _);
+
+        // Then
+        assert!(is_ok(first_destroy_result));
+        assert!(!is_ok(second_destroy_result));
+    }
+}
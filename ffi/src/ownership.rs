@@ -0,0 +1,58 @@
+//! Tracking of the pointers handed over to the C consumers
+//!
+//! In debug builds, every allocated pointer is registered until it is destroyed, so that
+//! destroying a pointer twice, or a pointer which was not allocated by this library, fails
+//! with an error instead of corrupting the memory. Release builds do not track anything.
+
+#[cfg(debug_assertions)]
+use std::collections::HashSet;
+#[cfg(debug_assertions)]
+use std::sync::Mutex;
+
+#[cfg(debug_assertions)]
+use failure::bail;
+#[cfg(debug_assertions)]
+use lazy_static::lazy_static;
+
+use ffi_utils::point_to_string;
+
+use crate::Result;
+
+#[cfg(debug_assertions)]
+lazy_static! {
+    static ref LIVE_POINTERS: Mutex<HashSet<usize>> = Mutex::new(HashSet::new());
+}
+
+/// Registers a pointer whose ownership is transferred to the consumer
+pub fn track<T>(pointer: *const T) -> *const T {
+    #[cfg(debug_assertions)]
+    LIVE_POINTERS.lock().unwrap().insert(pointer as usize);
+    pointer
+}
+
+/// Unregisters a pointer which the consumer gives back in order to destroy it
+pub fn untrack<T>(pointer: *const T) -> Result<()> {
+    #[cfg(debug_assertions)]
+    {
+        if !LIVE_POINTERS.lock().unwrap().remove(&(pointer as usize)) {
+            bail!(
+                "pointer {:?} was already destroyed or was not allocated by this library",
+                pointer
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether the pointer was allocated and not destroyed yet
+#[cfg(all(test, debug_assertions))]
+pub fn is_live<T>(pointer: *const T) -> bool {
+    LIVE_POINTERS.lock().unwrap().contains(&(pointer as usize))
+}
+
+/// Same as `point_to_string`, the string being tracked until it is destroyed
+pub fn point_to_tracked_string(pointer: *mut *const libc::c_char, string: String) -> Result<()> {
+    point_to_string(pointer, string)?;
+    track(unsafe { *pointer });
+    Ok(())
+}
@@ -316,6 +316,36 @@ typedef struct {
   int size;
 } CStringArray;
 
+/**
+ * Range of a text fragment in Unicode scalar values, UTF-8 bytes and UTF-16 code units
+ */
+typedef struct {
+  /**
+   * Start index in Unicode scalar values, as in the ranges of the slots
+   */
+  int32_t char_start;
+  /**
+   * End index in Unicode scalar values
+   */
+  int32_t char_end;
+  /**
+   * Start index in bytes of the UTF-8 encoding of the text
+   */
+  int32_t byte_start;
+  /**
+   * End index in bytes of the UTF-8 encoding of the text
+   */
+  int32_t byte_end;
+  /**
+   * Start index in code units of the UTF-16 encoding of the text
+   */
+  int32_t utf16_start;
+  /**
+   * End index in code units of the UTF-16 encoding of the text
+   */
+  int32_t utf16_end;
+} CTextRanges;
+
 /**
  * Representation of a number value
  */
@@ -437,72 +467,166 @@ typedef struct {
   SNIPS_PRECISION precision;
 } CDurationValue;
 
+/**
+ * Loads an engine from a directory, the engine being owned by the caller until it is destroyed
+ * with `snips_nlu_engine_destroy_client`
+ */
 SNIPS_RESULT snips_nlu_engine_create_from_dir(const char *root_dir, const CSnipsNluEngine **client);
 
+/**
+ * Loads an engine from a zip archive, the engine being owned by the caller until it is
+ * destroyed with `snips_nlu_engine_destroy_client`
+ */
 SNIPS_RESULT snips_nlu_engine_create_from_zip(const unsigned char *zip,
                                               unsigned int zip_size,
                                               const CSnipsNluEngine **client);
 
+/**
+ * Destroys an engine created with `snips_nlu_engine_create_from_dir` or
+ * `snips_nlu_engine_create_from_zip`, which must not be used afterwards
+ */
 SNIPS_RESULT snips_nlu_engine_destroy_client(CSnipsNluEngine *client);
 
+/**
+ * Destroys intents returned by `snips_nlu_engine_run_get_intents`, including the strings
+ * they contain
+ */
 SNIPS_RESULT snips_nlu_engine_destroy_intent_classifier_results(CIntentClassifierResultArray *result);
 
+/**
+ * Destroys a result returned by `snips_nlu_engine_run_parse`,
+ * `snips_nlu_engine_run_parse_with_alternatives` or `snips_nlu_engine_run_parse_with_filter`,
+ * including the slots and strings it contains
+ */
 SNIPS_RESULT snips_nlu_engine_destroy_result(CIntentParserResult *result);
 
+/**
+ * Destroys slots returned by `snips_nlu_engine_run_get_slots` or
+ * `snips_nlu_engine_run_get_slots_with_alternatives`, including the strings they contain
+ */
 SNIPS_RESULT snips_nlu_engine_destroy_slots(CSlotList *result);
 
+/**
+ * Destroys a string returned by any of the `*_into_json` functions, by
+ * `snips_nlu_engine_get_model_version` or by `snips_nlu_engine_get_last_error`, which must not
+ * be used afterwards
+ */
 SNIPS_RESULT snips_nlu_engine_destroy_string(char *string);
 
 /**
  * Used to retrieve the last error that happened in this thread. A function encountered an
- * error if its return type is of type SNIPS_RESULT and it returned SNIPS_RESULT_KO
+ * error if its return type is of type SNIPS_RESULT and it returned SNIPS_RESULT_KO. The string
+ * is owned by the caller and must be destroyed with `snips_nlu_engine_destroy_string`
  */
 SNIPS_RESULT snips_nlu_engine_get_last_error(const char **error);
 
+/**
+ * The string is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_string`
+ */
 SNIPS_RESULT snips_nlu_engine_get_model_version(const char **version);
 
+/**
+ * The string is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_string`
+ */
+SNIPS_RESULT snips_nlu_engine_get_supported_builtin_entities_into_json(const char *language,
+                                                                       const char **result_json);
+
+/**
+ * The string is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_string`
+ */
+SNIPS_RESULT snips_nlu_engine_get_supported_languages_into_json(const char **result_json);
+
+/**
+ * Converts the range of a slot of the input, e.g. `range_start` and `range_end` of a `CSlot`,
+ * into all the units supported by the engine, the result being written in the struct
+ * provided by the caller
+ */
+SNIPS_RESULT snips_nlu_engine_get_text_ranges(const char *input,
+                                              int32_t char_start,
+                                              int32_t char_end,
+                                              CTextRanges *result);
+
+/**
+ * The result is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_intent_classifier_results`
+ */
 SNIPS_RESULT snips_nlu_engine_run_get_intents(const CSnipsNluEngine *client,
                                               const char *input,
                                               const CIntentClassifierResultArray **result);
 
+/**
+ * The string is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_string`
+ */
 SNIPS_RESULT snips_nlu_engine_run_get_intents_into_json(const CSnipsNluEngine *client,
                                                         const char *input,
                                                         const char **result_json);
 
+/**
+ * The result is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_slots`
+ */
 SNIPS_RESULT snips_nlu_engine_run_get_slots(const CSnipsNluEngine *client,
                                             const char *input,
                                             const char *intent,
                                             const CSlotList **result);
 
+/**
+ * The string is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_string`
+ */
 SNIPS_RESULT snips_nlu_engine_run_get_slots_into_json(const CSnipsNluEngine *client,
                                                       const char *input,
                                                       const char *intent,
                                                       const char **result_json);
 
+/**
+ * The result is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_slots`
+ */
 SNIPS_RESULT snips_nlu_engine_run_get_slots_with_alternatives(const CSnipsNluEngine *client,
                                                               const char *input,
                                                               const char *intent,
                                                               unsigned int slots_alternatives,
                                                               const CSlotList **result);
 
+/**
+ * The string is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_string`
+ */
 SNIPS_RESULT snips_nlu_engine_run_get_slots_with_alternatives_into_json(const CSnipsNluEngine *client,
                                                                         const char *input,
                                                                         const char *intent,
                                                                         unsigned int slots_alternatives,
                                                                         const char **result_json);
 
+/**
+ * The result is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_result`
+ */
 SNIPS_RESULT snips_nlu_engine_run_parse(const CSnipsNluEngine *client,
                                         const char *input,
                                         const CStringArray *intents_whitelist,
                                         const CStringArray *intents_blacklist,
                                         const CIntentParserResult **result);
 
+/**
+ * The string is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_string`
+ */
 SNIPS_RESULT snips_nlu_engine_run_parse_into_json(const CSnipsNluEngine *client,
                                                   const char *input,
                                                   const CStringArray *intents_whitelist,
                                                   const CStringArray *intents_blacklist,
                                                   const char **result_json);
 
+/**
+ * The result is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_result`
+ */
 SNIPS_RESULT snips_nlu_engine_run_parse_with_alternatives(const CSnipsNluEngine *client,
                                                           const char *input,
                                                           const CStringArray *intents_whitelist,
@@ -511,6 +635,10 @@ SNIPS_RESULT snips_nlu_engine_run_parse_with_alternatives(const CSnipsNluEngine
                                                           unsigned int slots_alternatives,
                                                           const CIntentParserResult **result);
 
+/**
+ * The string is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_string`
+ */
 SNIPS_RESULT snips_nlu_engine_run_parse_with_alternatives_into_json(const CSnipsNluEngine *client,
                                                                     const char *input,
                                                                     const CStringArray *intents_whitelist,
@@ -519,4 +647,13 @@ SNIPS_RESULT snips_nlu_engine_run_parse_with_alternatives_into_json(const CSnips
                                                                     unsigned int slots_alternatives,
                                                                     const char **result_json);
 
+/**
+ * The result is owned by the caller and must be destroyed with
+ * `snips_nlu_engine_destroy_result`
+ */
+SNIPS_RESULT snips_nlu_engine_run_parse_with_filter(const CSnipsNluEngine *client,
+                                                    const char *input,
+                                                    const CStringArray *allowed_intents,
+                                                    const CIntentParserResult **result);
+
 #endif /* LIBSNIPS_NLU_H_ */
@@ -0,0 +1,164 @@
+use serde::Serialize;
+
+use crate::analysis::Dataset;
+use crate::errors::*;
+use crate::nlu_engine::SnipsNluEngine;
+use crate::utils::IntentName;
+
+/// Pairwise confusion between the intents of a dataset
+///
+/// Rows of the matrix correspond to the expected intents and columns to the predicted ones,
+/// both following the order of `intents`. The last row and column correspond to the None
+/// intent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfusabilityReport {
+    pub intents: Vec<Option<IntentName>>,
+    pub matrix: Vec<Vec<usize>>,
+    /// Off-diagonal cells of the matrix, sorted by decreasing count
+    pub confusions: Vec<IntentConfusion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentConfusion {
+    pub expected_intent: Option<IntentName>,
+    pub predicted_intent: Option<IntentName>,
+    pub count: usize,
+    pub exemplars: Vec<String>,
+}
+
+/// Runs the engine over all the utterances of the dataset and reports which intents get
+/// confused with each other, along with at most `max_exemplars` utterances per confusion
+///
+/// Intents are reported under their alias when the engine is configured with intent aliases.
+pub fn analyze_confusability(
+    engine: &SnipsNluEngine,
+    dataset: &Dataset,
+    max_exemplars: usize,
+) -> Result<ConfusabilityReport> {
+    let mut intents: Vec<Option<IntentName>> = engine
+        .intents()
+        .into_iter()
+        .map(|intent| Some(intent.to_string()))
+        .collect();
+    intents.push(None);
+    let nb_intents = intents.len();
+    let intent_index = |intent: &Option<IntentName>| -> Result<usize> {
+        intents
+            .iter()
+            .position(|i| i == intent)
+            .ok_or_else(|| SnipsNluError::UnknownIntent(intent.clone().unwrap_or_default()).into())
+    };
+
+    let mut matrix = vec![vec![0; nb_intents]; nb_intents];
+    let mut exemplars = vec![vec![vec![]; nb_intents]; nb_intents];
+    for (intent, text) in dataset.utterances() {
+        // The dataset uses the intent names of the model, while the engine exposes the aliases
+        let expected_index = intent_index(&Some(engine.intent_alias(intent).to_string()))?;
+        let predicted_intent = engine.parse(&text, None, None)?.intent.intent_name;
+        let predicted_index = intent_index(&predicted_intent)?;
+        matrix[expected_index][predicted_index] += 1;
+        if exemplars[expected_index][predicted_index].len() < max_exemplars {
+            exemplars[expected_index][predicted_index].push(text);
+        }
+    }
+
+    let mut confusions = vec![];
+    for (expected_index, expected_intent) in intents.iter().enumerate() {
+        for (predicted_index, predicted_intent) in intents.iter().enumerate() {
+            let count = matrix[expected_index][predicted_index];
+            if expected_index == predicted_index || count == 0 {
+                continue;
+            }
+            confusions.push(IntentConfusion {
+                expected_intent: expected_intent.clone(),
+                predicted_intent: predicted_intent.clone(),
+                count,
+                exemplars: exemplars[expected_index][predicted_index].clone(),
+            });
+        }
+    }
+    confusions.sort_by(|a, b| b.count.cmp(&a.count));
+
+    Ok(ConfusabilityReport {
+        intents,
+        matrix,
+        confusions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use maplit::hashmap;
+
+    use super::*;
+    use crate::config::EngineConfig;
+
+    fn beverage_engine_path() -> PathBuf {
+        Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage")
+    }
+
+    fn beverage_dataset_path() -> PathBuf {
+        Path::new("data")
+            .join("tests")
+            .join("datasets")
+            .join("beverage_dataset.json")
+    }
+
+    #[test]
+    fn test_analyze_confusability() {
+        // Given
+        let nlu_engine = SnipsNluEngine::from_path(beverage_engine_path()).unwrap();
+        let dataset = Dataset::from_path(beverage_dataset_path()).unwrap();
+
+        // When
+        let report = analyze_confusability(&nlu_engine, &dataset, 2).unwrap();
+
+        // Then
+        let expected_intents = vec![
+            Some("MakeCoffee".to_string()),
+            Some("MakeTea".to_string()),
+            None,
+        ];
+        let nb_utterances: usize = report.matrix.iter().flatten().sum();
+        assert_eq!(expected_intents, report.intents);
+        assert_eq!(dataset.utterances().len(), nb_utterances);
+        assert!(report
+            .confusions
+            .iter()
+            .all(|confusion| confusion.exemplars.len() <= 2));
+    }
+
+    #[test]
+    fn test_analyze_confusability_with_intent_aliases() {
+        // Given
+        let config = EngineConfig {
+            intent_aliases: hashmap! {
+                "PrepareTea".to_string() => "MakeTea".to_string(),
+            },
+            ..EngineConfig::default()
+        };
+        let nlu_engine =
+            SnipsNluEngine::from_path_with_config(beverage_engine_path(), &config).unwrap();
+        let dataset = Dataset::from_path(beverage_dataset_path()).unwrap();
+
+        // When
+        let report = analyze_confusability(&nlu_engine, &dataset, 2).unwrap();
+
+        // Then
+        let expected_intents = vec![
+            Some("MakeCoffee".to_string()),
+            Some("PrepareTea".to_string()),
+            None,
+        ];
+        let nb_utterances: usize = report.matrix.iter().flatten().sum();
+        assert_eq!(expected_intents, report.intents);
+        assert_eq!(dataset.utterances().len(), nb_utterances);
+    }
+}
@@ -0,0 +1,194 @@
+use std::ops::Range;
+
+use itertools::Itertools;
+use serde::Serialize;
+use snips_nlu_ontology::IntentClassifierResult;
+use snips_nlu_utils::language::Language as NluUtilsLanguage;
+use snips_nlu_utils::string::normalize;
+use snips_nlu_utils::token::tokenize;
+
+use crate::errors::*;
+use crate::language::FromLanguage;
+use crate::nlu_engine::SnipsNluEngine;
+use crate::utils::IntentName;
+
+/// Minimal modification of an utterance
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Perturbation {
+    RemovedStopWord { token: String },
+    RemovedGazetteerHit { token: String, gazetteer: String },
+    RemovedWord { token: String },
+    StemmedWord { token: String, stem: String },
+}
+
+/// Perturbation which makes the engine predict the expected intent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Counterfactual {
+    pub perturbation: Perturbation,
+    pub perturbed_input: String,
+    pub confidence_score: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CounterfactualReport {
+    pub input: String,
+    pub predicted_intent: IntentClassifierResult,
+    pub expected_intent: Option<IntentName>,
+    /// Counterfactuals sorted by decreasing confidence score of the expected intent
+    pub counterfactuals: Vec<Counterfactual>,
+}
+
+/// Looks for the single-token perturbations of `input` which make `engine` predict
+/// `expected_intent`, `None` corresponding to the None intent
+///
+/// Each token of the input is alternatively removed and replaced by its stem. The kind of
+/// removal (stop word, gazetteer hit or plain word) is reported so that it is easy to
+/// understand which part of the featurization drives the misclassification.
+pub fn find_counterfactuals(
+    engine: &SnipsNluEngine,
+    input: &str,
+    expected_intent: Option<&str>,
+) -> Result<CounterfactualReport> {
+    if let Some(intent) = expected_intent {
+        if !engine.intents().contains(&intent) {
+            return Err(SnipsNluError::UnknownIntent(intent.to_string()).into());
+        }
+    }
+    let predicted_intent = engine.parse(input, None, None)?.intent;
+    let mut report = CounterfactualReport {
+        input: input.to_string(),
+        predicted_intent,
+        expected_intent: expected_intent.map(|intent| intent.to_string()),
+        counterfactuals: vec![],
+    };
+    if report
+        .predicted_intent
+        .intent_name
+        .as_ref()
+        .map(|name| &**name)
+        == expected_intent
+    {
+        return Ok(report);
+    }
+
+    let shared_resources = engine.shared_resources();
+    let language = NluUtilsLanguage::from_language(engine.language()?);
+    let mut counterfactuals = vec![];
+    for token in tokenize(input, language) {
+        let normalized_token = normalize(&token.value);
+        let removal = if shared_resources.stop_words.contains(&normalized_token) {
+            Perturbation::RemovedStopWord {
+                token: token.value.clone(),
+            }
+        } else if let Some(gazetteer) = shared_resources
+            .gazetteers
+            .iter()
+            .find(|(_, gazetteer)| gazetteer.contains(&normalized_token))
+            .map(|(name, _)| name.to_string())
+        {
+            Perturbation::RemovedGazetteerHit {
+                token: token.value.clone(),
+                gazetteer,
+            }
+        } else {
+            Perturbation::RemovedWord {
+                token: token.value.clone(),
+            }
+        };
+        let mut perturbations = vec![(removal, replace_char_range(input, &token.char_range, ""))];
+
+        if let Some(stemmer) = shared_resources.stemmer.as_ref() {
+            let stem = stemmer.stem(&normalized_token).into_owned();
+            if stem != normalized_token {
+                let perturbed_input = replace_char_range(input, &token.char_range, &stem);
+                let stemming = Perturbation::StemmedWord {
+                    token: token.value.clone(),
+                    stem,
+                };
+                perturbations.push((stemming, perturbed_input));
+            }
+        }
+
+        for (perturbation, perturbed_input) in perturbations {
+            let intent = engine.parse(&perturbed_input, None, None)?.intent;
+            if intent.intent_name.as_ref().map(|name| &**name) == expected_intent {
+                counterfactuals.push(Counterfactual {
+                    perturbation,
+                    perturbed_input,
+                    confidence_score: intent.confidence_score,
+                });
+            }
+        }
+    }
+    report.counterfactuals = counterfactuals
+        .into_iter()
+        .sorted_by(|a, b| b.confidence_score.partial_cmp(&a.confidence_score).unwrap())
+        .collect();
+    Ok(report)
+}
+
+fn replace_char_range(input: &str, char_range: &Range<usize>, replacement: &str) -> String {
+    let prefix: String = input.chars().take(char_range.start).collect();
+    let suffix: String = input.chars().skip(char_range.end).collect();
+    format!("{}{}{}", prefix, replacement, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_replace_char_range() {
+        assert_eq!("hé  world", replace_char_range("hé you world", &(3..6), ""));
+        assert_eq!(
+            "hé me world",
+            replace_char_range("hé you world", &(3..6), "me")
+        );
+    }
+
+    #[test]
+    fn test_find_counterfactuals() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+
+        // When
+        let report = find_counterfactuals(
+            &nlu_engine,
+            "make me two cups of coffee",
+            Some("MakeCoffee"),
+        )
+        .unwrap();
+        let unknown_intent_report =
+            find_counterfactuals(&nlu_engine, "make me two cups of coffee", Some("MakeJuice"));
+        let none_intent_report = find_counterfactuals(&nlu_engine, "coffee", None).unwrap();
+
+        // Then
+        assert_eq!(
+            Some("MakeCoffee".to_string()),
+            report.predicted_intent.intent_name
+        );
+        assert!(report.counterfactuals.is_empty());
+        assert!(unknown_intent_report.is_err());
+        assert_eq!(
+            Some("MakeCoffee".to_string()),
+            none_intent_report.predicted_intent.intent_name
+        );
+        let counterfactual = &none_intent_report.counterfactuals[0];
+        assert_eq!(
+            Perturbation::RemovedWord {
+                token: "coffee".to_string()
+            },
+            counterfactual.perturbation
+        );
+        assert_eq!("", counterfactual.perturbed_input);
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+
+use failure::ResultExt;
+use serde::Deserialize;
+
+use crate::errors::*;
+use crate::utils::IntentName;
+
+/// Subset of the Snips NLU dataset format which is needed to run analyses
+#[derive(Debug, Clone, Deserialize)]
+pub struct Dataset {
+    pub language: String,
+    pub intents: HashMap<IntentName, DatasetIntent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DatasetIntent {
+    pub utterances: Vec<DatasetUtterance>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DatasetUtterance {
+    pub data: Vec<DatasetChunk>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DatasetChunk {
+    pub text: String,
+    pub entity: Option<String>,
+    pub slot_name: Option<String>,
+}
+
+impl Dataset {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let dataset_file = File::open(&path).with_context(|_| {
+            format!(
+                "Cannot open dataset file '{:?}'",
+                path.as_ref().file_name().unwrap_or_default()
+            )
+        })?;
+        let dataset =
+            serde_json::from_reader(dataset_file).with_context(|_| "Cannot deserialize dataset")?;
+        Ok(dataset)
+    }
+
+    /// Returns the (intent, text) pairs of all the utterances of the dataset, sorted by intent
+    pub fn utterances(&self) -> Vec<(&str, String)> {
+        let mut intents: Vec<&IntentName> = self.intents.keys().collect();
+        intents.sort();
+        intents
+            .into_iter()
+            .flat_map(|intent| {
+                self.intents[intent]
+                    .utterances
+                    .iter()
+                    .map(move |utterance| (&**intent, utterance.text()))
+            })
+            .collect()
+    }
+}
+
+impl DatasetUtterance {
+    pub fn text(&self) -> String {
+        self.data.iter().map(|chunk| &*chunk.text).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dataset_utterances() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("datasets")
+            .join("beverage_dataset.json");
+        let dataset = Dataset::from_path(path).unwrap();
+
+        // When
+        let utterances = dataset.utterances();
+
+        // Then
+        assert_eq!("en", dataset.language);
+        assert_eq!(
+            ("MakeCoffee", "make me one cup of coffee please".to_string()),
+            utterances[0]
+        );
+    }
+}
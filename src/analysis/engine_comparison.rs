@@ -0,0 +1,154 @@
+use serde::Serialize;
+use snips_nlu_ontology::{IntentClassifierResult, Slot};
+
+use crate::errors::*;
+use crate::nlu_engine::SnipsNluEngine;
+
+/// Differences between the parsing results of a reference engine and of a candidate engine
+/// over a corpus
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineComparison {
+    pub nb_inputs: usize,
+    pub nb_changed_intents: usize,
+    pub nb_changed_slots: usize,
+    /// Mean of the confidence deltas of the inputs whose intent did not change
+    pub mean_confidence_delta: f32,
+    /// Inputs which are parsed differently, in the order of the corpus
+    pub differences: Vec<ParsingDifference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsingDifference {
+    pub input: String,
+    pub reference_intent: IntentClassifierResult,
+    pub candidate_intent: IntentClassifierResult,
+    /// Confidence of the candidate minus confidence of the reference
+    pub confidence_delta: f32,
+    /// Slots of the reference result which are not in the candidate result
+    pub removed_slots: Vec<Slot>,
+    /// Slots of the candidate result which are not in the reference result
+    pub added_slots: Vec<Slot>,
+}
+
+impl ParsingDifference {
+    pub fn intent_changed(&self) -> bool {
+        self.reference_intent.intent_name != self.candidate_intent.intent_name
+    }
+
+    pub fn slots_changed(&self) -> bool {
+        !self.removed_slots.is_empty() || !self.added_slots.is_empty()
+    }
+}
+
+/// Parses the corpus with both engines, typically the current model and a new one before it
+/// is rolled out, and reports the inputs whose intent or slots changed, or whose confidence
+/// changed by more than `confidence_tolerance`
+pub fn compare_engines<I, S>(
+    reference: &SnipsNluEngine,
+    candidate: &SnipsNluEngine,
+    corpus: I,
+    confidence_tolerance: f32,
+) -> Result<EngineComparison>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut nb_inputs = 0;
+    let mut nb_changed_intents = 0;
+    let mut nb_changed_slots = 0;
+    let mut confidence_deltas_sum = 0.0;
+    let mut nb_unchanged_intents = 0;
+    let mut differences = vec![];
+    for input in corpus {
+        let input = input.as_ref();
+        let reference_result = reference.parse(input, None, None)?;
+        let candidate_result = candidate.parse(input, None, None)?;
+        let difference = ParsingDifference {
+            input: input.to_string(),
+            confidence_delta: candidate_result.intent.confidence_score
+                - reference_result.intent.confidence_score,
+            removed_slots: slots_difference(&reference_result.slots, &candidate_result.slots),
+            added_slots: slots_difference(&candidate_result.slots, &reference_result.slots),
+            reference_intent: reference_result.intent,
+            candidate_intent: candidate_result.intent,
+        };
+        nb_inputs += 1;
+        if difference.intent_changed() {
+            nb_changed_intents += 1;
+        } else {
+            nb_unchanged_intents += 1;
+            confidence_deltas_sum += difference.confidence_delta;
+        }
+        if difference.slots_changed() {
+            nb_changed_slots += 1;
+        }
+        if difference.intent_changed()
+            || difference.slots_changed()
+            || difference.confidence_delta.abs() > confidence_tolerance
+        {
+            differences.push(difference);
+        }
+    }
+    let mean_confidence_delta = if nb_unchanged_intents > 0 {
+        confidence_deltas_sum / nb_unchanged_intents as f32
+    } else {
+        0.0
+    };
+    Ok(EngineComparison {
+        nb_inputs,
+        nb_changed_intents,
+        nb_changed_slots,
+        mean_confidence_delta,
+        differences,
+    })
+}
+
+fn slots_difference(slots: &[Slot], other_slots: &[Slot]) -> Vec<Slot> {
+    slots
+        .iter()
+        .filter(|slot| !other_slots.contains(slot))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::float_cmp)]
+
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_compare_engines() {
+        // Given
+        let models_dir = Path::new("data").join("tests").join("models");
+        let beverage_engine =
+            SnipsNluEngine::from_path(models_dir.join("nlu_engine_beverage")).unwrap();
+        let game_engine = SnipsNluEngine::from_path(models_dir.join("nlu_engine_game")).unwrap();
+        let corpus = vec![
+            "Make me two cups of coffee please",
+            "make me two cups of tea",
+        ];
+
+        // When
+        let same_engine_comparison =
+            compare_engines(&beverage_engine, &beverage_engine, &corpus, 0.0).unwrap();
+        let comparison = compare_engines(&beverage_engine, &game_engine, &corpus, 0.0).unwrap();
+
+        // Then
+        assert_eq!(2, same_engine_comparison.nb_inputs);
+        assert!(same_engine_comparison.differences.is_empty());
+        assert_eq!(0.0, same_engine_comparison.mean_confidence_delta);
+        assert_eq!(2, comparison.nb_changed_intents);
+        assert_eq!(2, comparison.differences.len());
+        let coffee_difference = &comparison.differences[0];
+        assert_eq!(
+            Some("MakeCoffee".to_string()),
+            coffee_difference.reference_intent.intent_name
+        );
+        assert_eq!(1, coffee_difference.removed_slots.len());
+    }
+}
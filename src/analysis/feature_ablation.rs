@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::analysis::DatasetUtterance;
+use crate::errors::*;
+use crate::slot_filler::CRFSlotFiller;
+use crate::utils::SlotName;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFamilyImpact {
+    /// Identifier of the feature factory, e.g. `"entity_match"`
+    pub family: String,
+    /// Slot F1 score obtained when the features of the family are disabled
+    pub f1: f32,
+    /// Baseline F1 minus the ablated F1, families with a null or negative impact being pruning
+    /// candidates
+    pub f1_impact: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureAblationReport {
+    /// Slot F1 score obtained with all the features
+    pub baseline_f1: f32,
+    /// Impact of each feature family, sorted by decreasing impact
+    pub impacts: Vec<FeatureFamilyImpact>,
+}
+
+/// Extracts the slots of the labeled utterances with each feature family of the slot filler
+/// disabled in turn, and reports how much the slot F1 score drops
+///
+/// A slot is considered correct when both its name and its range match the labeled ones.
+pub fn analyze_feature_ablation(
+    slot_filler: &CRFSlotFiller,
+    utterances: &[DatasetUtterance],
+) -> Result<FeatureAblationReport> {
+    let expected_slots: Vec<HashSet<(SlotName, Range<usize>)>> =
+        utterances.iter().map(labeled_slots).collect();
+    let baseline_f1 = compute_f1(slot_filler, utterances, &expected_slots, &[])?;
+    let mut impacts = slot_filler
+        .feature_families()
+        .into_iter()
+        .map(|family| {
+            let f1 = compute_f1(slot_filler, utterances, &expected_slots, &[family])?;
+            Ok(FeatureFamilyImpact {
+                family: family.to_string(),
+                f1,
+                f1_impact: baseline_f1 - f1,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    impacts.sort_by(|a, b| b.f1_impact.partial_cmp(&a.f1_impact).unwrap());
+    Ok(FeatureAblationReport {
+        baseline_f1,
+        impacts,
+    })
+}
+
+fn labeled_slots(utterance: &DatasetUtterance) -> HashSet<(SlotName, Range<usize>)> {
+    let mut slots = HashSet::new();
+    let mut offset = 0;
+    for chunk in utterance.data.iter() {
+        let chunk_length = chunk.text.chars().count();
+        if let Some(slot_name) = chunk.slot_name.as_ref() {
+            slots.insert((slot_name.clone(), offset..offset + chunk_length));
+        }
+        offset += chunk_length;
+    }
+    slots
+}
+
+fn compute_f1(
+    slot_filler: &CRFSlotFiller,
+    utterances: &[DatasetUtterance],
+    expected_slots: &[HashSet<(SlotName, Range<usize>)>],
+    disabled_families: &[&str],
+) -> Result<f32> {
+    let mut nb_correct = 0;
+    let mut nb_predicted = 0;
+    let mut nb_expected = 0;
+    for (utterance, expected) in utterances.iter().zip(expected_slots) {
+        let predicted: HashSet<(SlotName, Range<usize>)> = slot_filler
+            .get_slots_with_disabled_features(&utterance.text(), disabled_families)?
+            .into_iter()
+            .map(|slot| (slot.slot_name, slot.char_range))
+            .collect();
+        nb_correct += predicted.intersection(expected).count();
+        nb_predicted += predicted.len();
+        nb_expected += expected.len();
+    }
+    if nb_predicted == 0 && nb_expected == 0 {
+        return Ok(1.0);
+    }
+    if nb_correct == 0 {
+        return Ok(0.0);
+    }
+    let precision = nb_correct as f32 / nb_predicted as f32;
+    let recall = nb_correct as f32 / nb_expected as f32;
+    Ok(2.0 * precision * recall / (precision + recall))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::analysis::Dataset;
+    use crate::resources::loading::load_engine_shared_resources;
+
+    use super::*;
+
+    #[test]
+    fn test_analyze_feature_ablation() {
+        // Given
+        let data_dir = Path::new("data").join("tests");
+        let engine_dir = data_dir.join("models").join("nlu_engine_beverage");
+        let dataset =
+            Dataset::from_path(data_dir.join("datasets").join("beverage_dataset.json")).unwrap();
+        let shared_resources = load_engine_shared_resources(&engine_dir).unwrap();
+        let slot_filler = CRFSlotFiller::from_path(
+            engine_dir
+                .join("probabilistic_intent_parser")
+                .join("slot_filler_0"),
+            shared_resources,
+        )
+        .unwrap();
+
+        // When
+        let report =
+            analyze_feature_ablation(&slot_filler, &dataset.intents["MakeCoffee"].utterances)
+                .unwrap();
+
+        // Then
+        let mut families: Vec<&str> = report.impacts.iter().map(|i| &*i.family).collect();
+        families.sort();
+        let expected_families = vec![
+            "builtin_entity_match",
+            "entity_match",
+            "is_digit",
+            "is_first",
+            "is_last",
+            "ngram",
+            "shape_ngram",
+        ];
+        assert_eq!(expected_families, families);
+        assert!(report.baseline_f1 > 0.0);
+        for impact in report.impacts.iter() {
+            assert!((report.baseline_f1 - impact.f1 - impact.f1_impact).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use serde::Serialize;
+
+use crate::errors::*;
+use crate::intent_classifier::LogRegIntentClassifier;
+use crate::slot_filler::CRFSlotFiller;
+
+/// Value of a feature of the intent classifier
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassifierFeature {
+    pub name: String,
+    pub value: f32,
+}
+
+/// Features fired by the CRF on a token
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenFeatures {
+    pub token: String,
+    /// Pairs of feature name and feature value, e.g. `("ngram_1", "coffee")`
+    pub features: Vec<(String, String)>,
+}
+
+/// Features computed by the probabilistic processing units on an input
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseFeatures {
+    pub input: String,
+    /// Full feature vector of the intent classifier, in the order of its features
+    pub classifier_features: Vec<ClassifierFeature>,
+    /// Features of each token, empty when no slot filler is provided
+    pub token_features: Vec<TokenFeatures>,
+}
+
+/// Computes the features which the intent classifier and, optionally, the CRF slot filler of
+/// an intent would use to parse the input
+///
+/// The result can be serialized to JSON, in order to analyze the behavior of the features
+/// without re-implementing the featurization.
+pub fn export_features(
+    intent_classifier: &LogRegIntentClassifier,
+    slot_filler: Option<&CRFSlotFiller>,
+    input: &str,
+) -> Result<ParseFeatures> {
+    let classifier_features = intent_classifier
+        .feature_names()
+        .into_iter()
+        .zip(intent_classifier.compute_features(input)?.into_iter())
+        .map(|(name, value)| ClassifierFeature { name, value })
+        .collect();
+    let token_features = if let Some(slot_filler) = slot_filler {
+        slot_filler
+            .tokenize(input)
+            .into_iter()
+            .zip(slot_filler.compute_features(input)?)
+            .map(|(token, features)| TokenFeatures {
+                token: token.value,
+                features,
+            })
+            .collect()
+    } else {
+        vec![]
+    };
+    Ok(ParseFeatures {
+        input: input.to_string(),
+        classifier_features,
+        token_features,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::resources::loading::load_engine_shared_resources;
+
+    use super::*;
+
+    #[test]
+    fn test_export_features() {
+        // Given
+        let parser_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let shared_resources = load_engine_shared_resources(&parser_dir).unwrap();
+        let parser_dir = parser_dir.join("probabilistic_intent_parser");
+        let intent_classifier = LogRegIntentClassifier::from_path(
+            parser_dir.join("intent_classifier"),
+            shared_resources.clone(),
+        )
+        .unwrap();
+        let slot_filler =
+            CRFSlotFiller::from_path(parser_dir.join("slot_filler_0"), shared_resources).unwrap();
+
+        // When
+        let features =
+            export_features(&intent_classifier, Some(&slot_filler), "make me two cups").unwrap();
+
+        // Then
+        assert_eq!(
+            intent_classifier.feature_names().len(),
+            features.classifier_features.len()
+        );
+        assert!(features
+            .classifier_features
+            .iter()
+            .any(|feature| feature.value > 0.0));
+        let tokens: Vec<&str> = features
+            .token_features
+            .iter()
+            .map(|token_features| &*token_features.token)
+            .collect();
+        assert_eq!(vec!["make", "me", "two", "cups"], tokens);
+        assert!(features.token_features[3]
+            .features
+            .iter()
+            .any(|(name, _)| name == "ngram_1"));
+        assert!(serde_json::to_string(&features).is_ok());
+    }
+}
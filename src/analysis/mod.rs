@@ -0,0 +1,30 @@
+//! Tools helping to debug datasets and trained models
+
+mod confusability;
+mod counterfactual;
+mod dataset;
+mod engine_comparison;
+mod feature_ablation;
+mod feature_export;
+mod model_size;
+mod pattern_coverage;
+mod preprocessing_parity;
+
+pub use self::confusability::{analyze_confusability, ConfusabilityReport, IntentConfusion};
+pub use self::counterfactual::{
+    find_counterfactuals, Counterfactual, CounterfactualReport, Perturbation,
+};
+pub use self::dataset::{Dataset, DatasetChunk, DatasetIntent, DatasetUtterance};
+pub use self::engine_comparison::{compare_engines, EngineComparison, ParsingDifference};
+pub use self::feature_ablation::{
+    analyze_feature_ablation, FeatureAblationReport, FeatureFamilyImpact,
+};
+pub use self::feature_export::{export_features, ClassifierFeature, ParseFeatures, TokenFeatures};
+pub use self::model_size::{
+    analyze_model, shrink_model, ComponentSize, DuplicatedFiles, ModelComponent, ModelSizeReport,
+};
+pub use self::pattern_coverage::{analyze_pattern_coverage, PatternCoverageReport, PatternUsage};
+pub use self::preprocessing_parity::{
+    check_preprocessing_parity, PreprocessingDivergence, PreprocessingParityReport,
+    PreprocessingSample,
+};
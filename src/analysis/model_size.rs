@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use failure::{bail, format_err, ResultExt};
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::errors::*;
+
+/// Part of a trained model, as far as its size is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModelComponent {
+    /// Regex patterns of the deterministic intent parser
+    RegexPatterns,
+    /// Lookup table of the lookup intent parser
+    LookupTable,
+    /// Weights of the CRF slot fillers
+    CrfWeights,
+    /// Weights of the logistic regression intent classifier
+    ClassifierWeights,
+    /// Vocabularies of the featurizer of the intent classifier
+    Vocabulary,
+    /// Gazetteers of the language resources and of the custom entity parser
+    Gazetteers,
+    /// Other language resources, such as stems, stop words or noise
+    LanguageResources,
+    /// Metadata files
+    Metadata,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentSize {
+    pub component: ModelComponent,
+    /// Size in bytes
+    pub size: usize,
+    pub nb_files: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatedFiles {
+    /// Paths of the identical files, relatively to the model directory
+    pub paths: Vec<PathBuf>,
+    /// Size in bytes of a single copy
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSizeReport {
+    /// Size in bytes of all the files of the model
+    pub total_size: usize,
+    /// Size of each component, sorted by decreasing size
+    pub components: Vec<ComponentSize>,
+    /// Groups of files having the same content, which are thus stored several times
+    pub duplicated_files: Vec<DuplicatedFiles>,
+    /// Components whose weights were left untouched when shrinking the model, which is always
+    /// the case of the CRF weights as they are stored in the binary format of crfsuite
+    pub unpruned_components: Vec<ModelComponent>,
+}
+
+/// Reports the size of each component of the trained engine located in the directory
+pub fn analyze_model<P: AsRef<Path>>(engine_dir: P) -> Result<ModelSizeReport> {
+    let engine_dir = engine_dir.as_ref();
+    let files = list_files(engine_dir)?;
+    let mut component_sizes: HashMap<ModelComponent, (usize, usize)> = HashMap::new();
+    let mut total_size = 0;
+    for file in files.iter() {
+        let size = fs::metadata(file)?.len() as usize;
+        let relative_path = file.strip_prefix(engine_dir)?;
+        let component_size = component_sizes
+            .entry(component_of(relative_path))
+            .or_insert((0, 0));
+        component_size.0 += size;
+        component_size.1 += 1;
+        total_size += size;
+    }
+
+    let components = component_sizes
+        .into_iter()
+        .map(|(component, (size, nb_files))| ComponentSize {
+            component,
+            size,
+            nb_files,
+        })
+        .sorted_by(|a, b| b.size.cmp(&a.size).then(a.component.cmp(&b.component)))
+        .collect();
+
+    let duplicated_files = find_duplicated_files(&files)?
+        .into_iter()
+        .map(|paths| -> Result<DuplicatedFiles> {
+            Ok(DuplicatedFiles {
+                size: fs::metadata(&paths[0])?.len() as usize,
+                paths: paths
+                    .iter()
+                    .map(|path| path.strip_prefix(engine_dir).map(|p| p.to_path_buf()))
+                    .collect::<std::result::Result<_, _>>()?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(ModelSizeReport {
+        total_size,
+        components,
+        duplicated_files,
+        unpruned_components: vec![],
+    })
+}
+
+/// Writes a smaller version of the trained engine located in `engine_dir` into `output_dir`,
+/// and reports the size of the written engine
+///
+/// The weights of the intent classifiers whose absolute value is lower than `weight_threshold`
+/// are set to zero, and the features having only zero weights are removed from the vocabularies
+/// of the featurizers. As the tf-idf features are normalized, this slightly changes the
+/// probabilities of the intents, hence the threshold must remain small. Identical files are
+/// hard linked so that they are only stored once. CRF weights are left untouched, which the
+/// returned report mentions in its `unpruned_components`.
+pub fn shrink_model<P: AsRef<Path>, Q: AsRef<Path>>(
+    engine_dir: P,
+    output_dir: Q,
+    weight_threshold: f64,
+) -> Result<ModelSizeReport> {
+    let engine_dir = engine_dir.as_ref();
+    let output_dir = output_dir.as_ref();
+    let mut output_files = vec![];
+    for file in list_files(engine_dir)? {
+        let output_file = output_dir.join(file.strip_prefix(engine_dir)?);
+        if let Some(parent) = output_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&file, &output_file)
+            .with_context(|_| format!("Cannot copy model file {:?}", file))?;
+        output_files.push(output_file);
+    }
+
+    for file in output_files.iter() {
+        if file.file_name().and_then(|name| name.to_str()) == Some("intent_classifier.json") {
+            prune_intent_classifier(file, weight_threshold)?;
+        }
+    }
+
+    // Deduplication happens last, as files sharing the same content are no longer independent
+    for paths in find_duplicated_files(&output_files)? {
+        for duplicate in paths.iter().skip(1) {
+            fs::remove_file(duplicate)?;
+            if fs::hard_link(&paths[0], duplicate).is_err() {
+                fs::copy(&paths[0], duplicate)?;
+            }
+        }
+    }
+
+    let mut report = analyze_model(output_dir)?;
+    report.unpruned_components = report
+        .components
+        .iter()
+        .map(|component| component.component)
+        .filter(|component| *component == ModelComponent::CrfWeights)
+        .collect();
+    Ok(report)
+}
+
+fn prune_intent_classifier(classifier_path: &Path, weight_threshold: f64) -> Result<()> {
+    let mut classifier = read_json(classifier_path)?;
+    let mut coeffs: Vec<Vec<f64>> = match classifier.get("coeffs") {
+        Some(Value::Null) | None => return Ok(()),
+        Some(coeffs) => serde_json::from_value(coeffs.clone())?,
+    };
+    for coeff in coeffs.iter_mut().flat_map(|row| row.iter_mut()) {
+        if coeff.abs() < weight_threshold {
+            *coeff = 0.;
+        }
+    }
+    let nb_features = coeffs.first().map(|row| row.len()).unwrap_or(0);
+    let used_features: Vec<bool> = (0..nb_features)
+        .map(|feature_idx| coeffs.iter().any(|row| row[feature_idx] != 0.))
+        .collect();
+
+    let classifier_dir = classifier_path
+        .parent()
+        .ok_or_else(|| format_err!("Invalid intent classifier path {:?}", classifier_path))?;
+    let kept_features = match classifier.get("featurizer").and_then(|name| name.as_str()) {
+        Some(featurizer_name) => {
+            prune_featurizer(&classifier_dir.join(featurizer_name), &used_features)?
+        }
+        None => (0..nb_features).collect(),
+    };
+
+    let pruned_coeffs: Vec<Vec<f64>> = coeffs
+        .iter()
+        .map(|row| kept_features.iter().map(|idx| row[*idx]).collect())
+        .collect();
+    classifier["coeffs"] = serde_json::to_value(pruned_coeffs)?;
+    write_json(classifier_path, &classifier)
+}
+
+/// Removes the unused features from the vectorizers of the featurizer, and returns the indices
+/// of the features which are kept
+fn prune_featurizer(featurizer_dir: &Path, used_features: &[bool]) -> Result<Vec<usize>> {
+    let featurizer = read_json(&featurizer_dir.join("featurizer.json"))?;
+    let tfidf_name = featurizer["tfidf_vectorizer"]
+        .as_str()
+        .ok_or_else(|| format_err!("Missing tf-idf vectorizer in featurizer"))?;
+    let tfidf_path = featurizer_dir.join(tfidf_name).join("vectorizer.json");
+    let mut tfidf_vectorizer = read_json(&tfidf_path)?;
+    let nb_tfidf_features = tfidf_vectorizer["vectorizer"]["idf_diag"]
+        .as_array()
+        .map(|idf_diag| idf_diag.len())
+        .ok_or_else(|| format_err!("Missing idf values in tf-idf vectorizer"))?;
+    if nb_tfidf_features > used_features.len() {
+        bail!("Featurizer has more features than the intent classifier");
+    }
+
+    let mut kept_features = vec![];
+    let is_hashed = !tfidf_vectorizer["config"]["hashing_buckets"].is_null();
+    if is_hashed {
+        // Hashed vocabularies have a fixed size
+        kept_features.extend(0..nb_tfidf_features);
+    } else {
+        let kept_tfidf_features = reindex_features(&used_features[..nb_tfidf_features]);
+        let vectorizer = &mut tfidf_vectorizer["vectorizer"];
+        let idf_diag = vectorizer["idf_diag"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        vectorizer["idf_diag"] = Value::Array(
+            kept_tfidf_features
+                .iter()
+                .map(|(old_idx, _)| idf_diag[*old_idx].clone())
+                .collect(),
+        );
+        let vocab: Map<String, Value> = vectorizer["vocab"]
+            .as_object()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(word, idx)| {
+                idx.as_u64()
+                    .and_then(|idx| kept_tfidf_features.get(&(idx as usize)))
+                    .map(|new_idx| (word, Value::from(*new_idx)))
+            })
+            .collect();
+        vectorizer["vocab"] = Value::Object(vocab);
+        kept_features.extend(kept_tfidf_features.keys().sorted());
+        write_json(&tfidf_path, &tfidf_vectorizer)?;
+    }
+
+    if let Some(cooccurrence_name) = featurizer["cooccurrence_vectorizer"].as_str() {
+        let cooccurrence_path = featurizer_dir
+            .join(cooccurrence_name)
+            .join("vectorizer.json");
+        let mut cooccurrence_vectorizer = read_json(&cooccurrence_path)?;
+        let kept_pairs = reindex_features(&used_features[nb_tfidf_features..]);
+        let word_pairs: Map<String, Value> = cooccurrence_vectorizer["word_pairs"]
+            .as_object()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(idx, pair)| {
+                idx.parse::<usize>()
+                    .ok()
+                    .and_then(|idx| kept_pairs.get(&idx))
+                    .map(|new_idx| (new_idx.to_string(), pair))
+            })
+            .collect();
+        cooccurrence_vectorizer["word_pairs"] = Value::Object(word_pairs);
+        kept_features.extend(
+            kept_pairs
+                .keys()
+                .sorted()
+                .map(|idx| nb_tfidf_features + idx),
+        );
+        write_json(&cooccurrence_path, &cooccurrence_vectorizer)?;
+    }
+    Ok(kept_features)
+}
+
+/// Maps the indices of the used features to their new, contiguous, indices
+fn reindex_features(used_features: &[bool]) -> HashMap<usize, usize> {
+    used_features
+        .iter()
+        .enumerate()
+        .filter(|(_, is_used)| **is_used)
+        .enumerate()
+        .map(|(new_idx, (old_idx, _))| (old_idx, new_idx))
+        .collect()
+}
+
+fn component_of(relative_path: &Path) -> ModelComponent {
+    let is_in = |dir_name: &str| {
+        relative_path
+            .components()
+            .any(|component| component.as_os_str() == dir_name)
+    };
+    let file_name = relative_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+    if relative_path.extension().and_then(|ext| ext.to_str()) == Some("crfsuite") {
+        ModelComponent::CrfWeights
+    } else if is_in("resources") {
+        if is_in("gazetteers") {
+            ModelComponent::Gazetteers
+        } else {
+            ModelComponent::LanguageResources
+        }
+    } else if file_name == "metadata.json" {
+        ModelComponent::Metadata
+    } else if is_in("custom_entity_parser") {
+        ModelComponent::Gazetteers
+    } else if is_in("deterministic_intent_parser") {
+        ModelComponent::RegexPatterns
+    } else if is_in("lookup_intent_parser") {
+        ModelComponent::LookupTable
+    } else if file_name == "vectorizer.json" {
+        ModelComponent::Vocabulary
+    } else if file_name == "intent_classifier.json" {
+        ModelComponent::ClassifierWeights
+    } else {
+        ModelComponent::Other
+    }
+}
+
+/// Groups of files having the same content, sorted by path
+///
+/// Contents are compared through their SHA-256 digest, as the duplicated files are hard linked
+/// when shrinking the model.
+fn find_duplicated_files(files: &[PathBuf]) -> Result<Vec<Vec<PathBuf>>> {
+    let mut files_by_content: HashMap<(usize, Vec<u8>), Vec<PathBuf>> = HashMap::new();
+    for file in files {
+        let content = fs::read(file)?;
+        files_by_content
+            .entry((content.len(), Sha256::digest(&content).to_vec()))
+            .or_insert_with(|| vec![])
+            .push(file.clone());
+    }
+    Ok(files_by_content
+        .into_iter()
+        .filter(|((size, _), paths)| *size > 0 && paths.len() > 1)
+        .map(|(_, paths)| paths.into_iter().sorted().collect::<Vec<_>>())
+        .sorted()
+        .collect())
+}
+
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir).with_context(|_| format!("Cannot read directory {:?}", dir))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(list_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files.into_iter().sorted().collect())
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let file = fs::File::open(path).with_context(|_| format!("Cannot open file {:?}", path))?;
+    Ok(serde_json::from_reader(file)
+        .with_context(|_| format!("Cannot deserialize json file {:?}", path))?)
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<()> {
+    let file = fs::File::create(path).with_context(|_| format!("Cannot write file {:?}", path))?;
+    serde_json::to_writer_pretty(file, value)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nlu_engine::SnipsNluEngine;
+
+    fn beverage_engine_dir() -> PathBuf {
+        Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage")
+    }
+
+    #[test]
+    fn test_analyze_model() {
+        // Given
+        let engine_dir = beverage_engine_dir();
+
+        // When
+        let report = analyze_model(&engine_dir).unwrap();
+
+        // Then
+        let crf_weights = report
+            .components
+            .iter()
+            .find(|component| component.component == ModelComponent::CrfWeights)
+            .unwrap();
+        assert_eq!(2, crf_weights.nb_files);
+        assert_eq!(
+            report.total_size,
+            report.components.iter().map(|c| c.size).sum::<usize>()
+        );
+        assert!(report
+            .components
+            .windows(2)
+            .all(|components| components[0].size >= components[1].size));
+    }
+
+    #[test]
+    fn test_shrink_model() {
+        // Given
+        let engine_dir = beverage_engine_dir();
+        let output_dir = tempfile::tempdir().unwrap();
+        let shrunk_engine_dir = output_dir.path().join("shrunk_engine");
+        let original_report = analyze_model(&engine_dir).unwrap();
+
+        // When
+        let report = shrink_model(&engine_dir, &shrunk_engine_dir, 0.05).unwrap();
+        let shrunk_engine = SnipsNluEngine::from_path(&shrunk_engine_dir).unwrap();
+        let result = shrunk_engine
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+
+        // Then
+        assert!(report.total_size < original_report.total_size);
+        assert!(original_report.unpruned_components.is_empty());
+        assert_eq!(vec![ModelComponent::CrfWeights], report.unpruned_components);
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+    }
+}
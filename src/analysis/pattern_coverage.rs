@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::errors::*;
+use crate::intent_parser::DeterministicIntentParser;
+use crate::utils::IntentName;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternUsage {
+    pub intent: IntentName,
+    pub pattern_index: usize,
+    pub pattern: String,
+    pub hits: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternCoverageReport {
+    /// Patterns which matched at least one utterance, sorted by decreasing number of hits
+    pub fired_patterns: Vec<PatternUsage>,
+    /// Patterns which never matched, and thus could be pruned
+    pub unused_patterns: Vec<PatternUsage>,
+    /// Utterances which are not matched by any pattern and which are thus handled by the
+    /// probabilistic parser
+    pub fall_through_utterances: Vec<String>,
+}
+
+/// Runs the patterns of the deterministic parser over a corpus of utterances and reports
+/// which of them fire
+///
+/// Only the first matching pattern of each intent is counted, as it is the one used by the
+/// parser.
+pub fn analyze_pattern_coverage(
+    parser: &DeterministicIntentParser,
+    utterances: &[&str],
+) -> Result<PatternCoverageReport> {
+    let mut hits: HashMap<(IntentName, usize), usize> = HashMap::new();
+    let mut fall_through_utterances = vec![];
+    for utterance in utterances {
+        let matching_patterns = parser.matching_patterns(utterance)?;
+        if matching_patterns.is_empty() {
+            fall_through_utterances.push(utterance.to_string());
+        }
+        for matching_pattern in matching_patterns {
+            *hits.entry(matching_pattern).or_insert(0) += 1;
+        }
+    }
+
+    let (fired_patterns, unused_patterns): (Vec<PatternUsage>, Vec<PatternUsage>) = parser
+        .patterns()
+        .into_iter()
+        .sorted_by_key(|(intent, _)| *intent)
+        .flat_map(|(intent, patterns)| {
+            patterns
+                .into_iter()
+                .enumerate()
+                .map(move |(pattern_index, pattern)| PatternUsage {
+                    intent: intent.to_string(),
+                    pattern_index,
+                    pattern: pattern.to_string(),
+                    hits: 0,
+                })
+        })
+        .map(|mut usage| {
+            usage.hits = hits
+                .get(&(usage.intent.clone(), usage.pattern_index))
+                .cloned()
+                .unwrap_or(0);
+            usage
+        })
+        .partition(|usage| usage.hits > 0);
+
+    Ok(PatternCoverageReport {
+        fired_patterns: fired_patterns
+            .into_iter()
+            .sorted_by(|a, b| b.hits.cmp(&a.hits))
+            .collect(),
+        unused_patterns,
+        fall_through_utterances,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::resources::loading::load_engine_shared_resources;
+
+    #[test]
+    fn test_analyze_pattern_coverage() {
+        // Given
+        let trained_engine_path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let parser_path = trained_engine_path.join("deterministic_intent_parser");
+        let shared_resources = load_engine_shared_resources(trained_engine_path).unwrap();
+        let parser = DeterministicIntentParser::from_path(parser_path, shared_resources).unwrap();
+        let utterances = vec![
+            "brew three cups of coffee",
+            "brew two cups of coffee",
+            "what is the weather like",
+        ];
+
+        // When
+        let report = analyze_pattern_coverage(&parser, &utterances).unwrap();
+
+        // Then
+        let expected_fired_patterns = vec![PatternUsage {
+            intent: "MakeCoffee".to_string(),
+            pattern_index: 1,
+            pattern: r"^\s*brew\s*(?P<group1>%SNIPSNUMBER%)\s*cups\s*of\s*coffee\s*$".to_string(),
+            hits: 2,
+        }];
+        assert_eq!(expected_fired_patterns, report.fired_patterns);
+        assert_eq!(6, report.unused_patterns.len());
+        assert_eq!(
+            vec!["what is the weather like".to_string()],
+            report.fall_through_utterances
+        );
+    }
+}
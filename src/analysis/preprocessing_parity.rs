@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use crate::intent_classifier::TfidfVectorizer;
+
+/// Utterance along with the tokens produced by the preprocessing of the Python trainer
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreprocessingSample {
+    pub input: String,
+    pub expected_tokens: Vec<String>,
+}
+
+/// First token on which the preprocessing of this crate differs from the one of the trainer
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreprocessingDivergence {
+    /// Index of the sample in the corpus
+    pub sample_index: usize,
+    pub input: String,
+    /// Index of the first differing token
+    pub token_index: usize,
+    /// Token of the trainer, `None` when this crate produced more tokens
+    pub expected_token: Option<String>,
+    /// Token of this crate, `None` when this crate produced fewer tokens
+    pub actual_token: Option<String>,
+    pub expected_tokens: Vec<String>,
+    pub actual_tokens: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreprocessingParityReport {
+    /// Number of samples checked, which stops at the first divergence
+    pub nb_checked_samples: usize,
+    pub first_divergence: Option<PreprocessingDivergence>,
+}
+
+impl PreprocessingParityReport {
+    pub fn is_consistent(&self) -> bool {
+        self.first_divergence.is_none()
+    }
+}
+
+/// Checks that the tokenization, normalization and stemming of the vectorizer, which follow
+/// its recorded configuration, reproduce exactly the tokens of the Python trainer
+///
+/// Any difference means that the features seen at inference time differ from the ones seen
+/// during training, which silently degrades the accuracy of the intent classification.
+pub fn check_preprocessing_parity<'a, I>(
+    vectorizer: &TfidfVectorizer,
+    samples: I,
+) -> PreprocessingParityReport
+where
+    I: IntoIterator<Item = &'a PreprocessingSample>,
+{
+    let mut nb_checked_samples = 0;
+    for (sample_index, sample) in samples.into_iter().enumerate() {
+        nb_checked_samples += 1;
+        let actual_tokens = vectorizer.preprocess(&sample.input);
+        let nb_tokens = actual_tokens.len().max(sample.expected_tokens.len());
+        let divergent_token_index =
+            (0..nb_tokens).find(|idx| sample.expected_tokens.get(*idx) != actual_tokens.get(*idx));
+        if let Some(token_index) = divergent_token_index {
+            let first_divergence = PreprocessingDivergence {
+                sample_index,
+                input: sample.input.clone(),
+                token_index,
+                expected_token: sample.expected_tokens.get(token_index).cloned(),
+                actual_token: actual_tokens.get(token_index).cloned(),
+                expected_tokens: sample.expected_tokens.clone(),
+                actual_tokens,
+            };
+            return PreprocessingParityReport {
+                nb_checked_samples,
+                first_divergence: Some(first_divergence),
+            };
+        }
+    }
+    PreprocessingParityReport {
+        nb_checked_samples,
+        first_divergence: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::resources::loading::load_engine_shared_resources;
+
+    use super::*;
+
+    fn sample(input: &str, expected_tokens: &[&str]) -> PreprocessingSample {
+        PreprocessingSample {
+            input: input.to_string(),
+            expected_tokens: expected_tokens.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_check_preprocessing_parity() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let vectorizer_dir = engine_dir
+            .join("probabilistic_intent_parser")
+            .join("intent_classifier")
+            .join("featurizer")
+            .join("tfidf_vectorizer");
+        let shared_resources = load_engine_shared_resources(&engine_dir).unwrap();
+        let vectorizer = TfidfVectorizer::from_path(vectorizer_dir, shared_resources).unwrap();
+        let samples = vec![
+            sample("Make me Twö cups", &["make", "me", "two", "cups"]),
+            sample("make me 2 cups", &["make", "me", "two", "cups"]),
+            sample("make me a coffee", &["make", "me", "a", "coffee"]),
+        ];
+
+        // When
+        let report = check_preprocessing_parity(&vectorizer, &samples);
+
+        // Then
+        let expected_divergence = PreprocessingDivergence {
+            sample_index: 1,
+            input: "make me 2 cups".to_string(),
+            token_index: 2,
+            expected_token: Some("two".to_string()),
+            actual_token: Some("2".to_string()),
+            expected_tokens: samples[1].expected_tokens.clone(),
+            actual_tokens: vec![
+                "make".to_string(),
+                "me".to_string(),
+                "2".to_string(),
+                "cups".to_string(),
+            ],
+        };
+        assert_eq!(2, report.nb_checked_samples);
+        assert_eq!(Some(expected_divergence), report.first_divergence);
+        assert!(!report.is_consistent());
+    }
+}
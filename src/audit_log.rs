@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use failure::format_err;
+use serde::Serialize;
+use snips_nlu_ontology::IntentParserResult;
+
+use crate::errors::*;
+
+/// Hook applied to the parsing results before they are stored in an `AuditLog`, typically to
+/// remove personal data from the inputs and slots
+pub trait Redactor: Send + Sync {
+    fn redact(&self, result: &mut IntentParserResult);
+}
+
+impl<F> Redactor for F
+where
+    F: Fn(&mut IntentParserResult) + Send + Sync,
+{
+    fn redact(&self, result: &mut IntentParserResult) {
+        self(result)
+    }
+}
+
+/// Parse stored in an `AuditLog`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    /// Unix timestamp of the parse, in seconds
+    pub timestamp: i64,
+    /// Redacted parsing result, which contains the input
+    pub result: IntentParserResult,
+}
+
+/// In-memory ring buffer of the most recent parses of an engine, which can be attached with
+/// `SnipsNluEngine::set_audit_log`
+///
+/// Once the capacity is reached, the oldest parses are dropped. Nothing is written to disk, so
+/// the log can be dumped by support tooling when a user reports a problem.
+pub struct AuditLog {
+    capacity: usize,
+    redactor: Option<Box<dyn Redactor>>,
+    entries: Mutex<VecDeque<AuditLogEntry>>,
+}
+
+impl AuditLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            redactor: None,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Applies the redactor to all the parsing results before storing them
+    pub fn with_redactor<R: Redactor + 'static>(mut self, redactor: R) -> Self {
+        self.redactor = Some(Box::new(redactor));
+        self
+    }
+
+    pub fn record(&self, result: &IntentParserResult) -> Result<()> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        let mut result = result.clone();
+        if let Some(redactor) = self.redactor.as_ref() {
+            redactor.redact(&mut result);
+        }
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let mut entries = self.lock_entries()?;
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(AuditLogEntry { timestamp, result });
+        Ok(())
+    }
+
+    /// Returns the stored parses, from the oldest to the most recent one
+    pub fn entries(&self) -> Result<Vec<AuditLogEntry>> {
+        Ok(self.lock_entries()?.iter().cloned().collect())
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        self.lock_entries()?.clear();
+        Ok(())
+    }
+
+    fn lock_entries(&self) -> Result<MutexGuard<VecDeque<AuditLogEntry>>> {
+        self.entries
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use snips_nlu_ontology::IntentClassifierResult;
+
+    use super::*;
+
+    fn parsing_result(input: &str) -> IntentParserResult {
+        IntentParserResult {
+            input: input.to_string(),
+            intent: IntentClassifierResult {
+                intent_name: Some("MakeCoffee".to_string()),
+                confidence_score: 0.8,
+            },
+            slots: vec![],
+            alternatives: vec![],
+        }
+    }
+
+    #[test]
+    fn test_audit_log_keeps_most_recent_parses() {
+        // Given
+        let audit_log = AuditLog::new(2).with_redactor(|result: &mut IntentParserResult| {
+            result.input = result.input.to_uppercase()
+        });
+
+        // When
+        for input in &["first input", "second input", "third input"] {
+            audit_log.record(&parsing_result(input)).unwrap();
+        }
+        let inputs: Vec<String> = audit_log
+            .entries()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.result.input)
+            .collect();
+
+        // Then
+        assert_eq!(vec!["SECOND INPUT", "THIRD INPUT"], inputs);
+    }
+}
@@ -0,0 +1,100 @@
+use snips_nlu_ontology::{IntentParserResult, Slot};
+
+/// Detection of a last word cut in the middle, typically when the user barges in while a voice
+/// front-end is transcribing
+///
+/// The partial word is still used to classify the intent, but no slot is extracted from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartialTokenDetection {
+    /// The partial word is followed by this marker, inserted by the front-end at the end of
+    /// the input, e.g. `"<partial>"`
+    Marker(String),
+    /// The last word is partial when it ends with a hyphen, which is how most speech
+    /// recognizers transcribe truncated words, e.g. `"play some jaz-"`
+    TrailingHyphen,
+}
+
+/// Input whose last word was detected as partial
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PartialInput<'a> {
+    /// Input without the marker, which is the one to parse
+    pub text: &'a str,
+    /// Index of the first character of the partial word
+    pub partial_token_start: usize,
+}
+
+impl PartialTokenDetection {
+    pub(crate) fn detect<'a>(&self, input: &'a str) -> Option<PartialInput<'a>> {
+        let input = input.trim_end();
+        let text = match self {
+            PartialTokenDetection::Marker(marker) if !marker.is_empty() => {
+                if !input.ends_with(&**marker) {
+                    return None;
+                }
+                input[..input.len() - marker.len()].trim_end()
+            }
+            PartialTokenDetection::Marker(_) => return None,
+            PartialTokenDetection::TrailingHyphen => {
+                if !input.ends_with('-') {
+                    return None;
+                }
+                &input[..input.len() - 1]
+            }
+        };
+        let partial_token_byte_start = text
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map(|(idx, c)| idx + c.len_utf8())
+            .unwrap_or(0);
+        Some(PartialInput {
+            text,
+            partial_token_start: text[..partial_token_byte_start].chars().count(),
+        })
+    }
+}
+
+impl<'a> PartialInput<'a> {
+    /// Removes the slots which overlap the partial word, in the result and its alternatives
+    pub fn remove_partial_slots(&self, result: &mut IntentParserResult) {
+        self.retain_complete_slots(&mut result.slots);
+        for alternative in result.alternatives.iter_mut() {
+            self.retain_complete_slots(&mut alternative.slots);
+        }
+    }
+
+    /// Removes the slots which overlap the partial word
+    pub fn retain_complete_slots(&self, slots: &mut Vec<Slot>) {
+        slots.retain(|slot| slot.range.end <= self.partial_token_start);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_partial_token() {
+        // Given
+        let marker_detection = PartialTokenDetection::Marker("<partial>".to_string());
+        let hyphen_detection = PartialTokenDetection::TrailingHyphen;
+
+        // When
+        let marked_input = marker_detection.detect("play some jaz <partial> ");
+        let hyphen_input = hyphen_detection.detect("joue du rockà-");
+        let complete_input = marker_detection.detect("play some jazz");
+
+        // Then
+        let expected_marked_input = PartialInput {
+            text: "play some jaz",
+            partial_token_start: 10,
+        };
+        let expected_hyphen_input = PartialInput {
+            text: "joue du rockà",
+            partial_token_start: 8,
+        };
+        assert_eq!(Some(expected_marked_input), marked_input);
+        assert_eq!(Some(expected_hyphen_input), hyphen_input);
+        assert_eq!(None, complete_input);
+    }
+}
@@ -0,0 +1,143 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use failure::bail;
+
+use crate::errors::*;
+
+thread_local! {
+    static CURRENT_TOKEN: RefCell<Option<CancellationToken>> = RefCell::new(None);
+}
+
+/// Token used to cancel a parsing which is running in another thread
+///
+/// The token is checked between the stages of the parsing pipeline, and regularly within the
+/// longest stages, in which case the parsing fails with a `SnipsNluError::ParseCancelled`
+/// error. Clones of a token share the same cancellation state.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.cancelled, &other.cancelled)
+    }
+}
+
+/// Runs the function with the token registered as the cancellation token of the current
+/// thread, so that `check_cancellation` can be called anywhere in the parsing pipeline
+pub fn with_cancellation_token<T, F>(token: Option<&CancellationToken>, f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    let token = match token {
+        Some(token) => token.clone(),
+        None => return f(),
+    };
+    let _guard = TokenGuard {
+        previous_token: CURRENT_TOKEN.with(|current| current.replace(Some(token))),
+    };
+    f()
+}
+
+/// Restores the previous cancellation token of the current thread when dropped, including when
+/// the function run with the token panics
+struct TokenGuard {
+    previous_token: Option<CancellationToken>,
+}
+
+impl Drop for TokenGuard {
+    fn drop(&mut self) {
+        let previous_token = self.previous_token.take();
+        CURRENT_TOKEN.with(|current| current.replace(previous_token));
+    }
+}
+
+/// Returns the cancellation token of the current thread, if any, so that it can be registered
+/// in the threads to which the parsing is dispatched
+pub(crate) fn current_cancellation_token() -> Option<CancellationToken> {
+    CURRENT_TOKEN.with(|current| current.borrow().clone())
+}
+
+/// Fails if the cancellation token of the current thread, if any, has been cancelled
+pub fn check_cancellation() -> Result<()> {
+    let is_cancelled = CURRENT_TOKEN.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .map(|token| token.is_cancelled())
+            .unwrap_or(false)
+    });
+    if is_cancelled {
+        bail!(SnipsNluError::ParseCancelled);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_cancellation() {
+        // Given
+        let token = CancellationToken::new();
+        let cancelled_token = CancellationToken::new();
+        cancelled_token.cancel();
+
+        // When
+        let result = with_cancellation_token(Some(&token), check_cancellation);
+        let cancelled_result = with_cancellation_token(Some(&cancelled_token), check_cancellation);
+        let result_without_token = with_cancellation_token(None, check_cancellation);
+
+        // Then
+        assert!(result.is_ok());
+        assert!(cancelled_result.is_err());
+        assert!(result_without_token.is_ok());
+        assert!(check_cancellation().is_ok());
+    }
+
+    #[test]
+    fn test_token_is_restored_after_panic() {
+        // Given
+        let cancelled_token = CancellationToken::new();
+        cancelled_token.cancel();
+
+        // When
+        let panic_result = std::panic::catch_unwind(|| {
+            with_cancellation_token(Some(&cancelled_token), || -> Result<()> {
+                panic!("parsing failed")
+            })
+        });
+
+        // Then
+        assert!(panic_result.is_err());
+        assert_eq!(None, current_cancellation_token());
+        assert!(check_cancellation().is_ok());
+    }
+}
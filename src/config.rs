@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use snips_nlu_ontology::{BuiltinEntityKind, IntentParserResult, Slot};
+
+use crate::barge_in::PartialTokenDetection;
+use crate::cancellation::CancellationToken;
+use crate::intent_parser::IntentParserRegistry;
+use crate::resources::MissingResourcePolicy;
+use crate::text_range::RangeUnit;
+use crate::thread_pool::ThreadPoolConfig;
+use crate::time_grain::{TimeGrainConfig, TimePreference};
+use crate::unicode::{PatternNormalization, UnicodeNormalization};
+use crate::utils::{EntityName, IntentName, SlotName};
+
+/// Options used when loading a `SnipsNluEngine`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EngineConfig {
+    pub regex_limits: RegexLimits,
+    /// When true, the patterns of the deterministic intent parser are compiled per intent when
+    /// this intent is matched for the first time rather than when the engine is loaded
+    ///
+    /// This makes the loading of engines having many patterns faster, but the
+    /// `SnipsNluError::RegexLimitExceeded` errors are then returned when parsing.
+    pub lazy_regex_compilation: bool,
+    /// Folding applied to the patterns of the deterministic intent parser and to the inputs
+    /// it parses, e.g. to match inputs typed without accents
+    pub pattern_normalization: PatternNormalization,
+    /// Maximum edit distance, per intent, between the misspelled words of an input and the
+    /// words of the patterns of the deterministic intent parser
+    ///
+    /// Inputs which do not match any pattern of these intents are matched again once their
+    /// words are replaced by the closest words of the patterns. Fuzzy matching is disabled
+    /// for the intents which are not listed.
+    pub fuzzy_patterns: HashMap<IntentName, usize>,
+    /// When set, slots of long inputs are extracted chunk by chunk
+    pub chunking: Option<ChunkingConfig>,
+    /// Literal (find, replace) pairs applied to inputs before parsing, in addition to the ones
+    /// stored in the engine directory
+    pub normalization_replacements: Vec<(String, String)>,
+    /// When true, the builtin entity parser is taken from the process-wide
+    /// `BuiltinEntityParserPool` instead of being loaded for this engine only
+    pub share_builtin_entity_parser: bool,
+    /// When true, the builtin entities of scopes of several kinds are extracted kind by kind
+    /// in parallel, on the thread pool of the engine, which reduces the latency on long inputs
+    pub parallel_builtin_entities: bool,
+    /// Builtin entity kinds used by specific intents, replacing the kinds of their slots
+    ///
+    /// The intent parsers only extract the builtin entities of the kinds which at least one
+    /// intent uses, and the deterministic intent parser only extracts, for each intent, the
+    /// kinds of this intent. Declaring fewer kinds, e.g. leaving out the datetimes of an
+    /// intent whose patterns never match them, avoids running the costliest grammars.
+    pub builtin_entity_scopes: HashMap<IntentName, Vec<BuiltinEntityKind>>,
+    /// Number of recent inputs whose builtin entities are kept by the engine, so that parsing
+    /// again an identical input, as often happens in dialogs, does not extract them again
+    ///
+    /// Whatever this capacity, the builtin entities of an input are extracted once and shared
+    /// by the successive stages of its parsing.
+    pub entity_cache_capacity: usize,
+    /// When true, the gazetteers, stems, word clusters and stop words are taken from the
+    /// process-wide `LanguageResourcesPool` instead of being parsed for this engine only
+    pub share_language_resources: bool,
+    /// Grain applied to the time slots of all intents, unless overridden in
+    /// `intents_time_grains`
+    pub default_time_grain: Option<TimeGrainConfig>,
+    /// Grain applied to the time slots of specific intents
+    pub intents_time_grains: HashMap<IntentName, TimeGrainConfig>,
+    /// When true, the details of time slots include their value relatively to the parsing
+    /// time
+    pub relative_time_output: bool,
+    /// Approximate memory budget, in bytes, which the engine must fit in
+    ///
+    /// When set, degraded strategies are selected so that the estimated memory usage of the
+    /// engine fits in the budget, and loading fails with a
+    /// `SnipsNluError::MemoryBudgetExceeded` error when it is not possible. The applied
+    /// `MemorySaving` strategies are reported by `SnipsNluEngine::memory_savings`.
+    pub memory_budget: Option<usize>,
+    /// Threads used for background loading and for the operations which run in parallel
+    pub thread_pool: ThreadPoolConfig,
+    /// Unicode normalization form applied to the language resources and to the inputs, so
+    /// that composed and decomposed accented characters match
+    pub unicode_normalization: UnicodeNormalization,
+    /// When set, the intent parsers are combined in an `EnsembleParser` instead of being run
+    /// as a cascade
+    pub ensemble: Option<EnsembleConfig>,
+    /// When set, the slots which the parsers following the one matching the intent find for
+    /// this intent are added to its slots, overlapping slots being resolved with this rule
+    pub slots_merging: Option<SlotConflictResolution>,
+    /// Whether the engine fails to load or uses degraded features when some language
+    /// resources used by its models are missing
+    pub missing_resource_policy: MissingResourcePolicy,
+    /// When true, inputs are transliterated to the Latin script before being parsed, with the
+    /// table of the language resources or, when there is none, with the default Cyrillic and
+    /// Greek table
+    pub transliteration: bool,
+    /// Caps on the size of the inputs and of the parsing results
+    pub output_limits: OutputLimits,
+    /// Unit of the ranges of the slots returned by the engine, Unicode scalar values by
+    /// default
+    ///
+    /// Ranges are also aligned on grapheme boundaries, so that a slot never ends between a
+    /// character and its combining marks.
+    pub range_unit: RangeUnit,
+    /// Paths of gazetteer files written by hand, such as product names, registered under a
+    /// name which the features of the models can reference like the built-in gazetteers
+    ///
+    /// Their entries are normalized and stemmed when loaded, and a custom gazetteer replaces
+    /// the built-in one having the same name.
+    pub custom_gazetteers: HashMap<String, PathBuf>,
+    /// Paths of word clusters files, in the format of the word clusters of the language
+    /// resources, registered under a name which the word cluster features of the models can
+    /// reference
+    ///
+    /// This allows to use Brown clusters computed on other corpora or for languages whose
+    /// resources ship none. A custom word clusters file replaces the built-in one having the
+    /// same name.
+    pub custom_word_clusters: HashMap<String, PathBuf>,
+    /// Stable intent names used by the application, mapped to the intent names of the model
+    ///
+    /// This allows to retrain models with renamed intents without changing the application:
+    /// the aliases are accepted wherever the engine takes an intent name, such as in the
+    /// intents filters, and the parsing results use them instead of the names of the model.
+    /// The options keyed by intent, such as the `intercept_adjustments`, accept aliases as
+    /// well. An intent has at most one alias, which cannot be the name of another intent of
+    /// the model, otherwise the engine fails to load.
+    pub intent_aliases: HashMap<IntentName, IntentName>,
+    /// Identifiers of the values of custom entities, by entity and by resolved value
+    ///
+    /// The identifier of the resolved value of a slot is reported in its `SlotDetails`, so that
+    /// applications can join the slot with their own records, e.g. a database row.
+    pub entity_value_ids: HashMap<EntityName, HashMap<String, String>>,
+    /// Paths of weighted gazetteers of custom entities, by entity, whose weights rank the
+    /// resolutions of the slots of these entities
+    ///
+    /// When several values of an entity match the same text, the most popular one in the
+    /// parsed intent becomes the value of the slot and the others its alternatives. See
+    /// `EntityValueWeights` for the format of the files.
+    pub entity_value_weights: HashMap<EntityName, PathBuf>,
+    /// When true, the slots extracted by the probabilistic slot filler have a confidence
+    /// score, the lowest marginal probability of the tags of their tokens
+    pub slot_confidence_scores: bool,
+    /// Additive adjustments applied to the intercepts of the intent classifier, per intent of
+    /// the model, e.g. to damp an intent which is triggered too often without retraining
+    ///
+    /// The applied adjustments are reported by `SnipsNluEngine::intent_classifier_intercepts`.
+    pub intercept_adjustments: HashMap<IntentName, f32>,
+    /// Intent parsers provided by the application, such as an exact-match cache, which are
+    /// inserted in the cascade of the parsers of the model
+    pub custom_intent_parsers: IntentParserRegistry,
+}
+
+impl EngineConfig {
+    /// Returns the config whose options keyed by intent are keyed by the intents of the model,
+    /// their entries keyed by aliases replacing the ones of the aliased intents
+    pub(crate) fn with_model_intent_keys(&self) -> Self {
+        if self.intent_aliases.is_empty() {
+            return self.clone();
+        }
+        Self {
+            fuzzy_patterns: self.model_intent_keys(&self.fuzzy_patterns),
+            builtin_entity_scopes: self.model_intent_keys(&self.builtin_entity_scopes),
+            intercept_adjustments: self.model_intent_keys(&self.intercept_adjustments),
+            ..self.clone()
+        }
+    }
+
+    fn model_intent_keys<T: Clone>(
+        &self,
+        options: &HashMap<IntentName, T>,
+    ) -> HashMap<IntentName, T> {
+        let (aliased_options, model_options): (Vec<_>, Vec<_>) = options
+            .iter()
+            .partition(|(intent, _)| self.intent_aliases.contains_key(*intent));
+        model_options
+            .into_iter()
+            .chain(aliased_options)
+            .map(|(intent, value)| {
+                let model_intent = self.intent_aliases.get(intent).unwrap_or(intent);
+                (model_intent.clone(), value.clone())
+            })
+            .collect()
+    }
+}
+
+/// Safety caps applied when compiling the patterns of the deterministic intent parser
+///
+/// Models exceeding these limits fail to load with a `SnipsNluError::RegexLimitExceeded` or a
+/// `SnipsNluError::TooManyPatterns` error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegexLimits {
+    /// Approximate size limit, in bytes, of each compiled pattern
+    pub size_limit: usize,
+    /// Approximate size limit, in bytes, of the cache used by the lazy DFA of each pattern
+    pub dfa_size_limit: usize,
+    /// Maximum total number of patterns, across all intents
+    pub max_patterns: usize,
+}
+
+impl Default for RegexLimits {
+    fn default() -> Self {
+        Self {
+            size_limit: 10 * (1 << 20),
+            dfa_size_limit: 2 * (1 << 20),
+            max_patterns: 100_000,
+        }
+    }
+}
+
+/// Splitting of long inputs used to bound the cost of slot extraction
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkingConfig {
+    /// Inputs with more tokens than this are split in chunks of this size
+    pub max_tokens: usize,
+    /// Number of tokens shared by two consecutive chunks
+    pub overlap: usize,
+}
+
+/// Weights used to fuse the scores of the intent parsers of an engine
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnsembleConfig {
+    /// Weight of each parser, identified by its unit name, e.g. "deterministic_intent_parser"
+    pub parsers_weights: HashMap<String, f32>,
+    /// Weight of the parsers missing from `parsers_weights`
+    pub default_weight: f32,
+}
+
+impl Default for EnsembleConfig {
+    fn default() -> Self {
+        Self {
+            parsers_weights: HashMap::new(),
+            default_weight: 1.0,
+        }
+    }
+}
+
+/// Rule deciding which slot is kept when slots found by different intent parsers overlap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotConflictResolution {
+    /// The slot of the parser which matched the intent is kept
+    MatchingParser,
+    /// The slot covering the most characters is kept, the one of the parser which matched the
+    /// intent winning ties
+    LongestSlot,
+}
+
+/// Caps protecting the downstream systems from pathological inputs and parsing results
+///
+/// Inputs are truncated to their first `max_input_chars` characters, and results keep their
+/// first slots and alternatives, so that truncation is deterministic.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OutputLimits {
+    /// Maximum number of characters of the inputs which are parsed
+    pub max_input_chars: Option<usize>,
+    /// Maximum number of slots of each result
+    pub max_slots: Option<usize>,
+    /// Maximum number of alternative intent parsing results
+    pub max_intents_alternatives: Option<usize>,
+    /// Maximum number of alternative values of each slot
+    pub max_slots_alternatives: Option<usize>,
+}
+
+impl OutputLimits {
+    pub(crate) fn truncate_input<'a>(&self, input: &'a str) -> &'a str {
+        match self
+            .max_input_chars
+            .and_then(|max_chars| input.char_indices().nth(max_chars))
+        {
+            Some((byte_offset, _)) => &input[..byte_offset],
+            None => input,
+        }
+    }
+
+    /// Truncates the slots and alternatives of the result, and tells whether some were removed
+    pub(crate) fn truncate_result(&self, result: &mut IntentParserResult) -> bool {
+        let mut is_truncated = false;
+        if let Some(max_intents_alternatives) = self.max_intents_alternatives {
+            is_truncated |= result.alternatives.len() > max_intents_alternatives;
+            result.alternatives.truncate(max_intents_alternatives);
+        }
+        is_truncated |= self.truncate_slots(&mut result.slots);
+        for alternative in result.alternatives.iter_mut() {
+            is_truncated |= self.truncate_slots(&mut alternative.slots);
+        }
+        is_truncated
+    }
+
+    fn truncate_slots(&self, slots: &mut Vec<Slot>) -> bool {
+        let mut is_truncated = false;
+        if let Some(max_slots) = self.max_slots {
+            is_truncated |= slots.len() > max_slots;
+            slots.truncate(max_slots);
+        }
+        if let Some(max_slots_alternatives) = self.max_slots_alternatives {
+            for slot in slots.iter_mut() {
+                is_truncated |= slot.alternatives.len() > max_slots_alternatives;
+                slot.alternatives.truncate(max_slots_alternatives);
+            }
+        }
+        is_truncated
+    }
+}
+
+/// Options used when parsing an input with `SnipsNluEngine::parse_with_options`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParseOptions {
+    /// When set, only these intents can be returned
+    pub intents_whitelist: Option<Vec<IntentName>>,
+    /// When set, these intents are never returned
+    pub intents_blacklist: Option<Vec<IntentName>>,
+    /// Number of alternative intent parsing results to return
+    pub intents_alternatives: usize,
+    /// Number of alternative slot values to return for each slot
+    pub slots_alternatives: usize,
+    /// Number of alternative sets of slots of the detected intent to return in the detailed
+    /// result, coming from the next most probable tag sequences
+    pub nbest_slots: usize,
+    /// Resolution of ambiguous dates, overriding the per-intent preferences stored in the
+    /// model
+    pub time_preference: Option<TimePreference>,
+    /// Token which can be used to stop the parsing from another thread
+    pub cancellation_token: Option<CancellationToken>,
+    /// When set, slots having one of these names are never returned
+    pub slots_blacklist: Option<Vec<SlotName>>,
+    /// When set, slots of these entities, such as `snips/datetime`, are never returned
+    pub entities_blacklist: Option<Vec<EntityName>>,
+    /// When set, no slot is extracted from a last word which was cut in the middle, typically
+    /// by a barge-in
+    pub partial_token_detection: Option<PartialTokenDetection>,
+}
+
+impl ParseOptions {
+    pub fn new<'a, 'b, W, B>(
+        intents_whitelist: W,
+        intents_blacklist: B,
+        intents_alternatives: usize,
+        slots_alternatives: usize,
+    ) -> Self
+    where
+        W: Into<Option<Vec<&'a str>>>,
+        B: Into<Option<Vec<&'b str>>>,
+    {
+        Self {
+            intents_whitelist: intents_whitelist.into().map(to_intent_names),
+            intents_blacklist: intents_blacklist.into().map(to_intent_names),
+            intents_alternatives,
+            slots_alternatives,
+            nbest_slots: 0,
+            time_preference: None,
+            cancellation_token: None,
+            slots_blacklist: None,
+            entities_blacklist: None,
+            partial_token_detection: None,
+        }
+    }
+
+    /// Tells whether the slot must be removed from the results
+    pub(crate) fn excludes_slot(&self, slot: &Slot) -> bool {
+        let is_blacklisted = |blacklist: &Option<Vec<String>>, value: &String| {
+            blacklist
+                .as_ref()
+                .map(|blacklist| blacklist.contains(value))
+                .unwrap_or(false)
+        };
+        is_blacklisted(&self.slots_blacklist, &slot.slot_name)
+            || is_blacklisted(&self.entities_blacklist, &slot.entity)
+    }
+}
+
+fn to_intent_names(intents: Vec<&str>) -> Vec<IntentName> {
+    intents
+        .into_iter()
+        .map(|intent| intent.to_string())
+        .collect()
+}
@@ -0,0 +1,585 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use failure::ResultExt;
+use itertools::Itertools;
+use snips_nlu_ontology::{BuiltinEntityKind, Grain};
+
+use crate::config::{ChunkingConfig, EngineConfig, EnsembleConfig, SlotConflictResolution};
+use crate::errors::*;
+use crate::resources::MissingResourcePolicy;
+use crate::text_range::RangeUnit;
+use crate::thread_pool::ThreadPoolConfig;
+use crate::time_grain::{TimeGrainConfig, TimeGrainMode};
+use crate::unicode::UnicodeNormalization;
+
+/// Prefix of the environment variables read by `EngineConfig::from_env`
+///
+/// The key of an option is the upper-cased path of the option, nested options being
+/// separated by a double underscore, e.g. `SNIPS_NLU_ENGINE_REGEX_LIMITS__MAX_PATTERNS`.
+pub const ENGINE_CONFIG_ENV_PREFIX: &str = "SNIPS_NLU_ENGINE_";
+
+/// Options keyed by intent, entity, gazetteer or parser names, which cannot be set through the
+/// environment as the names of the environment variables are lowercased
+const NAME_KEYED_OPTIONS: &[&[&str]] = &[
+    &["fuzzy_patterns"],
+    &["builtin_entity_scopes"],
+    &["intents_time_grains"],
+    &["ensemble", "parsers_weights"],
+    &["custom_gazetteers"],
+    &["custom_word_clusters"],
+    &["entity_value_weights"],
+    &["intercept_adjustments"],
+    &["intent_aliases"],
+    &["entity_value_ids"],
+];
+
+impl EngineConfig {
+    /// Builds a config from the default one and the `SNIPS_NLU_ENGINE_*` environment
+    /// variables
+    ///
+    /// Only the scalar options can be set through the environment, lists being comma
+    /// separated. The options keyed by intent, entity or gazetteer names, whose case matters,
+    /// can only be set in a TOML file.
+    pub fn from_env() -> Result<Self> {
+        Self::default().with_env()
+    }
+
+    /// Builds a config from the default one and the options of a TOML file
+    ///
+    /// The keys of the file are the names of the fields of the config, nested options being
+    /// tables, e.g. `max_patterns` in a `[regex_limits]` table. Unknown keys and invalid
+    /// values are reported as `SnipsNluError::InvalidConfig` errors.
+    pub fn from_toml<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|_| format!("Cannot read engine config file {:?}", path))?;
+        let value: toml::Value = toml::from_str(&content)
+            .with_context(|_| format!("Cannot parse engine config file {:?}", path))?;
+        let mut settings = vec![];
+        flatten_toml(vec![], value, &mut settings)?;
+        Self::default().with_settings(settings)
+    }
+
+    /// Overrides the options of the config with the `SNIPS_NLU_ENGINE_*` environment
+    /// variables
+    ///
+    /// The environment thus takes precedence over a TOML file with
+    /// `EngineConfig::from_toml(path)?.with_env()`, which takes precedence over the defaults.
+    pub fn with_env(self) -> Result<Self> {
+        self.with_env_vars(env::vars())
+    }
+
+    fn with_env_vars<I>(self, vars: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let settings = vars
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(ENGINE_CONFIG_ENV_PREFIX))
+            .map(|(name, value)| {
+                let path: Vec<String> = name[ENGINE_CONFIG_ENV_PREFIX.len()..]
+                    .split("__")
+                    .map(|segment| segment.to_lowercase())
+                    .collect();
+                let is_name_keyed = NAME_KEYED_OPTIONS.iter().any(|option| {
+                    path.len() > option.len()
+                        && path
+                            .iter()
+                            .zip(option.iter())
+                            .all(|(segment, option_segment)| segment == option_segment)
+                });
+                if is_name_keyed {
+                    return Err(invalid_config(
+                        &path.join("."),
+                        "options keyed by names can only be set in a TOML file",
+                    ));
+                }
+                Ok((path, value))
+            })
+            .collect::<Result<_>>()?;
+        self.with_settings(settings)
+    }
+
+    /// Applies the settings in the order of their keys, so that the options which enable a
+    /// nested config, e.g. `chunking.max_tokens`, are applied before the dependent ones
+    fn with_settings(mut self, settings: Vec<(Vec<String>, String)>) -> Result<Self> {
+        for (path, value) in settings
+            .into_iter()
+            .sorted_by(|(path_a, _), (path_b, _)| path_a.cmp(path_b))
+        {
+            self.apply_setting(&path, &value)?;
+        }
+        if self
+            .normalization_replacements
+            .iter()
+            .any(|(find, _)| find.is_empty())
+        {
+            return Err(invalid_config(
+                "normalization_replacements",
+                "each replacement must have a non empty 'find' string",
+            ));
+        }
+        Ok(self)
+    }
+
+    fn apply_setting(&mut self, path: &[String], value: &str) -> Result<()> {
+        let key = path.join(".");
+        let segments: Vec<&str> = path.iter().map(|segment| &**segment).collect();
+        match segments.as_slice() {
+            ["regex_limits", "size_limit"] => self.regex_limits.size_limit = parse(&key, value)?,
+            ["regex_limits", "dfa_size_limit"] => {
+                self.regex_limits.dfa_size_limit = parse(&key, value)?
+            }
+            ["regex_limits", "max_patterns"] => {
+                self.regex_limits.max_patterns = parse(&key, value)?
+            }
+            ["lazy_regex_compilation"] => self.lazy_regex_compilation = parse(&key, value)?,
+            ["pattern_normalization", "lowercase"] => {
+                self.pattern_normalization.lowercase = parse(&key, value)?
+            }
+            ["pattern_normalization", "strip_accents"] => {
+                self.pattern_normalization.strip_accents = parse(&key, value)?
+            }
+            ["fuzzy_patterns", intent] => {
+                self.fuzzy_patterns
+                    .insert(intent.to_string(), parse(&key, value)?);
+            }
+            ["chunking", "max_tokens"] => {
+                let max_tokens = parse(&key, value)?;
+                let overlap = self
+                    .chunking
+                    .as_ref()
+                    .map_or(0, |chunking| chunking.overlap);
+                self.chunking = Some(ChunkingConfig {
+                    max_tokens,
+                    overlap,
+                });
+            }
+            ["chunking", "overlap"] => match self.chunking.as_mut() {
+                Some(chunking) => chunking.overlap = parse(&key, value)?,
+                None => return Err(invalid_config(&key, "chunking.max_tokens must be set")),
+            },
+            ["normalization_replacements", index, field] => {
+                let index: usize = parse(&key, index)?;
+                if self.normalization_replacements.len() <= index {
+                    self.normalization_replacements
+                        .resize(index + 1, (String::new(), String::new()));
+                }
+                let replacement = &mut self.normalization_replacements[index];
+                match *field {
+                    "find" => replacement.0 = value.to_string(),
+                    "replace" => replacement.1 = value.to_string(),
+                    _ => return Err(unknown_key(&key)),
+                }
+            }
+            ["share_builtin_entity_parser"] => {
+                self.share_builtin_entity_parser = parse(&key, value)?
+            }
+            ["parallel_builtin_entities"] => self.parallel_builtin_entities = parse(&key, value)?,
+            ["entity_cache_capacity"] => self.entity_cache_capacity = parse(&key, value)?,
+            ["builtin_entity_scopes", intent] => {
+                let entity_kinds = value
+                    .split(',')
+                    .filter(|item| !item.trim().is_empty())
+                    .map(|item| {
+                        BuiltinEntityKind::from_identifier(item.trim())
+                            .map_err(|_| invalid_value(&key, item))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                self.builtin_entity_scopes
+                    .insert(intent.to_string(), entity_kinds);
+            }
+            ["share_language_resources"] => self.share_language_resources = parse(&key, value)?,
+            ["default_time_grain", field] => {
+                let time_grain =
+                    apply_time_grain_setting(self.default_time_grain.as_ref(), &key, field, value)?;
+                self.default_time_grain = Some(time_grain);
+            }
+            ["intents_time_grains", intent, field] => {
+                let time_grain = apply_time_grain_setting(
+                    self.intents_time_grains.get(*intent),
+                    &key,
+                    field,
+                    value,
+                )?;
+                self.intents_time_grains
+                    .insert(intent.to_string(), time_grain);
+            }
+            ["relative_time_output"] => self.relative_time_output = parse(&key, value)?,
+            ["memory_budget"] => self.memory_budget = Some(parse(&key, value)?),
+            ["thread_pool", "nb_threads"] => {
+                let nb_threads = parse(&key, value)?;
+                let pinned_cores = match &self.thread_pool {
+                    ThreadPoolConfig::Dedicated { pinned_cores, .. } => pinned_cores.clone(),
+                    _ => vec![],
+                };
+                self.thread_pool = ThreadPoolConfig::Dedicated {
+                    nb_threads,
+                    pinned_cores,
+                };
+            }
+            ["thread_pool", "pinned_cores"] => match &mut self.thread_pool {
+                ThreadPoolConfig::Dedicated { pinned_cores, .. } => {
+                    *pinned_cores = parse_list(&key, value)?
+                }
+                _ => return Err(invalid_config(&key, "thread_pool.nb_threads must be set")),
+            },
+            ["unicode_normalization"] => {
+                self.unicode_normalization = match &*value.to_lowercase() {
+                    "none" => UnicodeNormalization::None,
+                    "nfc" => UnicodeNormalization::Nfc,
+                    "nfd" => UnicodeNormalization::Nfd,
+                    "nfkc" => UnicodeNormalization::Nfkc,
+                    "nfkd" => UnicodeNormalization::Nfkd,
+                    _ => return Err(invalid_value(&key, value)),
+                }
+            }
+            ["ensemble", "default_weight"] => {
+                self.ensemble
+                    .get_or_insert_with(EnsembleConfig::default)
+                    .default_weight = parse(&key, value)?
+            }
+            ["ensemble", "parsers_weights", parser] => {
+                let weight = parse(&key, value)?;
+                self.ensemble
+                    .get_or_insert_with(EnsembleConfig::default)
+                    .parsers_weights
+                    .insert(parser.to_string(), weight);
+            }
+            ["slots_merging"] => {
+                self.slots_merging = match &*value.to_lowercase() {
+                    "none" => None,
+                    "matching_parser" => Some(SlotConflictResolution::MatchingParser),
+                    "longest_slot" => Some(SlotConflictResolution::LongestSlot),
+                    _ => return Err(invalid_value(&key, value)),
+                }
+            }
+            ["missing_resource_policy"] => {
+                self.missing_resource_policy = match &*value.to_lowercase() {
+                    "fail" => MissingResourcePolicy::Fail,
+                    "degrade" => MissingResourcePolicy::Degrade,
+                    _ => return Err(invalid_value(&key, value)),
+                }
+            }
+            ["transliteration"] => self.transliteration = parse(&key, value)?,
+            ["range_unit"] => {
+                self.range_unit = match &*value.to_lowercase() {
+                    "char" => RangeUnit::Char,
+                    "byte" => RangeUnit::Byte,
+                    "utf16" => RangeUnit::Utf16,
+                    _ => return Err(invalid_value(&key, value)),
+                }
+            }
+            ["output_limits", "max_input_chars"] => {
+                self.output_limits.max_input_chars = Some(parse(&key, value)?)
+            }
+            ["output_limits", "max_slots"] => {
+                self.output_limits.max_slots = Some(parse(&key, value)?)
+            }
+            ["output_limits", "max_intents_alternatives"] => {
+                self.output_limits.max_intents_alternatives = Some(parse(&key, value)?)
+            }
+            ["output_limits", "max_slots_alternatives"] => {
+                self.output_limits.max_slots_alternatives = Some(parse(&key, value)?)
+            }
+            ["custom_gazetteers", name] => {
+                self.custom_gazetteers
+                    .insert(name.to_string(), value.into());
+            }
+            ["custom_word_clusters", name] => {
+                self.custom_word_clusters
+                    .insert(name.to_string(), value.into());
+            }
+            ["entity_value_weights", entity] => {
+                self.entity_value_weights
+                    .insert(entity.to_string(), value.into());
+            }
+            ["intercept_adjustments", intent] => {
+                self.intercept_adjustments
+                    .insert(intent.to_string(), parse(&key, value)?);
+            }
+            ["intent_aliases", alias] => {
+                self.intent_aliases
+                    .insert(alias.to_string(), value.to_string());
+            }
+            ["entity_value_ids", entity, entity_value] => {
+                self.entity_value_ids
+                    .entry(entity.to_string())
+                    .or_insert_with(Default::default)
+                    .insert(entity_value.to_string(), value.to_string());
+            }
+            ["slot_confidence_scores"] => self.slot_confidence_scores = parse(&key, value)?,
+            _ => return Err(unknown_key(&key)),
+        }
+        Ok(())
+    }
+}
+
+fn apply_time_grain_setting(
+    time_grain: Option<&TimeGrainConfig>,
+    key: &str,
+    field: &str,
+    value: &str,
+) -> Result<TimeGrainConfig> {
+    match field {
+        "grain" => {
+            let grain = match &*value.to_lowercase() {
+                "year" => Grain::Year,
+                "quarter" => Grain::Quarter,
+                "month" => Grain::Month,
+                "week" => Grain::Week,
+                "day" => Grain::Day,
+                "hour" => Grain::Hour,
+                "minute" => Grain::Minute,
+                "second" => Grain::Second,
+                _ => return Err(invalid_value(key, value)),
+            };
+            let mode = time_grain.map_or(TimeGrainMode::Round, |time_grain| time_grain.mode);
+            Ok(TimeGrainConfig { grain, mode })
+        }
+        "mode" => {
+            let mode = match &*value.to_lowercase() {
+                "round" => TimeGrainMode::Round,
+                "expand" => TimeGrainMode::Expand,
+                _ => return Err(invalid_value(key, value)),
+            };
+            let grain = time_grain
+                .map(|time_grain| time_grain.grain)
+                .ok_or_else(|| invalid_config(key, "the grain must be set"))?;
+            Ok(TimeGrainConfig { grain, mode })
+        }
+        _ => Err(unknown_key(key)),
+    }
+}
+
+/// Flattens the TOML tables into (path, value) settings, arrays of scalars being joined with
+/// commas as in environment variables, and arrays of tables being indexed
+fn flatten_toml(
+    path: Vec<String>,
+    value: toml::Value,
+    settings: &mut Vec<(Vec<String>, String)>,
+) -> Result<()> {
+    let scalar_value = match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let mut nested_path = path.clone();
+                nested_path.push(key);
+                flatten_toml(nested_path, value, settings)?;
+            }
+            return Ok(());
+        }
+        toml::Value::Array(values) => {
+            if values.iter().all(|value| value.is_table()) {
+                for (index, value) in values.into_iter().enumerate() {
+                    let mut nested_path = path.clone();
+                    nested_path.push(index.to_string());
+                    flatten_toml(nested_path, value, settings)?;
+                }
+                return Ok(());
+            }
+            values
+                .into_iter()
+                .map(|value| scalar_to_string(&path, value))
+                .collect::<Result<Vec<String>>>()?
+                .join(",")
+        }
+        value => scalar_to_string(&path, value)?,
+    };
+    settings.push((path, scalar_value));
+    Ok(())
+}
+
+fn scalar_to_string(path: &[String], value: toml::Value) -> Result<String> {
+    match value {
+        toml::Value::String(string) => Ok(string),
+        toml::Value::Integer(integer) => Ok(integer.to_string()),
+        toml::Value::Float(float) => Ok(float.to_string()),
+        toml::Value::Boolean(boolean) => Ok(boolean.to_string()),
+        value => Err(invalid_config(
+            &path.join("."),
+            &format!("unsupported value {}", value),
+        )),
+    }
+}
+
+fn parse<T: FromStr>(key: &str, value: &str) -> Result<T> {
+    value.trim().parse().map_err(|_| invalid_value(key, value))
+}
+
+fn parse_list<T: FromStr>(key: &str, value: &str) -> Result<Vec<T>> {
+    value
+        .split(',')
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| parse(key, item))
+        .collect()
+}
+
+fn unknown_key(key: &str) -> failure::Error {
+    invalid_config(key, "unknown key")
+}
+
+fn invalid_value(key: &str, value: &str) -> failure::Error {
+    invalid_config(key, &format!("invalid value '{}'", value))
+}
+
+fn invalid_config(key: &str, message: &str) -> failure::Error {
+    SnipsNluError::InvalidConfig {
+        key: key.to_string(),
+        message: message.to_string(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use maplit::hashmap;
+
+    use crate::config::RegexLimits;
+
+    use super::*;
+
+    #[test]
+    fn test_engine_config_from_toml_and_env() {
+        // Given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_path = temp_dir.path().join("engine_config.toml");
+        fs::write(
+            &config_path,
+            r#"
+memory_budget = 50000000
+slots_merging = "longest_slot"
+
+[regex_limits]
+max_patterns = 500
+
+[chunking]
+max_tokens = 40
+overlap = 10
+
+[thread_pool]
+nb_threads = 2
+pinned_cores = [0, 1]
+
+[intent_aliases]
+TurnOnLights = "turnLightOn"
+
+[builtin_entity_scopes]
+setTemperature = ["snips/temperature", "snips/number"]
+
+[[normalization_replacements]]
+find = "u"
+replace = "you"
+"#,
+        )
+        .unwrap();
+        let env_vars = vec![
+            ("SNIPS_NLU_ENGINE_MEMORY_BUDGET", "20000000"),
+            ("SNIPS_NLU_ENGINE_OUTPUT_LIMITS__MAX_SLOTS", "5"),
+            ("SNIPS_NLU_ENGINE_SLOT_CONFIDENCE_SCORES", "true"),
+            ("SNIPS_NLU_ENGINE_ENTITY_CACHE_CAPACITY", "16"),
+            ("SNIPS_NLU_BENCH_SENTENCE", "ignored"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        // When
+        let config = EngineConfig::from_toml(&config_path)
+            .unwrap()
+            .with_env_vars(env_vars)
+            .unwrap();
+
+        // Then
+        let expected_regex_limits = RegexLimits {
+            max_patterns: 500,
+            ..RegexLimits::default()
+        };
+        let expected_aliases: HashMap<String, String> = hashmap! {
+            "TurnOnLights".to_string() => "turnLightOn".to_string(),
+        };
+        assert_eq!(Some(20_000_000), config.memory_budget);
+        assert_eq!(
+            Some(SlotConflictResolution::LongestSlot),
+            config.slots_merging
+        );
+        assert_eq!(expected_regex_limits, config.regex_limits);
+        assert_eq!(
+            Some(ChunkingConfig {
+                max_tokens: 40,
+                overlap: 10
+            }),
+            config.chunking
+        );
+        match config.thread_pool {
+            ThreadPoolConfig::Dedicated {
+                nb_threads,
+                ref pinned_cores,
+            } => {
+                assert_eq!(2, nb_threads);
+                assert_eq!(&vec![0, 1], pinned_cores);
+            }
+            _ => panic!("expected a dedicated thread pool"),
+        }
+        assert_eq!(expected_aliases, config.intent_aliases);
+        assert_eq!(
+            Some(&vec![
+                BuiltinEntityKind::Temperature,
+                BuiltinEntityKind::Number
+            ]),
+            config.builtin_entity_scopes.get("setTemperature")
+        );
+        assert_eq!(
+            vec![("u".to_string(), "you".to_string())],
+            config.normalization_replacements
+        );
+        assert_eq!(Some(5), config.output_limits.max_slots);
+        assert!(config.slot_confidence_scores);
+        assert_eq!(16, config.entity_cache_capacity);
+    }
+
+    #[test]
+    fn test_engine_config_rejects_unknown_keys_and_invalid_values() {
+        // Given
+        let unknown_key = vec![("SNIPS_NLU_ENGINE_MEMORY_BUGDET", "100")];
+        let invalid_value = vec![("SNIPS_NLU_ENGINE_TRANSLITERATION", "maybe")];
+        let name_keyed = vec![(
+            "SNIPS_NLU_ENGINE_INTENT_ALIASES__TURNONLIGHTS",
+            "turnLightOn",
+        )];
+        let to_vars = |vars: Vec<(&str, &str)>| {
+            vars.into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        // When
+        let unknown_key_error = EngineConfig::default()
+            .with_env_vars(to_vars(unknown_key))
+            .unwrap_err();
+        let invalid_value_error = EngineConfig::default()
+            .with_env_vars(to_vars(invalid_value))
+            .unwrap_err();
+        let name_keyed_error = EngineConfig::default()
+            .with_env_vars(to_vars(name_keyed))
+            .unwrap_err();
+
+        // Then
+        assert_eq!(
+            "Invalid engine config key 'memory_bugdet': unknown key",
+            unknown_key_error.to_string()
+        );
+        assert_eq!(
+            "Invalid engine config key 'transliteration': invalid value 'maybe'",
+            invalid_value_error.to_string()
+        );
+        assert_eq!(
+            "Invalid engine config key 'intent_aliases.turnonlights': options keyed by names can \
+             only be set in a TOML file",
+            name_keyed_error.to_string()
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use serde::Serialize;
+use snips_nlu_ontology::{Grain, IntentParserResult, Slot};
+
+use crate::slot_validation::RejectedSlot;
+use crate::text_range::TextRanges;
+use crate::time_grain::RelativeTime;
+use crate::utils::SlotName;
+use crate::value_range::ValueRange;
+
+/// Parsing result enriched with information which is not part of the ontology
+/// `IntentParserResult`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetailedIntentParserResult {
+    #[serde(flatten)]
+    pub result: IntentParserResult,
+    /// Additional information about each slot of `result`, in the same order
+    pub slots_details: Vec<SlotDetails>,
+    /// Required slots of the detected intent which were not found in the input
+    pub missing_slots: Vec<SlotName>,
+    /// Slots which were extracted but rejected by the registered slot validators
+    pub rejected_slots: Vec<RejectedSlot>,
+    /// Other sets of slots of the detected intent, sorted by decreasing probability, when
+    /// requested in the `ParseOptions`
+    pub alternative_slots: Vec<AlternativeSlots>,
+    /// Whether the input or the result was truncated to fit in the `OutputLimits` of the
+    /// engine
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotDetails {
+    pub provenance: SlotProvenance,
+    /// Resolution grain of time slots
+    pub grain: Option<Grain>,
+    /// Value of time slots relatively to the parsing time, only provided when enabled in the
+    /// `EngineConfig`
+    pub relative_time: Option<RelativeTime>,
+    /// Interval described around numeric slots, as in "between 3 and 5" or "more than 20
+    /// euros"
+    pub value_range: Option<ValueRange>,
+    /// Identifier of the resolved value of custom entity slots, when registered in the
+    /// `EngineConfig`
+    pub entity_value_id: Option<String>,
+    /// Range of the slot in the input, in all the units supported by `RangeUnit`, whatever
+    /// the unit of the range of the slot
+    pub text_ranges: TextRanges,
+}
+
+/// Set of slots extracted from one of the most probable tag sequences of the input
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlternativeSlots {
+    pub slots: Vec<Slot>,
+    /// Probability of the tag sequence from which the slots were extracted
+    pub probability: f32,
+}
+
+/// Indicates where the value of a slot comes from
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SlotProvenance {
+    /// The value was known at training time, or was resolved by a builtin entity parser
+    Trained,
+    /// The value was added at runtime through entity injection
+    Injected,
+}
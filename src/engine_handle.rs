@@ -0,0 +1,207 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use failure::{bail, format_err};
+use snips_nlu_ontology::IntentParserResult;
+
+use crate::config::{EngineConfig, ParseOptions};
+use crate::detailed_result::DetailedIntentParserResult;
+use crate::errors::*;
+use crate::nlu_engine::SnipsNluEngine;
+
+/// Behavior of the parsing methods of an `EngineHandle` whose engine is still loading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingParsePolicy {
+    /// Parsing blocks until the engine is loaded
+    Queue,
+    /// Parsing fails with a `SnipsNluError::EngineNotReady` error
+    Reject,
+}
+
+enum LoadingState {
+    Loading,
+    Ready(Arc<SnipsNluEngine>),
+    Failed(String),
+}
+
+/// Handle on a `SnipsNluEngine` which is loaded in a background thread
+///
+/// Handles are cheap to clone, and all the clones refer to the same engine.
+#[derive(Clone)]
+pub struct EngineHandle {
+    state: Arc<(Mutex<LoadingState>, Condvar)>,
+    pending_parse_policy: PendingParsePolicy,
+}
+
+impl EngineHandle {
+    pub(crate) fn load<P: AsRef<Path>>(path: P, config: EngineConfig) -> Self {
+        let state = Arc::new((Mutex::new(LoadingState::Loading), Condvar::new()));
+        let thread_state = state.clone();
+        let path: PathBuf = path.as_ref().to_path_buf();
+        let thread_pool = match config.thread_pool.build() {
+            Ok(thread_pool) => thread_pool,
+            Err(error) => {
+                let loading_state = LoadingState::Failed(format!(
+                    "Cannot build thread pool to load engine {:?}: {}",
+                    path, error
+                ));
+                return Self {
+                    state: Arc::new((Mutex::new(loading_state), Condvar::new())),
+                    pending_parse_policy: PendingParsePolicy::Queue,
+                };
+            }
+        };
+        let load_engine = move || {
+            // Panics are caught so that threads waiting for the engine are always notified
+            let loading_result = panic::catch_unwind(AssertUnwindSafe(|| {
+                SnipsNluEngine::from_path_with_config(&path, &config)
+            }));
+            let loading_state = match loading_result {
+                Ok(Ok(engine)) => LoadingState::Ready(Arc::new(engine)),
+                Ok(Err(error)) => {
+                    LoadingState::Failed(format!("Cannot load engine from {:?}: {}", path, error))
+                }
+                Err(_) => LoadingState::Failed(format!("Loading of engine {:?} panicked", path)),
+            };
+            let (state, readiness) = &*thread_state;
+            if let Ok(mut state) = state.lock() {
+                *state = loading_state;
+            }
+            readiness.notify_all();
+        };
+        if let Some(thread_pool) = thread_pool {
+            thread_pool.spawn(load_engine);
+        } else {
+            thread::spawn(load_engine);
+        }
+        Self {
+            state,
+            pending_parse_policy: PendingParsePolicy::Queue,
+        }
+    }
+
+    /// Sets the behavior of the parsing methods while the engine is loading, parses being
+    /// queued by default
+    pub fn with_pending_parse_policy(mut self, pending_parse_policy: PendingParsePolicy) -> Self {
+        self.pending_parse_policy = pending_parse_policy;
+        self
+    }
+
+    /// Returns true once the engine is loaded, and false while it is loading or if its loading
+    /// failed
+    pub fn is_ready(&self) -> bool {
+        match self.state.0.lock() {
+            Ok(state) => match *state {
+                LoadingState::Ready(_) => true,
+                _ => false,
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// Blocks until the engine is loaded, and returns it
+    pub fn wait_ready(&self) -> Result<Arc<SnipsNluEngine>> {
+        let (state, readiness) = &*self.state;
+        let mut state = state
+            .lock()
+            .map_err(|_| format_err!("Poisoned engine loading state"))?;
+        while let LoadingState::Loading = *state {
+            state = readiness
+                .wait(state)
+                .map_err(|_| format_err!("Poisoned engine loading state"))?;
+        }
+        match &*state {
+            LoadingState::Ready(engine) => Ok(engine.clone()),
+            LoadingState::Failed(message) => bail!(message.clone()),
+            LoadingState::Loading => unreachable!(),
+        }
+    }
+
+    /// Returns the engine if it is loaded, and `None` if it is still loading
+    pub fn engine(&self) -> Result<Option<Arc<SnipsNluEngine>>> {
+        let state = self
+            .state
+            .0
+            .lock()
+            .map_err(|_| format_err!("Poisoned engine loading state"))?;
+        match &*state {
+            LoadingState::Loading => Ok(None),
+            LoadingState::Ready(engine) => Ok(Some(engine.clone())),
+            LoadingState::Failed(message) => bail!(message.clone()),
+        }
+    }
+
+    pub fn parse<'a, 'b, W, B>(
+        &self,
+        input: &str,
+        intents_whitelist: W,
+        intents_blacklist: B,
+    ) -> Result<IntentParserResult>
+    where
+        W: Into<Option<Vec<&'a str>>>,
+        B: Into<Option<Vec<&'b str>>>,
+    {
+        self.ready_engine()?
+            .parse(input, intents_whitelist, intents_blacklist)
+    }
+
+    pub fn parse_with_options(
+        &self,
+        input: &str,
+        options: &ParseOptions,
+    ) -> Result<DetailedIntentParserResult> {
+        self.ready_engine()?.parse_with_options(input, options)
+    }
+
+    fn ready_engine(&self) -> Result<Arc<SnipsNluEngine>> {
+        match self.pending_parse_policy {
+            PendingParsePolicy::Queue => self.wait_ready(),
+            PendingParsePolicy::Reject => self
+                .engine()?
+                .ok_or_else(|| SnipsNluError::EngineNotReady.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_in_background() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+
+        // When
+        let handle = SnipsNluEngine::load_in_background(path);
+        let result = handle
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+
+        // Then
+        assert!(handle.is_ready());
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+    }
+
+    #[test]
+    fn test_load_in_background_should_report_loading_errors() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nonexistent_engine");
+
+        // When
+        let handle = SnipsNluEngine::load_in_background(path);
+        let result = handle.wait_ready();
+
+        // Then
+        assert!(result.is_err());
+        assert!(!handle.is_ready());
+    }
+}
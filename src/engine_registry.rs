@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use failure::format_err;
+use log::info;
+use lru_cache::LruCache;
+use snips_nlu_ontology::IntentParserResult;
+
+use crate::config::{EngineConfig, ParseOptions};
+use crate::detailed_result::DetailedIntentParserResult;
+use crate::errors::*;
+use crate::models::fingerprint;
+use crate::nlu_engine::SnipsNluEngine;
+use crate::resources::loading::LanguageLayers;
+
+/// Registry of the engines of several assistants, or tenants, served by the same process
+///
+/// Engines are loaded on their first use, and only the `max_loaded_engines` most recently used
+/// ones are kept in memory, the idle ones being reloaded when they are used again. The builtin
+/// entity parsers and the language resources are shared across all the engines of the
+/// registry which use identical ones.
+pub struct EngineRegistry {
+    config: EngineConfig,
+    language_layers: LanguageLayers,
+    engine_dirs: Mutex<HashMap<String, (PathBuf, [u8; 32])>>,
+    loaded_engines: Mutex<LruCache<String, Arc<SnipsNluEngine>>>,
+}
+
+impl EngineRegistry {
+    pub fn new(max_loaded_engines: usize) -> Self {
+        Self::with_config(max_loaded_engines, EngineConfig::default())
+    }
+
+    /// Creates a registry whose engines are all loaded with the provided config, in which the
+    /// builtin entity parsers are always shared
+    pub fn with_config(max_loaded_engines: usize, config: EngineConfig) -> Self {
+        Self {
+            config: EngineConfig {
+                share_builtin_entity_parser: true,
+                ..config
+            },
+            language_layers: LanguageLayers::default(),
+            engine_dirs: Mutex::new(HashMap::new()),
+            loaded_engines: Mutex::new(LruCache::new(max_loaded_engines)),
+        }
+    }
+
+    /// Registers the engine of a tenant, replacing its previous engine if any
+    ///
+    /// The engine is only loaded when the tenant is used for the first time. Registering
+    /// again an engine whose files did not change keeps it loaded.
+    pub fn register<P: AsRef<Path>>(&self, tenant_id: &str, engine_dir: P) -> Result<()> {
+        let engine_fingerprint = fingerprint(&engine_dir)?;
+        let previous_engine = self.lock_engine_dirs()?.insert(
+            tenant_id.to_string(),
+            (engine_dir.as_ref().to_path_buf(), engine_fingerprint),
+        );
+        let is_unchanged = previous_engine
+            .map(|(_, previous_fingerprint)| previous_fingerprint == engine_fingerprint)
+            .unwrap_or(false);
+        if !is_unchanged {
+            self.lock_loaded_engines()?.remove(tenant_id);
+        }
+        Ok(())
+    }
+
+    /// Removes a tenant from the registry, along with its engine
+    pub fn unregister(&self, tenant_id: &str) -> Result<()> {
+        if self.lock_engine_dirs()?.remove(tenant_id).is_none() {
+            return Err(SnipsNluError::UnknownTenant(tenant_id.to_string()).into());
+        }
+        self.lock_loaded_engines()?.remove(tenant_id);
+        Ok(())
+    }
+
+    /// Returns the engine of the tenant, loading it if needed
+    pub fn engine(&self, tenant_id: &str) -> Result<Arc<SnipsNluEngine>> {
+        if let Some(engine) = self.lock_loaded_engines()?.get_mut(tenant_id) {
+            return Ok(engine.clone());
+        }
+        let engine_dir = self
+            .lock_engine_dirs()?
+            .get(tenant_id)
+            .map(|(engine_dir, _)| engine_dir.clone())
+            .ok_or_else(|| SnipsNluError::UnknownTenant(tenant_id.to_string()))?;
+        // The engine is loaded without holding the locks, so that the other tenants can be
+        // used meanwhile
+        info!(
+            "Loading engine of tenant '{}' ({:?}) ...",
+            tenant_id, engine_dir
+        );
+        let engine = Arc::new(SnipsNluEngine::from_path_sharing_language_layers(
+            &engine_dir,
+            &self.config,
+            self.language_layers.clone(),
+        )?);
+        info!("Engine of tenant '{}' loaded", tenant_id);
+        self.lock_loaded_engines()?
+            .insert(tenant_id.to_string(), engine.clone());
+        Ok(engine)
+    }
+
+    pub fn parse(&self, tenant_id: &str, input: &str) -> Result<IntentParserResult> {
+        self.engine(tenant_id)?.parse(input, None, None)
+    }
+
+    pub fn parse_with_options(
+        &self,
+        tenant_id: &str,
+        input: &str,
+        options: &ParseOptions,
+    ) -> Result<DetailedIntentParserResult> {
+        self.engine(tenant_id)?.parse_with_options(input, options)
+    }
+
+    /// Identifiers of all the registered tenants, sorted alphabetically
+    pub fn tenants(&self) -> Result<Vec<String>> {
+        let mut tenants: Vec<String> = self.lock_engine_dirs()?.keys().cloned().collect();
+        tenants.sort();
+        Ok(tenants)
+    }
+
+    /// Identifiers of the tenants whose engine is currently loaded, from the least recently
+    /// used to the most recently used
+    pub fn loaded_tenants(&self) -> Result<Vec<String>> {
+        Ok(self
+            .lock_loaded_engines()?
+            .iter()
+            .map(|(tenant_id, _)| tenant_id.clone())
+            .collect())
+    }
+
+    /// Number of distinct sets of language resources loaded by the engines of the registry
+    pub fn nb_language_layers(&self) -> Result<usize> {
+        self.language_layers.len()
+    }
+
+    fn lock_engine_dirs(&self) -> Result<MutexGuard<HashMap<String, (PathBuf, [u8; 32])>>> {
+        self.engine_dirs
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))
+    }
+
+    fn lock_loaded_engines(&self) -> Result<MutexGuard<LruCache<String, Arc<SnipsNluEngine>>>> {
+        self.loaded_engines
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_registry() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let registry = EngineRegistry::new(1);
+        registry.register("tenant_1", &engine_dir).unwrap();
+        registry.register("tenant_2", &engine_dir).unwrap();
+
+        // When
+        let result_1 = registry
+            .parse("tenant_1", "Make me two cups of coffee please")
+            .unwrap();
+        let result_2 = registry
+            .parse("tenant_2", "Make me two cups of coffee please")
+            .unwrap();
+        let unknown_tenant_result = registry.parse("tenant_3", "Make me a coffee");
+
+        // Then
+        assert_eq!(Some("MakeCoffee".to_string()), result_1.intent.intent_name);
+        assert_eq!(result_1, result_2);
+        assert!(unknown_tenant_result.is_err());
+        assert_eq!(vec!["tenant_1", "tenant_2"], registry.tenants().unwrap());
+        assert_eq!(vec!["tenant_2"], registry.loaded_tenants().unwrap());
+        assert_eq!(1, registry.nb_language_layers().unwrap());
+        registry.register("tenant_2", &engine_dir).unwrap();
+        assert_eq!(vec!["tenant_2"], registry.loaded_tenants().unwrap());
+    }
+}
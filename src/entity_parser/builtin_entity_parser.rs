@@ -6,6 +6,7 @@ use snips_nlu_ontology::{BuiltinEntity, BuiltinEntityKind};
 use snips_nlu_parsers::BuiltinEntityParser as _BuiltinEntityParser;
 
 use super::utils::Cache;
+use crate::cancellation::check_cancellation;
 use crate::errors::*;
 
 pub trait BuiltinEntityParser: Send + Sync {
@@ -38,6 +39,7 @@ impl BuiltinEntityParser for CachingBuiltinEntityParser {
         use_cache: bool,
         max_alternative_resolved_values: usize,
     ) -> Result<Vec<BuiltinEntity>> {
+        check_cancellation()?;
         let lowercased_sentence = sentence.to_lowercase();
         if !use_cache {
             return self.parser.extract_entities(
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use failure::{format_err, ResultExt};
+use lazy_static::lazy_static;
+use log::info;
+use serde::Deserialize;
+
+use super::CachingBuiltinEntityParser;
+use crate::errors::*;
+
+const CACHE_CAPACITY: usize = 1000;
+
+lazy_static! {
+    static ref POOL: Mutex<HashMap<BuiltinEntityParserKey, Arc<CachingBuiltinEntityParser>>> =
+        Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Deserialize)]
+struct BuiltinEntityParserMetadata {
+    language: String,
+    gazetteer_parser: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GazetteerParserMetadata {
+    parsers_metadata: Vec<GazetteerEntityMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GazetteerEntityMetadata {
+    entity_identifier: String,
+}
+
+/// Identifies the builtin entity parsers which can be shared between engines
+///
+/// Parsers which only rely on grammars are shared across all engines of the same language.
+/// Parsers which also support gazetteer entities can only be shared when they are loaded from
+/// the same directory, as the gazetteer values depend on the training dataset.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BuiltinEntityParserKey {
+    pub language: String,
+    pub gazetteer_scope: Option<GazetteerScope>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GazetteerScope {
+    pub parser_dir: PathBuf,
+    pub entities: Vec<String>,
+}
+
+impl BuiltinEntityParserKey {
+    pub fn from_path<P: AsRef<Path>>(parser_dir: P) -> Result<Self> {
+        let metadata_path = parser_dir.as_ref().join("metadata.json");
+        let metadata_file = File::open(&metadata_path).with_context(|_| {
+            format!(
+                "Cannot open builtin entity parser metadata file '{:?}'",
+                metadata_path
+            )
+        })?;
+        let metadata: BuiltinEntityParserMetadata = serde_json::from_reader(metadata_file)
+            .with_context(|_| "Cannot deserialize builtin entity parser metadata")?;
+        let gazetteer_scope = if let Some(gazetteer_parser) = metadata.gazetteer_parser {
+            let gazetteer_parser_dir = parser_dir.as_ref().join(gazetteer_parser).canonicalize()?;
+            let gazetteer_metadata_path = gazetteer_parser_dir.join("metadata.json");
+            let gazetteer_metadata_file =
+                File::open(&gazetteer_metadata_path).with_context(|_| {
+                    format!(
+                        "Cannot open gazetteer parser metadata file '{:?}'",
+                        gazetteer_metadata_path
+                    )
+                })?;
+            let gazetteer_metadata: GazetteerParserMetadata =
+                serde_json::from_reader(gazetteer_metadata_file)
+                    .with_context(|_| "Cannot deserialize gazetteer parser metadata")?;
+            let mut entities: Vec<String> = gazetteer_metadata
+                .parsers_metadata
+                .into_iter()
+                .map(|parser_metadata| parser_metadata.entity_identifier)
+                .collect();
+            entities.sort();
+            Some(GazetteerScope {
+                parser_dir: gazetteer_parser_dir,
+                entities,
+            })
+        } else {
+            None
+        };
+        Ok(Self {
+            language: metadata.language,
+            gazetteer_scope,
+        })
+    }
+}
+
+/// Process-wide pool of builtin entity parsers
+///
+/// Loading a builtin entity parser is slow and memory-heavy, the pool allows to load it once
+/// per `BuiltinEntityParserKey` and to share it between engines.
+pub struct BuiltinEntityParserPool;
+
+impl BuiltinEntityParserPool {
+    /// Returns the pooled parser corresponding to the provided parser directory, loading it if
+    /// needed
+    pub fn get_or_load<P: AsRef<Path>>(parser_dir: P) -> Result<Arc<CachingBuiltinEntityParser>> {
+        let key = BuiltinEntityParserKey::from_path(&parser_dir)?;
+        let mut pool = POOL
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))?;
+        if let Some(parser) = pool.get(&key) {
+            return Ok(parser.clone());
+        }
+        info!("Adding builtin entity parser {:?} to the pool", key);
+        let parser = Arc::new(CachingBuiltinEntityParser::from_path(
+            parser_dir,
+            CACHE_CAPACITY,
+        )?);
+        pool.insert(key, parser.clone());
+        Ok(parser)
+    }
+
+    /// Loads the parser ahead of time, so that the engines using it load faster
+    pub fn preload<P: AsRef<Path>>(parser_dir: P) -> Result<()> {
+        Self::get_or_load(parser_dir).map(|_| ())
+    }
+
+    /// Removes all the parsers from the pool
+    ///
+    /// The memory of a parser is only released once all the engines using it are dropped.
+    pub fn shutdown() -> Result<()> {
+        POOL.lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))?
+            .clear();
+        info!("Builtin entity parser pool shut down");
+        Ok(())
+    }
+
+    /// Number of parsers currently in the pool
+    pub fn len() -> Result<usize> {
+        Ok(POOL
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))?
+            .len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_entity_parser_pool() {
+        // Given
+        let models_dir = Path::new("data").join("tests").join("models");
+        let beverage_parser_dir = models_dir
+            .join("nlu_engine_beverage")
+            .join("builtin_entity_parser");
+        let game_parser_dir = models_dir
+            .join("nlu_engine_game")
+            .join("builtin_entity_parser");
+        let music_parser_dir = models_dir
+            .join("nlu_engine_music")
+            .join("builtin_entity_parser");
+
+        // When
+        BuiltinEntityParserPool::preload(&beverage_parser_dir).unwrap();
+        let beverage_parser = BuiltinEntityParserPool::get_or_load(&beverage_parser_dir).unwrap();
+        let game_parser = BuiltinEntityParserPool::get_or_load(&game_parser_dir).unwrap();
+        let music_parser = BuiltinEntityParserPool::get_or_load(&music_parser_dir).unwrap();
+        let nb_pooled_parsers = BuiltinEntityParserPool::len().unwrap();
+        BuiltinEntityParserPool::shutdown().unwrap();
+
+        // Then
+        assert!(Arc::ptr_eq(&beverage_parser, &game_parser));
+        assert!(!Arc::ptr_eq(&beverage_parser, &music_parser));
+        assert_eq!(2, nb_pooled_parsers);
+        assert_eq!(0, BuiltinEntityParserPool::len().unwrap());
+    }
+}
@@ -14,6 +14,7 @@ use snips_nlu_parsers::{GazetteerEntityMatch, GazetteerParser};
 use snips_nlu_utils::language::Language as NluUtilsLanguage;
 use snips_nlu_utils::token::*;
 
+use crate::cancellation::check_cancellation;
 use crate::entity_parser::utils::Cache;
 use crate::errors::*;
 use crate::language::FromLanguage;
@@ -72,6 +73,7 @@ impl CustomEntityParser for CachingCustomEntityParser {
         filter_entity_kinds: Option<&[String]>,
         max_alternative_resolved_values: usize,
     ) -> Result<Vec<CustomEntity>> {
+        check_cancellation()?;
         let lowercased_sentence = sentence.to_lowercase();
         let cache_key = CacheKey {
             input: lowercased_sentence,
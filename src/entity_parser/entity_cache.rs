@@ -0,0 +1,288 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use failure::format_err;
+use lru_cache::LruCache;
+use snips_nlu_ontology::{BuiltinEntity, BuiltinEntityKind};
+
+use crate::entity_parser::builtin_entity_parser::BuiltinEntityParser;
+use crate::errors::*;
+
+thread_local! {
+    static CURRENT_ENTITY_CACHE: RefCell<Option<Arc<EntityCache>>> = RefCell::new(None);
+}
+
+static NEXT_ENTITY_CACHES_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Builtin entities extracted during the parsing of an input, shared by the stages of the
+/// parsing so that the deterministic parser, the intent classifier and the slot filler do not
+/// extract the same entities again
+pub(crate) struct EntityCache {
+    /// Identifier of the `EntityCaches` which created the cache, hence of its engine
+    owner_id: usize,
+    entities: Mutex<HashMap<EntityCacheKey, Vec<BuiltinEntity>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct EntityCacheKey {
+    input: String,
+    kinds: Option<Vec<BuiltinEntityKind>>,
+    max_alternative_resolved_values: usize,
+}
+
+impl EntityCache {
+    fn new(owner_id: usize) -> Self {
+        Self {
+            owner_id,
+            entities: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get_or_extract<F>(&self, key: EntityCacheKey, extract: F) -> Result<Vec<BuiltinEntity>>
+    where
+        F: FnOnce() -> Result<Vec<BuiltinEntity>>,
+    {
+        if let Some(entities) = self.lock()?.get(&key) {
+            return Ok(entities.clone());
+        }
+        // The lock is not held while extracting, so that concurrent parsings of the same input
+        // do not wait for each other
+        let entities = extract()?;
+        self.lock()?.insert(key, entities.clone());
+        Ok(entities)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<HashMap<EntityCacheKey, Vec<BuiltinEntity>>>> {
+        self.entities
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))
+    }
+}
+
+/// Runs the function with the cache registered as the entity cache of the current thread,
+/// unless a cache of the same engine is already registered, in which case this one is kept
+///
+/// A cache registered by another engine, which happens when an engine is used while another
+/// one is parsing, is never reused, since the entities of the two engines may differ.
+pub(crate) fn with_entity_cache<T, F>(cache: Arc<EntityCache>, f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    let has_engine_cache = CURRENT_ENTITY_CACHE.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .map(|current| current.owner_id == cache.owner_id)
+            .unwrap_or(false)
+    });
+    if has_engine_cache {
+        return f();
+    }
+    let _guard = CacheGuard {
+        previous_cache: CURRENT_ENTITY_CACHE.with(|current| current.replace(Some(cache))),
+    };
+    f()
+}
+
+/// Restores the previous entity cache of the current thread when dropped, including when the
+/// function run with the cache panics
+struct CacheGuard {
+    previous_cache: Option<Arc<EntityCache>>,
+}
+
+impl Drop for CacheGuard {
+    fn drop(&mut self) {
+        let previous_cache = self.previous_cache.take();
+        CURRENT_ENTITY_CACHE.with(|current| current.replace(previous_cache));
+    }
+}
+
+fn current_entity_cache() -> Option<Arc<EntityCache>> {
+    CURRENT_ENTITY_CACHE.with(|current| current.borrow().clone())
+}
+
+/// Entity caches of the most recently parsed inputs, so that an input parsed again, which
+/// often happens in dialogs, reuses the entities extracted the first time
+pub(crate) struct EntityCaches {
+    id: usize,
+    caches: Option<Mutex<LruCache<String, Arc<EntityCache>>>>,
+}
+
+impl EntityCaches {
+    /// Keeps the caches of the `capacity` most recent inputs, or none when it is 0
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            id: NEXT_ENTITY_CACHES_ID.fetch_add(1, Ordering::SeqCst),
+            caches: if capacity > 0 {
+                Some(Mutex::new(LruCache::new(capacity)))
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Returns the entity cache of the input, which is new when the input was not parsed
+    /// recently
+    pub fn get(&self, input: &str) -> Result<Arc<EntityCache>> {
+        let caches = match self.caches.as_ref() {
+            Some(caches) => caches,
+            None => return Ok(Arc::new(EntityCache::new(self.id))),
+        };
+        let mut caches = caches
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))?;
+        if let Some(cache) = caches.get_mut(input) {
+            return Ok(cache.clone());
+        }
+        let cache = Arc::new(EntityCache::new(self.id));
+        caches.insert(input.to_string(), cache.clone());
+        Ok(cache)
+    }
+}
+
+/// Builtin entity parser which reuses the entities already extracted from the same input
+/// during the current parsing, as recorded in the entity cache of the current thread
+///
+/// Extractions outside of a parsing of the engine, or without cache, are delegated to the
+/// wrapped parser.
+pub(crate) struct EntityCachingBuiltinEntityParser {
+    parser: Arc<dyn BuiltinEntityParser>,
+}
+
+impl EntityCachingBuiltinEntityParser {
+    pub fn new(parser: Arc<dyn BuiltinEntityParser>) -> Self {
+        Self { parser }
+    }
+}
+
+impl BuiltinEntityParser for EntityCachingBuiltinEntityParser {
+    fn extract_entities(
+        &self,
+        sentence: &str,
+        filter_entity_kinds: Option<&[BuiltinEntityKind]>,
+        use_cache: bool,
+        max_alternative_resolved_values: usize,
+    ) -> Result<Vec<BuiltinEntity>> {
+        let extract = || {
+            self.parser.extract_entities(
+                sentence,
+                filter_entity_kinds,
+                use_cache,
+                max_alternative_resolved_values,
+            )
+        };
+        match current_entity_cache() {
+            Some(cache) if use_cache => {
+                let key = EntityCacheKey {
+                    input: sentence.to_string(),
+                    kinds: filter_entity_kinds.map(|entity_kinds| entity_kinds.to_vec()),
+                    max_alternative_resolved_values,
+                };
+                cache.get_or_extract(key, extract)
+            }
+            _ => extract(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use super::*;
+
+    struct CountingBuiltinEntityParser {
+        nb_extractions: AtomicUsize,
+    }
+
+    impl BuiltinEntityParser for CountingBuiltinEntityParser {
+        fn extract_entities(
+            &self,
+            _sentence: &str,
+            _filter_entity_kinds: Option<&[BuiltinEntityKind]>,
+            _use_cache: bool,
+            _max_alternative_resolved_values: usize,
+        ) -> Result<Vec<BuiltinEntity>> {
+            self.nb_extractions.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_extract_entities_with_entity_cache() {
+        // Given
+        let parser = Arc::new(CountingBuiltinEntityParser {
+            nb_extractions: AtomicUsize::new(0),
+        });
+        let caching_parser = EntityCachingBuiltinEntityParser::new(parser.clone());
+        let caches = EntityCaches::new(1);
+        let entity_kinds = [BuiltinEntityKind::Number, BuiltinEntityKind::Duration];
+        let parse = |input: &str| {
+            with_entity_cache(caches.get(input)?, || {
+                caching_parser.extract_entities(input, Some(&entity_kinds), true, 0)?;
+                caching_parser.extract_entities(input, Some(&entity_kinds), true, 0)?;
+                caching_parser.extract_entities(input, None, true, 0)?;
+                caching_parser.extract_entities(input, None, false, 0)
+            })
+        };
+
+        // When
+        parse("in two minutes").unwrap();
+        let nb_first_extractions = parser.nb_extractions.load(Ordering::SeqCst);
+        parse("in two minutes").unwrap();
+        let nb_repeated_extractions = parser.nb_extractions.load(Ordering::SeqCst);
+        parse("in three minutes").unwrap();
+        caching_parser
+            .extract_entities("in three minutes", None, true, 0)
+            .unwrap();
+        let nb_extractions = parser.nb_extractions.load(Ordering::SeqCst);
+
+        // Then
+        assert_eq!(3, nb_first_extractions);
+        assert_eq!(4, nb_repeated_extractions);
+        assert_eq!(8, nb_extractions);
+    }
+
+    #[test]
+    fn test_extract_entities_with_entity_caches_of_distinct_engines() {
+        // Given
+        let parser = Arc::new(CountingBuiltinEntityParser {
+            nb_extractions: AtomicUsize::new(0),
+        });
+        let caching_parser = EntityCachingBuiltinEntityParser::new(parser.clone());
+        let first_caches = EntityCaches::new(1);
+        let second_caches = EntityCaches::new(1);
+        let input = "in two minutes";
+
+        // When
+        with_entity_cache(first_caches.get(input).unwrap(), || {
+            caching_parser.extract_entities(input, None, true, 0)?;
+            with_entity_cache(second_caches.get(input)?, || {
+                caching_parser.extract_entities(input, None, true, 0)
+            })?;
+            caching_parser.extract_entities(input, None, true, 0)
+        })
+        .unwrap();
+
+        // Then
+        assert_eq!(2, parser.nb_extractions.load(Ordering::SeqCst));
+        assert!(current_entity_cache().is_none());
+    }
+
+    #[test]
+    fn test_entity_cache_is_reset_after_panic() {
+        // Given
+        let caches = EntityCaches::new(1);
+        let cache = caches.get("in two minutes").unwrap();
+
+        // When
+        let result =
+            panic::catch_unwind(|| with_entity_cache::<(), _>(cache, || panic!("parsing failed")));
+
+        // Then
+        assert!(result.is_err());
+        assert!(current_entity_cache().is_none());
+    }
+}
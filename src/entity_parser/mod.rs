@@ -1,6 +1,22 @@
 pub mod builtin_entity_parser;
+pub mod builtin_entity_parser_pool;
 pub mod custom_entity_parser;
+mod entity_cache;
+pub mod parallel_builtin_entity_parser;
+pub mod scoped_builtin_entity_parser;
+pub mod streaming_entity_spotter;
+pub mod synonym_entity;
 mod utils;
 
 pub use self::builtin_entity_parser::*;
+pub use self::builtin_entity_parser_pool::*;
 pub use self::custom_entity_parser::*;
+pub(crate) use self::entity_cache::{
+    with_entity_cache, EntityCaches, EntityCachingBuiltinEntityParser,
+};
+pub use self::parallel_builtin_entity_parser::ParallelBuiltinEntityParser;
+pub use self::scoped_builtin_entity_parser::ScopedBuiltinEntityParser;
+pub use self::streaming_entity_spotter::{
+    EntityMention, StreamingEntitySpotter, StreamingSpotterConfig,
+};
+pub use self::synonym_entity::{SynonymEntity, SynonymEntityBuilder};
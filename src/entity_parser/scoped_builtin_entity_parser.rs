@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use snips_nlu_ontology::{BuiltinEntity, BuiltinEntityKind};
+
+use crate::entity_parser::builtin_entity_parser::BuiltinEntityParser;
+use crate::errors::*;
+
+/// Builtin entity parser which only extracts the entities of a fixed set of kinds
+///
+/// The kinds requested to this parser are intersected with its scope before being passed to
+/// the wrapped parser, and extractions whose kinds are all out of the scope return no entity
+/// without running the wrapped parser. Extractions without filter are restricted to the scope.
+pub struct ScopedBuiltinEntityParser {
+    parser: Arc<dyn BuiltinEntityParser>,
+    scope: Vec<BuiltinEntityKind>,
+}
+
+impl ScopedBuiltinEntityParser {
+    pub fn new(parser: Arc<dyn BuiltinEntityParser>, scope: Vec<BuiltinEntityKind>) -> Self {
+        Self { parser, scope }
+    }
+}
+
+impl BuiltinEntityParser for ScopedBuiltinEntityParser {
+    fn extract_entities(
+        &self,
+        sentence: &str,
+        filter_entity_kinds: Option<&[BuiltinEntityKind]>,
+        use_cache: bool,
+        max_alternative_resolved_values: usize,
+    ) -> Result<Vec<BuiltinEntity>> {
+        let entity_kinds: Vec<BuiltinEntityKind> = match filter_entity_kinds {
+            Some(entity_kinds) => entity_kinds
+                .iter()
+                .filter(|kind| self.scope.contains(kind))
+                .cloned()
+                .collect(),
+            None => self.scope.clone(),
+        };
+        if entity_kinds.is_empty() {
+            return Ok(vec![]);
+        }
+        self.parser.extract_entities(
+            sentence,
+            Some(&entity_kinds),
+            use_cache,
+            max_alternative_resolved_values,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use snips_nlu_ontology::{NumberValue, SlotValue};
+
+    use super::*;
+
+    struct RecordingBuiltinEntityParser {
+        requested_kinds: Mutex<Vec<Option<Vec<BuiltinEntityKind>>>>,
+    }
+
+    impl BuiltinEntityParser for RecordingBuiltinEntityParser {
+        fn extract_entities(
+            &self,
+            _sentence: &str,
+            filter_entity_kinds: Option<&[BuiltinEntityKind]>,
+            _use_cache: bool,
+            _max_alternative_resolved_values: usize,
+        ) -> Result<Vec<BuiltinEntity>> {
+            self.requested_kinds
+                .lock()
+                .unwrap()
+                .push(filter_entity_kinds.map(|kinds| kinds.to_vec()));
+            Ok(vec![BuiltinEntity {
+                value: "two".to_string(),
+                range: 0..3,
+                entity: SlotValue::Number(NumberValue { value: 2.0 }),
+                alternatives: vec![],
+                entity_kind: BuiltinEntityKind::Number,
+            }])
+        }
+    }
+
+    #[test]
+    fn test_extract_entities_within_scope() {
+        // Given
+        let parser = Arc::new(RecordingBuiltinEntityParser {
+            requested_kinds: Mutex::new(vec![]),
+        });
+        let scoped_parser = ScopedBuiltinEntityParser::new(
+            parser.clone(),
+            vec![BuiltinEntityKind::Number, BuiltinEntityKind::Duration],
+        );
+
+        // When
+        let out_of_scope_entities = scoped_parser
+            .extract_entities("two", Some(&[BuiltinEntityKind::Datetime]), true, 0)
+            .unwrap();
+        let filtered_entities = scoped_parser
+            .extract_entities(
+                "two",
+                Some(&[BuiltinEntityKind::Datetime, BuiltinEntityKind::Number]),
+                true,
+                0,
+            )
+            .unwrap();
+        let unfiltered_entities = scoped_parser
+            .extract_entities("two", None, true, 0)
+            .unwrap();
+
+        // Then
+        assert!(out_of_scope_entities.is_empty());
+        assert_eq!(1, filtered_entities.len());
+        assert_eq!(1, unfiltered_entities.len());
+        let expected_requested_kinds = vec![
+            Some(vec![BuiltinEntityKind::Number]),
+            Some(vec![BuiltinEntityKind::Number, BuiltinEntityKind::Duration]),
+        ];
+        assert_eq!(
+            expected_requested_kinds,
+            *parser.requested_kinds.lock().unwrap()
+        );
+    }
+}
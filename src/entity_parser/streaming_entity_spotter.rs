@@ -0,0 +1,245 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::Arc;
+
+use failure::bail;
+use snips_nlu_utils::string::substring_with_char_range;
+
+use crate::entity_parser::custom_entity_parser::CustomEntityParser;
+use crate::errors::*;
+use crate::utils::EntityName;
+
+/// Options of a `StreamingEntitySpotter`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamingSpotterConfig {
+    /// Maximum number of tokens kept in memory, which must be at least twice
+    /// `max_mention_tokens`
+    pub window_size: usize,
+    /// Maximum number of tokens of a mention, mentions being emitted once enough tokens
+    /// follow them to know that no longer overlapping mention can replace them
+    pub max_mention_tokens: usize,
+    /// Entities to spot, all the entities of the parser being spotted when `None`
+    pub entities: Option<Vec<EntityName>>,
+}
+
+impl Default for StreamingSpotterConfig {
+    fn default() -> Self {
+        Self {
+            window_size: 64,
+            max_mention_tokens: 8,
+            entities: None,
+        }
+    }
+}
+
+/// Entity mention found in a stream of tokens
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityMention {
+    pub value: String,
+    pub resolved_value: String,
+    pub entity: EntityName,
+    /// Char range of the mention in the whole stream, whose tokens are separated by a single
+    /// space
+    pub range: Range<usize>,
+    /// Indices of the tokens of the mention in the whole stream
+    pub token_range: Range<usize>,
+}
+
+/// Spotter of the mentions of custom entities in an unbounded stream of tokens, such as a
+/// live transcript
+///
+/// The entities are extracted with a `CustomEntityParser`, e.g. the one of an engine or a
+/// `SynonymEntity`, from a sliding window over the last tokens of the stream, so that memory
+/// stays bounded. Mentions are emitted, in the order of the stream, as soon as they are
+/// complete.
+pub struct StreamingEntitySpotter {
+    parser: Arc<dyn CustomEntityParser>,
+    config: StreamingSpotterConfig,
+    window: VecDeque<String>,
+    /// Index, in the stream, of the first token of the window
+    window_start: usize,
+    /// Char offset, in the stream, of the first token of the window
+    window_offset: usize,
+    /// Index of the token following the last emitted mention
+    emitted_until: usize,
+}
+
+impl StreamingEntitySpotter {
+    pub fn new(
+        parser: Arc<dyn CustomEntityParser>,
+        config: StreamingSpotterConfig,
+    ) -> Result<Self> {
+        if config.max_mention_tokens == 0 || config.window_size < 2 * config.max_mention_tokens {
+            bail!(
+                "Window size ({}) must be at least twice the maximum number of tokens of a \
+                 mention ({})",
+                config.window_size,
+                config.max_mention_tokens
+            );
+        }
+        Ok(Self {
+            parser,
+            config,
+            window: VecDeque::new(),
+            window_start: 0,
+            window_offset: 0,
+            emitted_until: 0,
+        })
+    }
+
+    /// Adds tokens to the stream, and returns the mentions which they complete
+    pub fn push_tokens<I, S>(&mut self, tokens: I) -> Result<Vec<EntityMention>>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut mentions = vec![];
+        for token in tokens {
+            let token = token.into();
+            if token.trim().is_empty() {
+                continue;
+            }
+            if self.window.len() == self.config.window_size {
+                let dropped_token = self.window.pop_front().unwrap();
+                self.window_start += 1;
+                self.window_offset += dropped_token.chars().count() + 1;
+            }
+            self.window.push_back(token);
+            mentions.extend(self.spot(false)?);
+        }
+        Ok(mentions)
+    }
+
+    /// Ends the stream, and returns the mentions which were not emitted yet
+    pub fn finish(mut self) -> Result<Vec<EntityMention>> {
+        self.spot(true)
+    }
+
+    /// Number of tokens received so far
+    pub fn nb_tokens(&self) -> usize {
+        self.window_start + self.window.len()
+    }
+
+    fn spot(&mut self, is_final: bool) -> Result<Vec<EntityMention>> {
+        let nb_tokens = self.nb_tokens();
+        let window_text = self
+            .window
+            .iter()
+            .map(|token| &**token)
+            .collect::<Vec<_>>()
+            .join(" ");
+        // Char offset, in the window, of the end of each token
+        let token_ends: Vec<usize> = self
+            .window
+            .iter()
+            .scan(0, |offset, token| {
+                let token_end = *offset + token.chars().count();
+                *offset = token_end + 1;
+                Some(token_end)
+            })
+            .collect();
+        let window_start = self.window_start;
+        let token_index = |char_offset: usize| {
+            window_start
+                + token_ends
+                    .iter()
+                    .position(|token_end| char_offset <= *token_end)
+                    .unwrap_or(token_ends.len() - 1)
+        };
+        let mut entities = self.parser.extract_entities(
+            &window_text,
+            self.config.entities.as_ref().map(|entities| &**entities),
+            0,
+        )?;
+        entities.sort_by_key(|entity| entity.range.start);
+        let mut mentions = vec![];
+        for entity in entities {
+            let token_range = token_index(entity.range.start)..token_index(entity.range.end) + 1;
+            if token_range.start < self.emitted_until {
+                continue;
+            }
+            if !is_final && token_range.end + self.config.max_mention_tokens - 1 > nb_tokens {
+                break;
+            }
+            self.emitted_until = token_range.end;
+            mentions.push(EntityMention {
+                value: substring_with_char_range(window_text.clone(), &entity.range),
+                resolved_value: entity.resolved_value,
+                entity: entity.entity_identifier,
+                range: self.window_offset + entity.range.start
+                    ..self.window_offset + entity.range.end,
+                token_range,
+            });
+        }
+        Ok(mentions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use snips_nlu_ontology::Language;
+
+    use crate::entity_parser::synonym_entity::SynonymEntityBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_spot_entities_in_stream() {
+        // Given
+        let entity = SynonymEntityBuilder::new("device", Language::EN)
+            .value("light", vec!["ceiling light"])
+            .value("speaker", vec!["sound system"])
+            .build();
+        let config = StreamingSpotterConfig {
+            window_size: 4,
+            max_mention_tokens: 2,
+            entities: None,
+        };
+        let mut spotter = StreamingEntitySpotter::new(Arc::new(entity), config).unwrap();
+
+        // When
+        let first_mentions = spotter
+            .push_tokens(vec!["turn", "off", "the", "ceiling"])
+            .unwrap();
+        let second_mentions = spotter.push_tokens(vec!["light", "and", "the"]).unwrap();
+        let third_mentions = spotter.push_tokens(vec!["speaker"]).unwrap();
+        let last_mentions = spotter.finish().unwrap();
+
+        // Then
+        let expected_second_mentions = vec![EntityMention {
+            value: "ceiling light".to_string(),
+            resolved_value: "light".to_string(),
+            entity: "device".to_string(),
+            range: 13..26,
+            token_range: 3..5,
+        }];
+        let expected_last_mentions = vec![EntityMention {
+            value: "speaker".to_string(),
+            resolved_value: "speaker".to_string(),
+            entity: "device".to_string(),
+            range: 35..42,
+            token_range: 7..8,
+        }];
+        assert_eq!(Vec::<EntityMention>::new(), first_mentions);
+        assert_eq!(expected_second_mentions, second_mentions);
+        assert_eq!(Vec::<EntityMention>::new(), third_mentions);
+        assert_eq!(expected_last_mentions, last_mentions);
+    }
+
+    #[test]
+    fn test_invalid_streaming_spotter_config() {
+        // Given
+        let entity = SynonymEntityBuilder::new("device", Language::EN).build();
+        let config = StreamingSpotterConfig {
+            window_size: 3,
+            max_mention_tokens: 2,
+            entities: None,
+        };
+
+        // When
+        let spotter = StreamingEntitySpotter::new(Arc::new(entity), config);
+
+        // Then
+        assert!(spotter.is_err());
+    }
+}
@@ -0,0 +1,335 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use itertools::Itertools;
+use snips_nlu_ontology::Language;
+use snips_nlu_parsers::gazetteer_entity_parser::EntityValue as GazetteerEntityValue;
+use snips_nlu_utils::language::Language as NluUtilsLanguage;
+use snips_nlu_utils::string::substring_with_char_range;
+use snips_nlu_utils::token::{tokenize, tokenize_light, Token};
+
+use crate::cancellation::check_cancellation;
+use crate::entity_parser::custom_entity_parser::{
+    CustomEntity, CustomEntityParser, CustomEntityParserUsage,
+};
+use crate::errors::*;
+use crate::language::FromLanguage;
+use crate::resources::stemmer::Stemmer;
+use crate::utils::EntityName;
+
+/// Builder of a custom gazetteer entity from canonical values and their synonyms
+///
+/// The values are normalized, and stemmed when a stemmer is provided, the same way as the
+/// values of the custom entities of a trained engine.
+pub struct SynonymEntityBuilder {
+    entity_name: EntityName,
+    language: Language,
+    stemmer: Option<(Arc<dyn Stemmer>, CustomEntityParserUsage)>,
+    values: Vec<(String, Vec<String>)>,
+}
+
+impl SynonymEntityBuilder {
+    pub fn new<S: Into<EntityName>>(entity_name: S, language: Language) -> Self {
+        Self {
+            entity_name: entity_name.into(),
+            language,
+            stemmer: None,
+            values: vec![],
+        }
+    }
+
+    /// Stems the values with `stemmer`, `usage` telling whether the stemmed values replace
+    /// the original ones or are added to them
+    pub fn stemmer(mut self, stemmer: Arc<dyn Stemmer>, usage: CustomEntityParserUsage) -> Self {
+        self.stemmer = Some((stemmer, usage));
+        self
+    }
+
+    /// Adds a canonical value, which is also matched as is, along with its synonyms
+    ///
+    /// When a synonym is shared by several canonical values, the first added value is the
+    /// resolved one and the others are alternatives.
+    pub fn value<S, I, T>(mut self, canonical_value: S, synonyms: I) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.values.push((
+            canonical_value.into(),
+            synonyms.into_iter().map(|synonym| synonym.into()).collect(),
+        ));
+        self
+    }
+
+    /// Returns the values in the format expected by the gazetteer entity parsers, as done
+    /// during entity injection
+    pub fn gazetteer_values(&self) -> Vec<GazetteerEntityValue> {
+        let normalized_values: Vec<GazetteerEntityValue> = self
+            .values
+            .iter()
+            .flat_map(|(canonical_value, synonyms)| {
+                Some(canonical_value)
+                    .into_iter()
+                    .chain(synonyms.iter())
+                    .map(move |value| GazetteerEntityValue {
+                        raw_value: normalize(value),
+                        resolved_value: canonical_value.clone(),
+                    })
+            })
+            .collect();
+        let (stemmer, usage) = match self.stemmer.as_ref() {
+            Some((stemmer, usage)) if *usage != CustomEntityParserUsage::WithoutStems => {
+                (stemmer, usage)
+            }
+            _ => return normalized_values,
+        };
+        let language = NluUtilsLanguage::from_language(self.language);
+        let stemmed_values = normalized_values
+            .iter()
+            .map(|value| GazetteerEntityValue {
+                raw_value: stem(&value.raw_value, language, &**stemmer),
+                resolved_value: value.resolved_value.clone(),
+            })
+            .collect();
+        match usage {
+            CustomEntityParserUsage::WithStems => stemmed_values,
+            _ => normalized_values
+                .into_iter()
+                .chain(stemmed_values)
+                .unique()
+                .collect(),
+        }
+    }
+
+    pub fn build(self) -> SynonymEntity {
+        let language = NluUtilsLanguage::from_language(self.language);
+        let mut patterns: Vec<String> = vec![];
+        let mut resolutions: HashMap<String, usize> = HashMap::new();
+        let mut pattern_values: Vec<Vec<usize>> = vec![];
+        for value in self.gazetteer_values() {
+            let pattern = tokenize_light(&value.raw_value, language).join(" ");
+            if pattern.is_empty() {
+                continue;
+            }
+            let value_index = self
+                .values
+                .iter()
+                .position(|(canonical_value, _)| *canonical_value == value.resolved_value)
+                .unwrap();
+            let pattern_index = *resolutions.entry(pattern.clone()).or_insert_with(|| {
+                patterns.push(pattern);
+                pattern_values.push(vec![]);
+                patterns.len() - 1
+            });
+            if !pattern_values[pattern_index].contains(&value_index) {
+                pattern_values[pattern_index].push(value_index);
+            }
+        }
+        let index = AhoCorasickBuilder::new()
+            .match_kind(MatchKind::Standard)
+            .build(&patterns);
+        SynonymEntity {
+            entity_name: self.entity_name,
+            language,
+            stemmer: self.stemmer,
+            canonical_values: self.values.into_iter().map(|(value, _)| value).collect(),
+            index,
+            resolutions,
+            pattern_values,
+        }
+    }
+}
+
+/// Custom gazetteer entity built with a `SynonymEntityBuilder`
+///
+/// The entity can be used as a `CustomEntityParser`, in which case the matches are exact
+/// matches of the normalized tokens, the longest ones being preferred.
+pub struct SynonymEntity {
+    entity_name: EntityName,
+    language: NluUtilsLanguage,
+    stemmer: Option<(Arc<dyn Stemmer>, CustomEntityParserUsage)>,
+    canonical_values: Vec<String>,
+    index: AhoCorasick,
+    resolutions: HashMap<String, usize>,
+    pattern_values: Vec<Vec<usize>>,
+}
+
+impl SynonymEntity {
+    pub fn entity_name(&self) -> &str {
+        &self.entity_name
+    }
+
+    /// Returns the canonical value of `value`, if it is one of the values of the entity
+    pub fn resolve(&self, value: &str) -> Option<&str> {
+        let tokens = tokenize_light(&normalize(value), self.language);
+        self.stemmed_variants(tokens)
+            .into_iter()
+            .filter_map(|variant| self.resolutions.get(&variant.join(" ")))
+            .next()
+            .map(|pattern_index| &*self.canonical_values[self.pattern_values[*pattern_index][0]])
+    }
+
+    /// Returns the variants of the normalized tokens to match, depending on the stemmer usage
+    fn stemmed_variants(&self, tokens: Vec<String>) -> Vec<Vec<String>> {
+        match self.stemmer.as_ref() {
+            Some((stemmer, CustomEntityParserUsage::WithStems)) => {
+                vec![stem_tokens(&tokens, &**stemmer)]
+            }
+            Some((stemmer, CustomEntityParserUsage::WithAndWithoutStems)) => {
+                let stemmed_tokens = stem_tokens(&tokens, &**stemmer);
+                vec![tokens, stemmed_tokens]
+            }
+            _ => vec![tokens],
+        }
+    }
+
+    /// Returns the matches of the index in `tokens` as token ranges, along with the pattern
+    /// index, keeping only the matches aligned with the token boundaries
+    fn find_token_matches(&self, tokens: &[String]) -> Vec<(usize, usize, usize)> {
+        let mut token_starts = HashMap::new();
+        let mut token_ends = HashMap::new();
+        let mut offset = 0;
+        for (token_index, token) in tokens.iter().enumerate() {
+            token_starts.insert(offset, token_index);
+            offset += token.len();
+            token_ends.insert(offset, token_index + 1);
+            offset += 1;
+        }
+        self.index
+            .find_overlapping_iter(&tokens.join(" "))
+            .filter_map(|m| {
+                let start = token_starts.get(&m.start())?;
+                let end = token_ends.get(&m.end())?;
+                Some((*start, *end, m.pattern()))
+            })
+            .collect()
+    }
+}
+
+impl CustomEntityParser for SynonymEntity {
+    fn extract_entities(
+        &self,
+        sentence: &str,
+        filter_entity_kinds: Option<&[String]>,
+        max_alternative_resolved_values: usize,
+    ) -> Result<Vec<CustomEntity>> {
+        check_cancellation()?;
+        if let Some(entity_kinds) = filter_entity_kinds {
+            if !entity_kinds.contains(&self.entity_name) {
+                return Ok(vec![]);
+            }
+        }
+        let tokens: Vec<Token> = tokenize(sentence, self.language);
+        let normalized_tokens = tokens.iter().map(|token| normalize(&token.value)).collect();
+        let mut matches: Vec<(usize, usize, usize)> = self
+            .stemmed_variants(normalized_tokens)
+            .iter()
+            .flat_map(|variant| self.find_token_matches(variant))
+            .collect();
+        // Longest matches first
+        matches.sort_by_key(|(start, end, pattern_index)| {
+            (Reverse(end - start), *start, *pattern_index)
+        });
+
+        let mut is_matched = vec![false; tokens.len()];
+        let mut entities = vec![];
+        for (start, end, pattern_index) in matches {
+            if is_matched[start..end].iter().any(|matched| *matched) {
+                continue;
+            }
+            for matched in is_matched[start..end].iter_mut() {
+                *matched = true;
+            }
+            let char_range = tokens[start].char_range.start..tokens[end - 1].char_range.end;
+            let mut resolved_values = self.pattern_values[pattern_index]
+                .iter()
+                .map(|value_index| self.canonical_values[*value_index].clone());
+            entities.push(CustomEntity {
+                value: substring_with_char_range(sentence.to_string(), &char_range),
+                resolved_value: resolved_values.next().unwrap(),
+                alternative_resolved_values: resolved_values
+                    .take(max_alternative_resolved_values)
+                    .collect(),
+                range: char_range,
+                entity_identifier: self.entity_name.clone(),
+            });
+        }
+        entities.sort_by_key(|entity| entity.range.start);
+        Ok(entities)
+    }
+}
+
+fn normalize(value: &str) -> String {
+    value.to_lowercase()
+}
+
+fn stem(value: &str, language: NluUtilsLanguage, stemmer: &dyn Stemmer) -> String {
+    stem_tokens(&tokenize_light(value, language), stemmer).join(" ")
+}
+
+fn stem_tokens(tokens: &[String], stemmer: &dyn Stemmer) -> Vec<String> {
+    tokens
+        .iter()
+        .map(|token| stemmer.stem(token).into_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+
+    use crate::resources::stemmer::HashMapStemmer;
+
+    use super::*;
+
+    #[test]
+    fn test_synonym_entity() {
+        // Given
+        let stemmer = HashMapStemmer::from_iter(vec![
+            ("lights".to_string(), "light".to_string()),
+            ("lamps".to_string(), "lamp".to_string()),
+        ]);
+        let entity = SynonymEntityBuilder::new("device", Language::EN)
+            .stemmer(
+                Arc::new(stemmer),
+                CustomEntityParserUsage::WithAndWithoutStems,
+            )
+            .value("light", vec!["lamp", "ceiling light"])
+            .value("speaker", vec!["sound system"])
+            .value("lamp", Vec::<String>::new())
+            .build();
+
+        // When
+        let entities = entity
+            .extract_entities("Turn off the ceiling lights and the Lamps", None, 1)
+            .unwrap();
+        let filtered_entities = entity
+            .extract_entities("turn off the speaker", Some(&["color".to_string()]), 0)
+            .unwrap();
+
+        // Then
+        let expected_entities = vec![
+            CustomEntity {
+                value: "ceiling lights".to_string(),
+                resolved_value: "light".to_string(),
+                alternative_resolved_values: vec![],
+                range: 13..27,
+                entity_identifier: "device".to_string(),
+            },
+            CustomEntity {
+                value: "Lamps".to_string(),
+                resolved_value: "light".to_string(),
+                alternative_resolved_values: vec!["lamp".to_string()],
+                range: 36..41,
+                entity_identifier: "device".to_string(),
+            },
+        ];
+        assert_eq!(expected_entities, entities);
+        assert_eq!(Vec::<CustomEntity>::new(), filtered_entities);
+        assert_eq!(Some("speaker"), entity.resolve("Sound System"));
+        assert_eq!(None, entity.resolve("sound"));
+    }
+}
@@ -8,6 +8,22 @@ pub enum SnipsNluError {
     WrongModelVersion{ model: String, runner: &'static str},
     #[fail(display = "Unknown intent: '{}'", _0)]
     UnknownIntent(String),
+    #[fail(display = "Unknown tenant: '{}'", _0)]
+    UnknownTenant(String),
+    #[fail(display = "No sub-engine in language '{}'", _0)]
+    UnknownLanguage(String),
+    #[fail(display = "Pattern of intent '{}' exceeds the regex limits: {}", intent, message)]
+    RegexLimitExceeded { intent: String, message: String },
+    #[fail(display = "Too many patterns: found {} but limit is {}", nb_patterns, limit)]
+    TooManyPatterns { nb_patterns: usize, limit: usize },
+    #[fail(display = "Estimated memory usage is {} bytes but budget is {} bytes", estimated_size, memory_budget)]
+    MemoryBudgetExceeded { estimated_size: usize, memory_budget: usize },
+    #[fail(display = "Engine is still loading")]
+    EngineNotReady,
+    #[fail(display = "Parsing was cancelled")]
+    ParseCancelled,
+    #[fail(display = "Invalid engine config key '{}': {}", key, message)]
+    InvalidConfig { key: String, message: String },
     #[fail(display = "Internal error: {}", _0)]
     InternalError(String),
 }
@@ -6,21 +6,16 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use failure::ResultExt;
-use itertools::Itertools;
 use log::info;
-use snips_nlu_ontology::{BuiltinGazetteerEntityKind, GrammarEntityKind};
-use snips_nlu_parsers::gazetteer_entity_parser::{
-    EntityValue as GazetteerEntityValue, Parser as GazetteerEntityParser,
-};
+use snips_nlu_ontology::{BuiltinGazetteerEntityKind, GrammarEntityKind, Language};
+use snips_nlu_parsers::gazetteer_entity_parser::Parser as GazetteerEntityParser;
 use snips_nlu_parsers::{BuiltinParserMetadata, GazetteerParserMetadata};
-use snips_nlu_utils::language::Language as NluUtilsLanguage;
-use snips_nlu_utils::token::tokenize_light;
 
 use crate::entity_parser::custom_entity_parser::CustomEntityParserMetadata;
 use crate::entity_parser::custom_entity_parser::CustomEntityParserUsage;
+use crate::entity_parser::synonym_entity::SynonymEntityBuilder;
 use crate::models::nlu_engine::NluEngineModel;
 use crate::resources::loading::load_engine_shared_resources;
-use crate::resources::stemmer::Stemmer;
 use crate::resources::SharedResources;
 
 use super::errors::{NluInjectionError, NluInjectionErrorKind};
@@ -28,12 +23,12 @@ use super::errors::{NluInjectionError, NluInjectionErrorKind};
 pub type InjectedEntity = String;
 pub type InjectedValue = String;
 
-fn normalize(s: &str) -> String {
-    s.to_lowercase()
-}
+/// Name of the file, located in the engine directory, which keeps track of the values that
+/// were injected in each entity
+pub const INJECTED_VALUES_FILENAME: &str = "injected_values.json";
 
 struct NluEngineInfo {
-    language: NluUtilsLanguage,
+    language: Language,
     builtin_entity_parser_dir: PathBuf,
     custom_entity_parser_dir: PathBuf,
     custom_entities: HashSet<InjectedEntity>,
@@ -113,6 +108,23 @@ impl<P: AsRef<Path>> NluInjector<P> {
             })
         }?;
 
+        let mut injected_values = load_injected_values(self.nlu_engine_dir.as_ref())
+            .with_context(|_| NluInjectionErrorKind::InternalInjectionError {
+                msg: format!(
+                    "could not load previously injected values from {:?}",
+                    self.nlu_engine_dir.as_ref()
+                ),
+            })?;
+        for (entity, values) in self.entity_values.iter() {
+            let entity_injected_values = injected_values
+                .entry(entity.to_string())
+                .or_insert_with(HashSet::new);
+            if self.from_vanilla {
+                entity_injected_values.clear();
+            }
+            entity_injected_values.extend(values.iter().cloned());
+        }
+
         let maybe_stemmer = shared_resources.stemmer.as_ref();
 
         // Normalize and stem all values if needed
@@ -121,18 +133,25 @@ impl<P: AsRef<Path>> NluInjector<P> {
             .entity_values
             .into_iter()
             .map(|(entity, values)| {
-                let normalize_entity_values = normalize_entity_value(values);
-                if engine_info.custom_entities.contains(&*entity) {
-                    let stemmed_entity_values = stem_entity_value(
-                        normalize_entity_values,
-                        &engine_info,
-                        &custom_parser_info,
-                        maybe_stemmer,
-                    )?;
-                    Ok((entity, stemmed_entity_values))
-                } else {
-                    Ok((entity, normalize_entity_values))
+                let mut entity_builder =
+                    SynonymEntityBuilder::new(entity.clone(), engine_info.language);
+                if engine_info.custom_entities.contains(&*entity)
+                    && custom_parser_info.parser_usage != CustomEntityParserUsage::WithoutStems
+                {
+                    let stemmer =
+                        maybe_stemmer.ok_or(NluInjectionErrorKind::InternalInjectionError {
+                            msg: format!(
+                                "found {:?} parser usage but no stemmer in NLU engine.",
+                                custom_parser_info.parser_usage
+                            ),
+                        })?;
+                    entity_builder = entity_builder
+                        .stemmer(stemmer.clone(), custom_parser_info.parser_usage.clone());
                 }
+                let entity_builder = values.into_iter().fold(entity_builder, |builder, value| {
+                    builder.value(value, Vec::<String>::new())
+                });
+                Ok((entity, entity_builder.gazetteer_values()))
             })
             .collect::<Result<HashMap<_, _>, NluInjectionError>>()?;
 
@@ -164,11 +183,59 @@ impl<P: AsRef<Path>> NluInjector<P> {
             })?;
         }
 
+        info!("Recording injected values...");
+        dump_injected_values(self.nlu_engine_dir.as_ref(), &injected_values)?;
+
         info!("Injection performed with success !");
         Ok(())
     }
 }
 
+/// Load the values which have been injected so far in the engine located in `engine_dir`
+///
+/// An empty mapping is returned when no injection has been performed.
+pub fn load_injected_values<P: AsRef<Path>>(
+    engine_dir: P,
+) -> crate::errors::Result<HashMap<InjectedEntity, HashSet<InjectedValue>>> {
+    let injected_values_path = engine_dir.as_ref().join(INJECTED_VALUES_FILENAME);
+    if !injected_values_path.exists() {
+        return Ok(HashMap::new());
+    }
+    let injected_values_file = fs::File::open(&injected_values_path).with_context(|_| {
+        format!(
+            "Cannot open injected values file '{:?}'",
+            &injected_values_path
+        )
+    })?;
+    let injected_values = serde_json::from_reader(injected_values_file)
+        .with_context(|_| "Cannot deserialize injected values json data")?;
+    Ok(injected_values)
+}
+
+fn dump_injected_values(
+    engine_dir: &Path,
+    injected_values: &HashMap<InjectedEntity, HashSet<InjectedValue>>,
+) -> Result<(), NluInjectionError> {
+    let injected_values_path = engine_dir.join(INJECTED_VALUES_FILENAME);
+    let injected_values_file = fs::File::create(&injected_values_path).with_context(|_| {
+        NluInjectionErrorKind::InternalInjectionError {
+            msg: format!(
+                "could not create injected values file {:?}",
+                injected_values_path
+            ),
+        }
+    })?;
+    serde_json::to_writer(injected_values_file, injected_values).with_context(|_| {
+        NluInjectionErrorKind::InternalInjectionError {
+            msg: format!(
+                "could not write injected values in {:?}",
+                injected_values_path
+            ),
+        }
+    })?;
+    Ok(())
+}
+
 fn get_entity_parsers_dirs(
     engine_info: &NluEngineInfo,
     maybe_builtin_parser_info: &Option<BuiltinGazetteerParserInfo>,
@@ -345,9 +412,11 @@ fn get_nlu_engine_info<P: AsRef<Path>>(engine_dir: P) -> Result<NluEngineInfo, N
             }
         })?;
 
-    let language = NluUtilsLanguage::from_str(&*nlu_engine_model.dataset_metadata.language_code)
-        .map_err(|_| NluInjectionErrorKind::InternalInjectionError {
-            msg: "invalid nlu engine language".to_string(),
+    let language =
+        Language::from_str(&*nlu_engine_model.dataset_metadata.language_code).map_err(|_| {
+            NluInjectionErrorKind::InternalInjectionError {
+                msg: "invalid nlu engine language".to_string(),
+            }
         })?;
 
     let custom_entities =
@@ -368,68 +437,21 @@ fn get_nlu_engine_info<P: AsRef<Path>>(engine_dir: P) -> Result<NluEngineInfo, N
     })
 }
 
-fn normalize_entity_value(entity_values: Vec<InjectedValue>) -> Vec<GazetteerEntityValue> {
-    entity_values
-        .into_iter()
-        .map(|value| GazetteerEntityValue {
-            raw_value: normalize(&*value),
-            resolved_value: value,
-        })
-        .collect()
-}
-
-fn stem_entity_value(
-    entity_values: Vec<GazetteerEntityValue>,
-    engine_info: &NluEngineInfo,
-    custom_entity_parser_info: &CustomGazetteerParserInfo,
-    maybe_stemmer: Option<&Arc<dyn Stemmer>>,
-) -> Result<Vec<GazetteerEntityValue>, NluInjectionError> {
-    let stemmed_entity_values = match custom_entity_parser_info.parser_usage {
-        CustomEntityParserUsage::WithoutStems => vec![],
-        _ => entity_values
-            .iter()
-            .map(|value| {
-                let stemmer =
-                    &maybe_stemmer.ok_or(NluInjectionErrorKind::InternalInjectionError {
-                        msg: format!(
-                            "found {:?} parser usage but no stemmer in NLU engine.",
-                            custom_entity_parser_info.parser_usage
-                        ),
-                    })?;
-                let raw_value = tokenize_light(&*value.raw_value, engine_info.language)
-                    .into_iter()
-                    .map(|token| stemmer.stem(&*token))
-                    .join(" ");
-
-                Ok(GazetteerEntityValue {
-                    raw_value,
-                    resolved_value: value.resolved_value.clone(),
-                })
-            })
-            .collect::<Result<_, NluInjectionError>>()?,
-    };
-    let all_values = match custom_entity_parser_info.parser_usage {
-        CustomEntityParserUsage::WithStems => stemmed_entity_values,
-        _ => {
-            let mut values = entity_values;
-            values.extend(stemmed_entity_values);
-            values.into_iter().unique().collect::<Vec<_>>()
-        }
-    };
-    Ok(all_values)
-}
-
 #[cfg(test)]
 mod tests {
     extern crate fs_extra;
     extern crate tempfile;
 
+    use std::borrow::Cow;
+
     use self::fs_extra::dir;
     use self::tempfile::tempdir;
     use snips_nlu_ontology::*;
 
-    use crate::SharedResources;
+    use crate::resources::stemmer::Stemmer;
     use crate::SnipsNluEngine;
+    use crate::{MissingResourcePolicy, SharedResources};
+    use crate::{SlotDetails, SlotProvenance, TextRanges};
 
     use super::*;
 
@@ -439,11 +461,26 @@ mod tests {
     }
 
     impl<'a> Stemmer for MockedStemmer<'a> {
-        fn stem(&self, value: &str) -> String {
-            self.values.get(value).cloned().unwrap_or(value).to_string()
+        fn stem<'b>(&'b self, value: &'b str) -> Cow<'b, str> {
+            Cow::Borrowed(self.values.get(value).cloned().unwrap_or(value))
         }
     }
 
+    #[test]
+    fn test_load_injected_values_when_no_injection() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+
+        // When
+        let injected_values = load_injected_values(path).unwrap();
+
+        // Then
+        assert!(injected_values.is_empty());
+    }
+
     #[test]
     fn test_injection() {
         let path = Path::new("data")
@@ -471,6 +508,7 @@ mod tests {
             stemmer: Some(Arc::new(stemmer.clone())),
             word_clusterers: HashMap::new(),
             stop_words: HashSet::new(),
+            missing_resource_policy: MissingResourcePolicy::Fail,
         });
 
         // Behaviour before injection
@@ -526,6 +564,7 @@ mod tests {
             stemmer: Some(Arc::new(stemmer)),
             word_clusterers: injected_resources.word_clusterers.clone(),
             stop_words: HashSet::new(),
+            missing_resource_policy: MissingResourcePolicy::Fail,
         };
 
         let nlu_engine = SnipsNluEngine::from_path_with_resources(
@@ -557,6 +596,29 @@ mod tests {
         }];
         assert_eq!(parsing.slots, ground_true_slots);
 
+        let detailed_parsing = nlu_engine
+            .parse_with_details(
+                "je souhaiterais écouter l'album thisisthebestalbum",
+                None,
+                None,
+                0,
+                0,
+            )
+            .unwrap();
+        let expected_details = vec![SlotDetails {
+            provenance: SlotProvenance::Injected,
+            grain: None,
+            relative_time: None,
+            value_range: None,
+            entity_value_id: None,
+            text_ranges: TextRanges {
+                chars: 32..50,
+                bytes: 33..51,
+                utf16: 32..50,
+            },
+        }];
+        assert_eq!(detailed_parsing.slots_details, expected_details);
+
         let parsing = nlu_engine
             .parse("je voudrais ecouter ma playlist funk", None, None)
             .unwrap();
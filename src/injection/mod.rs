@@ -2,4 +2,6 @@ mod errors;
 mod injection;
 
 pub use self::errors::{NluInjectionError, NluInjectionErrorKind};
-pub use self::injection::{InjectedEntity, InjectedValue, NluInjector};
+pub use self::injection::{
+    load_injected_values, InjectedEntity, InjectedValue, NluInjector, INJECTED_VALUES_FILENAME,
+};
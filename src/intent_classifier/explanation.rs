@@ -0,0 +1,36 @@
+use serde::Serialize;
+
+use crate::utils::IntentName;
+
+/// Weight of a single classifier feature
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureWeight {
+    pub feature: String,
+    pub weight: f32,
+}
+
+/// Summary of the features which contribute the most, positively and negatively, to the
+/// classification of an intent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentExplanation {
+    pub intent_name: Option<IntentName>,
+    pub intercept: f32,
+    /// Features with a positive weight, sorted by decreasing weight
+    pub positive_features: Vec<FeatureWeight>,
+    /// Features with a negative weight, sorted by increasing weight
+    pub negative_features: Vec<FeatureWeight>,
+}
+
+/// Intercept of the intent classifier for an intent, i.e. its bias independently of the input
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassIntercept {
+    pub intent_name: Option<IntentName>,
+    /// Intercept used by the classifier, adjustment included
+    pub intercept: f32,
+    /// Additive adjustment applied to the trained intercept when the engine was loaded, as
+    /// configured in `EngineConfig::intercept_adjustments`
+    pub adjustment: f32,
+}
@@ -5,12 +5,12 @@ use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use failure::{format_err, ResultExt};
+use failure::{bail, format_err, ResultExt};
 use itertools::Itertools;
 use ndarray::prelude::*;
 use snips_nlu_ontology::{BuiltinEntityKind, Language};
 use snips_nlu_utils::language::Language as NluUtilsLanguage;
-use snips_nlu_utils::string::normalize;
+use snips_nlu_utils::string::{hash_str_to_i32, normalize};
 use snips_nlu_utils::token::{compute_all_ngrams, tokenize_light};
 
 use crate::errors::*;
@@ -85,11 +85,20 @@ impl Featurizer {
         };
         Ok(Array::from_iter(features))
     }
+
+    /// Returns the names of the features, in the same order as in the output of `transform`
+    pub fn feature_names(&self) -> Vec<String> {
+        let mut names = self.tfidf_vectorizer.feature_names();
+        if let Some(vectorizer) = self.cooccurrence_vectorizer.as_ref() {
+            names.extend(vectorizer.feature_names());
+        }
+        names
+    }
 }
 
 pub struct TfidfVectorizer {
     builtin_entity_scope: Vec<BuiltinEntityKind>,
-    vocabulary: HashMap<String, usize>,
+    vocabulary: Vocabulary,
     idf_diag: Vec<f32>,
     word_clusterer: Option<Arc<dyn WordClusterer>>,
     stemmer: Option<Arc<dyn Stemmer>>,
@@ -120,25 +129,31 @@ impl TfidfVectorizer {
         model: TfidfVectorizerModel,
         shared_resources: Arc<SharedResources>,
     ) -> Result<Self> {
-        let vocabulary = model.vectorizer.vocab;
         let idf_diag = model.vectorizer.idf_diag;
+        let vocabulary = if let Some(nb_buckets) = model.config.hashing_buckets {
+            if nb_buckets == 0 || idf_diag.len() != nb_buckets {
+                bail!(
+                    "Invalid hashed vocabulary: {} buckets for {} idf values",
+                    nb_buckets,
+                    idf_diag.len()
+                );
+            }
+            Vocabulary::Hashed { nb_buckets }
+        } else {
+            Vocabulary::Explicit(model.vectorizer.vocab)
+        };
 
         let ontology_language = Language::from_str(model.language_code.as_ref())?;
         let language = NluUtilsLanguage::from_language(ontology_language);
 
         let opt_word_clusterer = if let Some(clusters_name) = model.config.word_clusters_name {
-            Some(
+            shared_resources.check_resource(
                 shared_resources
                     .word_clusterers
                     .get(&clusters_name)
-                    .cloned()
-                    .ok_or_else(|| {
-                        format_err!(
-                            "Cannot find word clusters '{}' in shared resources",
-                            clusters_name
-                        )
-                    })?,
-            )
+                    .cloned(),
+                &format!("word clusters '{}'", clusters_name),
+            )?
         } else {
             None
         };
@@ -153,13 +168,7 @@ impl TfidfVectorizer {
             .collect::<Result<Vec<BuiltinEntityKind>>>()?;
 
         let stemmer = if model.config.use_stemming {
-            Some(
-                shared_resources
-                    .stemmer
-                    .as_ref()
-                    .cloned()
-                    .ok_or_else(|| format_err!("Cannot find stemmer in shared resources"))?,
-            )
+            shared_resources.check_resource(shared_resources.stemmer.clone(), "stemmer")?
         } else {
             None
         };
@@ -175,6 +184,13 @@ impl TfidfVectorizer {
         })
     }
 
+    /// Normalized, and possibly stemmed, tokens of the utterance from which the word features
+    /// are computed
+    pub fn preprocess(&self, utterance: &str) -> Vec<String> {
+        let tokens = tokenize_light(utterance, self.language);
+        normalize_stem(&tokens, self.stemmer.clone())
+    }
+
     pub fn transform(&self, utterance: &str) -> Result<Vec<f32>> {
         let tokens = tokenize_light(utterance, self.language);
         let normalized_tokens = normalize_stem(&tokens, self.stemmer.clone());
@@ -217,14 +233,13 @@ impl TfidfVectorizer {
             word_clusters,
         ];
 
-        let vocabulary_size = self.vocabulary.values().max().unwrap() + 1;
-        let mut features: Vec<f32> = vec![0.; vocabulary_size];
+        let mut features: Vec<f32> = vec![0.; self.vocabulary.size()];
         let mut match_idx: HashSet<usize> = HashSet::new();
         for extracted_features in features_it.iter() {
             for word in extracted_features {
-                if let Some(word_idx) = self.vocabulary.get(word) {
-                    features[*word_idx] += 1.;
-                    match_idx.insert(*word_idx);
+                if let Some(word_idx) = self.vocabulary.index(word) {
+                    features[word_idx] += 1.;
+                    match_idx.insert(word_idx);
                 }
             }
         }
@@ -239,6 +254,51 @@ impl TfidfVectorizer {
         features = features.iter().map(|c| *c / safe_l2_norm).collect_vec();
         Ok(features)
     }
+
+    pub fn feature_names(&self) -> Vec<String> {
+        match &self.vocabulary {
+            Vocabulary::Explicit(vocabulary) => {
+                let mut names = vec![String::new(); self.vocabulary.size()];
+                for (word, word_idx) in vocabulary.iter() {
+                    names[*word_idx] = word.to_string();
+                }
+                names
+            }
+            Vocabulary::Hashed { nb_buckets } => (0..*nb_buckets)
+                .map(|bucket| format!("hash_bucket_{}", bucket))
+                .collect(),
+        }
+    }
+}
+
+/// Mapping from the words to their index in the tf-idf features
+///
+/// With feature hashing, words are not stored in the model and are instead hashed into a fixed
+/// number of buckets, several words possibly sharing the same bucket.
+enum Vocabulary {
+    Explicit(HashMap<String, usize>),
+    Hashed { nb_buckets: usize },
+}
+
+impl Vocabulary {
+    fn index(&self, word: &str) -> Option<usize> {
+        match self {
+            Vocabulary::Explicit(vocabulary) => vocabulary.get(word).cloned(),
+            Vocabulary::Hashed { nb_buckets } => {
+                let hash = hash_str_to_i32(word) as i64;
+                Some(hash.rem_euclid(*nb_buckets as i64) as usize)
+            }
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Vocabulary::Explicit(vocabulary) => {
+                vocabulary.values().max().map(|max| max + 1).unwrap_or(0)
+            }
+            Vocabulary::Hashed { nb_buckets } => *nb_buckets,
+        }
+    }
 }
 
 pub struct CooccurrenceVectorizer {
@@ -347,6 +407,14 @@ impl CooccurrenceVectorizer {
         Ok(features)
     }
 
+    pub fn feature_names(&self) -> Vec<String> {
+        let mut names = vec![String::new(); self.word_pairs.len()];
+        for ((first_word, second_word), pair_idx) in self.word_pairs.iter() {
+            names[*pair_idx] = format!("({}, {})", first_word, second_word);
+        }
+        names
+    }
+
     fn placeholder_fn(&self, entity_kind: &str) -> String {
         tokenize_light(entity_kind, self.language)
             .join("")
@@ -408,7 +476,12 @@ fn get_word_clusters(
 
 fn normalize_stem(tokens: &[String], opt_stemmer: Option<Arc<dyn Stemmer>>) -> Vec<String> {
     opt_stemmer
-        .map(|stemmer| tokens.iter().map(|t| stemmer.stem(&normalize(t))).collect())
+        .map(|stemmer| {
+            tokens
+                .iter()
+                .map(|t| stemmer.stem(&normalize(t)).into_owned())
+                .collect()
+        })
         .unwrap_or_else(|| tokens.iter().map(|t| normalize(t)).collect())
 }
 
@@ -433,7 +506,7 @@ mod tests {
     };
     use crate::resources::stemmer::HashMapStemmer;
     use crate::resources::word_clusterer::HashMapWordClusterer;
-    use crate::resources::SharedResources;
+    use crate::resources::{MissingResourcePolicy, SharedResources};
     use crate::testutils::assert_epsilon_eq_array1;
     use crate::testutils::MockedBuiltinEntityParser;
     use crate::testutils::MockedCustomEntityParser;
@@ -496,6 +569,7 @@ mod tests {
             word_clusterers: HashMap::new(),
             gazetteers: HashMap::new(),
             stop_words: HashSet::new(),
+            missing_resource_policy: MissingResourcePolicy::Fail,
         };
 
         let vocab = hashmap![
@@ -533,6 +607,7 @@ mod tests {
         let tfidf_vectorizer_config = TfidfVectorizerConfiguration {
             use_stemming: true,
             word_clusters_name: None,
+            hashing_buckets: None,
         };
 
         let tfidf_vectorizer_model = TfidfVectorizerModel {
@@ -667,6 +742,7 @@ mod tests {
             word_clusterers: HashMap::new(),
             gazetteers: HashMap::new(),
             stop_words,
+            missing_resource_policy: MissingResourcePolicy::Fail,
         });
 
         let vocab = hashmap![
@@ -713,6 +789,7 @@ mod tests {
         let tfidf_vectorizer_config = TfidfVectorizerConfiguration {
             use_stemming: true,
             word_clusters_name: None,
+            hashing_buckets: None,
         };
 
         let tfidf_vectorizer_model = TfidfVectorizerModel {
@@ -775,6 +852,51 @@ mod tests {
         assert_epsilon_eq_array1(&expected_features, &features, 1e-6);
     }
 
+    #[test]
+    fn test_transform_with_feature_hashing() {
+        // Given
+        let resources = Arc::new(SharedResources {
+            custom_entity_parser: Arc::new(MockedCustomEntityParser::from_iter(vec![])),
+            builtin_entity_parser: Arc::new(MockedBuiltinEntityParser::from_iter(vec![])),
+            stemmer: None,
+            word_clusterers: HashMap::new(),
+            gazetteers: HashMap::new(),
+            stop_words: HashSet::new(),
+            missing_resource_policy: MissingResourcePolicy::Fail,
+        });
+        let nb_buckets = 16;
+        let vectorizer_model = |nb_buckets: usize, nb_idf_values: usize| TfidfVectorizerModel {
+            language_code: "en".to_string(),
+            builtin_entity_scope: vec![],
+            vectorizer: SklearnVectorizerModel {
+                idf_diag: vec![2.0; nb_idf_values],
+                vocab: HashMap::new(),
+            },
+            config: TfidfVectorizerConfiguration {
+                use_stemming: false,
+                word_clusters_name: None,
+                hashing_buckets: Some(nb_buckets),
+            },
+        };
+        let tfidf_vectorizer =
+            TfidfVectorizer::new(vectorizer_model(nb_buckets, nb_buckets), resources.clone())
+                .unwrap();
+
+        // When
+        let features = tfidf_vectorizer.transform("Hello hello").unwrap();
+        let feature_names = tfidf_vectorizer.feature_names();
+        let invalid_vectorizer = TfidfVectorizer::new(vectorizer_model(nb_buckets, 10), resources);
+
+        // Then
+        let hello_bucket = (hash_str_to_i32("hello") as i64).rem_euclid(nb_buckets as i64);
+        let mut expected_features = vec![0.; nb_buckets];
+        expected_features[hello_bucket as usize] = 1.;
+        assert_eq!(expected_features, features);
+        assert_eq!(nb_buckets, feature_names.len());
+        assert_eq!("hash_bucket_0", feature_names[0]);
+        assert!(invalid_vectorizer.is_err());
+    }
+
     #[test]
     fn test_get_word_cluster_features() {
         // Given
@@ -815,6 +937,7 @@ house	cluster_house
             word_clusterers: HashMap::new(),
             gazetteers: HashMap::new(),
             stop_words: hashset!(),
+            missing_resource_policy: MissingResourcePolicy::Fail,
         });
         let config = CooccurrenceVectorizerConfiguration {
             window_size: None,
@@ -875,6 +998,7 @@ house	cluster_house
             word_clusterers: HashMap::new(),
             gazetteers: HashMap::new(),
             stop_words: hashset!(),
+            missing_resource_policy: MissingResourcePolicy::Fail,
         });
         let config = CooccurrenceVectorizerConfiguration {
             window_size: None,
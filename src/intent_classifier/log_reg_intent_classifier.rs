@@ -1,20 +1,22 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::iter::FromIterator;
 use std::path::Path;
 use std::sync::Arc;
 
-use failure::ResultExt;
+use failure::{format_err, ResultExt};
 use itertools::Itertools;
 use log::{debug, info};
 use ndarray::prelude::*;
 use snips_nlu_ontology::IntentClassifierResult;
 
 use crate::errors::*;
-use crate::intent_classifier::{Featurizer, IntentClassifier};
+use crate::intent_classifier::{
+    ClassIntercept, FeatureWeight, Featurizer, IntentClassifier, IntentExplanation,
+};
 use crate::models::IntentClassifierModel;
 use crate::resources::SharedResources;
-use crate::utils::IntentName;
+use crate::utils::{is_blank_input, IntentName};
 
 use super::logreg::MulticlassLogisticRegression;
 
@@ -22,6 +24,8 @@ pub struct LogRegIntentClassifier {
     intent_list: Vec<Option<IntentName>>,
     featurizer: Option<Featurizer>,
     logreg: Option<MulticlassLogisticRegression>,
+    /// Adjustments applied to the intercepts, in the order of `intent_list`
+    intercept_adjustments: Vec<f32>,
 }
 
 impl LogRegIntentClassifier {
@@ -64,12 +68,42 @@ impl LogRegIntentClassifier {
 
         info!("Log reg intent classifier loaded");
 
+        let intercept_adjustments = vec![0.; model.intent_list.len()];
         Ok(Self {
             intent_list: model.intent_list,
             featurizer,
             logreg,
+            intercept_adjustments,
         })
     }
+
+    /// Adds adjustments to the intercepts of some intents, e.g. to damp an intent which is
+    /// triggered too often, without retraining the classifier
+    ///
+    /// A negative adjustment lowers the probability of the intent for all inputs.
+    pub fn adjust_intercepts(&mut self, adjustments: &HashMap<IntentName, f32>) -> Result<()> {
+        for (intent, adjustment) in adjustments.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+            let class_index = self
+                .intent_list
+                .iter()
+                .position(|intent_name| intent_name.as_ref() == Some(intent))
+                .ok_or_else(|| SnipsNluError::UnknownIntent(intent.to_string()))?;
+            let logreg = self.logreg.as_mut().ok_or_else(|| {
+                format_err!(
+                    "Cannot adjust the intercept of intent '{}': the intent classifier has no \
+                     weights",
+                    intent
+                )
+            })?;
+            logreg.adjust_intercept(class_index, *adjustment)?;
+            self.intercept_adjustments[class_index] += *adjustment;
+            info!(
+                "Intercept of intent '{}' adjusted by {}",
+                intent, adjustment
+            );
+        }
+        Ok(())
+    }
 }
 
 impl IntentClassifier for LogRegIntentClassifier {
@@ -95,6 +129,75 @@ impl IntentClassifier for LogRegIntentClassifier {
     fn get_intents(&self, input: &str) -> Result<Vec<IntentClassifierResult>> {
         self.get_intents_with_whitelist(input, None)
     }
+
+    fn explain(&self, intent: Option<&str>, top_n: usize) -> Result<IntentExplanation> {
+        let class_index = self
+            .intent_list
+            .iter()
+            .position(|intent_name| intent_name.as_ref().map(|name| &**name) == intent)
+            .ok_or_else(|| format_err!("Unknown intent: {:?}", intent))?;
+        let intent_name = intent.map(|name| name.to_string());
+
+        let (featurizer, logreg) = match (self.featurizer.as_ref(), self.logreg.as_ref()) {
+            (Some(featurizer), Some(logreg)) => (featurizer, logreg),
+            _ => {
+                return Ok(IntentExplanation {
+                    intent_name,
+                    intercept: 0.,
+                    positive_features: vec![],
+                    negative_features: vec![],
+                })
+            }
+        };
+
+        let (intercept, weights) = logreg.class_weights(class_index)?;
+        let feature_weights: Vec<FeatureWeight> = featurizer
+            .feature_names()
+            .into_iter()
+            .zip(weights.into_iter())
+            .map(|(feature, weight)| FeatureWeight { feature, weight })
+            .collect();
+        let positive_features = feature_weights
+            .iter()
+            .filter(|feature_weight| feature_weight.weight > 0.)
+            .cloned()
+            .sorted_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap())
+            .take(top_n)
+            .collect();
+        let negative_features = feature_weights
+            .iter()
+            .filter(|feature_weight| feature_weight.weight < 0.)
+            .cloned()
+            .sorted_by(|a, b| a.weight.partial_cmp(&b.weight).unwrap())
+            .take(top_n)
+            .collect();
+
+        Ok(IntentExplanation {
+            intent_name,
+            intercept,
+            positive_features,
+            negative_features,
+        })
+    }
+
+    fn intercepts(&self) -> Result<Vec<ClassIntercept>> {
+        self.intent_list
+            .iter()
+            .zip(self.intercept_adjustments.iter())
+            .enumerate()
+            .map(|(class_index, (intent_name, adjustment))| {
+                let intercept = match self.logreg.as_ref() {
+                    Some(logreg) => logreg.class_weights(class_index)?.0,
+                    None => 0.,
+                };
+                Ok(ClassIntercept {
+                    intent_name: intent_name.clone(),
+                    intercept,
+                    adjustment: *adjustment,
+                })
+            })
+            .collect()
+    }
 }
 
 impl LogRegIntentClassifier {
@@ -110,7 +213,7 @@ impl LogRegIntentClassifier {
             }]);
         }
 
-        if input.is_empty() || self.featurizer.is_none() || self.logreg.is_none() {
+        if is_blank_input(input) || self.featurizer.is_none() || self.logreg.is_none() {
             return Ok(self
                 .intent_list
                 .iter()
@@ -161,6 +264,15 @@ impl LogRegIntentClassifier {
             .map(|featurizer| featurizer.transform(input))
             .unwrap_or_else(|| Ok(Array::from_iter(vec![])))
     }
+
+    /// Returns the names of the features, in the same order as in the output of
+    /// `compute_features`
+    pub fn feature_names(&self) -> Vec<String> {
+        self.featurizer
+            .as_ref()
+            .map(|featurizer| featurizer.feature_names())
+            .unwrap_or_else(|| vec![])
+    }
 }
 
 #[cfg(test)]
@@ -220,6 +332,7 @@ mod tests {
         let tfidf_vectorizer_config = TfidfVectorizerConfiguration {
             use_stemming: false,
             word_clusters_name: None,
+            hashing_buckets: None,
         };
 
         let tfidf_vectorizer_model = TfidfVectorizerModel {
@@ -317,6 +430,7 @@ mod tests {
         let logreg = MulticlassLogisticRegression::new(intercept, coeffs).unwrap();
         LogRegIntentClassifier {
             featurizer: Some(featurizer),
+            intercept_adjustments: vec![0.; intent_list.len()],
             intent_list,
             logreg: Some(logreg),
         }
@@ -415,4 +529,88 @@ mod tests {
         assert_eq!(Some("MakeCoffee".to_string()), result2.intent_name);
         assert_eq!(None, result3.intent_name);
     }
+
+    #[test]
+    fn test_adjust_intercepts() {
+        // Given
+        let mut classifier = get_sample_log_reg_classifier();
+        let tea_score = |classifier: &LogRegIntentClassifier| {
+            classifier
+                .get_intents("Make me two cups of tea")
+                .unwrap()
+                .into_iter()
+                .find(|result| result.intent_name == Some("MakeTea".to_string()))
+                .unwrap()
+                .confidence_score
+        };
+        let score_before_adjustment = tea_score(&classifier);
+
+        // When
+        classifier
+            .adjust_intercepts(&hashmap!["MakeTea".to_string() => -1.5])
+            .unwrap();
+        let unknown_intent_adjustment =
+            classifier.adjust_intercepts(&hashmap!["MakeChocolate".to_string() => -1.5]);
+        let intercepts = classifier.intercepts().unwrap();
+
+        // Then
+        let expected_intercepts = vec![
+            ClassIntercept {
+                intent_name: Some("MakeCoffee".to_string()),
+                intercept: -0.06864156,
+                adjustment: 0.,
+            },
+            ClassIntercept {
+                intent_name: Some("MakeTea".to_string()),
+                intercept: -0.08753256 - 1.5,
+                adjustment: -1.5,
+            },
+            ClassIntercept {
+                intent_name: None,
+                intercept: -0.05181312,
+                adjustment: 0.,
+            },
+        ];
+        assert_eq!(expected_intercepts, intercepts);
+        assert!(tea_score(&classifier) < score_before_adjustment);
+        assert!(unknown_intent_adjustment.is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        // Given
+        let classifier = get_sample_log_reg_classifier();
+
+        // When
+        let explanation = classifier.explain(Some("MakeCoffee"), 2).unwrap();
+        let unknown_intent_explanation = classifier.explain(Some("MakeChocolate"), 2);
+
+        // Then
+        let expected_explanation = IntentExplanation {
+            intent_name: Some("MakeCoffee".to_string()),
+            intercept: -0.06864156,
+            positive_features: vec![
+                FeatureWeight {
+                    feature: "coffee".to_string(),
+                    weight: 2.40544488,
+                },
+                FeatureWeight {
+                    feature: "brew".to_string(),
+                    weight: 1.01186938,
+                },
+            ],
+            negative_features: vec![
+                FeatureWeight {
+                    feature: "unknownword".to_string(),
+                    weight: -2.84005242,
+                },
+                FeatureWeight {
+                    feature: "tea".to_string(),
+                    weight: -1.3218943,
+                },
+            ],
+        };
+        assert_eq!(expected_explanation, explanation);
+        assert!(unknown_intent_explanation.is_err());
+    }
 }
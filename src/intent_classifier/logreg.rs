@@ -1,3 +1,4 @@
+use failure::bail;
 use ndarray::prelude::*;
 use ndarray::{array, stack};
 
@@ -39,6 +40,51 @@ impl MulticlassLogisticRegression {
         })
     }
 
+    /// Returns the intercept and the feature weights associated to the class with the
+    /// provided index
+    pub fn class_weights(&self, class_index: usize) -> Result<(f32, Vec<f32>)> {
+        self.check_class_index(class_index)?;
+        if self.is_binary() {
+            // In the binary case, the weights correspond to the second class and the weights
+            // of the first class are deduced by symmetry
+            let sign = if class_index == 1 { 1. } else { -1. };
+            let column = self.weights.column(0);
+            let intercept = sign * column[0];
+            let weights = column.iter().skip(1).map(|w| sign * *w).collect();
+            return Ok((intercept, weights));
+        }
+        let column = self.weights.column(class_index);
+        let intercept = column[0];
+        let weights = column.iter().skip(1).cloned().collect();
+        Ok((intercept, weights))
+    }
+
+    /// Adds an adjustment to the intercept of the class with the provided index, which shifts
+    /// the log-odds of this class for all inputs
+    pub fn adjust_intercept(&mut self, class_index: usize, adjustment: f32) -> Result<()> {
+        self.check_class_index(class_index)?;
+        if self.is_binary() {
+            // The intercept of the first class is the opposite of the one of the second class
+            let sign = if class_index == 1 { 1. } else { -1. };
+            self.weights[[0, 0]] += sign * adjustment;
+        } else {
+            self.weights[[0, class_index]] += adjustment;
+        }
+        Ok(())
+    }
+
+    fn check_class_index(&self, class_index: usize) -> Result<()> {
+        let nb_classes = if self.is_binary() { 2 } else { self.nb_classes() };
+        if class_index >= nb_classes {
+            bail!(
+                "Invalid class index {}, number of classes is {}",
+                class_index,
+                nb_classes
+            );
+        }
+        Ok(())
+    }
+
     pub fn run(&self, features: &ArrayView1<f32>) -> Result<Array1<f32>> {
         let reshaped_features = features.into_shape((1, self.nb_features()))?;
         let reshaped_features = stack![Axis(1), array![[1.]], reshaped_features];
@@ -101,4 +147,64 @@ mod tests {
         let expected_predictions = array![0.2890504, 0.7109495];
         assert_epsilon_eq_array1(&predictions, &expected_predictions, 1e-06);
     }
+
+    #[test]
+    fn test_class_weights() {
+        // Given
+        let intercept = array![0.98, 0.32];
+        let weights = array![[2.5, -0.6], [1.2, 1.2]];
+        let regression = MulticlassLogisticRegression::new(intercept, weights).unwrap();
+
+        // When
+        let class_weights = regression.class_weights(1).unwrap();
+        let invalid_class_weights = regression.class_weights(2);
+
+        // Then
+        assert_eq!((0.32, vec![-0.6, 1.2]), class_weights);
+        assert!(invalid_class_weights.is_err());
+    }
+
+    #[test]
+    fn test_adjust_intercept() {
+        // Given
+        let intercept = array![0.98, 0.32];
+        let weights = array![[2.5, -0.6], [1.2, 1.2]];
+        let mut regression = MulticlassLogisticRegression::new(intercept, weights).unwrap();
+        let mut binary_regression =
+            MulticlassLogisticRegression::new(array![0.98], array![[2.5], [1.2]]).unwrap();
+
+        // When
+        regression.adjust_intercept(1, -0.5).unwrap();
+        binary_regression.adjust_intercept(0, 0.5).unwrap();
+        let invalid_adjustment = regression.adjust_intercept(2, -0.5);
+        let intercepts = array![
+            regression.class_weights(0).unwrap().0,
+            regression.class_weights(1).unwrap().0
+        ];
+        let binary_intercepts = array![
+            binary_regression.class_weights(0).unwrap().0,
+            binary_regression.class_weights(1).unwrap().0
+        ];
+
+        // Then
+        assert_epsilon_eq_array1(&intercepts, &array![0.98, -0.18], 1e-06);
+        assert_epsilon_eq_array1(&binary_intercepts, &array![-0.48, 0.48], 1e-06);
+        assert!(invalid_adjustment.is_err());
+    }
+
+    #[test]
+    fn test_class_weights_when_binary() {
+        // Given
+        let intercept = array![0.98];
+        let weights = array![[2.5], [1.2]];
+        let regression = MulticlassLogisticRegression::new(intercept, weights).unwrap();
+
+        // When
+        let first_class_weights = regression.class_weights(0).unwrap();
+        let second_class_weights = regression.class_weights(1).unwrap();
+
+        // Then
+        assert_eq!((-0.98, vec![-2.5, -1.2]), first_class_weights);
+        assert_eq!((0.98, vec![2.5, 1.2]), second_class_weights);
+    }
 }
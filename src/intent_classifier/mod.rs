@@ -1,19 +1,23 @@
+mod explanation;
 mod featurizer;
 mod log_reg_intent_classifier;
 mod logreg;
 
+use std::collections::HashMap;
 use std::fs::File;
 use std::path::Path;
 use std::sync::Arc;
 
 use crate::errors::*;
-use failure::{format_err, ResultExt};
+use failure::{bail, format_err, ResultExt};
 use snips_nlu_ontology::IntentClassifierResult;
 
+pub use self::explanation::{ClassIntercept, FeatureWeight, IntentExplanation};
 pub use self::featurizer::{CooccurrenceVectorizer, Featurizer, TfidfVectorizer};
 pub use self::log_reg_intent_classifier::LogRegIntentClassifier;
 use crate::models::ProcessingUnitMetadata;
 use crate::resources::SharedResources;
+use crate::utils::IntentName;
 
 pub trait IntentClassifier: Send + Sync {
     fn get_intent(
@@ -23,11 +27,29 @@ pub trait IntentClassifier: Send + Sync {
     ) -> Result<IntentClassifierResult>;
 
     fn get_intents(&self, input: &str) -> Result<Vec<IntentClassifierResult>>;
+
+    /// Returns the `top_n` features having the highest positive and negative weights for the
+    /// provided intent, `None` corresponding to the None intent
+    ///
+    /// Intent classifiers which do not rely on feature weights return an error.
+    fn explain(&self, _intent: Option<&str>, _top_n: usize) -> Result<IntentExplanation> {
+        bail!("Explanations are not supported by this intent classifier")
+    }
+
+    /// Returns the intercept of each intent, `None` corresponding to the None intent
+    ///
+    /// Intent classifiers which do not rely on intercepts return an error.
+    fn intercepts(&self) -> Result<Vec<ClassIntercept>> {
+        bail!("Intercepts are not supported by this intent classifier")
+    }
 }
 
+/// Loads the intent classifier, the provided additive adjustments being applied to the
+/// intercepts of the intents
 pub fn build_intent_classifier<P: AsRef<Path>>(
     path: P,
     shared_resources: Arc<SharedResources>,
+    intercept_adjustments: &HashMap<IntentName, f32>,
 ) -> Result<Box<dyn IntentClassifier>> {
     let metadata_path = path.as_ref().join("metadata.json");
     let metadata_file = File::open(&metadata_path).with_context(|_| {
@@ -40,8 +62,48 @@ pub fn build_intent_classifier<P: AsRef<Path>>(
         .with_context(|_| "Cannot deserialize intent classifier json data")?;
     match metadata {
         ProcessingUnitMetadata::LogRegIntentClassifier => {
-            Ok(Box::new(LogRegIntentClassifier::from_path(path, shared_resources)?) as _)
+            let mut classifier = LogRegIntentClassifier::from_path(path, shared_resources)?;
+            classifier.adjust_intercepts(intercept_adjustments)?;
+            Ok(Box::new(classifier) as _)
         }
         _ => Err(format_err!("{:?} is not an intent classifier", metadata)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ConstantIntentClassifier;
+
+    impl IntentClassifier for ConstantIntentClassifier {
+        fn get_intent(
+            &self,
+            _input: &str,
+            _intents_whitelist: Option<&[&str]>,
+        ) -> Result<IntentClassifierResult> {
+            Ok(IntentClassifierResult {
+                intent_name: None,
+                confidence_score: 1.0,
+            })
+        }
+
+        fn get_intents(&self, input: &str) -> Result<Vec<IntentClassifierResult>> {
+            Ok(vec![self.get_intent(input, None)?])
+        }
+    }
+
+    #[test]
+    fn test_explanations_are_unsupported_by_default() {
+        // Given
+        let classifier = ConstantIntentClassifier;
+
+        // When
+        let explanation = classifier.explain(None, 5);
+        let intercepts = classifier.intercepts();
+
+        // Then
+        assert!(explanation.is_err());
+        assert!(intercepts.is_err());
+    }
+}
@@ -3,7 +3,7 @@ use std::fs::File;
 use std::ops::Range;
 use std::path::Path;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use failure::{format_err, ResultExt};
 use log::{debug, info};
@@ -11,30 +11,42 @@ use regex::{Regex, RegexBuilder};
 use snips_nlu_ontology::{BuiltinEntityKind, IntentClassifierResult, Language};
 use snips_nlu_utils::language::Language as NluUtilsLanguage;
 use snips_nlu_utils::range::ranges_overlap;
-use snips_nlu_utils::string::{convert_to_char_range, substring_with_char_range};
+use snips_nlu_utils::string::substring_with_char_range;
 use snips_nlu_utils::token::{tokenize, tokenize_light};
 
+use crate::cancellation::check_cancellation;
+use crate::config::{EngineConfig, RegexLimits};
 use crate::errors::*;
 use crate::language::FromLanguage;
 use crate::models::DeterministicParserModel;
+use crate::normalization_dictionary::NormalizedInput;
 use crate::resources::SharedResources;
 use crate::slot_utils::*;
+use crate::tokenization::tokenize_aligned;
+use crate::unicode::PatternNormalization;
 use crate::utils::{
-    deduplicate_overlapping_items, replace_entities, EntityName, IntentName, MatchedEntity,
-    SlotName,
+    deduplicate_overlapping_items, is_blank_input, replace_entities, CharIndex, EntityName,
+    IntentName, MatchedEntity, SlotName,
 };
 
+use super::fuzzy_matching::FuzzyMatcher;
+use super::pattern_template::compile_pattern_template;
 use super::{internal_parsing_result, IntentParser, InternalParsingResult};
 use itertools::Itertools;
 
 pub struct DeterministicIntentParser {
     language: Language,
-    regexes_per_intent: HashMap<IntentName, Vec<Regex>>,
+    regexes_per_intent: HashMap<IntentName, IntentRegexes>,
+    regex_limits: RegexLimits,
     group_names_to_slot_names: HashMap<String, SlotName>,
     slot_names_to_entities: HashMap<IntentName, HashMap<SlotName, EntityName>>,
     stop_words: HashSet<String>,
     specific_stop_words: HashMap<IntentName, HashSet<String>>,
     entity_scopes: HashMap<IntentName, (Vec<BuiltinEntityKind>, Vec<EntityName>)>,
+    placeholder_delimiter: char,
+    placeholder_prefix: String,
+    pattern_normalization: PatternNormalization,
+    fuzzy_matchers: HashMap<IntentName, FuzzyMatcher>,
     shared_resources: Arc<SharedResources>,
 }
 
@@ -42,11 +54,43 @@ impl DeterministicIntentParser {
     pub fn from_path<P: AsRef<Path>>(
         path: P,
         shared_resources: Arc<SharedResources>,
+    ) -> Result<Self> {
+        Self::from_path_with_regex_limits(path, shared_resources, &RegexLimits::default())
+    }
+
+    pub fn from_path_with_regex_limits<P: AsRef<Path>>(
+        path: P,
+        shared_resources: Arc<SharedResources>,
+        regex_limits: &RegexLimits,
+    ) -> Result<Self> {
+        info!(
+            "Loading deterministic intent parser ({:?}) ...",
+            path.as_ref()
+        );
+        let model = Self::load_model(path)?;
+        let parser = Self::new_with_regex_limits(model, shared_resources, regex_limits);
+        info!("Deterministic intent parser loaded");
+        parser
+    }
+
+    /// Loads the parser with the regex limits of the `EngineConfig`, its patterns being
+    /// compiled on their first use when `lazy_regex_compilation` is enabled
+    pub fn from_path_with_config<P: AsRef<Path>>(
+        path: P,
+        shared_resources: Arc<SharedResources>,
+        config: &EngineConfig,
     ) -> Result<Self> {
         info!(
             "Loading deterministic intent parser ({:?}) ...",
             path.as_ref()
         );
+        let model = Self::load_model(path)?;
+        let parser = Self::new_with_config(model, shared_resources, config);
+        info!("Deterministic intent parser loaded");
+        parser
+    }
+
+    fn load_model<P: AsRef<Path>>(path: P) -> Result<DeterministicParserModel> {
         let parser_model_path = path.as_ref().join("intent_parser.json");
         let model_file = File::open(&parser_model_path).with_context(|_| {
             format!(
@@ -54,11 +98,8 @@ impl DeterministicIntentParser {
                 &parser_model_path
             )
         })?;
-        let model: DeterministicParserModel = serde_json::from_reader(model_file)
-            .with_context(|_| "Cannot deserialize DeterministicIntentParser json data")?;
-        let parser = Self::new(model, shared_resources);
-        info!("Deterministic intent parser loaded");
-        parser
+        Ok(serde_json::from_reader(model_file)
+            .with_context(|_| "Cannot deserialize DeterministicIntentParser json data")?)
     }
 }
 
@@ -66,17 +107,40 @@ impl DeterministicIntentParser {
     pub fn new(
         model: DeterministicParserModel,
         shared_resources: Arc<SharedResources>,
+    ) -> Result<Self> {
+        Self::new_with_regex_limits(model, shared_resources, &RegexLimits::default())
+    }
+
+    pub fn new_with_regex_limits(
+        model: DeterministicParserModel,
+        shared_resources: Arc<SharedResources>,
+        regex_limits: &RegexLimits,
+    ) -> Result<Self> {
+        let config = EngineConfig {
+            regex_limits: regex_limits.clone(),
+            ..EngineConfig::default()
+        };
+        Self::new_with_config(model, shared_resources, &config)
+    }
+
+    pub fn new_with_config(
+        model: DeterministicParserModel,
+        shared_resources: Arc<SharedResources>,
+        config: &EngineConfig,
     ) -> Result<Self> {
         let language = Language::from_str(&model.language_code)?;
         let entity_scopes = model
             .slot_names_to_entities
             .iter()
             .map(|(intent, mapping)| {
-                let builtin_entities = mapping
-                    .iter()
-                    .flat_map(|(_, entity)| BuiltinEntityKind::from_identifier(entity).ok())
-                    .unique()
-                    .collect();
+                let builtin_entities = match config.builtin_entity_scopes.get(intent) {
+                    Some(entity_kinds) => entity_kinds.clone(),
+                    None => mapping
+                        .iter()
+                        .flat_map(|(_, entity)| BuiltinEntityKind::from_identifier(entity).ok())
+                        .unique()
+                        .collect(),
+                };
                 let custom_entities = mapping
                     .iter()
                     .flat_map(|(_, entity)| {
@@ -109,14 +173,42 @@ impl DeterministicIntentParser {
                 )
             })
             .collect();
+        let patterns = normalize_patterns(
+            merge_pattern_templates(model.patterns, model.pattern_templates)?,
+            &config.pattern_normalization,
+        );
+        let fuzzy_matchers = config
+            .fuzzy_patterns
+            .iter()
+            .filter(|(_, max_edit_distance)| **max_edit_distance > 0)
+            .filter_map(|(intent, max_edit_distance)| {
+                patterns.get(intent).map(|intent_patterns| {
+                    let matcher = FuzzyMatcher::new(
+                        intent_patterns,
+                        model.config.placeholder_delimiter,
+                        *max_edit_distance,
+                    );
+                    (intent.to_string(), matcher)
+                })
+            })
+            .collect();
         Ok(DeterministicIntentParser {
             language,
-            regexes_per_intent: compile_regexes_per_intent(model.patterns)?,
+            regexes_per_intent: build_regexes_per_intent(
+                patterns,
+                &config.regex_limits,
+                config.lazy_regex_compilation,
+            )?,
+            regex_limits: config.regex_limits.clone(),
             group_names_to_slot_names: model.group_names_to_slot_names,
             slot_names_to_entities: model.slot_names_to_entities,
             stop_words,
             specific_stop_words,
             entity_scopes,
+            placeholder_delimiter: model.config.placeholder_delimiter,
+            placeholder_prefix: model.config.placeholder_prefix,
+            pattern_normalization: config.pattern_normalization,
+            fuzzy_matchers,
             shared_resources,
         })
     }
@@ -128,6 +220,9 @@ impl IntentParser for DeterministicIntentParser {
         input: &str,
         intents_whitelist: Option<&[&str]>,
     ) -> Result<InternalParsingResult> {
+        if is_blank_input(input) {
+            return Ok(InternalParsingResult::empty());
+        }
         debug!("Extracting intents and slots with deterministic intent parser...");
         let result = self
             .parse_top_intents(input, 1, intents_whitelist)?
@@ -164,7 +259,7 @@ impl IntentParser for DeterministicIntentParser {
             .iter()
             .filter_map(|res| res.intent_name.clone())
             .collect();
-        for intent in self.regexes_per_intent.keys() {
+        for intent in self.regexes_per_intent.keys().sorted() {
             if !matched_intents.contains(intent) {
                 top_intents.push(IntentClassifierResult {
                     intent_name: Some(intent.to_string()),
@@ -191,13 +286,74 @@ impl IntentParser for DeterministicIntentParser {
 }
 
 impl DeterministicIntentParser {
-    #[allow(clippy::map_clone)]
+    /// Returns the patterns of the parser, grouped by intent
+    pub fn patterns(&self) -> HashMap<&str, Vec<&str>> {
+        self.regexes_per_intent
+            .iter()
+            .map(|(intent, regexes)| {
+                (
+                    &**intent,
+                    regexes.patterns.iter().map(|pattern| &**pattern).collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns, for each intent having a pattern matching the input, the index of the first
+    /// matching pattern
+    pub fn matching_patterns(&self, input: &str) -> Result<Vec<(IntentName, usize)>> {
+        Ok(self
+            .match_patterns(input, None)?
+            .into_iter()
+            .map(|(pattern_index, result)| (result.intent.intent_name.unwrap(), pattern_index))
+            .sorted()
+            .collect())
+    }
+
     fn parse_top_intents(
         &self,
         input: &str,
         top_n: usize,
         intents: Option<&[&str]>,
     ) -> Result<Vec<InternalParsingResult>> {
+        let results: Vec<InternalParsingResult> = self
+            .match_patterns(input, intents)?
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect();
+
+        // In some rare cases there can be multiple ambiguous intents
+        // In such cases, priority is given to results containing fewer slots
+        let weights = results
+            .iter()
+            .map(|res| 1. / (1. + res.slots.len() as f32))
+            .collect::<Vec<_>>();
+        let total_weight: f32 = weights.iter().sum();
+
+        Ok(results
+            .into_iter()
+            .enumerate()
+            .map(|(idx, mut res)| {
+                res.intent.confidence_score = weights[idx] / total_weight;
+                res
+            })
+            .sorted_by(|res1, res2| {
+                res2.intent
+                    .confidence_score
+                    .partial_cmp(&res1.intent.confidence_score)
+                    .unwrap()
+                    .then_with(|| res1.intent.intent_name.cmp(&res2.intent.intent_name))
+            })
+            .take(top_n)
+            .collect())
+    }
+
+    #[allow(clippy::map_clone)]
+    fn match_patterns(
+        &self,
+        input: &str,
+        intents: Option<&[&str]>,
+    ) -> Result<Vec<(usize, InternalParsingResult)>> {
         let mut results = vec![];
 
         let intents_set: HashSet<&str> = intents
@@ -213,13 +369,28 @@ impl DeterministicIntentParser {
             .iter()
             .filter(|(intent, _)| intents_set.contains(&***intent));
 
+        // The input is matched in its normalized form, and the ranges of the slots are mapped
+        // back to the original input
+        let normalized_input = self
+            .pattern_normalization
+            .apply_with_segments(input)
+            .map(|(text, segments)| NormalizedInput::from_segments(text, segments));
+        let text = normalized_input
+            .as_ref()
+            .map_or(input, |normalized_input| &*normalized_input.text);
+        let sanitized_input =
+            neutralize_placeholders(text, self.placeholder_delimiter, &self.placeholder_prefix);
         for (intent, (builtin_scope, custom_scope)) in filtered_entity_scopes {
-            let builtin_entities = self
-                .shared_resources
-                .builtin_entity_parser
-                .extract_entities(input, Some(builtin_scope.as_ref()), true, 0)?
-                .into_iter()
-                .map(|entity| entity.into());
+            check_cancellation()?;
+            let builtin_entities = if builtin_scope.is_empty() {
+                vec![]
+            } else {
+                self.shared_resources
+                    .builtin_entity_parser
+                    .extract_entities(input, Some(builtin_scope.as_ref()), true, 0)?
+            }
+            .into_iter()
+            .map(|entity| entity.into());
 
             let custom_entities = self
                 .shared_resources
@@ -231,56 +402,66 @@ impl DeterministicIntentParser {
             let mut matched_entities: Vec<MatchedEntity> = vec![];
             matched_entities.extend(builtin_entities);
             matched_entities.extend(custom_entities);
+            if let Some(normalized_input) = normalized_input.as_ref() {
+                for entity in matched_entities.iter_mut() {
+                    entity.range = normalized_input.normalized_range(&entity.range);
+                }
+            }
 
             let (ranges_mapping, formatted_input) =
-                replace_entities(input, matched_entities, get_entity_placeholder);
-            let cleaned_input = self.preprocess_text(input, &**intent);
+                replace_entities(&sanitized_input, matched_entities, |entity_label| {
+                    get_entity_placeholder(
+                        entity_label,
+                        self.placeholder_delimiter,
+                        &self.placeholder_prefix,
+                    )
+                });
+            let cleaned_input = self.preprocess_text(&sanitized_input, &**intent);
             let cleaned_formatted_input = self.preprocess_text(&*formatted_input, &**intent);
-            if let Some(matching_result_formatted) = self
+            let regexes = self
                 .regexes_per_intent
                 .get(intent)
                 .ok_or_else(|| format_err!("No associated regexes for intent '{}'", intent))?
-                .iter()
-                .find_map(|regex| {
-                    self.get_matching_result(input, &*cleaned_input, regex, intent, None)
-                        .or_else(|| {
-                            self.get_matching_result(
-                                input,
-                                &*cleaned_formatted_input,
-                                regex,
-                                intent,
-                                Some(&ranges_mapping),
-                            )
-                        })
-                })
-            {
-                results.push(matching_result_formatted);
+                .regexes(intent, &self.regex_limits)?;
+            let mut matching_result = self.find_matching_result(
+                text,
+                (&*cleaned_input, None),
+                (&*cleaned_formatted_input, None),
+                &regexes,
+                intent,
+                &ranges_mapping,
+            )?;
+            // Misspelled words are only corrected when the input does not match exactly, so
+            // that fuzzy matching never changes the result of the exact one
+            let fuzzy_matcher = self.fuzzy_matchers.get(intent);
+            if let (None, Some(fuzzy_matcher)) = (matching_result.as_ref(), fuzzy_matcher) {
+                let corrected_input = fuzzy_matcher.correct(&cleaned_input);
+                let corrected_formatted_input = fuzzy_matcher.correct(&cleaned_formatted_input);
+                if corrected_input.is_some() || corrected_formatted_input.is_some() {
+                    matching_result = self.find_matching_result(
+                        text,
+                        corrected_text(&cleaned_input, corrected_input.as_ref()),
+                        corrected_text(
+                            &cleaned_formatted_input,
+                            corrected_formatted_input.as_ref(),
+                        ),
+                        &regexes,
+                        intent,
+                        &ranges_mapping,
+                    )?;
+                }
+            }
+            if let Some((pattern_index, mut result)) = matching_result {
+                if let Some(normalized_input) = normalized_input.as_ref() {
+                    for slot in result.slots.iter_mut() {
+                        slot.char_range = normalized_input.original_range(&slot.char_range);
+                        slot.value = substring_with_char_range(input.to_string(), &slot.char_range);
+                    }
+                }
+                results.push((pattern_index, result));
             }
         }
-
-        // In some rare cases there can be multiple ambiguous intents
-        // In such cases, priority is given to results containing fewer slots
-        let weights = results
-            .iter()
-            .map(|res| 1. / (1. + res.slots.len() as f32))
-            .collect::<Vec<_>>();
-        let total_weight: f32 = weights.iter().sum();
-
-        Ok(results
-            .into_iter()
-            .enumerate()
-            .map(|(idx, mut res)| {
-                res.intent.confidence_score = weights[idx] / total_weight;
-                res
-            })
-            .sorted_by(|res1, res2| {
-                res2.intent
-                    .confidence_score
-                    .partial_cmp(&res1.intent.confidence_score)
-                    .unwrap()
-            })
-            .take(top_n)
-            .collect())
+        Ok(results)
     }
 
     fn preprocess_text(&self, string: &str, intent: &str) -> String {
@@ -288,7 +469,7 @@ impl DeterministicIntentParser {
             .specific_stop_words
             .get(intent)
             .unwrap_or_else(|| &self.stop_words);
-        let tokens = tokenize(string, NluUtilsLanguage::from_language(self.language));
+        let tokens = tokenize_aligned(string, NluUtilsLanguage::from_language(self.language));
         let mut current_idx = 0;
         let mut cleaned_string = "".to_string();
         for mut token in tokens {
@@ -306,10 +487,46 @@ impl DeterministicIntentParser {
         cleaned_string
     }
 
+    /// Returns the index of the first pattern matching the cleaned input, or the cleaned input
+    /// in which the entities are replaced by placeholders, along with the parsing result
+    fn find_matching_result(
+        &self,
+        input: &str,
+        cleaned_input: (&str, Option<&NormalizedInput>),
+        cleaned_formatted_input: (&str, Option<&NormalizedInput>),
+        regexes: &[Regex],
+        intent: &str,
+        builtin_entities_ranges_mapping: &HashMap<Range<usize>, Range<usize>>,
+    ) -> Result<Option<(usize, InternalParsingResult)>> {
+        for (pattern_index, regex) in regexes.iter().enumerate() {
+            // Intents may have thousands of patterns, hence the cancellation is checked
+            // between patterns rather than once per intent
+            check_cancellation()?;
+            let result = self
+                .get_matching_result(input, cleaned_input, regex, intent, None)
+                .or_else(|| {
+                    self.get_matching_result(
+                        input,
+                        cleaned_formatted_input,
+                        regex,
+                        intent,
+                        Some(builtin_entities_ranges_mapping),
+                    )
+                });
+            if let Some(result) = result {
+                return Ok(Some((pattern_index, result)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Matches the formatted input against a regex, the ranges of the slots being mapped back
+    /// to the input through the corrections of misspelled words, if any, and then through the
+    /// ranges mapping of the placeholders
     fn get_matching_result(
         &self,
         input: &str,
-        formatted_input: &str,
+        (formatted_input, corrections): (&str, Option<&NormalizedInput>),
         regex: &Regex,
         intent: &str,
         builtin_entities_ranges_mapping: Option<&HashMap<Range<usize>, Range<usize>>>,
@@ -318,6 +535,7 @@ impl DeterministicIntentParser {
             return None;
         }
 
+        let char_index = CharIndex::new(formatted_input);
         for caps in regex.captures_iter(&formatted_input) {
             if caps.len() == 0 {
                 continue;
@@ -339,7 +557,10 @@ impl DeterministicIntentParser {
                     let slot_name = self.group_names_to_slot_names[group_name].to_string();
                     let entity = self.slot_names_to_entities[intent][&slot_name].to_string();
                     let byte_range = a_match.start()..a_match.end();
-                    let mut char_range = convert_to_char_range(&formatted_input, &byte_range);
+                    let mut char_range = char_index.char_range(&byte_range);
+                    if let Some(corrections) = corrections {
+                        char_range = corrections.original_range(&char_range);
+                    }
                     if let Some(ranges_mapping) = builtin_entities_ranges_mapping {
                         char_range =
                             ranges_mapping.get(&char_range).cloned().unwrap_or_else(|| {
@@ -355,6 +576,7 @@ impl DeterministicIntentParser {
                         char_range,
                         entity,
                         slot_name,
+                        confidence_score: None,
                     }
                 })
                 .collect();
@@ -367,17 +589,137 @@ impl DeterministicIntentParser {
     }
 }
 
-fn compile_regexes_per_intent(
+/// Returns the corrected text when some words were corrected, and the text itself otherwise
+fn corrected_text<'a>(
+    text: &'a str,
+    corrections: Option<&'a NormalizedInput>,
+) -> (&'a str, Option<&'a NormalizedInput>) {
+    match corrections {
+        Some(corrections) => (&*corrections.text, Some(corrections)),
+        None => (text, None),
+    }
+}
+
+/// Adds the patterns compiled from the templates to the patterns of each intent
+fn merge_pattern_templates(
+    mut patterns: HashMap<IntentName, Vec<String>>,
+    pattern_templates: HashMap<IntentName, Vec<String>>,
+) -> Result<HashMap<IntentName, Vec<String>>> {
+    for (intent, templates) in pattern_templates {
+        let compiled_templates = templates
+            .iter()
+            .map(|template| compile_pattern_template(template))
+            .collect::<Result<Vec<_>>>()?;
+        patterns
+            .entry(intent)
+            .or_insert_with(Vec::new)
+            .extend(compiled_templates);
+    }
+    Ok(patterns)
+}
+
+fn normalize_patterns(
     patterns: HashMap<IntentName, Vec<String>>,
-) -> Result<HashMap<IntentName, Vec<Regex>>> {
+    pattern_normalization: &PatternNormalization,
+) -> HashMap<IntentName, Vec<String>> {
+    if !pattern_normalization.strip_accents {
+        return patterns;
+    }
     patterns
         .into_iter()
         .map(|(intent, patterns)| {
-            let regexes: Result<_> = patterns
-                .into_iter()
-                .map(|p| Ok(RegexBuilder::new(&p).case_insensitive(true).build()?))
+            let normalized_patterns = patterns
+                .iter()
+                .map(|pattern| pattern_normalization.apply_to_pattern(pattern).into_owned())
                 .collect();
-            Ok((intent, regexes?))
+            (intent, normalized_patterns)
+        })
+        .collect()
+}
+
+/// Patterns of an intent along with their regexes, which are compiled either when the parser
+/// is built or when the intent is matched for the first time
+struct IntentRegexes {
+    patterns: Vec<String>,
+    regexes: RwLock<Option<Arc<Vec<Regex>>>>,
+}
+
+impl IntentRegexes {
+    fn regexes(&self, intent: &str, regex_limits: &RegexLimits) -> Result<Arc<Vec<Regex>>> {
+        if let Some(regexes) = self
+            .regexes
+            .read()
+            .map_err(|e| format_err!("Poisonous lock: {}", e))?
+            .as_ref()
+        {
+            return Ok(regexes.clone());
+        }
+        let mut regexes = self
+            .regexes
+            .write()
+            .map_err(|e| format_err!("Poisonous lock: {}", e))?;
+        // Another thread may have compiled the regexes while the lock was released
+        if let Some(regexes) = regexes.as_ref() {
+            return Ok(regexes.clone());
+        }
+        debug!("Compiling the patterns of intent '{}'...", intent);
+        let compiled_regexes = Arc::new(compile_regexes(intent, &self.patterns, regex_limits)?);
+        *regexes = Some(compiled_regexes.clone());
+        Ok(compiled_regexes)
+    }
+}
+
+fn build_regexes_per_intent(
+    patterns: HashMap<IntentName, Vec<String>>,
+    regex_limits: &RegexLimits,
+    lazy_regex_compilation: bool,
+) -> Result<HashMap<IntentName, IntentRegexes>> {
+    let nb_patterns: usize = patterns.values().map(|p| p.len()).sum();
+    if nb_patterns > regex_limits.max_patterns {
+        return Err(SnipsNluError::TooManyPatterns {
+            nb_patterns,
+            limit: regex_limits.max_patterns,
+        }
+        .into());
+    }
+    patterns
+        .into_iter()
+        .map(|(intent, patterns)| {
+            let regexes = if lazy_regex_compilation {
+                None
+            } else {
+                Some(Arc::new(compile_regexes(&intent, &patterns, regex_limits)?))
+            };
+            let intent_regexes = IntentRegexes {
+                patterns,
+                regexes: RwLock::new(regexes),
+            };
+            Ok((intent, intent_regexes))
+        })
+        .collect()
+}
+
+fn compile_regexes(
+    intent: &str,
+    patterns: &[String],
+    regex_limits: &RegexLimits,
+) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|p| {
+            RegexBuilder::new(p)
+                .case_insensitive(true)
+                .size_limit(regex_limits.size_limit)
+                .dfa_size_limit(regex_limits.dfa_size_limit)
+                .build()
+                .map_err(|error| match error {
+                    regex::Error::CompiledTooBig(_) => SnipsNluError::RegexLimitExceeded {
+                        intent: intent.to_string(),
+                        message: error.to_string(),
+                    }
+                    .into(),
+                    _ => error.into(),
+                })
         })
         .collect()
 }
@@ -400,13 +742,59 @@ fn deduplicate_overlapping_slots(
     deduped
 }
 
-fn get_entity_placeholder(entity_label: &str) -> String {
+fn get_entity_placeholder(entity_label: &str, delimiter: char, prefix: &str) -> String {
     // Here we don't need language specific tokenization,
     // we just want to generate a feature name, that's why we use EN
     let normalized_entity_label = tokenize_light(entity_label, NluUtilsLanguage::EN)
         .join("")
         .to_uppercase();
-    format!("%{}%", normalized_entity_label)
+    format!(
+        "{}{}{}{}",
+        delimiter, prefix, normalized_entity_label, delimiter
+    )
+}
+
+/// Replaces with spaces the delimiters of the sequences of the text which could be matched as
+/// entity placeholders by the case-insensitive patterns, so that only the placeholders of the
+/// matched entities are matched
+///
+/// The char ranges of the text are preserved, and delimiters which do not surround a possible
+/// placeholder, such as the ones of "50% off", are kept.
+fn neutralize_placeholders(text: &str, delimiter: char, prefix: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let prefix: Vec<char> = prefix.chars().collect();
+    let mut neutralized_chars = chars.clone();
+    let mut start = 0;
+    while start < chars.len() {
+        if chars[start] != delimiter {
+            start += 1;
+            continue;
+        }
+        let label_start = start + 1 + prefix.len();
+        let has_prefix = chars.get(start + 1..label_start).map_or(false, |chars| {
+            chars
+                .iter()
+                .zip(prefix.iter())
+                .all(|(c, p)| c.to_lowercase().eq(p.to_lowercase()))
+        });
+        let label_length = if has_prefix {
+            chars[label_start..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '_')
+                .count()
+        } else {
+            0
+        };
+        let end = label_start + label_length;
+        if label_length > 0 && chars.get(end) == Some(&delimiter) {
+            neutralized_chars[start] = ' ';
+            neutralized_chars[end] = ' ';
+            start = end + 1;
+        } else {
+            start += 1;
+        }
+    }
+    neutralized_chars.into_iter().collect()
 }
 
 fn get_range_shift(
@@ -496,9 +884,14 @@ mod tests {
         DeterministicParserModel {
             language_code: "en".to_string(),
             patterns,
+            pattern_templates: HashMap::new(),
             group_names_to_slot_names,
             slot_names_to_entities,
-            config: DeterministicParserConfig { ignore_stop_words },
+            config: DeterministicParserConfig {
+                ignore_stop_words,
+                placeholder_delimiter: '%',
+                placeholder_prefix: String::new(),
+            },
             stop_words_whitelist,
         }
     }
@@ -527,6 +920,7 @@ mod tests {
             char_range: 5..8,
             entity: "snips/number".to_string(),
             slot_name: "number_of_cups".to_string(),
+            confidence_score: None,
         }];
         assert_eq!(expected_intent, parsing_result.intent.intent_name);
         assert_eq!(expected_slots, parsing_result.slots);
@@ -566,6 +960,36 @@ mod tests {
         assert_eq!(expected_parsing, parsing);
     }
 
+    #[test]
+    fn test_parse_intent_with_pattern_templates() {
+        // Given
+        let mut model = build_sample_model(
+            hashmap![
+                "intent1" => vec![r"^\s*foo\s*bar\s*baz\s*$"],
+            ],
+            hashmap![],
+            hashmap![
+                "intent1" => hashmap![],
+                "intent2" => hashmap![],
+            ],
+            false,
+            hashmap![],
+        );
+        model.pattern_templates = hashmap![
+            "intent2".to_string() => vec!["foo [bar] (ban|qux)".to_string()],
+        ];
+        let shared_resources = Arc::new(SharedResourcesBuilder::default().build());
+        let parser = DeterministicIntentParser::new(model, shared_resources).unwrap();
+
+        // When
+        let parsing = parser.parse("foo qux", None).unwrap();
+        let patterns = parser.patterns();
+
+        // Then
+        assert_eq!(Some("intent2".to_string()), parsing.intent.intent_name);
+        assert_eq!(1, patterns["intent2"].len());
+    }
+
     #[test]
     fn test_parse_intent_with_filter() {
         // Given
@@ -600,6 +1024,137 @@ mod tests {
         assert_eq!(expected_parsing, parsing);
     }
 
+    #[test]
+    fn test_matching_patterns() {
+        // Given
+        let model = build_sample_model(
+            hashmap![
+                "intent1" => vec![r"^\s*foo\s*bar\s*baz\s*$", r"^\s*foo\s*bar\s*$"],
+                "intent2" => vec![r"^\s*foo\s*bar\s*ban\s*$", r"^\s*foo\s*.*$"],
+            ],
+            hashmap![],
+            hashmap![
+                "intent1" => hashmap![],
+                "intent2" => hashmap![],
+            ],
+            false,
+            hashmap![],
+        );
+        let shared_resources = Arc::new(SharedResourcesBuilder::default().build());
+        let parser = DeterministicIntentParser::new(model, shared_resources).unwrap();
+
+        // When
+        let matching_patterns = parser.matching_patterns("foo bar").unwrap();
+
+        // Then
+        let expected_matching_patterns =
+            vec![("intent1".to_string(), 1), ("intent2".to_string(), 1)];
+        assert_eq!(expected_matching_patterns, matching_patterns);
+    }
+
+    #[test]
+    fn test_regex_limits() {
+        // Given
+        let model = || {
+            build_sample_model(
+                hashmap![
+                    "intent1" => vec![r"^\s*foo\s*bar\s*baz\s*$", r"^\s*\w{100}\s*$"],
+                    "intent2" => vec![r"^\s*foo\s*bar\s*ban\s*$"],
+                ],
+                hashmap![],
+                hashmap![
+                    "intent1" => hashmap![],
+                    "intent2" => hashmap![],
+                ],
+                false,
+                hashmap![],
+            )
+        };
+        let shared_resources = Arc::new(SharedResourcesBuilder::default().build());
+        let small_size_limit = RegexLimits {
+            size_limit: 10_000,
+            ..RegexLimits::default()
+        };
+        let small_max_patterns = RegexLimits {
+            max_patterns: 2,
+            ..RegexLimits::default()
+        };
+
+        // When
+        let size_limit_error = DeterministicIntentParser::new_with_regex_limits(
+            model(),
+            shared_resources.clone(),
+            &small_size_limit,
+        )
+        .err()
+        .unwrap();
+        let max_patterns_error = DeterministicIntentParser::new_with_regex_limits(
+            model(),
+            shared_resources.clone(),
+            &small_max_patterns,
+        )
+        .err()
+        .unwrap();
+        let parser = DeterministicIntentParser::new(model(), shared_resources);
+
+        // Then
+        match size_limit_error.downcast::<SnipsNluError>().unwrap() {
+            SnipsNluError::RegexLimitExceeded { intent, .. } => assert_eq!("intent1", &intent),
+            error => panic!("Unexpected error: {}", error),
+        }
+        match max_patterns_error.downcast::<SnipsNluError>().unwrap() {
+            SnipsNluError::TooManyPatterns { nb_patterns, limit } => {
+                assert_eq!((3, 2), (nb_patterns, limit))
+            }
+            error => panic!("Unexpected error: {}", error),
+        }
+        assert!(parser.is_ok());
+    }
+
+    #[test]
+    fn test_lazy_regex_compilation() {
+        // Given
+        let model = build_sample_model(
+            hashmap![
+                "intent1" => vec![r"^\s*foo\s*bar\s*baz\s*$", r"^\s*\w{100}\s*$"],
+                "intent2" => vec![r"^\s*foo\s*bar\s*ban\s*$"],
+            ],
+            hashmap![],
+            hashmap![
+                "intent1" => hashmap![],
+                "intent2" => hashmap![],
+            ],
+            false,
+            hashmap![],
+        );
+        let shared_resources = Arc::new(SharedResourcesBuilder::default().build());
+        let config = EngineConfig {
+            regex_limits: RegexLimits {
+                size_limit: 10_000,
+                ..RegexLimits::default()
+            },
+            lazy_regex_compilation: true,
+            ..EngineConfig::default()
+        };
+
+        // When
+        let parser =
+            DeterministicIntentParser::new_with_config(model, shared_resources, &config).unwrap();
+        let parsing = parser.parse("foo bar ban", Some(&["intent2"])).unwrap();
+        let size_limit_error = parser.parse("foo bar baz", None).err().unwrap();
+
+        // Then
+        let expected_intent = IntentClassifierResult {
+            intent_name: Some("intent2".to_string()),
+            confidence_score: 1.0,
+        };
+        assert_eq!(expected_intent, parsing.intent);
+        match size_limit_error.downcast::<SnipsNluError>().unwrap() {
+            SnipsNluError::RegexLimitExceeded { intent, .. } => assert_eq!("intent1", &intent),
+            error => panic!("Unexpected error: {}", error),
+        }
+    }
+
     #[test]
     fn test_parse_intent_with_stop_words() {
         // Given
@@ -700,12 +1255,14 @@ mod tests {
                     char_range: 8..11,
                     entity: "snips/number".to_string(),
                     slot_name: "number".to_string(),
+                    confidence_score: None,
                 },
                 InternalSlot {
                     value: "one".to_string(),
                     char_range: 17..20,
                     entity: "snips/number".to_string(),
                     slot_name: "number".to_string(),
+                    confidence_score: None,
                 },
             ],
         };
@@ -988,24 +1545,28 @@ mod tests {
                     char_range: 13..17,
                     entity: "name".to_string(),
                     slot_name: "name".to_string(),
+                    confidence_score: None,
                 },
                 InternalSlot {
                     value: "Snips".to_string(),
                     char_range: 21..26,
                     entity: "location".to_string(),
                     slot_name: "location".to_string(),
+                    confidence_score: None,
                 },
                 InternalSlot {
                     value: "this afternoon".to_string(),
                     char_range: 34..48,
                     entity: "snips/datetime".to_string(),
                     slot_name: "time".to_string(),
+                    confidence_score: None,
                 },
                 InternalSlot {
                     value: "tomorrow".to_string(),
                     char_range: 52..60,
                     entity: "snips/datetime".to_string(),
                     slot_name: "time".to_string(),
+                    confidence_score: None,
                 },
             ],
         };
@@ -1082,6 +1643,7 @@ mod tests {
                 char_range: 7..11,
                 entity: "object".to_string(),
                 slot_name: "object".to_string(),
+                confidence_score: None,
             }],
         };
 
@@ -1220,6 +1782,212 @@ mod tests {
             char_range: 11..15,
             entity: "name".to_string(),
             slot_name: "name".to_string(),
+            confidence_score: None,
+        }];
+        assert_eq!(expected_slots, slots);
+    }
+
+    #[test]
+    fn test_parse_slots_with_pattern_normalization() {
+        // Given
+        let text = "Reserve chez Jose\u{301}";
+        let model = build_sample_model(
+            hashmap!["booking" => vec![r"^\s*réserve\s*chez\s*(?P<group0>%NAME%)\s*$"]],
+            hashmap!["group0" => "name"],
+            hashmap!["booking" => hashmap!["name" => "name"]],
+            false,
+            hashmap![],
+        );
+        let mocked_custom_entity_parser = MockedCustomEntityParser::from_iter(vec![(
+            text.to_string(),
+            vec![CustomEntity {
+                value: "Jose\u{301}".to_string(),
+                resolved_value: "José".to_string(),
+                alternative_resolved_values: vec![],
+                range: 13..18,
+                entity_identifier: "name".to_string(),
+            }],
+        )]);
+        let shared_resources = Arc::new(
+            SharedResourcesBuilder::default()
+                .custom_entity_parser(mocked_custom_entity_parser)
+                .build(),
+        );
+        let config = EngineConfig {
+            pattern_normalization: PatternNormalization {
+                lowercase: true,
+                strip_accents: true,
+            },
+            ..EngineConfig::default()
+        };
+        let parser =
+            DeterministicIntentParser::new_with_config(model, shared_resources, &config).unwrap();
+
+        // When
+        let result = parser.parse(text, None).unwrap();
+
+        // Then
+        let expected_slots = vec![InternalSlot {
+            value: "Jose\u{301}".to_string(),
+            char_range: 13..18,
+            entity: "name".to_string(),
+            slot_name: "name".to_string(),
+            confidence_score: None,
+        }];
+        assert_eq!(Some("booking".to_string()), result.intent.intent_name);
+        assert_eq!(expected_slots, result.slots);
+    }
+
+    #[test]
+    fn test_parse_slots_with_fuzzy_patterns() {
+        // Given
+        let text = "turn onn the kitchen light";
+        let model = || {
+            build_sample_model(
+                hashmap!["turnLightOn" => vec![r"^\s*turn\s*on\s*the\s*(?P<group0>%ROOM%)\s*lights\s*$"]],
+                hashmap!["group0" => "room"],
+                hashmap!["turnLightOn" => hashmap!["room" => "room"]],
+                false,
+                hashmap![],
+            )
+        };
+        let mocked_custom_entity_parser = MockedCustomEntityParser::from_iter(vec![(
+            text.to_string(),
+            vec![CustomEntity {
+                value: "kitchen".to_string(),
+                resolved_value: "kitchen".to_string(),
+                alternative_resolved_values: vec![],
+                range: 13..20,
+                entity_identifier: "room".to_string(),
+            }],
+        )]);
+        let shared_resources = Arc::new(
+            SharedResourcesBuilder::default()
+                .custom_entity_parser(mocked_custom_entity_parser)
+                .build(),
+        );
+        let config = EngineConfig {
+            fuzzy_patterns: hashmap!["turnLightOn".to_string() => 1],
+            ..EngineConfig::default()
+        };
+        let exact_parser =
+            DeterministicIntentParser::new(model(), shared_resources.clone()).unwrap();
+        let fuzzy_parser =
+            DeterministicIntentParser::new_with_config(model(), shared_resources, &config).unwrap();
+
+        // When
+        let exact_result = exact_parser.parse(text, None).unwrap();
+        let fuzzy_result = fuzzy_parser.parse(text, None).unwrap();
+
+        // Then
+        let expected_slots = vec![InternalSlot {
+            value: "kitchen".to_string(),
+            char_range: 13..20,
+            entity: "room".to_string(),
+            slot_name: "room".to_string(),
+            confidence_score: None,
+        }];
+        assert_eq!(None, exact_result.intent.intent_name);
+        assert_eq!(
+            Some("turnLightOn".to_string()),
+            fuzzy_result.intent.intent_name
+        );
+        assert_eq!(expected_slots, fuzzy_result.slots);
+    }
+
+    #[test]
+    fn test_parse_slots_with_builtin_entity_scopes() {
+        // Given
+        let text = "set the volume to five";
+        let model = || {
+            build_sample_model(
+                hashmap!["setVolume" => vec![r"^\s*set\s*the\s*volume\s*to\s*(?P<group0>%SNIPSNUMBER%)\s*$"]],
+                hashmap!["group0" => "volume"],
+                hashmap!["setVolume" => hashmap!["volume" => "snips/number"]],
+                false,
+                hashmap![],
+            )
+        };
+        let mocked_builtin_entity_parser = MockedBuiltinEntityParser::from_iter(vec![(
+            text.to_string(),
+            vec![BuiltinEntity {
+                value: "five".to_string(),
+                range: 18..22,
+                entity: SlotValue::Number(NumberValue { value: 5. }),
+                alternatives: vec![],
+                entity_kind: BuiltinEntityKind::Number,
+            }],
+        )]);
+        let shared_resources = Arc::new(
+            SharedResourcesBuilder::default()
+                .builtin_entity_parser(mocked_builtin_entity_parser)
+                .build(),
+        );
+        let config = EngineConfig {
+            builtin_entity_scopes: hashmap!["setVolume".to_string() => vec![]],
+            ..EngineConfig::default()
+        };
+        let parser = DeterministicIntentParser::new(model(), shared_resources.clone()).unwrap();
+        let scoped_parser =
+            DeterministicIntentParser::new_with_config(model(), shared_resources, &config).unwrap();
+
+        // When
+        let result = parser.parse(text, None).unwrap();
+        let scoped_result = scoped_parser.parse(text, None).unwrap();
+
+        // Then
+        let expected_slots = vec![InternalSlot {
+            value: "five".to_string(),
+            char_range: 18..22,
+            entity: "snips/number".to_string(),
+            slot_name: "volume".to_string(),
+            confidence_score: None,
+        }];
+        assert_eq!(Some("setVolume".to_string()), result.intent.intent_name);
+        assert_eq!(expected_slots, result.slots);
+        assert_eq!(None, scoped_result.intent.intent_name);
+    }
+
+    #[test]
+    fn test_parse_slots_with_astral_plane_chars() {
+        // Given
+        let text = "Hello 🎉𝄞 John 😀";
+
+        let model = build_sample_model(
+            hashmap!["greeting" => vec![r"^\s*hello\s*\S*\s*(?P<group0>%NAME%)\s*\S*\s*$"]],
+            hashmap!["group0" => "name"],
+            hashmap!["greeting" => hashmap!["name" => "name"]],
+            true,
+            hashmap![],
+        );
+
+        let mocked_custom_entity_parser = MockedCustomEntityParser::from_iter(vec![(
+            text.to_string(),
+            vec![CustomEntity {
+                value: "John".to_string(),
+                resolved_value: "John".to_string(),
+                alternative_resolved_values: vec![],
+                range: 9..13,
+                entity_identifier: "name".to_string(),
+            }],
+        )]);
+        let shared_resources = Arc::new(
+            SharedResourcesBuilder::default()
+                .custom_entity_parser(mocked_custom_entity_parser)
+                .build(),
+        );
+        let parser = DeterministicIntentParser::new(model, shared_resources).unwrap();
+
+        // When
+        let slots = parser.parse(text, None).unwrap().slots;
+
+        // Then
+        let expected_slots = vec![InternalSlot {
+            value: "John".to_string(),
+            char_range: 9..13,
+            entity: "name".to_string(),
+            slot_name: "name".to_string(),
+            confidence_score: None,
         }];
         assert_eq!(expected_slots, slots);
     }
@@ -1268,6 +2036,7 @@ mod tests {
                 char_range: 13..26,
                 entity: "name".to_string(),
                 slot_name: "name".to_string(),
+                confidence_score: None,
             }],
         };
 
@@ -1320,12 +2089,212 @@ mod tests {
             char_range: 6..10,
             entity: "name".to_string(),
             slot_name: "name".to_string(),
+            confidence_score: None,
         }];
 
         assert_eq!(expected_slots_1, slots_1);
         assert_eq!(Vec::<InternalSlot>::new(), slots_2);
     }
 
+    #[test]
+    fn test_get_intents_with_ties() {
+        // Given
+        let model = build_sample_model(
+            hashmap![
+                "intent_b" => vec![r"^\s*hello\s*$"],
+                "intent_a" => vec![r"^\s*hello\s*$"],
+                "intent_d" => vec![r"^\s*bye\s*$"],
+                "intent_c" => vec![r"^\s*bye\s*$"],
+            ],
+            hashmap![],
+            hashmap![
+                "intent_a" => hashmap![],
+                "intent_b" => hashmap![],
+                "intent_c" => hashmap![],
+                "intent_d" => hashmap![],
+            ],
+            false,
+            hashmap![],
+        );
+        let shared_resources = Arc::new(SharedResourcesBuilder::default().build());
+        let parser = DeterministicIntentParser::new(model, shared_resources).unwrap();
+
+        // When
+        let intents: Vec<Option<String>> = parser
+            .get_intents("hello")
+            .unwrap()
+            .into_iter()
+            .map(|res| res.intent_name)
+            .collect();
+
+        // Then
+        let expected_intents = vec![
+            Some("intent_a".to_string()),
+            Some("intent_b".to_string()),
+            Some("intent_c".to_string()),
+            Some("intent_d".to_string()),
+            None,
+        ];
+        assert_eq!(expected_intents, intents);
+    }
+
+    #[test]
+    fn test_parse_input_containing_placeholders() {
+        // Given
+        let model = build_sample_model(
+            hashmap!["greeting" => vec![r"^\s*hello\s*(?P<group0>%NAME%)\s*$"]],
+            hashmap!["group0" => "name"],
+            hashmap!["greeting" => hashmap!["name" => "name"]],
+            false,
+            hashmap![],
+        );
+        let shared_resources = Arc::new(SharedResourcesBuilder::default().build());
+        let parser = DeterministicIntentParser::new(model, shared_resources).unwrap();
+
+        // When
+        let result = parser.parse("Hello %NAME%", None).unwrap();
+
+        // Then
+        assert_eq!(None, result.intent.intent_name);
+        assert!(result.slots.is_empty());
+    }
+
+    #[test]
+    fn test_parse_with_custom_placeholder_delimiter() {
+        // Given
+        let text = "Hello John";
+        let mut model = build_sample_model(
+            hashmap!["greeting" => vec![r"^\s*hello\s*(?P<group0>§NAME§)\s*$"]],
+            hashmap!["group0" => "name"],
+            hashmap!["greeting" => hashmap!["name" => "name"]],
+            false,
+            hashmap![],
+        );
+        model.config.placeholder_delimiter = '§';
+        let mocked_custom_entity_parser = MockedCustomEntityParser::from_iter(vec![(
+            text.to_string(),
+            vec![CustomEntity {
+                value: "John".to_string(),
+                resolved_value: "John".to_string(),
+                alternative_resolved_values: vec![],
+                range: 6..10,
+                entity_identifier: "name".to_string(),
+            }],
+        )]);
+        let shared_resources = Arc::new(
+            SharedResourcesBuilder::default()
+                .custom_entity_parser(mocked_custom_entity_parser)
+                .build(),
+        );
+        let parser = DeterministicIntentParser::new(model, shared_resources).unwrap();
+
+        // When
+        let result = parser.parse(text, None).unwrap();
+        let placeholder_result = parser.parse("Hello §NAME§", None).unwrap();
+
+        // Then
+        let expected_slots = vec![InternalSlot {
+            value: "John".to_string(),
+            char_range: 6..10,
+            entity: "name".to_string(),
+            slot_name: "name".to_string(),
+            confidence_score: None,
+        }];
+        assert_eq!(Some("greeting".to_string()), result.intent.intent_name);
+        assert_eq!(expected_slots, result.slots);
+        assert_eq!(None, placeholder_result.intent.intent_name);
+    }
+
+    #[test]
+    fn test_parse_with_placeholder_prefix_and_literal_delimiters() {
+        // Given
+        let text = "Hello John 100%";
+        let mut model = build_sample_model(
+            hashmap!["greeting" => vec![r"^\s*hello\s*(?P<group0>%ENT_NAME%)\s*100%\s*$"]],
+            hashmap!["group0" => "name"],
+            hashmap!["greeting" => hashmap!["name" => "name"]],
+            false,
+            hashmap![],
+        );
+        model.config.placeholder_prefix = "ENT_".to_string();
+        let mocked_custom_entity_parser = MockedCustomEntityParser::from_iter(vec![(
+            text.to_string(),
+            vec![CustomEntity {
+                value: "John".to_string(),
+                resolved_value: "John".to_string(),
+                alternative_resolved_values: vec![],
+                range: 6..10,
+                entity_identifier: "name".to_string(),
+            }],
+        )]);
+        let shared_resources = Arc::new(
+            SharedResourcesBuilder::default()
+                .custom_entity_parser(mocked_custom_entity_parser)
+                .build(),
+        );
+        let parser = DeterministicIntentParser::new(model, shared_resources).unwrap();
+
+        // When
+        let result = parser.parse(text, None).unwrap();
+        let placeholder_result = parser.parse("Hello %ent_name% 100%", None).unwrap();
+        let unprefixed_placeholder_result = parser.parse("Hello %NAME% 100%", None).unwrap();
+
+        // Then
+        let expected_slots = vec![InternalSlot {
+            value: "John".to_string(),
+            char_range: 6..10,
+            entity: "name".to_string(),
+            slot_name: "name".to_string(),
+            confidence_score: None,
+        }];
+        assert_eq!(Some("greeting".to_string()), result.intent.intent_name);
+        assert_eq!(expected_slots, result.slots);
+        assert_eq!(None, placeholder_result.intent.intent_name);
+        assert_eq!(None, unprefixed_placeholder_result.intent.intent_name);
+    }
+
+    #[test]
+    fn test_neutralize_placeholders() {
+        // Given
+        let texts = vec![
+            "50% off",
+            "Hello %NAME%",
+            "%name% and %Name_2%",
+            "100%% sure%",
+            "%%NAME%%",
+            "%ÉTÉ%",
+            "%A%B%",
+        ];
+        let prefixed_texts = vec!["%NAME% %ent_NAME%", "%ENT_% %ENT%"];
+
+        // When
+        let neutralized_texts: Vec<String> = texts
+            .iter()
+            .map(|text| neutralize_placeholders(text, '%', ""))
+            .collect();
+        let neutralized_prefixed_texts: Vec<String> = prefixed_texts
+            .iter()
+            .map(|text| neutralize_placeholders(text, '%', "ENT_"))
+            .collect();
+
+        // Then
+        let expected_texts = vec![
+            "50% off",
+            "Hello  NAME ",
+            " name  and  Name_2 ",
+            "100%% sure%",
+            "% NAME %",
+            " ÉTÉ ",
+            " A B%",
+        ];
+        let expected_prefixed_texts = vec!["%NAME%  ent_NAME ", "%ENT_% %ENT%"];
+        assert_eq!(expected_texts, neutralized_texts);
+        assert_eq!(expected_prefixed_texts, neutralized_prefixed_texts);
+        for (text, neutralized_text) in texts.iter().zip(neutralized_texts.iter()) {
+            assert_eq!(text.chars().count(), neutralized_text.chars().count());
+        }
+    }
+
     #[test]
     fn test_deduplicate_overlapping_slots() {
         // Given
@@ -1336,24 +2305,28 @@ mod tests {
                 char_range: 0..3,
                 entity: "e1".to_string(),
                 slot_name: "s1".to_string(),
+                confidence_score: None,
             },
             InternalSlot {
                 value: "loco".to_string(),
                 char_range: 4..8,
                 entity: "e1".to_string(),
                 slot_name: "s2".to_string(),
+                confidence_score: None,
             },
             InternalSlot {
                 value: "kid loco".to_string(),
                 char_range: 0..8,
                 entity: "e1".to_string(),
                 slot_name: "s3".to_string(),
+                confidence_score: None,
             },
             InternalSlot {
                 value: "song".to_string(),
                 char_range: 9..13,
                 entity: "e2".to_string(),
                 slot_name: "s4".to_string(),
+                confidence_score: None,
             },
         ];
 
@@ -1367,12 +2340,14 @@ mod tests {
                 char_range: 0..8,
                 entity: "e1".to_string(),
                 slot_name: "s3".to_string(),
+                confidence_score: None,
             },
             InternalSlot {
                 value: "song".to_string(),
                 char_range: 9..13,
                 entity: "e2".to_string(),
                 slot_name: "s4".to_string(),
+                confidence_score: None,
             },
         ];
         assert_eq!(deduplicated_slots, expected_slots);
@@ -1398,8 +2373,9 @@ mod tests {
         ];
 
         // When
-        let (range_mapping, formatted_text) =
-            replace_entities(text, entities, get_entity_placeholder);
+        let (range_mapping, formatted_text) = replace_entities(text, entities, |label| {
+            get_entity_placeholder(label, '%', "")
+        });
 
         // Then
         let expected_mapping = HashMap::from_iter(vec![(0..14, 0..9), (24..42, 19..28)]);
@@ -1415,7 +2391,7 @@ mod tests {
         let entity_label = "snips/datetime";
 
         // When
-        let formatted_label = get_entity_placeholder(entity_label);
+        let formatted_label = get_entity_placeholder(entity_label, '%', "");
 
         // Then
         assert_eq!("%SNIPSDATETIME%", &formatted_label)
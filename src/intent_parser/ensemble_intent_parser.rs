@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use snips_nlu_ontology::IntentClassifierResult;
+
+use super::{IntentParser, InternalParsingResult};
+use crate::cancellation::check_cancellation;
+use crate::errors::*;
+use crate::intent_classifier::ClassIntercept;
+use crate::slot_filler::SlotsHypothesis;
+use crate::slot_utils::InternalSlot;
+use crate::utils::{is_blank_input, IntentName};
+
+/// Intent parser which runs all its parsers and fuses their intent scores
+///
+/// Unlike the cascade of the engine, in which the first parser returning an intent wins, the
+/// score of each intent is the weighted average of the scores given by all the parsers. This
+/// prevents a parser which over-matches from hiding the results of the other ones.
+pub struct EnsembleParser {
+    parsers: Vec<(Box<dyn IntentParser>, f32)>,
+}
+
+impl Default for EnsembleParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnsembleParser {
+    pub fn new() -> Self {
+        Self { parsers: vec![] }
+    }
+
+    /// Registers a parser, whose scores are multiplied by the weight when fused
+    pub fn with_parser(mut self, parser: Box<dyn IntentParser>, weight: f32) -> Self {
+        self.parsers.push((parser, weight));
+        self
+    }
+
+    /// Fused results, along with the parsers' weighted scores of each intent, sorted by
+    /// decreasing fused score
+    fn fuse_intents(
+        &self,
+        input: &str,
+    ) -> Result<Vec<(IntentClassifierResult, Vec<(usize, f32)>)>> {
+        let total_weight: f32 = self.parsers.iter().map(|(_, weight)| *weight).sum();
+        let mut weighted_scores: HashMap<Option<IntentName>, Vec<(usize, f32)>> = HashMap::new();
+        for (parser_idx, (parser, weight)) in self.parsers.iter().enumerate() {
+            check_cancellation()?;
+            for result in parser.get_intents(input)? {
+                weighted_scores
+                    .entry(result.intent_name)
+                    .or_insert_with(Vec::new)
+                    .push((parser_idx, weight * result.confidence_score));
+            }
+        }
+        Ok(weighted_scores
+            .into_iter()
+            .map(|(intent_name, scores)| {
+                let weighted_sum: f32 = scores.iter().map(|(_, score)| *score).sum();
+                let confidence_score = if total_weight > 0.0 {
+                    weighted_sum / total_weight
+                } else {
+                    0.0
+                };
+                let intent = IntentClassifierResult {
+                    intent_name,
+                    confidence_score,
+                };
+                (intent, scores)
+            })
+            .sorted_by(|(a, _), (b, _)| {
+                b.confidence_score
+                    .partial_cmp(&a.confidence_score)
+                    .unwrap()
+                    .then_with(|| a.intent_name.cmp(&b.intent_name))
+            })
+            .collect())
+    }
+
+    /// Slots extracted by the first parser returning some, parsers being tried in the given
+    /// order
+    fn get_slots_by_priority<I>(
+        &self,
+        input: &str,
+        intent: &str,
+        parsers: I,
+    ) -> Result<Vec<InternalSlot>>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        for parser_idx in parsers {
+            let slots = self.parsers[parser_idx].0.get_slots(input, intent)?;
+            if !slots.is_empty() {
+                return Ok(slots);
+            }
+        }
+        Ok(vec![])
+    }
+}
+
+impl IntentParser for EnsembleParser {
+    fn parse(
+        &self,
+        input: &str,
+        intents_whitelist: Option<&[&str]>,
+    ) -> Result<InternalParsingResult> {
+        if is_blank_input(input) {
+            return Ok(InternalParsingResult::empty());
+        }
+        let top_result = self.fuse_intents(input)?.into_iter().find(|(result, _)| {
+            match (&result.intent_name, intents_whitelist) {
+                (Some(name), Some(whitelist)) => whitelist.contains(&&**name),
+                _ => true,
+            }
+        });
+        let (intent, mut scores) = match top_result {
+            Some(top_result) => top_result,
+            None => return Ok(InternalParsingResult::empty()),
+        };
+        let slots = if let Some(name) = intent.intent_name.as_ref() {
+            scores.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
+            let parsers = scores.into_iter().map(|(parser_idx, _)| parser_idx);
+            self.get_slots_by_priority(input, name, parsers)?
+        } else {
+            vec![]
+        };
+        Ok(InternalParsingResult { intent, slots })
+    }
+
+    fn get_intents(&self, input: &str) -> Result<Vec<IntentClassifierResult>> {
+        Ok(self
+            .fuse_intents(input)?
+            .into_iter()
+            .map(|(result, _)| result)
+            .collect())
+    }
+
+    fn get_slots(&self, input: &str, intent: &str) -> Result<Vec<InternalSlot>> {
+        let parsers = (0..self.parsers.len())
+            .sorted_by(|a, b| self.parsers[*b].1.partial_cmp(&self.parsers[*a].1).unwrap());
+        self.get_slots_by_priority(input, intent, parsers)
+    }
+
+    fn get_slots_nbest(&self, input: &str, intent: &str, n: usize) -> Result<Vec<SlotsHypothesis>> {
+        let parsers = (0..self.parsers.len())
+            .sorted_by(|a, b| self.parsers[*b].1.partial_cmp(&self.parsers[*a].1).unwrap());
+        for parser_idx in parsers {
+            let hypotheses = self.parsers[parser_idx]
+                .0
+                .get_slots_nbest(input, intent, n)?;
+            if hypotheses
+                .iter()
+                .any(|hypothesis| !hypothesis.slots.is_empty())
+            {
+                return Ok(hypotheses);
+            }
+        }
+        Ok(vec![])
+    }
+
+    fn intent_classifier_intercepts(&self) -> Result<Vec<ClassIntercept>> {
+        let mut intercepts = vec![];
+        for (parser, _) in self.parsers.iter() {
+            intercepts.extend(parser.intent_classifier_intercepts()?);
+        }
+        Ok(intercepts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockedIntentParser {
+        intents: Vec<(Option<&'static str>, f32)>,
+        slots: Vec<InternalSlot>,
+    }
+
+    impl IntentParser for MockedIntentParser {
+        fn parse(
+            &self,
+            _input: &str,
+            intents_whitelist: Option<&[&str]>,
+        ) -> Result<InternalParsingResult> {
+            let best_intent = self
+                .intents
+                .iter()
+                .filter(|(name, _)| match (name, intents_whitelist) {
+                    (Some(name), Some(whitelist)) => whitelist.contains(name),
+                    _ => true,
+                })
+                .max_by(|(_, score), (_, other_score)| score.partial_cmp(other_score).unwrap());
+            Ok(match best_intent {
+                Some((Some(name), score)) => InternalParsingResult {
+                    intent: IntentClassifierResult {
+                        intent_name: Some(name.to_string()),
+                        confidence_score: *score,
+                    },
+                    slots: self.slots.clone(),
+                },
+                _ => InternalParsingResult::empty(),
+            })
+        }
+
+        fn get_intents(&self, _input: &str) -> Result<Vec<IntentClassifierResult>> {
+            Ok(self
+                .intents
+                .iter()
+                .map(|(name, score)| IntentClassifierResult {
+                    intent_name: name.map(|name| name.to_string()),
+                    confidence_score: *score,
+                })
+                .collect())
+        }
+
+        fn get_slots(&self, _input: &str, _intent: &str) -> Result<Vec<InternalSlot>> {
+            Ok(self.slots.clone())
+        }
+    }
+
+    fn slot(value: &str) -> InternalSlot {
+        InternalSlot {
+            value: value.to_string(),
+            char_range: 0..value.chars().count(),
+            entity: "entity".to_string(),
+            slot_name: "slot".to_string(),
+            confidence_score: None,
+        }
+    }
+
+    fn build_ensemble_parser() -> EnsembleParser {
+        let over_matching_parser = MockedIntentParser {
+            intents: vec![(Some("intent1"), 1.0), (Some("intent2"), 0.0), (None, 0.0)],
+            slots: vec![slot("deterministic")],
+        };
+        let probabilistic_parser = MockedIntentParser {
+            intents: vec![
+                (Some("intent2"), 0.9),
+                (Some("intent1"), 0.05),
+                (None, 0.05),
+            ],
+            slots: vec![slot("probabilistic")],
+        };
+        EnsembleParser::new()
+            .with_parser(Box::new(over_matching_parser), 1.0)
+            .with_parser(Box::new(probabilistic_parser), 2.0)
+    }
+
+    #[test]
+    fn test_parse_with_fused_scores() {
+        // Given
+        let parser = build_ensemble_parser();
+
+        // When
+        let result = parser.parse("hello", None).unwrap();
+
+        // Then
+        assert_eq!(Some("intent2".to_string()), result.intent.intent_name);
+        assert!((result.intent.confidence_score - 0.6).abs() < 1e-6);
+        assert_eq!(vec![slot("probabilistic")], result.slots);
+    }
+
+    #[test]
+    fn test_parse_with_whitelist() {
+        // Given
+        let parser = build_ensemble_parser();
+
+        // When
+        let result = parser.parse("hello", Some(&["intent1"])).unwrap();
+
+        // Then
+        assert_eq!(Some("intent1".to_string()), result.intent.intent_name);
+        assert_eq!(vec![slot("deterministic")], result.slots);
+    }
+
+    #[test]
+    fn test_get_intents_with_fused_scores() {
+        // Given
+        let parser = build_ensemble_parser();
+
+        // When
+        let intents: Vec<Option<String>> = parser
+            .get_intents("hello")
+            .unwrap()
+            .into_iter()
+            .map(|result| result.intent_name)
+            .collect();
+
+        // Then
+        let expected_intents = vec![
+            Some("intent2".to_string()),
+            Some("intent1".to_string()),
+            None,
+        ];
+        assert_eq!(expected_intents, intents);
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::BTreeSet;
+
+use crate::normalization_dictionary::NormalizedInput;
+
+/// Corrector of the misspelled words of an input, which replaces each word absent from the
+/// patterns of an intent by the closest word of these patterns, within a bounded edit distance
+pub(crate) struct FuzzyMatcher {
+    vocabulary: BTreeSet<String>,
+    max_edit_distance: usize,
+    placeholder_delimiter: char,
+}
+
+impl FuzzyMatcher {
+    pub fn new(patterns: &[String], placeholder_delimiter: char, max_edit_distance: usize) -> Self {
+        let vocabulary = patterns
+            .iter()
+            .flat_map(|pattern| pattern_words(pattern, placeholder_delimiter))
+            .collect();
+        Self {
+            vocabulary,
+            max_edit_distance,
+            placeholder_delimiter,
+        }
+    }
+
+    /// Returns the corrected input, along with the mapping of its char ranges to the ones of
+    /// the input, or `None` when no word needs to be corrected
+    ///
+    /// Entity placeholders are left untouched, as well as the words which are not longer than
+    /// the maximum edit distance.
+    pub fn correct(&self, input: &str) -> Option<NormalizedInput> {
+        let chars: Vec<char> = input.chars().collect();
+        let mut corrected_input = String::with_capacity(input.len());
+        let mut segments = vec![];
+        let mut corrected_offset = 0;
+        let mut offset = 0;
+        while offset < chars.len() {
+            if !chars[offset].is_alphanumeric() {
+                corrected_input.push(chars[offset]);
+                corrected_offset += 1;
+                offset += 1;
+                continue;
+            }
+            let word_end = offset
+                + chars[offset..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric())
+                    .count();
+            let word: String = chars[offset..word_end].iter().collect();
+            let is_placeholder = (offset > 0 && chars[offset - 1] == self.placeholder_delimiter)
+                || chars.get(word_end) == Some(&self.placeholder_delimiter);
+            let correction = if is_placeholder {
+                None
+            } else {
+                self.closest_word(&word)
+            };
+            match correction {
+                Some(correction) => {
+                    let correction_length = correction.chars().count();
+                    segments.push((
+                        corrected_offset..corrected_offset + correction_length,
+                        offset..word_end,
+                    ));
+                    corrected_input.push_str(correction);
+                    corrected_offset += correction_length;
+                }
+                None => {
+                    corrected_input.push_str(&word);
+                    corrected_offset += word_end - offset;
+                }
+            }
+            offset = word_end;
+        }
+        if segments.is_empty() {
+            None
+        } else {
+            Some(NormalizedInput::from_segments(corrected_input, segments))
+        }
+    }
+
+    /// Returns the closest word of the patterns, ties being broken alphabetically, when the
+    /// word is not part of the patterns
+    fn closest_word(&self, word: &str) -> Option<&str> {
+        let word = word.to_lowercase();
+        let word_chars: Vec<char> = word.chars().collect();
+        if word_chars.len() <= self.max_edit_distance || self.vocabulary.contains(&word) {
+            return None;
+        }
+        self.vocabulary
+            .iter()
+            .filter_map(|candidate| {
+                let candidate_chars: Vec<char> = candidate.chars().collect();
+                bounded_edit_distance(&word_chars, &candidate_chars, self.max_edit_distance)
+                    .map(|distance| (distance, candidate))
+            })
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, candidate)| &**candidate)
+    }
+}
+
+/// Extracts the lowercased literal words of a pattern, skipping the regex escapes, the names
+/// of the groups and the entity placeholders
+fn pattern_words(pattern: &str, placeholder_delimiter: char) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut in_placeholder = false;
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() {
+            if !in_placeholder {
+                word.extend(c.to_lowercase());
+            }
+            continue;
+        }
+        if !word.is_empty() {
+            words.push(word.clone());
+            word.clear();
+        }
+        if c == placeholder_delimiter {
+            in_placeholder = !in_placeholder;
+        } else if c == '\\' {
+            chars.next();
+        } else if c == '(' && chars.peek() == Some(&'?') {
+            // Skips the group name, e.g. "(?P<group_1>"
+            chars.next();
+            if chars.peek() == Some(&'P') {
+                for c in &mut chars {
+                    if c == '>' {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Levenshtein distance between two words, or `None` when it exceeds `max_distance`
+///
+/// The computation stops as soon as all the prefixes of the first word are too far from the
+/// second one, so that distant words are discarded quickly.
+fn bounded_edit_distance(lhs: &[char], rhs: &[char], max_distance: usize) -> Option<usize> {
+    if lhs.len().max(rhs.len()) - lhs.len().min(rhs.len()) > max_distance {
+        return None;
+    }
+    let mut previous_row: Vec<usize> = (0..=rhs.len()).collect();
+    for (i, lhs_char) in lhs.iter().enumerate() {
+        let mut current_row = vec![i + 1; rhs.len() + 1];
+        for (j, rhs_char) in rhs.iter().enumerate() {
+            let substitution_cost = if lhs_char == rhs_char { 0 } else { 1 };
+            current_row[j + 1] = (previous_row[j] + substitution_cost)
+                .min(previous_row[j + 1] + 1)
+                .min(current_row[j] + 1);
+        }
+        if current_row.iter().all(|distance| *distance > max_distance) {
+            return None;
+        }
+        previous_row = current_row;
+    }
+    Some(previous_row[rhs.len()]).filter(|distance| *distance <= max_distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_words() {
+        // Given
+        let pattern = r"^\s*turn\s*on\s*the\s*(?P<group_1>%DEVICE%)\s*(?:please)?\s*$";
+
+        // When
+        let words = pattern_words(pattern, '%');
+
+        // Then
+        let expected_words = vec!["turn", "on", "the", "please"];
+        assert_eq!(expected_words, words);
+    }
+
+    #[test]
+    fn test_bounded_edit_distance() {
+        // Given
+        let chars = |word: &str| word.chars().collect::<Vec<_>>();
+
+        // When
+        let substitution = bounded_edit_distance(&chars("tirn"), &chars("turn"), 1);
+        let deletion = bounded_edit_distance(&chars("trn"), &chars("turn"), 1);
+        let too_distant = bounded_edit_distance(&chars("tune"), &chars("turn"), 1);
+
+        // Then
+        assert_eq!(Some(1), substitution);
+        assert_eq!(Some(1), deletion);
+        assert_eq!(None, too_distant);
+    }
+
+    #[test]
+    fn test_correct_input() {
+        // Given
+        let patterns = vec![r"^\s*turn\s*on\s*the\s*(?P<group_1>%DEVICE%)\s*$".to_string()];
+        let matcher = FuzzyMatcher::new(&patterns, '%', 1);
+
+        // When
+        let corrected_input = matcher.correct("Turn onn thw %DEVICE%").unwrap();
+        let exact_input = matcher.correct("turn on the %DEVICE%");
+
+        // Then
+        assert_eq!("Turn on the %DEVICE%", corrected_input.text);
+        assert_eq!(9..12, corrected_input.original_range(&(8..11)));
+        assert!(exact_input.is_none());
+    }
+}
@@ -4,7 +4,9 @@ use crate::language::FromLanguage;
 use crate::models::LookupParserModel;
 use crate::resources::SharedResources;
 use crate::slot_utils::*;
-use crate::utils::{deduplicate_overlapping_entities, IntentName, MatchedEntity, SlotName};
+use crate::utils::{
+    deduplicate_overlapping_entities, is_blank_input, IntentName, MatchedEntity, SlotName,
+};
 use crate::IntentParser;
 use crate::{EntityScope, GroupedEntityScope, InputHash, IntentId, SlotId};
 use failure::ResultExt;
@@ -98,6 +100,9 @@ impl IntentParser for LookupIntentParser {
         input: &str,
         intents_whitelist: Option<&[&str]>,
     ) -> Result<InternalParsingResult> {
+        if is_blank_input(input) {
+            return Ok(InternalParsingResult::empty());
+        }
         debug!("Extracting intents and slots with lookup intent parser...");
         let result = self
             .parse_top_intents(input, 1, intents_whitelist)?
@@ -211,6 +216,7 @@ impl LookupIntentParser {
                     .confidence_score
                     .partial_cmp(&res1.intent.confidence_score)
                     .unwrap()
+                    .then_with(|| res1.intent.intent_name.cmp(&res2.intent.intent_name))
             })
             .take(top_n)
             .collect())
@@ -317,6 +323,7 @@ impl LookupIntentParser {
                     char_range: char_range.clone(),
                     entity: entity_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 });
             }
             Some(InternalParsingResult { intent, slots })
@@ -449,6 +456,7 @@ mod tests {
             char_range: 5..8,
             entity: "snips/number".to_string(),
             slot_name: "number_of_cups".to_string(),
+            confidence_score: None,
         }];
         assert_eq!(expected_intent, parsing_result.intent.intent_name);
         assert_eq!(expected_slots, parsing_result.slots);
@@ -644,12 +652,14 @@ mod tests {
                     char_range: 8..11,
                     entity: "snips/number".to_string(),
                     slot_name: "number".to_string(),
+                    confidence_score: None,
                 },
                 InternalSlot {
                     value: "one".to_string(),
                     char_range: 17..20,
                     entity: "snips/number".to_string(),
                     slot_name: "number".to_string(),
+                    confidence_score: None,
                 },
             ],
         };
@@ -723,6 +733,7 @@ mod tests {
                 char_range: 16..21,
                 entity: "name".to_string(),
                 slot_name: "name".to_string(),
+                confidence_score: None,
             }],
         };
 
@@ -1035,24 +1046,28 @@ mod tests {
                     char_range: 13..17,
                     entity: "name".to_string(),
                     slot_name: "name".to_string(),
+                    confidence_score: None,
                 },
                 InternalSlot {
                     value: "Snips".to_string(),
                     char_range: 21..26,
                     entity: "location".to_string(),
                     slot_name: "location".to_string(),
+                    confidence_score: None,
                 },
                 InternalSlot {
                     value: "this afternoon".to_string(),
                     char_range: 34..48,
                     entity: "snips/datetime".to_string(),
                     slot_name: "time".to_string(),
+                    confidence_score: None,
                 },
                 InternalSlot {
                     value: "tomorrow".to_string(),
                     char_range: 52..60,
                     entity: "snips/datetime".to_string(),
                     slot_name: "time".to_string(),
+                    confidence_score: None,
                 },
             ],
         };
@@ -1136,6 +1151,7 @@ mod tests {
                 char_range: 7..11,
                 entity: "object".to_string(),
                 slot_name: "object".to_string(),
+                confidence_score: None,
             }],
         };
 
@@ -1311,6 +1327,7 @@ mod tests {
                 char_range: 13..26,
                 entity: "name".to_string(),
                 slot_name: "name".to_string(),
+                confidence_score: None,
             }],
         };
 
@@ -1377,6 +1394,7 @@ mod tests {
             char_range: 6..10,
             entity: "name".to_string(),
             slot_name: "name".to_string(),
+            confidence_score: None,
         }];
 
         assert_eq!(expected_slots_1, slots_1);
@@ -1,6 +1,10 @@
 pub mod deterministic_intent_parser;
+pub mod ensemble_intent_parser;
+mod fuzzy_matching;
 pub mod lookup_intent_parser;
+mod pattern_template;
 pub mod probabilistic_intent_parser;
+mod registry;
 
 use std::path::Path;
 use std::sync::Arc;
@@ -9,11 +13,16 @@ use failure::format_err;
 use snips_nlu_ontology::IntentClassifierResult;
 
 pub use self::deterministic_intent_parser::DeterministicIntentParser;
+pub use self::ensemble_intent_parser::EnsembleParser;
 pub use self::lookup_intent_parser::LookupIntentParser;
 pub use self::probabilistic_intent_parser::ProbabilisticIntentParser;
+pub use self::registry::{CascadePosition, IntentParserFactory, IntentParserRegistry};
+use crate::config::EngineConfig;
 use crate::errors::*;
+use crate::intent_classifier::ClassIntercept;
 use crate::models::ProcessingUnitMetadata;
 use crate::resources::SharedResources;
+use crate::slot_filler::SlotsHypothesis;
 pub use crate::slot_utils::InternalSlot;
 use crate::utils::IntentName;
 
@@ -59,22 +68,43 @@ pub trait IntentParser: Send + Sync {
     fn get_intents(&self, input: &str) -> Result<Vec<IntentClassifierResult>>;
 
     fn get_slots(&self, input: &str, intent: &str) -> Result<Vec<InternalSlot>>;
+
+    /// Returns at most `n` hypotheses of slots of the intent, sorted by decreasing probability
+    ///
+    /// Parsers which are not probabilistic only return the slots of `get_slots`, if any.
+    fn get_slots_nbest(&self, input: &str, intent: &str, n: usize) -> Result<Vec<SlotsHypothesis>> {
+        let slots = self.get_slots(input, intent)?;
+        if n == 0 || slots.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(vec![SlotsHypothesis {
+            slots,
+            probability: 1.0,
+        }])
+    }
+
+    /// Returns the intercepts of the intent classifier of the parser, which are empty for
+    /// parsers without classifier
+    fn intent_classifier_intercepts(&self) -> Result<Vec<ClassIntercept>> {
+        Ok(vec![])
+    }
 }
 
 pub fn build_intent_parser<P: AsRef<Path>>(
     metadata: ProcessingUnitMetadata,
     path: P,
     shared_resources: Arc<SharedResources>,
+    config: &EngineConfig,
 ) -> Result<Box<dyn IntentParser>> {
     match metadata {
         ProcessingUnitMetadata::LookupIntentParser => {
             Ok(Box::new(LookupIntentParser::from_path(path, shared_resources)?) as _)
         }
         ProcessingUnitMetadata::DeterministicIntentParser => Ok(Box::new(
-            DeterministicIntentParser::from_path(path, shared_resources)?,
+            DeterministicIntentParser::from_path_with_config(path, shared_resources, config)?,
         ) as _),
         ProcessingUnitMetadata::ProbabilisticIntentParser => Ok(Box::new(
-            ProbabilisticIntentParser::from_path(path, shared_resources)?,
+            ProbabilisticIntentParser::from_path_with_config(path, shared_resources, config)?,
         ) as _),
         _ => Err(format_err!("{:?} is not an intent parser", metadata)),
     }
@@ -0,0 +1,180 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use failure::bail;
+
+use crate::errors::*;
+
+const SEPARATOR: &str = r"\s*";
+
+/// Compiles a compact pattern template into a regex pattern of the deterministic intent parser
+///
+/// Templates are sequences of whitespace separated elements:
+/// - words, which are matched literally
+/// - `(a|b c)` groups, matching one of their alternatives
+/// - `[a|b c]` groups, matching one of their alternatives or nothing
+/// - `{group1:%SNIPSNUMBER%}` slots, where `group1` is the name of the slot group and
+///   `%SNIPSNUMBER%` the placeholder of its entity
+///
+/// For instance, `"[please] (make|brew) {group1:%SNIPSNUMBER%} cups of coffee"` replaces the
+/// four patterns obtained by expanding the groups.
+pub(crate) fn compile_pattern_template(template: &str) -> Result<String> {
+    let mut compiler = TemplateCompiler {
+        template,
+        chars: template.chars().peekable(),
+    };
+    let alternatives = compiler.compile_alternatives(None)?;
+    Ok(format!(
+        r"^{}{}$",
+        SEPARATOR,
+        group_alternatives(alternatives)
+    ))
+}
+
+struct TemplateCompiler<'a> {
+    template: &'a str,
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> TemplateCompiler<'a> {
+    /// Compiles alternatives separated by '|', up to the closing delimiter which is consumed
+    fn compile_alternatives(&mut self, closing_delimiter: Option<char>) -> Result<Vec<String>> {
+        let mut alternatives = vec![];
+        loop {
+            alternatives.push(self.compile_sequence()?);
+            match self.chars.next() {
+                Some('|') => continue,
+                Some(c) if Some(c) == closing_delimiter => return Ok(alternatives),
+                None if closing_delimiter.is_none() => return Ok(alternatives),
+                _ => bail!("Invalid pattern template '{}'", self.template),
+            }
+        }
+    }
+
+    /// Compiles elements up to the next '|' or closing delimiter, which is not consumed
+    fn compile_sequence(&mut self) -> Result<String> {
+        let mut pattern = String::new();
+        loop {
+            self.skip_whitespaces();
+            match self.chars.peek() {
+                None | Some('|') | Some(')') | Some(']') | Some('}') => return Ok(pattern),
+                Some('(') => {
+                    self.chars.next();
+                    let alternatives = self.compile_alternatives(Some(')'))?;
+                    pattern.push_str(&group_alternatives(alternatives));
+                }
+                Some('[') => {
+                    self.chars.next();
+                    let alternatives = self.compile_alternatives(Some(']'))?;
+                    pattern.push_str(&format!("(?:{})?", alternatives.join("|")));
+                }
+                Some('{') => {
+                    self.chars.next();
+                    pattern.push_str(&self.compile_slot()?);
+                }
+                Some(_) => {
+                    let word = self.take_word();
+                    pattern.push_str(&regex::escape(&word));
+                    pattern.push_str(SEPARATOR);
+                }
+            }
+        }
+    }
+
+    fn compile_slot(&mut self) -> Result<String> {
+        let mut slot = String::new();
+        loop {
+            match self.chars.next() {
+                Some('}') => break,
+                Some(c) => slot.push(c),
+                None => bail!("Unclosed slot in pattern template '{}'", self.template),
+            }
+        }
+        let mut slot_parts = slot.splitn(2, ':');
+        match (slot_parts.next(), slot_parts.next()) {
+            (Some(group_name), Some(entity))
+                if !group_name.is_empty()
+                    && group_name.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                Ok(format!(
+                    "(?P<{}>{}){}",
+                    group_name,
+                    regex::escape(entity.trim()),
+                    SEPARATOR
+                ))
+            }
+            _ => bail!(
+                "Invalid slot '{}' in pattern template '{}'",
+                slot,
+                self.template
+            ),
+        }
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(c) = self.chars.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.chars.next();
+        }
+    }
+
+    fn take_word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self.chars.peek() {
+            if c.is_whitespace() || "()[]{}|".contains(*c) {
+                break;
+            }
+            word.push(*c);
+            self.chars.next();
+        }
+        word
+    }
+}
+
+fn group_alternatives(alternatives: Vec<String>) -> String {
+    if alternatives.len() == 1 {
+        alternatives.into_iter().next().unwrap()
+    } else {
+        format!("(?:{})", alternatives.join("|"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::*;
+
+    #[test]
+    fn test_compile_pattern_template() {
+        // Given
+        let template = "[please] (make|brew) {group1:%SNIPSNUMBER%} cup[s] of coffee";
+
+        // When
+        let pattern = compile_pattern_template(template).unwrap();
+        let simple_pattern =
+            compile_pattern_template("make {group1:%SNIPSNUMBER%} cup of coffee").unwrap();
+
+        // Then
+        assert_eq!(
+            r"^\s*make\s*(?P<group1>%SNIPSNUMBER%)\s*cup\s*of\s*coffee\s*$",
+            simple_pattern
+        );
+        let regex = Regex::new(&pattern).unwrap();
+        assert!(regex.is_match("please make %SNIPSNUMBER% cups of coffee"));
+        assert!(regex.is_match("brew %SNIPSNUMBER% cup of coffee"));
+        assert!(!regex.is_match("prepare %SNIPSNUMBER% cup of coffee"));
+        let captures = regex.captures("make %SNIPSNUMBER% cups of coffee").unwrap();
+        assert_eq!("%SNIPSNUMBER%", &captures["group1"]);
+    }
+
+    #[test]
+    fn test_compile_invalid_pattern_templates() {
+        assert!(compile_pattern_template("make (a|one cup").is_err());
+        assert!(compile_pattern_template("make a] cup").is_err());
+        assert!(compile_pattern_template("make {group1} cup").is_err());
+        assert!(compile_pattern_template("make {group1:%SNIPSNUMBER% cup").is_err());
+    }
+}
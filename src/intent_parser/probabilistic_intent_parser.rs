@@ -6,12 +6,14 @@ use std::sync::Arc;
 
 use failure::{format_err, ResultExt};
 
+use crate::cancellation::check_cancellation;
+use crate::config::EngineConfig;
 use crate::errors::*;
-use crate::intent_classifier::{build_intent_classifier, IntentClassifier};
+use crate::intent_classifier::{build_intent_classifier, ClassIntercept, IntentClassifier};
 use crate::models::ProbabilisticParserModel;
 use crate::resources::SharedResources;
-use crate::slot_filler::{build_slot_filler, SlotFiller};
-use crate::utils::IntentName;
+use crate::slot_filler::{build_slot_filler, SlotFiller, SlotsHypothesis};
+use crate::utils::{is_blank_input, IntentName};
 
 use super::{IntentClassifierResult, IntentParser, InternalParsingResult};
 use crate::slot_utils::InternalSlot;
@@ -25,6 +27,18 @@ impl ProbabilisticIntentParser {
     pub fn from_path<P: AsRef<Path>>(
         path: P,
         shared_resources: Arc<SharedResources>,
+    ) -> Result<Self> {
+        Self::from_path_with_config(path, shared_resources, &EngineConfig::default())
+    }
+
+    /// Loads the parser, its slot fillers being configured according to the `EngineConfig`
+    ///
+    /// When a chunking config is provided, the slot fillers are wrapped so that long inputs
+    /// are processed chunk by chunk.
+    pub fn from_path_with_config<P: AsRef<Path>>(
+        path: P,
+        shared_resources: Arc<SharedResources>,
+        config: &EngineConfig,
     ) -> Result<Self> {
         let parser_model_path = path.as_ref().join("intent_parser.json");
         let model_file = File::open(&parser_model_path).with_context(|_| {
@@ -36,17 +50,23 @@ impl ProbabilisticIntentParser {
         let model: ProbabilisticParserModel = serde_json::from_reader(model_file)
             .with_context(|_| "Cannot deserialize ProbabilisticIntentParser json data")?;
         let intent_classifier_path = path.as_ref().join("intent_classifier");
-        let intent_classifier =
-            build_intent_classifier(intent_classifier_path, shared_resources.clone())?;
+        let intent_classifier = build_intent_classifier(
+            intent_classifier_path,
+            shared_resources.clone(),
+            &config.intercept_adjustments,
+        )?;
         let slot_fillers_vec: Result<Vec<_>> = model
             .slot_fillers
             .iter()
             .map(|metadata| {
                 let slot_filler_path = path.as_ref().join(&metadata.slot_filler_name);
-                Ok((
-                    metadata.intent.to_string(),
-                    build_slot_filler(slot_filler_path, shared_resources.clone())?,
-                ))
+                let slot_filler = build_slot_filler(
+                    slot_filler_path,
+                    shared_resources.clone(),
+                    config.slot_confidence_scores,
+                    config.chunking.as_ref(),
+                )?;
+                Ok((metadata.intent.to_string(), slot_filler))
             })
             .collect();
         let slot_fillers = HashMap::from_iter(slot_fillers_vec?);
@@ -63,9 +83,13 @@ impl IntentParser for ProbabilisticIntentParser {
         input: &str,
         intents_whitelist: Option<&[&str]>,
     ) -> Result<InternalParsingResult> {
+        if is_blank_input(input) {
+            return Ok(InternalParsingResult::empty());
+        }
         let intent_result = self
             .intent_classifier
             .get_intent(input, intents_whitelist)?;
+        check_cancellation()?;
         let slots = if let Some(name) = intent_result.intent_name.as_ref() {
             self.slot_fillers
                 .get(name)
@@ -90,6 +114,17 @@ impl IntentParser for ProbabilisticIntentParser {
             .ok_or_else(|| format_err!("Unknown intent: {}", intent))
             .and_then(|slot_filler| slot_filler.get_slots(input))
     }
+
+    fn get_slots_nbest(&self, input: &str, intent: &str, n: usize) -> Result<Vec<SlotsHypothesis>> {
+        self.slot_fillers
+            .get(intent)
+            .ok_or_else(|| format_err!("Unknown intent: {}", intent))
+            .and_then(|slot_filler| slot_filler.get_slots_nbest(input, n))
+    }
+
+    fn intent_classifier_intercepts(&self) -> Result<Vec<ClassIntercept>> {
+        self.intent_classifier.intercepts()
+    }
 }
 
 #[cfg(test)]
@@ -121,6 +156,7 @@ mod tests {
             char_range: 8..11,
             entity: "snips/number".to_string(),
             slot_name: "number_of_cups".to_string(),
+            confidence_score: None,
         }];
         assert_eq!(expected_intent, parsing_result.intent.intent_name);
         assert_eq!(expected_slots, parsing_result.slots);
@@ -149,12 +185,14 @@ mod tests {
                 char_range: 8..11,
                 entity: "snips/number".to_string(),
                 slot_name: "number_of_cups".to_string(),
+                confidence_score: None,
             },
             InternalSlot {
                 value: "hot".to_string(),
                 char_range: 12..15,
                 entity: "Temperature".to_string(),
                 slot_name: "beverage_temperature".to_string(),
+                confidence_score: None,
             },
         ];
         assert_eq!(expected_slots, slots);
@@ -0,0 +1,137 @@
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use failure::format_err;
+
+use crate::errors::*;
+use crate::intent_parser::IntentParser;
+use crate::resources::SharedResources;
+
+/// Function building a custom intent parser when the engine is loaded, or reloaded after an
+/// entity injection, from the engine directory and the resources shared by the engine parsers
+pub type IntentParserFactory =
+    dyn Fn(&Path, Arc<SharedResources>) -> Result<Box<dyn IntentParser>> + Send + Sync;
+
+/// Position of a custom intent parser in the cascade of the engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CascadePosition {
+    First,
+    Last,
+    /// Before the parser having this unit name, e.g. "probabilistic_intent_parser", or the
+    /// name of a previously registered custom parser
+    Before(String),
+    /// After the parser having this unit name
+    After(String),
+}
+
+/// Custom intent parsers inserted in the cascade of the engine, in addition to the ones of
+/// the trained model
+///
+/// The custom parsers are run like the built-in ones: the first parser returning an intent
+/// wins, the intents probabilities and slots of all the parsers are merged, and they are
+/// weighted by their name when an `EnsembleConfig` is used. The slots they return must refer
+/// to builtin entities or to custom entities of the model, so that they can be resolved.
+#[derive(Clone, Default)]
+pub struct IntentParserRegistry {
+    parsers: Vec<RegisteredIntentParser>,
+}
+
+#[derive(Clone)]
+struct RegisteredIntentParser {
+    name: String,
+    position: CascadePosition,
+    factory: Arc<IntentParserFactory>,
+}
+
+impl IntentParserRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a parser under a unique name, parsers being inserted in the order of their
+    /// registration
+    pub fn register<S, F>(mut self, name: S, position: CascadePosition, factory: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&Path, Arc<SharedResources>) -> Result<Box<dyn IntentParser>> + Send + Sync + 'static,
+    {
+        self.parsers.push(RegisteredIntentParser {
+            name: name.into(),
+            position,
+            factory: Arc::new(factory),
+        });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parsers.is_empty()
+    }
+
+    /// Names of the registered parsers
+    pub fn names(&self) -> Vec<&str> {
+        self.parsers.iter().map(|parser| &*parser.name).collect()
+    }
+
+    /// Builds the registered parsers and inserts them in the cascade of named parsers
+    pub(crate) fn insert_parsers(
+        &self,
+        engine_dir: &Path,
+        shared_resources: Arc<SharedResources>,
+        mut parsers: Vec<(String, Box<dyn IntentParser>)>,
+    ) -> Result<Vec<(String, Box<dyn IntentParser>)>> {
+        for registered_parser in self.parsers.iter() {
+            if parsers
+                .iter()
+                .any(|(name, _)| *name == registered_parser.name)
+            {
+                return Err(format_err!(
+                    "Intent parser '{}' is already part of the cascade",
+                    registered_parser.name
+                ));
+            }
+            let position_index = |unit_name: &str| {
+                parsers
+                    .iter()
+                    .position(|(name, _)| name == unit_name)
+                    .ok_or_else(|| format_err!("Unknown intent parser '{}'", unit_name))
+            };
+            let index = match &registered_parser.position {
+                CascadePosition::First => 0,
+                CascadePosition::Last => parsers.len(),
+                CascadePosition::Before(unit_name) => position_index(unit_name)?,
+                CascadePosition::After(unit_name) => position_index(unit_name)? + 1,
+            };
+            let parser = (registered_parser.factory)(engine_dir, shared_resources.clone())?;
+            parsers.insert(index, (registered_parser.name.clone(), parser));
+        }
+        Ok(parsers)
+    }
+}
+
+impl fmt::Debug for IntentParserRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.parsers
+                    .iter()
+                    .map(|parser| (&parser.name, &parser.position)),
+            )
+            .finish()
+    }
+}
+
+impl PartialEq for IntentParserRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.parsers.len() == other.parsers.len()
+            && self
+                .parsers
+                .iter()
+                .zip(other.parsers.iter())
+                .all(|(parser, other_parser)| {
+                    parser.name == other_parser.name
+                        && parser.position == other_parser.position
+                        && Arc::ptr_eq(&parser.factory, &other_parser.factory)
+                })
+    }
+}
@@ -4,32 +4,121 @@
     clippy::module_inception
 )]
 
+pub mod analysis;
+mod audit_log;
+mod barge_in;
+mod cancellation;
+mod config;
+mod config_source;
+mod detailed_result;
+mod engine_handle;
+mod engine_registry;
 mod entity_parser;
 pub mod errors;
 pub mod injection;
 mod intent_classifier;
 mod intent_parser;
 mod language;
+mod memory_budget;
 pub mod models;
+mod multilingual_engine;
 mod nlu_engine;
+mod normalization_dictionary;
+pub mod python_compat;
+#[cfg(feature = "remote")]
+mod remote;
 mod resources;
+mod self_test;
 mod slot_filler;
 mod slot_utils;
+mod slot_validation;
+mod spelled_sequence;
 #[cfg(test)]
 mod testutils;
+mod text_range;
+mod thread_pool;
+mod time_grain;
+mod tokenization;
+mod transliteration;
+mod unicode;
+mod usage_statistics;
 mod utils;
+mod value_normalization;
+mod value_range;
+mod value_weights;
 
 pub const MODEL_VERSION: &str = "0.20.0";
 
 pub extern crate snips_nlu_ontology as ontology;
+pub use crate::audit_log::{AuditLog, AuditLogEntry, Redactor};
+pub use crate::barge_in::PartialTokenDetection;
+pub use crate::cancellation::CancellationToken;
+pub use crate::config::{
+    ChunkingConfig, EngineConfig, EnsembleConfig, OutputLimits, ParseOptions, RegexLimits,
+    SlotConflictResolution,
+};
+pub use crate::config_source::ENGINE_CONFIG_ENV_PREFIX;
+pub use crate::detailed_result::{
+    AlternativeSlots, DetailedIntentParserResult, SlotDetails, SlotProvenance,
+};
+pub use crate::engine_handle::{EngineHandle, PendingParsePolicy};
+pub use crate::engine_registry::EngineRegistry;
+pub use crate::entity_parser::{
+    BuiltinEntityParserKey, BuiltinEntityParserPool, CustomEntity, CustomEntityParser,
+    CustomEntityParserUsage, EntityMention, GazetteerScope, ParallelBuiltinEntityParser,
+    ScopedBuiltinEntityParser, StreamingEntitySpotter, StreamingSpotterConfig, SynonymEntity,
+    SynonymEntityBuilder,
+};
 pub use crate::errors::*;
-pub use crate::intent_classifier::{IntentClassifier, LogRegIntentClassifier};
+pub use crate::intent_classifier::{
+    ClassIntercept, FeatureWeight, IntentClassifier, IntentExplanation, LogRegIntentClassifier,
+    TfidfVectorizer,
+};
 pub use crate::intent_parser::{
-    DeterministicIntentParser, IntentParser, LookupIntentParser, ProbabilisticIntentParser,
+    CascadePosition, DeterministicIntentParser, EnsembleParser, IntentParser, IntentParserFactory,
+    IntentParserRegistry, InternalParsingResult, InternalSlot, LookupIntentParser,
+    ProbabilisticIntentParser,
 };
+pub use crate::memory_budget::MemorySaving;
 pub use crate::models::*;
+pub use crate::multilingual_engine::{MultilingualNluEngine, MultilingualParsingResult};
 pub use crate::nlu_engine::SnipsNluEngine;
-pub use crate::resources::loading::load_shared_resources;
-pub use crate::resources::SharedResources;
-pub use crate::slot_filler::{CRFSlotFiller, SlotFiller};
+pub use crate::normalization_dictionary::{
+    NormalizationDictionary, NormalizedInput, NORMALIZATION_DICTIONARY_FILENAME,
+};
+#[cfg(feature = "remote")]
+pub use crate::remote::FINGERPRINT_HEADER;
+pub use crate::resources::dialect::{CsvDialect, ParsingWarning};
+pub use crate::resources::gazetteer::{
+    ComposedGazetteer, FstGazetteer, Gazetteer, GazetteerReader, HashSetGazetteer,
+    FST_GAZETTEER_EXTENSION,
+};
+pub use crate::resources::loading::{
+    load_shared_resources, load_shared_resources_with_config, LanguageResourcesPool, ResourceLoader,
+};
+pub use crate::resources::pack::{
+    ResourcePackBuilder, ResourcePackManifest, RESOURCE_PACK_FORMAT_VERSION,
+    RESOURCE_PACK_MANIFEST_FILENAME,
+};
+pub use crate::resources::stemmer::{
+    FallbackStemmer, HashMapStemmer, InternedStemmer, SnowballStemmer, Stemmer, StemmerBackend,
+    BINARY_STEMS_EXTENSION,
+};
+pub use crate::resources::{MissingResourcePolicy, SharedResources};
+pub use crate::self_test::{LatencyPercentiles, SelfTestFailure, SelfTestReport};
+pub use crate::slot_filler::{CRFSlotFiller, ChunkedSlotFiller, SlotFiller, SlotsHypothesis};
+pub use crate::slot_validation::{RejectedSlot, SlotValidation, SlotValidator, SlotValidators};
+pub use crate::spelled_sequence::SpelledSequence;
+pub use crate::text_range::{grapheme_char_range, RangeUnit, TextRanges};
+pub use crate::thread_pool::ThreadPoolConfig;
+pub use crate::time_grain::{RelativeTime, TimeGrainConfig, TimeGrainMode, TimePreference};
+pub use crate::tokenization::TokenAlignment;
+pub use crate::transliteration::{Transliterator, TRANSLITERATION_TABLE_FILENAME};
+pub use crate::unicode::{PatternNormalization, UnicodeNormalization};
+pub use crate::usage_statistics::{
+    NgramCount, UsageStatistics, UsageStatisticsCollector, UsageStatisticsConfig,
+};
+pub use crate::value_normalization::{custom_value_normalizer, ValueNormalizer, ValueNormalizers};
+pub use crate::value_range::{RangeBound, ValueRange};
+pub use crate::value_weights::{EntityValueWeights, ValueWeights};
 pub use snips_nlu_ontology::Language;
@@ -0,0 +1,219 @@
+use std::fs::{self, File};
+use std::path::Path;
+
+use failure::bail;
+use log::info;
+use serde::Serialize;
+
+use crate::errors::*;
+use crate::resources::loading::ResourcesMetadata;
+
+/// Capacity of the caches of the entity parsers, when they are enabled
+pub const ENTITY_PARSER_CACHE_CAPACITY: usize = 1000;
+
+/// Rough memory footprint of a cached entity parsing result
+const CACHE_ENTRY_SIZE: usize = 1024;
+
+/// Degraded strategy applied when loading an engine, in order to fit in its memory budget
+///
+/// The weights of the models are always loaded with their full precision: the CRF models of
+/// the slot fillers are loaded by the crfsuite library, which does not support quantized
+/// weights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MemorySaving {
+    /// Entity parsing results are no longer cached, which makes repeated parsing slower
+    DisabledEntityParserCaches,
+    /// Stems are stored once and shared by all the words having them, which makes stemming
+    /// slightly slower
+    InternedStems,
+    /// Text gazetteers are compiled to finite state transducers, which makes loading slower
+    FstGazetteers,
+    /// Gazetteers whose stemmed version is loaded as well are replaced with lookups of stems in
+    /// the stemmed version, which makes them less precise
+    DeduplicatedStemmedGazetteers,
+}
+
+/// Memory saving strategies selected for an engine, given its memory budget
+///
+/// The memory footprint of the engine is estimated from the size of its files, which is only
+/// an approximation of the memory actually used once it is loaded. Strategies are applied
+/// one after the other until the estimated footprint fits in the budget.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MemoryPlan {
+    pub savings: Vec<MemorySaving>,
+}
+
+impl MemoryPlan {
+    pub fn from_engine_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+        engine_dir: P,
+        resources_dir: Q,
+        memory_budget: usize,
+    ) -> Result<Self> {
+        let caches_size = 2 * ENTITY_PARSER_CACHE_CAPACITY * CACHE_ENTRY_SIZE;
+        let mut estimated_size = directory_size(engine_dir.as_ref())? + caches_size;
+        let gazetteer_savings = GazetteerSavings::from_resources_dir(resources_dir.as_ref())?;
+        let strategies = vec![
+            (MemorySaving::DisabledEntityParserCaches, caches_size),
+            (
+                MemorySaving::InternedStems,
+                // Stems are roughly as long as the words they replace, so interning them saves
+                // about half of the space taken by the stems file
+                directory_size(&resources_dir.as_ref().join("stemming"))? / 2,
+            ),
+            (MemorySaving::FstGazetteers, gazetteer_savings.fst),
+            (
+                MemorySaving::DeduplicatedStemmedGazetteers,
+                gazetteer_savings.stemmed_duplicates,
+            ),
+        ];
+        let mut savings = vec![];
+        for (saving, saved_size) in strategies {
+            if estimated_size <= memory_budget {
+                break;
+            }
+            info!("Memory budget exceeded, applying strategy {:?}", saving);
+            savings.push(saving);
+            estimated_size -= saved_size;
+        }
+        if estimated_size > memory_budget {
+            bail!(SnipsNluError::MemoryBudgetExceeded {
+                estimated_size,
+                memory_budget,
+            });
+        }
+        Ok(Self { savings })
+    }
+
+    pub fn entity_parser_cache_capacity(&self) -> usize {
+        if self
+            .savings
+            .contains(&MemorySaving::DisabledEntityParserCaches)
+        {
+            0
+        } else {
+            ENTITY_PARSER_CACHE_CAPACITY
+        }
+    }
+
+    pub fn interned_stems(&self) -> bool {
+        self.savings.contains(&MemorySaving::InternedStems)
+    }
+
+    pub fn fst_gazetteers(&self) -> bool {
+        self.savings.contains(&MemorySaving::FstGazetteers)
+    }
+
+    pub fn deduplicated_stemmed_gazetteers(&self) -> bool {
+        self.savings
+            .contains(&MemorySaving::DeduplicatedStemmedGazetteers)
+    }
+}
+
+/// Estimated sizes saved by the strategies applied to the gazetteers of the resources
+#[derive(Debug, Default)]
+struct GazetteerSavings {
+    fst: usize,
+    stemmed_duplicates: usize,
+}
+
+impl GazetteerSavings {
+    fn from_resources_dir(resources_dir: &Path) -> Result<Self> {
+        let metadata_path = resources_dir.join("metadata.json");
+        if !metadata_path.exists() {
+            return Ok(Self::default());
+        }
+        let metadata: ResourcesMetadata = serde_json::from_reader(File::open(&metadata_path)?)?;
+        let stemmed_duplicates = if metadata.has_stemmer() {
+            metadata.stemmed_gazetteer_duplicates()
+        } else {
+            vec![]
+        };
+        let mut savings = Self::default();
+        for gazetteer_name in metadata.gazetteers.iter().flatten() {
+            let gazetteer_path = ResourcesMetadata::gazetteer_path(resources_dir, gazetteer_name);
+            if !gazetteer_path.exists() {
+                continue;
+            }
+            let gazetteer_size = fs::metadata(&gazetteer_path)?.len() as usize;
+            // Shared prefixes and suffixes make the transducers of natural language
+            // gazetteers about half as large as their text files, and the duplicates are
+            // dropped after the gazetteers were compiled
+            let compiled_size = if gazetteer_path.extension().map_or(false, |ext| ext == "txt") {
+                savings.fst += gazetteer_size / 2;
+                gazetteer_size - gazetteer_size / 2
+            } else {
+                gazetteer_size
+            };
+            if stemmed_duplicates.contains(&gazetteer_name.as_str()) {
+                savings.stemmed_duplicates += compiled_size;
+            }
+        }
+        Ok(savings)
+    }
+}
+
+/// Total size, in bytes, of the files contained in the directory and its sub-directories
+fn directory_size(path: &Path) -> Result<usize> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            size += directory_size(&entry.path())?;
+        } else {
+            size += metadata.len() as usize;
+        }
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_plan() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let resources_dir = engine_dir.join("resources").join("en");
+        let engine_size = directory_size(&engine_dir).unwrap();
+
+        // When
+        let large_budget_plan =
+            MemoryPlan::from_engine_dir(&engine_dir, &resources_dir, 10 * engine_size).unwrap();
+        let small_budget_plan =
+            MemoryPlan::from_engine_dir(&engine_dir, &resources_dir, engine_size).unwrap();
+        let stems_size = directory_size(&resources_dir.join("stemming")).unwrap();
+        let gazetteers_budget_plan = MemoryPlan::from_engine_dir(
+            &engine_dir,
+            &resources_dir,
+            engine_size - stems_size / 2 - 1,
+        )
+        .unwrap();
+        let tiny_budget_plan = MemoryPlan::from_engine_dir(&engine_dir, &resources_dir, 1000);
+
+        // Then
+        assert_eq!(MemoryPlan::default(), large_budget_plan);
+        assert_eq!(
+            vec![MemorySaving::DisabledEntityParserCaches],
+            small_budget_plan.savings
+        );
+        assert_eq!(0, small_budget_plan.entity_parser_cache_capacity());
+        assert_eq!(
+            vec![
+                MemorySaving::DisabledEntityParserCaches,
+                MemorySaving::InternedStems,
+                MemorySaving::FstGazetteers,
+            ],
+            gazetteers_budget_plan.savings
+        );
+        assert!(tiny_budget_plan.is_err());
+    }
+}
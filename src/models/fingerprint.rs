@@ -0,0 +1,120 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path};
+
+use failure::{format_err, ResultExt};
+use itertools::Itertools;
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+
+use crate::errors::*;
+
+/// Computes a SHA-256 hash of the content of a trained engine, which is either a directory or a
+/// zip archive
+///
+/// The hash only depends on the relative paths and on the content of the files, and not on the
+/// order in which they are listed or on their timestamps. A zip archive and the engine
+/// directory it contains thus have the same fingerprint.
+pub fn fingerprint<P: AsRef<Path>>(path: P) -> Result<[u8; 32]> {
+    let path = path.as_ref();
+    let files_digests = if path.is_dir() {
+        directory_files_digests(path, path)?
+    } else {
+        let file =
+            fs::File::open(path).with_context(|_| format!("Cannot open model file {:?}", path))?;
+        zip_files_digests(file)?
+    };
+    let mut hasher = Sha256::new();
+    for (relative_path, digest) in files_digests.into_iter().sorted() {
+        hasher.input(relative_path.as_bytes());
+        hasher.input(&[0u8]);
+        hasher.input(&digest);
+    }
+    let mut fingerprint = [0; 32];
+    fingerprint.copy_from_slice(&hasher.result());
+    Ok(fingerprint)
+}
+
+fn directory_files_digests(root: &Path, dir: &Path) -> Result<Vec<(String, [u8; 32])>> {
+    let mut files_digests = vec![];
+    for entry in fs::read_dir(dir).with_context(|_| format!("Cannot read directory {:?}", dir))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files_digests.extend(directory_files_digests(root, &path)?);
+        } else {
+            let relative_path = relative_path(path.strip_prefix(root)?.components())
+                .ok_or_else(|| format_err!("Invalid model file path {:?}", path))?;
+            let mut file =
+                fs::File::open(&path).with_context(|_| format!("Cannot open file {:?}", path))?;
+            files_digests.push((relative_path, digest(&mut file)?));
+        }
+    }
+    Ok(files_digests)
+}
+
+fn zip_files_digests<R: io::Read + io::Seek>(reader: R) -> Result<Vec<(String, [u8; 32])>> {
+    let mut archive = ZipArchive::new(reader).with_context(|_| "Could not read model zip data")?;
+    let mut files_digests = vec![];
+    for file_index in 0..archive.len() {
+        let mut file = archive.by_index(file_index)?;
+        if file.name().ends_with('/') || file.name().ends_with('\\') {
+            continue;
+        }
+        // The archive contains the engine directory, whose name is ignored
+        let file_path = file.sanitized_name();
+        let relative_path = relative_path(file_path.components().skip(1))
+            .ok_or_else(|| format_err!("Invalid model archive path {:?}", file_path))?;
+        files_digests.push((relative_path, digest(&mut file)?));
+    }
+    Ok(files_digests)
+}
+
+/// Path with '/' separators, so that fingerprints do not depend on the platform
+fn relative_path<'a, I: Iterator<Item = Component<'a>>>(components: I) -> Option<String> {
+    let components = components
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    if components.is_empty() {
+        None
+    } else {
+        Some(components.join("/"))
+    }
+}
+
+pub(crate) fn digest<R: io::Read>(reader: &mut R) -> Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    io::copy(reader, &mut hasher)?;
+    let mut digest = [0; 32];
+    digest.copy_from_slice(&hasher.result());
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::extract_nlu_engine_zip_archive;
+
+    #[test]
+    fn test_fingerprint() {
+        // Given
+        let models_dir = Path::new("data").join("tests").join("models");
+        let zip_path = models_dir.join("nlu_engine_beverage.zip");
+        let temp_dir = tempfile::tempdir().unwrap();
+        let zip_file = fs::File::open(&zip_path).unwrap();
+        let extracted_dir = extract_nlu_engine_zip_archive(zip_file, temp_dir.path()).unwrap();
+
+        // When
+        let zip_fingerprint = fingerprint(&zip_path).unwrap();
+        let extracted_fingerprint = fingerprint(&extracted_dir).unwrap();
+        let beverage_fingerprint = fingerprint(models_dir.join("nlu_engine_beverage")).unwrap();
+        let game_fingerprint = fingerprint(models_dir.join("nlu_engine_game")).unwrap();
+
+        // Then
+        assert_eq!(zip_fingerprint, extracted_fingerprint);
+        assert_eq!(
+            beverage_fingerprint,
+            fingerprint(models_dir.join("nlu_engine_beverage")).unwrap()
+        );
+        assert_ne!(beverage_fingerprint, game_fingerprint);
+    }
+}
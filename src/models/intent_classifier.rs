@@ -31,11 +31,15 @@ pub struct TfidfVectorizerModel {
 pub struct TfidfVectorizerConfiguration {
     pub use_stemming: bool,
     pub word_clusters_name: Option<String>,
+    /// Number of buckets into which words are hashed, in place of an explicit vocabulary
+    #[serde(default)]
+    pub hashing_buckets: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct SklearnVectorizerModel {
     pub idf_diag: Vec<f32>,
+    #[serde(default)]
     pub vocab: HashMap<String, usize>,
 }
 
@@ -12,6 +12,9 @@ pub type SlotId = i32;
 pub struct DeterministicParserModel {
     pub language_code: String,
     pub patterns: HashMap<IntentName, Vec<String>>,
+    /// Compact patterns with optional and alternation groups, compiled along with `patterns`
+    #[serde(default)]
+    pub pattern_templates: HashMap<IntentName, Vec<String>>,
     pub group_names_to_slot_names: HashMap<String, SlotName>,
     pub slot_names_to_entities: HashMap<IntentName, HashMap<SlotName, EntityName>>,
     #[serde(default)]
@@ -46,6 +49,17 @@ pub struct EntityScope {
 pub struct DeterministicParserConfig {
     #[serde(default)]
     pub ignore_stop_words: bool,
+    /// Character surrounding the entity placeholders of the patterns, e.g. `%SNIPSNUMBER%`
+    #[serde(default = "default_placeholder_delimiter")]
+    pub placeholder_delimiter: char,
+    /// Prefix of the entity labels of the placeholders, e.g. `ENT_` in `%ENT_SNIPSNUMBER%`,
+    /// which makes placeholders unlikely to collide with the words of the language
+    #[serde(default)]
+    pub placeholder_prefix: String,
+}
+
+fn default_placeholder_delimiter() -> char {
+    '%'
 }
 
 #[derive(Debug, Deserialize)]
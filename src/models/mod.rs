@@ -1,9 +1,11 @@
+pub(crate) mod fingerprint;
 pub mod intent_classifier;
 pub mod intent_parser;
 pub mod nlu_engine;
 pub mod processing_unit_metadata;
 pub mod slot_filler;
 
+pub use self::fingerprint::fingerprint;
 pub use self::intent_classifier::*;
 pub use self::intent_parser::*;
 pub use self::nlu_engine::*;
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use serde::Deserialize;
 
+use crate::time_grain::TimePreference;
 use crate::utils::{EntityName, IntentName, SlotName};
 
 #[derive(Debug, Deserialize)]
@@ -24,6 +25,14 @@ pub struct DatasetMetadata {
     pub language_code: String,
     pub entities: HashMap<String, Entity>,
     pub slot_name_mappings: HashMap<IntentName, HashMap<SlotName, EntityName>>,
+    /// Slots which must be filled for each intent, intents without required slots being
+    /// omitted
+    #[serde(default)]
+    pub required_slots: HashMap<IntentName, Vec<SlotName>>,
+    /// Resolution of ambiguous dates for each intent, intents without preference being
+    /// omitted
+    #[serde(default)]
+    pub time_preferences: HashMap<IntentName, TimePreference>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -0,0 +1,292 @@
+use std::fs;
+use std::path::Path;
+
+use failure::{bail, ResultExt};
+use log::info;
+use snips_nlu_ontology::{IntentParserResult, Language};
+use snips_nlu_utils::language::Language as NluUtilsLanguage;
+use snips_nlu_utils::token::tokenize_light;
+
+use crate::config::{EngineConfig, ParseOptions};
+use crate::detailed_result::DetailedIntentParserResult;
+use crate::errors::*;
+use crate::language::FromLanguage;
+use crate::nlu_engine::SnipsNluEngine;
+
+/// Engine of an assistant trained in several languages, made of one sub-engine per language
+///
+/// The assistant directory contains one trained engine directory per language, e.g. `en/` and
+/// `fr/`, the language of each sub-engine being read from its model. Inputs are parsed by the
+/// sub-engine of the language provided by the caller or, when none is provided, of the
+/// detected language.
+pub struct MultilingualNluEngine {
+    engines: Vec<(Language, SnipsNluEngine)>,
+}
+
+/// Result of the parsing of an input by a `MultilingualNluEngine`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultilingualParsingResult<R> {
+    /// Language of the sub-engine which parsed the input
+    pub language: Language,
+    /// Whether the language was detected rather than provided by the caller
+    pub is_detected: bool,
+    pub result: R,
+}
+
+impl MultilingualNluEngine {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_path_with_config(path, &EngineConfig::default())
+    }
+
+    /// Loads all the sub-engines of the assistant directory with the provided config
+    pub fn from_path_with_config<P: AsRef<Path>>(path: P, config: &EngineConfig) -> Result<Self> {
+        let mut engine_dirs = fs::read_dir(&path)
+            .with_context(|_| format!("Cannot read assistant directory {:?}", path.as_ref()))?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        engine_dirs.retain(|engine_dir| engine_dir.join("nlu_engine.json").is_file());
+        engine_dirs.sort();
+
+        let mut engines: Vec<(Language, SnipsNluEngine)> = vec![];
+        for engine_dir in engine_dirs {
+            info!("Loading sub-engine {:?} ...", engine_dir);
+            let engine = SnipsNluEngine::from_path_with_config(&engine_dir, config)?;
+            let language = engine.language()?;
+            if engines
+                .iter()
+                .any(|(other_language, _)| *other_language == language)
+            {
+                bail!(
+                    "Found several sub-engines in '{}' in assistant directory {:?}",
+                    language,
+                    path.as_ref()
+                );
+            }
+            engines.push((language, engine));
+        }
+        if engines.is_empty() {
+            bail!(
+                "No sub-engine found in assistant directory {:?}",
+                path.as_ref()
+            );
+        }
+        Ok(Self { engines })
+    }
+
+    /// Languages of the sub-engines, in the order in which their directories are sorted
+    pub fn languages(&self) -> Vec<Language> {
+        self.engines.iter().map(|(language, _)| *language).collect()
+    }
+
+    /// Returns the sub-engine of the language, if any
+    pub fn engine(&self, language: Language) -> Option<&SnipsNluEngine> {
+        self.engines
+            .iter()
+            .find(|(engine_language, _)| *engine_language == language)
+            .map(|(_, engine)| engine)
+    }
+
+    /// Detects the language of the input from the stop words of the sub-engines
+    ///
+    /// The language whose stop words cover the most words of the input is returned, and
+    /// `None` when no language stands out, e.g. for inputs made of names or numbers only.
+    pub fn detect_language(&self, input: &str) -> Option<Language> {
+        let mut scores: Vec<(Language, usize)> = self
+            .engines
+            .iter()
+            .map(|(language, engine)| {
+                let stop_words = &engine.shared_resources().stop_words;
+                let score = tokenize_light(input, NluUtilsLanguage::from_language(*language))
+                    .into_iter()
+                    .filter(|token| stop_words.contains(&token.to_lowercase()))
+                    .count();
+                (*language, score)
+            })
+            .collect();
+        scores.sort_by(|(_, lhs_score), (_, rhs_score)| rhs_score.cmp(lhs_score));
+        match scores.as_slice() {
+            [(language, score)] if *score > 0 => Some(*language),
+            [(language, score), (_, next_score), ..] if score > next_score => Some(*language),
+            _ => None,
+        }
+    }
+
+    /// Parses the input with the sub-engine of the language, or of the detected language when
+    /// `language` is `None`
+    ///
+    /// When the language cannot be detected, the input is parsed by all the sub-engines and
+    /// the result having the most probable intent is returned.
+    pub fn parse(
+        &self,
+        input: &str,
+        language: Option<Language>,
+    ) -> Result<MultilingualParsingResult<IntentParserResult>> {
+        self.parse_with(input, language, |engine| engine.parse(input, None, None))
+    }
+
+    /// Parses the input with the provided options, and returns a detailed result
+    ///
+    /// The sub-engine is selected as in `parse`.
+    pub fn parse_with_options(
+        &self,
+        input: &str,
+        language: Option<Language>,
+        options: &ParseOptions,
+    ) -> Result<MultilingualParsingResult<DetailedIntentParserResult>> {
+        self.parse_with(input, language, |engine| {
+            engine.parse_with_options(input, options)
+        })
+    }
+
+    fn parse_with<R, F>(
+        &self,
+        input: &str,
+        language: Option<Language>,
+        parse: F,
+    ) -> Result<MultilingualParsingResult<R>>
+    where
+        R: IntentScore,
+        F: Fn(&SnipsNluEngine) -> Result<R>,
+    {
+        if let Some(language) = language {
+            let engine = self
+                .engine(language)
+                .ok_or_else(|| SnipsNluError::UnknownLanguage(language.to_string()))?;
+            return Ok(MultilingualParsingResult {
+                language,
+                is_detected: false,
+                result: parse(engine)?,
+            });
+        }
+        if let Some(language) = self.detect_language(input) {
+            let engine = self.engine(language).unwrap();
+            return Ok(MultilingualParsingResult {
+                language,
+                is_detected: true,
+                result: parse(engine)?,
+            });
+        }
+        let mut best_result: Option<(Language, R)> = None;
+        for (language, engine) in self.engines.iter() {
+            let result = parse(engine)?;
+            let is_better = best_result.as_ref().map_or(true, |(_, best_result)| {
+                result.intent_score() > best_result.intent_score()
+            });
+            if is_better {
+                best_result = Some((*language, result));
+            }
+        }
+        let (language, result) = best_result.unwrap();
+        Ok(MultilingualParsingResult {
+            language,
+            is_detected: true,
+            result,
+        })
+    }
+}
+
+/// Score used to select the result of an input whose language was not detected, results
+/// without intent ranking last
+trait IntentScore {
+    fn intent_score(&self) -> f32;
+}
+
+impl IntentScore for IntentParserResult {
+    fn intent_score(&self) -> f32 {
+        if self.intent.intent_name.is_some() {
+            self.intent.confidence_score
+        } else {
+            -1.0
+        }
+    }
+}
+
+impl IntentScore for DetailedIntentParserResult {
+    fn intent_score(&self) -> f32 {
+        self.result.intent_score()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fs_extra::dir;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn copy_test_engines(engine_names: &[&str]) -> tempfile::TempDir {
+        let assistant_dir = tempdir().unwrap();
+        for engine_name in engine_names {
+            let engine_dir = Path::new("data")
+                .join("tests")
+                .join("models")
+                .join(engine_name);
+            dir::copy(engine_dir, assistant_dir.path(), &dir::CopyOptions::new()).unwrap();
+        }
+        assistant_dir
+    }
+
+    #[test]
+    fn test_parse_with_language_detection() {
+        // Given
+        let assistant_dir = copy_test_engines(&["nlu_engine_beverage", "nlu_engine_music"]);
+        let engine = MultilingualNluEngine::from_path(assistant_dir.path()).unwrap();
+
+        // When
+        let en_result = engine
+            .parse("Make me two cups of coffee please", None)
+            .unwrap();
+        let fr_result = engine
+            .parse("je voudrais écouter ma playlist funk", None)
+            .unwrap();
+
+        // Then
+        assert_eq!(vec![Language::EN, Language::FR], engine.languages());
+        assert_eq!(Language::EN, en_result.language);
+        assert!(en_result.is_detected);
+        assert_eq!(
+            Some("MakeCoffee".to_string()),
+            en_result.result.intent.intent_name
+        );
+        assert_eq!(Language::FR, fr_result.language);
+        assert_eq!(
+            Some("adri:PlayMusic".to_string()),
+            fr_result.result.intent.intent_name
+        );
+    }
+
+    #[test]
+    fn test_parse_with_language_hint() {
+        // Given
+        let assistant_dir = copy_test_engines(&["nlu_engine_beverage", "nlu_engine_music"]);
+        let engine = MultilingualNluEngine::from_path(assistant_dir.path()).unwrap();
+
+        // When
+        let result = engine
+            .parse("Make me two cups of coffee please", Some(Language::EN))
+            .unwrap();
+        let unknown_language_result =
+            engine.parse("Make me two cups of coffee please", Some(Language::DE));
+
+        // Then
+        assert_eq!(Language::EN, result.language);
+        assert!(!result.is_detected);
+        assert_eq!(
+            Some("MakeCoffee".to_string()),
+            result.result.intent.intent_name
+        );
+        assert!(unknown_language_result.is_err());
+    }
+
+    #[test]
+    fn test_load_with_duplicated_language() {
+        // Given
+        let assistant_dir = copy_test_engines(&["nlu_engine_beverage", "nlu_engine_game"]);
+
+        // When
+        let engine = MultilingualNluEngine::from_path(assistant_dir.path());
+
+        // Then
+        assert!(engine.is_err());
+    }
+}
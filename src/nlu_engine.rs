@@ -1,38 +1,122 @@
 use std::collections::{HashMap, HashSet};
 use std::fs;
+#[cfg(feature = "async")]
+use std::future::Future;
 use std::io;
+use std::mem;
 use std::path::Path;
+use std::slice;
 use std::str::FromStr;
 use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use failure::{bail, format_err, ResultExt};
+#[cfg(feature = "async")]
+use futures::channel::oneshot;
 use itertools::Itertools;
 use snips_nlu_ontology::{
     BuiltinEntityKind, IntentClassifierResult, IntentParserResult, Language, Slot, SlotValue,
 };
+use snips_nlu_utils::language::Language as NluUtilsLanguage;
 use snips_nlu_utils::string::substring_with_char_range;
 
-use crate::entity_parser::{BuiltinEntityParser, CustomEntityParser};
+use crate::audit_log::{AuditLog, AuditLogEntry};
+use crate::barge_in::PartialInput;
+use crate::cancellation::{check_cancellation, with_cancellation_token};
+use crate::config::{EngineConfig, ParseOptions};
+use crate::detailed_result::{
+    AlternativeSlots, DetailedIntentParserResult, SlotDetails, SlotProvenance,
+};
+use crate::engine_handle::EngineHandle;
+use crate::entity_parser::{
+    with_entity_cache, BuiltinEntityParser, CachingCustomEntityParser, CustomEntityParser,
+    EntityCaches, ScopedBuiltinEntityParser, StreamingEntitySpotter, StreamingSpotterConfig,
+};
 use crate::errors::*;
+use crate::injection::{load_injected_values, InjectedEntity, InjectedValue};
+use crate::intent_classifier::ClassIntercept;
 use crate::intent_parser::*;
+use crate::memory_budget::{MemoryPlan, MemorySaving};
 use crate::models::{
     DatasetMetadata, Entity, ModelVersion, NluEngineModel, ProcessingUnitMetadata,
 };
+use crate::normalization_dictionary::NormalizationDictionary;
 use crate::ontology::IntentParserAlternative;
-use crate::resources::loading::load_shared_resources;
+use crate::resources::loading::{LanguageLayers, ResourceLoader};
 use crate::resources::SharedResources;
+use crate::self_test::{
+    check_result, LatencyPercentiles, SelfTestFailure, SelfTestReport, SELF_TEST_ROUNDS,
+    SELF_TEST_UTTERANCES,
+};
 use crate::slot_utils::*;
-use crate::utils::{extract_nlu_engine_zip_archive, EntityName, IterOps, SlotName};
+use crate::slot_validation::{RejectedSlot, SlotValidator, SlotValidators};
+use crate::text_range::{convert_slots_ranges, grapheme_char_range, TextRanges};
+use crate::time_grain::{apply_time_grain, apply_time_preference, relative_time, time_grain};
+use crate::transliteration::Transliterator;
+use crate::usage_statistics::UsageStatisticsCollector;
+use crate::utils::{
+    extract_nlu_engine_zip_archive, is_blank_input, EntityName, IntentName, IterOps, SlotName,
+};
+use crate::value_normalization::{ValueNormalizer, ValueNormalizers};
+use crate::value_range::{value_range, ValueRange, RANGE_ENTITY_KINDS};
+use crate::value_weights::{EntityValueWeights, ValueWeights};
 
 pub struct SnipsNluEngine {
     dataset_metadata: DatasetMetadata,
     intent_parsers: Vec<Box<dyn IntentParser>>,
     shared_resources: Arc<SharedResources>,
+    injected_values: HashMap<InjectedEntity, HashSet<InjectedValue>>,
+    normalization_dictionary: NormalizationDictionary,
+    slot_validators: SlotValidators,
+    value_normalizers: ValueNormalizers,
+    value_weights: ValueWeights,
+    config: EngineConfig,
+    /// Aliases of the config, by intent of the model
+    aliases_by_model_intent: HashMap<IntentName, IntentName>,
+    memory_savings: Vec<MemorySaving>,
+    entity_caches: EntityCaches,
+    usage_statistics_collector: Option<Arc<UsageStatisticsCollector>>,
+    audit_log: Option<AuditLog>,
+}
+
+/// Result of a parsing bounded by the output limits of the engine
+struct LimitedParsing<'a> {
+    result: IntentParserResult,
+    rejected_slots: Vec<RejectedSlot>,
+    /// Whether the input or the result was truncated
+    truncated: bool,
+    /// Part of the input which was actually parsed
+    text: &'a str,
+    partial_input: Option<PartialInput<'a>>,
 }
 
 impl SnipsNluEngine {
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_path_with_config(path, &EngineConfig::default())
+    }
+
+    pub fn from_path_with_config<P: AsRef<Path>>(path: P, config: &EngineConfig) -> Result<Self> {
+        Self::load(path, config, None)
+    }
+
+    /// Loads the engine, reusing the language resources of the cache when they are identical
+    pub(crate) fn from_path_sharing_language_layers<P: AsRef<Path>>(
+        path: P,
+        config: &EngineConfig,
+        language_layers: LanguageLayers,
+    ) -> Result<Self> {
+        Self::load(path, config, Some(language_layers))
+    }
+
+    fn load<P: AsRef<Path>>(
+        path: P,
+        config: &EngineConfig,
+        language_layers: Option<LanguageLayers>,
+    ) -> Result<Self> {
         let model = SnipsNluEngine::load_model(&path)?;
+        let aliases_by_model_intent = Self::index_intent_aliases(&model, config)?;
 
         let language = Language::from_str(&model.dataset_metadata.language_code)?;
 
@@ -40,18 +124,168 @@ impl SnipsNluEngine {
         let builtin_parser_path = path.as_ref().join(&model.builtin_entity_parser);
         let custom_parser_path = path.as_ref().join(&model.custom_entity_parser);
 
-        let shared_resources =
-            load_shared_resources(&resources_path, builtin_parser_path, custom_parser_path)?;
-
-        let parsers = Self::load_intent_parsers(path, &model, shared_resources.clone())?;
+        let memory_plan = if let Some(memory_budget) = config.memory_budget {
+            MemoryPlan::from_engine_dir(&path, &resources_path, memory_budget)?
+        } else {
+            MemoryPlan::default()
+        };
+        let mut resource_loader = ResourceLoader::with_memory_plan(memory_plan.clone());
+        if let Some(language_layers) = language_layers {
+            resource_loader = resource_loader.sharing_language_layers(language_layers);
+        }
+        let shared_resources = resource_loader.load(
+            &resources_path,
+            builtin_parser_path,
+            custom_parser_path,
+            config,
+        )?;
+
+        let parsers = Self::load_intent_parsers(&path, &model, shared_resources.clone(), config)?;
+        let injected_values = load_injected_values(&path)?;
+        let normalization_dictionary =
+            Self::load_normalization_dictionary(&path, &resources_path, config)?;
+        let value_weights = ValueWeights::from_paths(&config.entity_value_weights)?;
 
         Ok(SnipsNluEngine {
             dataset_metadata: model.dataset_metadata,
             intent_parsers: parsers,
             shared_resources,
+            injected_values,
+            normalization_dictionary,
+            slot_validators: SlotValidators::default(),
+            value_normalizers: ValueNormalizers::default(),
+            value_weights,
+            config: config.clone(),
+            aliases_by_model_intent,
+            memory_savings: memory_plan.savings,
+            entity_caches: EntityCaches::new(config.entity_cache_capacity),
+            usage_statistics_collector: None,
+            audit_log: None,
         })
     }
 
+    /// Replaces the model of the engine with the one of a retrained engine of the same language
+    ///
+    /// The builtin entity parser and the language resources of the engine are kept, which
+    /// makes the update much faster than loading a new engine, so the new engine must have
+    /// been trained with the same version of the resources. Validators, audit log and usage
+    /// statistics collector are kept as well. The engine is left unchanged when the update
+    /// fails.
+    pub fn update_model<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let model = SnipsNluEngine::load_model(&path)?;
+        let aliases_by_model_intent = Self::index_intent_aliases(&model, &self.config)?;
+        let language = Language::from_str(&model.dataset_metadata.language_code)?;
+        if language != self.language()? {
+            bail!(
+                "Cannot update engine with a model in '{}' instead of '{}'",
+                model.dataset_metadata.language_code,
+                self.dataset_metadata.language_code
+            );
+        }
+        let resources_path = path.as_ref().join("resources").join(language.to_string());
+        let memory_plan = if let Some(memory_budget) = self.config.memory_budget {
+            MemoryPlan::from_engine_dir(&path, &resources_path, memory_budget)?
+        } else {
+            MemoryPlan::default()
+        };
+        let custom_entity_parser = CachingCustomEntityParser::from_path(
+            path.as_ref().join(&model.custom_entity_parser),
+            memory_plan.entity_parser_cache_capacity(),
+        )?;
+        let shared_resources = Arc::new(SharedResources {
+            builtin_entity_parser: self.shared_resources.builtin_entity_parser.clone(),
+            custom_entity_parser: Arc::new(custom_entity_parser),
+            gazetteers: self.shared_resources.gazetteers.clone(),
+            stemmer: self.shared_resources.stemmer.clone(),
+            word_clusterers: self.shared_resources.word_clusterers.clone(),
+            stop_words: self.shared_resources.stop_words.clone(),
+            missing_resource_policy: self.shared_resources.missing_resource_policy,
+        });
+        let parsers =
+            Self::load_intent_parsers(&path, &model, shared_resources.clone(), &self.config)?;
+        let injected_values = load_injected_values(&path)?;
+        let normalization_dictionary =
+            Self::load_normalization_dictionary(&path, &resources_path, &self.config)?;
+
+        self.dataset_metadata = model.dataset_metadata;
+        self.aliases_by_model_intent = aliases_by_model_intent;
+        self.intent_parsers = parsers;
+        self.shared_resources = shared_resources;
+        self.injected_values = injected_values;
+        self.normalization_dictionary = normalization_dictionary;
+        self.memory_savings = memory_plan.savings;
+        self.entity_caches = EntityCaches::new(self.config.entity_cache_capacity);
+        Ok(())
+    }
+
+    fn load_normalization_dictionary<P: AsRef<Path>, Q: AsRef<Path>>(
+        path: P,
+        resources_path: Q,
+        config: &EngineConfig,
+    ) -> Result<NormalizationDictionary> {
+        let mut normalization_replacements = NormalizationDictionary::load_replacements(&path)?;
+        normalization_replacements.extend(config.normalization_replacements.iter().cloned());
+        let normalization_dictionary = NormalizationDictionary::new_with_unicode_normalization(
+            normalization_replacements,
+            config.unicode_normalization,
+        )?;
+        if config.transliteration {
+            Ok(normalization_dictionary
+                .with_transliterator(Transliterator::from_resources_dir(resources_path)?))
+        } else {
+            Ok(normalization_dictionary)
+        }
+    }
+
+    /// Loads the engine in a background thread, and returns a handle on it which can be used
+    /// right away
+    pub fn load_in_background<P: AsRef<Path>>(path: P) -> EngineHandle {
+        Self::load_in_background_with_config(path, EngineConfig::default())
+    }
+
+    pub fn load_in_background_with_config<P: AsRef<Path>>(
+        path: P,
+        config: EngineConfig,
+    ) -> EngineHandle {
+        EngineHandle::load(path, config)
+    }
+
+    /// Loads the engine in a separate thread, the returned future resolving once the model
+    /// files are read and deserialized, so that the executor polling it is never blocked
+    #[cfg(feature = "async")]
+    pub fn load_async<P: AsRef<Path>>(path: P) -> impl Future<Output = Result<Self>> {
+        Self::load_async_with_config(path, EngineConfig::default())
+    }
+
+    #[cfg(feature = "async")]
+    pub fn load_async_with_config<P: AsRef<Path>>(
+        path: P,
+        config: EngineConfig,
+    ) -> impl Future<Output = Result<Self>> {
+        let path = path.as_ref().to_path_buf();
+        let (sender, receiver) = oneshot::channel();
+        let thread_pool = config.thread_pool.build();
+        let load_engine = move || {
+            // The receiver is gone when the future was dropped, in which case the engine is
+            // simply discarded
+            let _ = sender.send(Self::from_path_with_config(&path, &config));
+        };
+        // As in `EngineHandle::load`, the engine is loaded in the thread pool of the config
+        let spawn_result = thread_pool.map(|thread_pool| match thread_pool {
+            Some(thread_pool) => thread_pool.spawn(load_engine),
+            None => {
+                thread::spawn(load_engine);
+            }
+        });
+        async move {
+            spawn_result.with_context(|_| "Cannot build thread pool to load engine")?;
+            receiver
+                .await
+                .map_err(|_| format_err!("Loading of the engine was interrupted"))
+                .and_then(|loading_result| loading_result)
+        }
+    }
+
     fn check_model_version<P: AsRef<Path>>(path: P) -> Result<()> {
         let model_file = fs::File::open(&path)?;
 
@@ -72,8 +306,25 @@ impl SnipsNluEngine {
         })?;
         let model_file = fs::File::open(&engine_model_path)
             .with_context(|_| format!("Could not open nlu engine file {:?}", &engine_model_path))?;
-        let model = serde_json::from_reader(model_file)
+        let model: NluEngineModel = serde_json::from_reader(model_file)
             .with_context(|_| format!("Invalid nlu engine file {:?}", &engine_model_path))?;
+        for (intent, required_slots) in model.dataset_metadata.required_slots.iter() {
+            let slot_names = model
+                .dataset_metadata
+                .slot_name_mappings
+                .get(intent)
+                .ok_or_else(|| SnipsNluError::UnknownIntent(intent.to_string()))?;
+            if let Some(unknown_slot) = required_slots
+                .iter()
+                .find(|slot_name| !slot_names.contains_key(*slot_name))
+            {
+                bail!(
+                    "Required slot '{}' is not a slot of intent '{}'",
+                    unknown_slot,
+                    intent
+                );
+            }
+        }
         Ok(model)
     }
 
@@ -81,8 +332,11 @@ impl SnipsNluEngine {
         engine_dir: P,
         model: &NluEngineModel,
         shared_resources: Arc<SharedResources>,
+        config: &EngineConfig,
     ) -> Result<Vec<Box<dyn IntentParser>>> {
-        model
+        let config = &config.with_model_intent_keys();
+        let shared_resources = Self::scope_builtin_entities(model, shared_resources, config);
+        let parsers = model
             .intent_parsers
             .iter()
             .map(|parser_name| {
@@ -98,9 +352,109 @@ impl SnipsNluEngine {
                             parser_name
                         )
                     })?;
-                Ok(build_intent_parser(metadata, parser_path, shared_resources.clone())? as _)
+                let parser =
+                    build_intent_parser(metadata, parser_path, shared_resources.clone(), config)?;
+                Ok((parser_name.to_string(), parser))
             })
-            .collect::<Result<Vec<_>>>()
+            .collect::<Result<Vec<_>>>()?;
+        let parsers = if config.custom_intent_parsers.is_empty() {
+            parsers
+        } else {
+            config.custom_intent_parsers.insert_parsers(
+                engine_dir.as_ref(),
+                shared_resources,
+                parsers,
+            )?
+        };
+        if let Some(ensemble_config) = config.ensemble.as_ref() {
+            let ensemble_parser = parsers.into_iter().fold(
+                EnsembleParser::new(),
+                |ensemble_parser, (parser_name, parser)| {
+                    let weight = ensemble_config
+                        .parsers_weights
+                        .get(&parser_name)
+                        .cloned()
+                        .unwrap_or(ensemble_config.default_weight);
+                    ensemble_parser.with_parser(parser, weight)
+                },
+            );
+            return Ok(vec![Box::new(ensemble_parser)]);
+        }
+        Ok(parsers.into_iter().map(|(_, parser)| parser).collect())
+    }
+
+    /// Indexes the intent aliases of the config by intent of the model, after checking that
+    /// each intent has at most one alias and that no alias is the name of an intent of the
+    /// model
+    fn index_intent_aliases(
+        model: &NluEngineModel,
+        config: &EngineConfig,
+    ) -> Result<HashMap<IntentName, IntentName>> {
+        let model_intents = &model.dataset_metadata.slot_name_mappings;
+        let mut aliases_by_model_intent = HashMap::with_capacity(config.intent_aliases.len());
+        for (alias, model_intent) in config.intent_aliases.iter().sorted() {
+            let invalid_alias = |message: String| SnipsNluError::InvalidConfig {
+                key: format!("intent_aliases.{}", alias),
+                message,
+            };
+            if model_intents.contains_key(alias) {
+                bail!(invalid_alias(format!(
+                    "alias collides with the intent '{}' of the model",
+                    alias
+                )));
+            }
+            if let Some(other_alias) =
+                aliases_by_model_intent.insert(model_intent.clone(), alias.clone())
+            {
+                bail!(invalid_alias(format!(
+                    "intent '{}' is already aliased as '{}'",
+                    model_intent, other_alias
+                )));
+            }
+        }
+        Ok(aliases_by_model_intent)
+    }
+
+    /// Restricts the builtin entity parser of the intent parsers to the kinds which at least
+    /// one intent uses, when some intents declare their kinds in the config
+    ///
+    /// The engine keeps the unrestricted parser, so that it remains reusable when the model is
+    /// updated.
+    fn scope_builtin_entities(
+        model: &NluEngineModel,
+        shared_resources: Arc<SharedResources>,
+        config: &EngineConfig,
+    ) -> Arc<SharedResources> {
+        if config.builtin_entity_scopes.is_empty() {
+            return shared_resources;
+        }
+        let scope = model
+            .dataset_metadata
+            .slot_name_mappings
+            .iter()
+            .flat_map(
+                |(intent, mapping)| match config.builtin_entity_scopes.get(intent) {
+                    Some(entity_kinds) => entity_kinds.clone(),
+                    None => mapping
+                        .values()
+                        .flat_map(|entity| BuiltinEntityKind::from_identifier(entity).ok())
+                        .collect(),
+                },
+            )
+            .unique()
+            .collect();
+        Arc::new(SharedResources {
+            builtin_entity_parser: Arc::new(ScopedBuiltinEntityParser::new(
+                shared_resources.builtin_entity_parser.clone(),
+                scope,
+            )),
+            custom_entity_parser: shared_resources.custom_entity_parser.clone(),
+            gazetteers: shared_resources.gazetteers.clone(),
+            stemmer: shared_resources.stemmer.clone(),
+            word_clusterers: shared_resources.word_clusterers.clone(),
+            stop_words: shared_resources.stop_words.clone(),
+            missing_resource_policy: shared_resources.missing_resource_policy,
+        })
     }
 }
 
@@ -111,22 +465,184 @@ impl SnipsNluEngine {
         shared_resources: Arc<SharedResources>,
     ) -> Result<Self> {
         let model = SnipsNluEngine::load_model(&path)?;
-        let parsers = Self::load_intent_parsers(path, &model, shared_resources.clone())?;
+        let parsers = Self::load_intent_parsers(
+            &path,
+            &model,
+            shared_resources.clone(),
+            &EngineConfig::default(),
+        )?;
+        let injected_values = load_injected_values(&path)?;
+        let normalization_dictionary =
+            NormalizationDictionary::new(NormalizationDictionary::load_replacements(&path)?)?;
 
         Ok(SnipsNluEngine {
             dataset_metadata: model.dataset_metadata,
             intent_parsers: parsers,
             shared_resources,
+            injected_values,
+            normalization_dictionary,
+            slot_validators: SlotValidators::default(),
+            value_normalizers: ValueNormalizers::default(),
+            value_weights: ValueWeights::default(),
+            config: EngineConfig::default(),
+            aliases_by_model_intent: HashMap::new(),
+            memory_savings: vec![],
+            entity_caches: EntityCaches::new(0),
+            usage_statistics_collector: None,
+            audit_log: None,
         })
     }
 }
 
+impl SnipsNluEngine {
+    /// Returns the languages in which engines can be loaded
+    pub fn supported_languages() -> &'static [Language] {
+        Language::all()
+    }
+
+    /// Returns the builtin entities which can be extracted in the given language, according to
+    /// the languages supported by the compiled builtin entity parser
+    pub fn supported_builtin_entities(language: Language) -> Vec<BuiltinEntityKind> {
+        BuiltinEntityKind::all()
+            .iter()
+            .filter(|kind| kind.supported_languages().contains(&language))
+            .cloned()
+            .collect()
+    }
+
+    pub fn language(&self) -> Result<Language> {
+        Ok(Language::from_str(&self.dataset_metadata.language_code)?)
+    }
+
+    /// Returns the names of the intents supported by the engine
+    pub fn intents(&self) -> Vec<&str> {
+        self.dataset_metadata
+            .slot_name_mappings
+            .keys()
+            .map(|intent| self.intent_alias(intent))
+            .sorted()
+            .collect()
+    }
+
+    /// Returns the intent name of the model corresponding to an intent name of the
+    /// application, which is either an alias or the intent name itself
+    fn model_intent_name<'a>(&'a self, intent: &'a str) -> &'a str {
+        self.config
+            .intent_aliases
+            .get(intent)
+            .map_or(intent, |model_intent| &**model_intent)
+    }
+
+    /// Fails with `SnipsNluError::UnknownIntent` when the intent, or its alias, is not an
+    /// intent of the model
+    fn check_intent(&self, intent: &str) -> Result<()> {
+        if !self
+            .dataset_metadata
+            .slot_name_mappings
+            .contains_key(self.model_intent_name(intent))
+        {
+            bail!(SnipsNluError::UnknownIntent(intent.to_string()));
+        }
+        Ok(())
+    }
+
+    fn model_intent_names<'a>(&'a self, intents: Vec<&'a str>) -> Vec<&'a str> {
+        intents
+            .into_iter()
+            .map(|intent| self.model_intent_name(intent))
+            .collect()
+    }
+
+    /// Returns the name under which an intent of the model is exposed to the application
+    pub(crate) fn intent_alias<'a>(&'a self, model_intent: &'a str) -> &'a str {
+        self.aliases_by_model_intent
+            .get(model_intent)
+            .map_or(model_intent, |alias| &**alias)
+    }
+
+    fn alias_intent_result(&self, result: &mut IntentClassifierResult) {
+        result.intent_name = result
+            .intent_name
+            .as_ref()
+            .map(|intent| self.intent_alias(intent).to_string());
+    }
+
+    /// Degraded strategies which were applied when loading the engine, in order to fit in
+    /// the memory budget of its `EngineConfig`
+    pub fn memory_savings(&self) -> &[MemorySaving] {
+        &self.memory_savings
+    }
+
+    /// Intercepts of the intent classifiers of the engine, along with the adjustments which
+    /// were applied to them when loading the engine
+    pub fn intent_classifier_intercepts(&self) -> Result<Vec<ClassIntercept>> {
+        let mut intercepts = vec![];
+        for parser in self.intent_parsers.iter() {
+            for mut intercept in parser.intent_classifier_intercepts()? {
+                intercept.intent_name = intercept
+                    .intent_name
+                    .map(|intent| self.intent_alias(&intent).to_string());
+                intercepts.push(intercept);
+            }
+        }
+        Ok(intercepts)
+    }
+
+    pub(crate) fn shared_resources(&self) -> Arc<SharedResources> {
+        self.shared_resources.clone()
+    }
+}
+
 impl SnipsNluEngine {
     pub fn from_zip<R: io::Read + io::Seek>(reader: R) -> Result<Self> {
+        Self::from_zip_with_config(reader, &EngineConfig::default())
+    }
+
+    pub fn from_zip_with_config<R: io::Read + io::Seek>(
+        reader: R,
+        config: &EngineConfig,
+    ) -> Result<Self> {
         let temp_dir = tempfile::Builder::new().prefix("temp_dir_nlu_").tempdir()?;
         let temp_dir_path = temp_dir.path();
         let engine_dir_path = extract_nlu_engine_zip_archive(reader, temp_dir_path)?;
-        Ok(SnipsNluEngine::from_path(engine_dir_path)?)
+        SnipsNluEngine::from_path_with_config(engine_dir_path, config)
+    }
+
+    /// Loads the engine from a zip archive held in memory, typically downloaded from the
+    /// network
+    ///
+    /// The CRF models and the builtin entity parser can only be loaded from files, so the
+    /// archive is still extracted in a temporary directory, which is removed once the engine
+    /// is loaded.
+    pub fn from_zip_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_zip_bytes_with_config(bytes, &EngineConfig::default())
+    }
+
+    pub fn from_zip_bytes_with_config(bytes: &[u8], config: &EngineConfig) -> Result<Self> {
+        Self::from_zip_with_config(io::Cursor::new(bytes), config)
+    }
+
+    /// Loads the engine from a model archive served over HTTPS, typically by a model registry
+    ///
+    /// The archive is cached in `cache_dir` and is only downloaded again when its ETag changed,
+    /// interrupted downloads being resumed. The fingerprint of a downloaded archive is checked
+    /// against the `X-Model-Fingerprint` header of the response, when provided, before the
+    /// engine is loaded. The cached archive is used when the registry cannot be reached.
+    #[cfg(feature = "remote")]
+    pub fn from_url<P: AsRef<Path>>(url: &str, cache_dir: P) -> Result<Self> {
+        Self::from_url_with_config(url, cache_dir, &EngineConfig::default())
+    }
+
+    #[cfg(feature = "remote")]
+    pub fn from_url_with_config<P: AsRef<Path>>(
+        url: &str,
+        cache_dir: P,
+        config: &EngineConfig,
+    ) -> Result<Self> {
+        let model_path = crate::remote::fetch_model(url, cache_dir.as_ref())?;
+        let model_file = fs::File::open(&model_path)
+            .with_context(|_| format!("Cannot open cached model {:?}", model_path))?;
+        Self::from_zip_with_config(model_file, config)
     }
 }
 
@@ -152,6 +668,323 @@ impl SnipsNluEngine {
         intents_alternatives: usize,
         slots_alternatives: usize,
     ) -> Result<IntentParserResult>
+    where
+        W: Into<Option<Vec<&'a str>>>,
+        B: Into<Option<Vec<&'b str>>>,
+    {
+        let options = ParseOptions::new(
+            intents_whitelist,
+            intents_blacklist,
+            intents_alternatives,
+            slots_alternatives,
+        );
+        let mut result = self.parse_with_output_limits(input, &options)?.result;
+        self.convert_result_ranges(&mut result);
+        Ok(result)
+    }
+
+    /// Parses the input, restricting the classification of both the deterministic and the
+    /// probabilistic intent parsers to the `allowed_intents`
+    ///
+    /// The intents which are not allowed are never considered, so they cannot appear in the
+    /// result or in its alternatives.
+    pub fn parse_with_filter(
+        &self,
+        input: &str,
+        allowed_intents: &[&str],
+    ) -> Result<IntentParserResult> {
+        self.parse(input, allowed_intents.to_vec(), None::<Vec<&str>>)
+    }
+
+    /// Parses the input with the provided options, and returns a detailed result
+    ///
+    /// When a cancellation token is provided in the options and cancelled during parsing, a
+    /// `SnipsNluError::ParseCancelled` error is returned.
+    pub fn parse_with_options(
+        &self,
+        input: &str,
+        options: &ParseOptions,
+    ) -> Result<DetailedIntentParserResult> {
+        with_cancellation_token(options.cancellation_token.as_ref(), || {
+            self.parse_with_slots_details(input, options)
+        })
+    }
+
+    fn parse_with_slots_details(
+        &self,
+        input: &str,
+        options: &ParseOptions,
+    ) -> Result<DetailedIntentParserResult> {
+        let LimitedParsing {
+            mut result,
+            mut rejected_slots,
+            truncated,
+            text: parsed_input,
+            partial_input,
+        } = self.parse_with_output_limits(input, options)?;
+        check_cancellation()?;
+        let parsing_timestamp = if self.config.relative_time_output {
+            Some(current_timestamp()?)
+        } else {
+            None
+        };
+        let slots_details = result
+            .slots
+            .iter()
+            .map(|slot| {
+                Ok(SlotDetails {
+                    provenance: self.get_slot_provenance(slot),
+                    grain: time_grain(&slot.value),
+                    relative_time: parsing_timestamp
+                        .and_then(|timestamp| relative_time(&slot.value, timestamp)),
+                    value_range: self.get_value_range(&result.input, slot)?,
+                    entity_value_id: self.get_entity_value_id(slot).map(|id| id.to_string()),
+                    text_ranges: TextRanges::new(
+                        &result.input,
+                        &grapheme_char_range(&result.input, &slot.range),
+                    ),
+                })
+            })
+            .collect::<Result<_>>()?;
+        let missing_slots = result
+            .intent
+            .intent_name
+            .as_ref()
+            .map(|intent| self.get_missing_slots(intent, &result.slots))
+            .unwrap_or_else(|| vec![]);
+        let mut alternative_slots: Vec<AlternativeSlots> = match result.intent.intent_name.as_ref()
+        {
+            Some(intent) if options.nbest_slots > 0 => {
+                let mut hypotheses = self
+                    .slots_nbest(parsed_input, intent, options.nbest_slots + 1)?
+                    .into_iter()
+                    .map(|mut alternative| {
+                        alternative
+                            .slots
+                            .retain(|slot| !options.excludes_slot(slot));
+                        if let Some(partial_input) = partial_input.as_ref() {
+                            partial_input.retain_complete_slots(&mut alternative.slots);
+                        }
+                        alternative
+                    });
+                // The best hypothesis is the one of the result, before its slots were
+                // post-processed, so it is skipped along with its duplicates
+                match hypotheses.next() {
+                    Some(best) => hypotheses
+                        .filter(|alternative| alternative.slots != best.slots)
+                        .take(options.nbest_slots)
+                        .collect(),
+                    None => vec![],
+                }
+            }
+            _ => vec![],
+        };
+        for alternative in alternative_slots.iter_mut() {
+            self.convert_ranges(&result.input, &mut alternative.slots);
+        }
+        for rejected_slot in rejected_slots.iter_mut() {
+            self.convert_ranges(&result.input, slice::from_mut(&mut rejected_slot.slot));
+        }
+        self.convert_result_ranges(&mut result);
+        Ok(DetailedIntentParserResult {
+            result,
+            slots_details,
+            missing_slots,
+            rejected_slots,
+            alternative_slots,
+            truncated,
+        })
+    }
+
+    /// Runs the function with the entity cache of the input, so that the builtin entities
+    /// extracted from it by the successive stages of the parsing are extracted only once
+    fn with_input_entity_cache<T, F>(&self, input: &str, f: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        with_entity_cache(self.entity_caches.get(input)?, f)
+    }
+
+    /// Parses the input truncated to the output limits of the engine, and truncates the result
+    /// accordingly
+    fn parse_with_output_limits<'a>(
+        &self,
+        input: &'a str,
+        options: &ParseOptions,
+    ) -> Result<LimitedParsing<'a>> {
+        let output_limits = &self.config.output_limits;
+        let partial_input = options
+            .partial_token_detection
+            .as_ref()
+            .and_then(|detection| detection.detect(input));
+        let parsed_input = partial_input
+            .as_ref()
+            .map_or(input, |partial_input| partial_input.text);
+        let truncated_input = output_limits.truncate_input(parsed_input);
+        let (mut result, rejected_slots) = self.with_input_entity_cache(truncated_input, || {
+            self.parse_with_rejected_slots(truncated_input, options)
+        })?;
+        self.alias_intent_result(&mut result.intent);
+        for alternative in result.alternatives.iter_mut() {
+            self.alias_intent_result(&mut alternative.intent);
+        }
+        if let Some(partial_input) = partial_input.as_ref() {
+            partial_input.remove_partial_slots(&mut result);
+        }
+        result.input = input.to_string();
+        let is_truncated = output_limits.truncate_result(&mut result)
+            || truncated_input.len() < parsed_input.len();
+        if let Some(audit_log) = self.audit_log.as_ref() {
+            audit_log.record(&result)?;
+        }
+        Ok(LimitedParsing {
+            result,
+            rejected_slots,
+            truncated: is_truncated,
+            text: truncated_input,
+            partial_input,
+        })
+    }
+
+    /// Parses the input and validates the extracted slots, the slots rejected by the
+    /// validators of the top intent being returned separately
+    fn parse_with_rejected_slots(
+        &self,
+        input: &str,
+        options: &ParseOptions,
+    ) -> Result<(IntentParserResult, Vec<RejectedSlot>)> {
+        if is_blank_input(input) {
+            let result = IntentParserResult {
+                input: input.to_string(),
+                intent: IntentClassifierResult {
+                    intent_name: None,
+                    confidence_score: 1.0,
+                },
+                slots: vec![],
+                alternatives: vec![],
+            };
+            self.record_usage_statistics(&result)?;
+            return Ok((result, vec![]));
+        }
+        let intents_whitelist = options
+            .intents_whitelist
+            .as_ref()
+            .map(|whitelist| whitelist.iter().map(|intent| &**intent).collect::<Vec<_>>());
+        let intents_blacklist = options
+            .intents_blacklist
+            .as_ref()
+            .map(|blacklist| blacklist.iter().map(|intent| &**intent).collect::<Vec<_>>());
+        // Alternative resolutions of time slots are needed to apply time preferences, and the
+        // ones of weighted entities to rank them
+        let uses_time_preferences = self.uses_time_preferences(options);
+        let uses_value_weights = !self.value_weights.is_empty();
+        let mut slots_alternatives = options.slots_alternatives;
+        if uses_time_preferences {
+            slots_alternatives = slots_alternatives.max(TIME_PREFERENCE_CANDIDATES);
+        }
+        if uses_value_weights {
+            slots_alternatives = slots_alternatives.max(VALUE_WEIGHTS_CANDIDATES);
+        }
+        let mut result =
+            if let Some(normalized_input) = self.normalization_dictionary.normalize(input) {
+                let mut result = self.parse_normalized_input(
+                    &normalized_input.text,
+                    intents_whitelist,
+                    intents_blacklist,
+                    options.intents_alternatives,
+                    slots_alternatives,
+                )?;
+                result.input = input.to_string();
+                normalized_input.restore_slots(input, &mut result.slots);
+                for alternative in result.alternatives.iter_mut() {
+                    normalized_input.restore_slots(input, &mut alternative.slots);
+                }
+                result
+            } else {
+                self.parse_normalized_input(
+                    input,
+                    intents_whitelist,
+                    intents_blacklist,
+                    options.intents_alternatives,
+                    slots_alternatives,
+                )?
+            };
+        check_cancellation()?;
+        // Excluded slots are removed after decoding, so that no retraining is needed
+        result.slots.retain(|slot| !options.excludes_slot(slot));
+        for alternative in result.alternatives.iter_mut() {
+            alternative
+                .slots
+                .retain(|slot| !options.excludes_slot(slot));
+        }
+        if uses_value_weights {
+            if let Some(intent) = result.intent.intent_name.as_ref() {
+                self.value_weights
+                    .rank(intent, &mut result.slots, options.slots_alternatives);
+            }
+            for alternative in result.alternatives.iter_mut() {
+                if let Some(intent) = alternative.intent.intent_name.as_ref() {
+                    self.value_weights.rank(
+                        intent,
+                        &mut alternative.slots,
+                        options.slots_alternatives,
+                    );
+                }
+            }
+        }
+        if uses_time_preferences {
+            let timestamp = current_timestamp()?;
+            if let Some(intent) = result.intent.intent_name.clone() {
+                self.apply_time_preference(&intent, options, timestamp, &mut result.slots);
+            }
+            for alternative in result.alternatives.iter_mut() {
+                if let Some(intent) = alternative.intent.intent_name.clone() {
+                    self.apply_time_preference(&intent, options, timestamp, &mut alternative.slots);
+                }
+            }
+        }
+        if let Some(intent) = result.intent.intent_name.as_ref() {
+            self.apply_time_grain(intent, &mut result.slots);
+        }
+        for alternative in result.alternatives.iter_mut() {
+            if let Some(intent) = alternative.intent.intent_name.as_ref() {
+                self.apply_time_grain(intent, &mut alternative.slots);
+            }
+        }
+        if !self.value_normalizers.is_empty() {
+            self.value_normalizers.normalize(&mut result.slots);
+            for alternative in result.alternatives.iter_mut() {
+                self.value_normalizers.normalize(&mut alternative.slots);
+            }
+        }
+        self.record_usage_statistics(&result)?;
+        if self.slot_validators.is_empty() {
+            return Ok((result, vec![]));
+        }
+        let (slots, rejected_slots) = self.slot_validators.validate(result.slots);
+        result.slots = slots;
+        for alternative in result.alternatives.iter_mut() {
+            let slots = mem::replace(&mut alternative.slots, vec![]);
+            alternative.slots = self.slot_validators.validate(slots).0;
+        }
+        Ok((result, rejected_slots))
+    }
+
+    fn record_usage_statistics(&self, result: &IntentParserResult) -> Result<()> {
+        if let Some(collector) = self.usage_statistics_collector.as_ref() {
+            collector.record(result, NluUtilsLanguage::from_language(self.language()?))?;
+        }
+        Ok(())
+    }
+
+    fn parse_normalized_input<'a, 'b, W, B>(
+        &self,
+        input: &str,
+        intents_whitelist: W,
+        intents_blacklist: B,
+        intents_alternatives: usize,
+        slots_alternatives: usize,
+    ) -> Result<IntentParserResult>
     where
         W: Into<Option<Vec<&'a str>>>,
         B: Into<Option<Vec<&'b str>>>,
@@ -163,11 +996,19 @@ impl SnipsNluEngine {
             .map(|whitelist| whitelist.as_ref());
         let mut parsing_result: Option<IntentParserResult> = None;
         let mut none_score: f32 = 0.0;
-        for parser in &self.intent_parsers {
+        for (parser_index, parser) in self.intent_parsers.iter().enumerate() {
+            check_cancellation()?;
             let internal_parsing_result = parser.parse(input, intents_whitelist)?;
-            if internal_parsing_result.intent.intent_name.is_some() {
+            if let Some(intent_name) = internal_parsing_result.intent.intent_name.as_ref() {
+                let mut slots = internal_parsing_result.slots;
+                if let Some(conflict_resolution) = self.config.slots_merging {
+                    for other_parser in self.intent_parsers.iter().skip(parser_index + 1) {
+                        let other_slots = other_parser.get_slots(input, intent_name)?;
+                        slots = merge_slots(slots, other_slots, conflict_resolution);
+                    }
+                }
                 let resolved_slots = self
-                    .resolve_slots(input, internal_parsing_result.slots, slots_alternatives)
+                    .resolve_slots(input, slots, slots_alternatives)
                     .with_context(|_| "Cannot resolve slots".to_string())?;
 
                 parsing_result = Some(IntentParserResult {
@@ -198,9 +1039,10 @@ impl SnipsNluEngine {
         if intents_alternatives == 0 {
             return Ok(parsing_result);
         }
+        check_cancellation()?;
 
         let alternative_results: Vec<IntentParserAlternative> = self
-            .get_intents(input)?
+            .get_model_intents(input)?
             .into_iter()
             .filter(|res| {
                 res.intent_name
@@ -218,7 +1060,7 @@ impl SnipsNluEngine {
                 res.intent_name
                     .as_ref()
                     .map(|intent_name| {
-                        Ok(self.get_slots_with_alternatives(
+                        Ok(self.get_normalized_input_slots(
                             input,
                             intent_name,
                             slots_alternatives,
@@ -233,35 +1075,273 @@ impl SnipsNluEngine {
         Ok(parsing_result)
     }
 
-    fn get_intents_whitelist<'a: 'c, 'b: 'c, 'c, W, B>(
-        &'c self,
+    /// Same as `parse_with_alternatives`, but the result is enriched with additional
+    /// information about each extracted slot, such as its provenance
+    pub fn parse_with_details<'a, 'b, W, B>(
+        &self,
+        input: &str,
         intents_whitelist: W,
         intents_blacklist: B,
-    ) -> Result<Option<Vec<&'c str>>>
+        intents_alternatives: usize,
+        slots_alternatives: usize,
+    ) -> Result<DetailedIntentParserResult>
     where
         W: Into<Option<Vec<&'a str>>>,
         B: Into<Option<Vec<&'b str>>>,
     {
-        let all_intents: HashSet<&str> = self
-            .dataset_metadata
-            .slot_name_mappings
-            .keys()
-            .map(|intent| &**intent)
-            .collect();
-        let intents_whitelist = intents_whitelist.into();
-        let intents_blacklist = intents_blacklist.into();
-        if let Some(unknown_intent) = vec![intents_whitelist.as_ref(), intents_blacklist.as_ref()]
-            .into_iter()
-            .flatten()
-            .flatten()
-            .find(|intent| !all_intents.contains(*intent))
-        {
-            return Err(format_err!(
-                "Cannot use unknown intent '{}' in intents filter",
-                unknown_intent
-            ));
+        let options = ParseOptions::new(
+            intents_whitelist,
+            intents_blacklist,
+            intents_alternatives,
+            slots_alternatives,
+        );
+        self.parse_with_options(input, &options)
+    }
+
+    /// Expresses the char ranges of the slots of the result in the unit of the `EngineConfig`
+    fn convert_result_ranges(&self, result: &mut IntentParserResult) {
+        self.convert_ranges(&result.input, &mut result.slots);
+        for alternative in result.alternatives.iter_mut() {
+            self.convert_ranges(&result.input, &mut alternative.slots);
+        }
+    }
+
+    fn convert_ranges(&self, input: &str, slots: &mut [Slot]) {
+        convert_slots_ranges(input, slots, self.config.range_unit);
+    }
+
+    fn get_value_range(&self, input: &str, slot: &Slot) -> Result<Option<ValueRange>> {
+        let entity_kind = match BuiltinEntityKind::from_identifier(&slot.entity) {
+            Ok(entity_kind) if RANGE_ENTITY_KINDS.contains(&entity_kind) => entity_kind,
+            _ => return Ok(None),
         };
-        let reverted_whitelist: Option<Vec<&str>> = intents_blacklist.map(|blacklist| {
+        let entities = self
+            .shared_resources
+            .builtin_entity_parser
+            .extract_entities(input, Some(&[entity_kind]), true, 0)?;
+        Ok(value_range(input, slot, &entities, self.language()?))
+    }
+
+    fn uses_time_preferences(&self, options: &ParseOptions) -> bool {
+        options.time_preference.is_some() || !self.dataset_metadata.time_preferences.is_empty()
+    }
+
+    fn apply_time_preference(
+        &self,
+        intent: &str,
+        options: &ParseOptions,
+        reference_timestamp: i64,
+        slots: &mut [Slot],
+    ) {
+        let time_preference = options
+            .time_preference
+            .or_else(|| self.dataset_metadata.time_preferences.get(intent).cloned());
+        for slot in slots.iter_mut() {
+            if let Some(time_preference) = time_preference {
+                apply_time_preference(slot, time_preference, reference_timestamp);
+            }
+            slot.alternatives.truncate(options.slots_alternatives);
+        }
+    }
+
+    fn apply_time_grain(&self, intent: &str, slots: &mut [Slot]) {
+        let time_grain_config = self
+            .config
+            .intents_time_grains
+            .get(intent)
+            .or_else(|| {
+                self.config
+                    .intents_time_grains
+                    .get(self.intent_alias(intent))
+            })
+            .or_else(|| self.config.default_time_grain.as_ref());
+        if let Some(time_grain_config) = time_grain_config {
+            for slot in slots.iter_mut() {
+                if let Some(value) = apply_time_grain(&slot.value, time_grain_config) {
+                    slot.value = value;
+                }
+            }
+        }
+    }
+
+    /// Records statistics about all the subsequent parses in the collector
+    pub fn set_usage_statistics_collector(&mut self, collector: Arc<UsageStatisticsCollector>) {
+        self.usage_statistics_collector = Some(collector);
+    }
+
+    /// Stores all the subsequent parses in the audit log
+    pub fn set_audit_log(&mut self, audit_log: AuditLog) {
+        self.audit_log = Some(audit_log);
+    }
+
+    /// Returns the parses stored in the audit log, from the oldest to the most recent one, or
+    /// nothing when no audit log is set
+    pub fn recent_parses(&self) -> Result<Vec<AuditLogEntry>> {
+        match self.audit_log.as_ref() {
+            Some(audit_log) => audit_log.entries(),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Registers a validator applied to all the extracted slots having the provided name
+    pub fn add_slot_name_validator<V>(&mut self, slot_name: &str, validator: V)
+    where
+        V: SlotValidator + 'static,
+    {
+        self.slot_validators
+            .add_slot_name_validator(slot_name, validator)
+    }
+
+    /// Registers a validator applied to all the extracted slots having the provided entity
+    pub fn add_entity_validator<V>(&mut self, entity: &str, validator: V)
+    where
+        V: SlotValidator + 'static,
+    {
+        self.slot_validators.add_entity_validator(entity, validator)
+    }
+
+    /// Registers a normalizer applied to the resolved values of all the extracted slots having
+    /// the provided entity, before the slot validators
+    pub fn add_entity_normalizer<N>(&mut self, entity: &str, normalizer: N)
+    where
+        N: ValueNormalizer + 'static,
+    {
+        self.value_normalizers
+            .add_entity_normalizer(entity, normalizer)
+    }
+
+    /// Returns a spotter of the mentions of the custom entities of the engine in a stream of
+    /// tokens, such as a live transcript
+    pub fn streaming_entity_spotter(
+        &self,
+        config: StreamingSpotterConfig,
+    ) -> Result<StreamingEntitySpotter> {
+        StreamingEntitySpotter::new(self.shared_resources.custom_entity_parser.clone(), config)
+    }
+
+    /// Ranks the resolutions of the slots of the entity with popularity weights, instead of
+    /// the weights of the weighted gazetteer registered in the `EngineConfig`, if any
+    pub fn set_entity_value_weights(&mut self, entity: &str, weights: EntityValueWeights) {
+        self.value_weights.set_entity_weights(entity, weights)
+    }
+
+    /// Returns the required slots of the intent which are not filled by the provided slots,
+    /// in the order in which they are declared in the model
+    pub fn get_missing_slots(&self, intent: &str, slots: &[Slot]) -> Vec<SlotName> {
+        self.dataset_metadata
+            .required_slots
+            .get(self.model_intent_name(intent))
+            .map(|required_slots| {
+                required_slots
+                    .iter()
+                    .filter(|required_slot| {
+                        !slots.iter().any(|slot| &slot.slot_name == *required_slot)
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_else(|| vec![])
+    }
+
+    /// Tells whether the resolved value of the slot was injected at runtime or was already
+    /// known at training time
+    pub fn get_slot_provenance(&self, slot: &Slot) -> SlotProvenance {
+        let is_injected = self
+            .injected_values
+            .get(&slot.entity)
+            .and_then(|values| slot_value_as_str(&slot.value).map(|value| values.contains(value)))
+            .unwrap_or(false);
+        if is_injected {
+            SlotProvenance::Injected
+        } else {
+            SlotProvenance::Trained
+        }
+    }
+
+    /// Parses a built-in battery of multilingual utterances, checks the invariants of the
+    /// results and measures the parsing latencies
+    ///
+    /// This is a smoke test which embedders can run at startup on the target hardware. The
+    /// parses are recorded in the audit log and in the usage statistics like any other parse.
+    pub fn self_test(&self) -> SelfTestReport {
+        let mut failures = vec![];
+        let mut durations = Vec::with_capacity(SELF_TEST_ROUNDS * SELF_TEST_UTTERANCES.len());
+        let options = ParseOptions::new(None::<Vec<&str>>, None::<Vec<&str>>, 2, 1);
+        for round in 0..SELF_TEST_ROUNDS {
+            for input in SELF_TEST_UTTERANCES {
+                let start = Instant::now();
+                // Ranges are checked in chars, before their conversion to the configured unit
+                let result = self
+                    .parse_with_output_limits(input, &options)
+                    .map(|parsed| parsed.result);
+                durations.push(start.elapsed());
+                if round > 0 {
+                    continue;
+                }
+                let failure_reason = match result {
+                    Ok(result) => check_result(input, &result),
+                    Err(error) => Some(format!("parsing failed: {}", error)),
+                };
+                if let Some(reason) = failure_reason {
+                    failures.push(SelfTestFailure {
+                        input: input.to_string(),
+                        reason,
+                    });
+                }
+            }
+        }
+        SelfTestReport {
+            passed: failures.is_empty(),
+            nb_utterances: SELF_TEST_UTTERANCES.len(),
+            failures,
+            latencies: LatencyPercentiles::from_durations(durations),
+        }
+    }
+
+    /// Returns the identifier registered in the `EngineConfig` for the resolved value of the
+    /// slot, if any
+    pub fn get_entity_value_id(&self, slot: &Slot) -> Option<&str> {
+        let value = slot_value_as_str(&slot.value)?;
+        self.config
+            .entity_value_ids
+            .get(&slot.entity)?
+            .get(value)
+            .map(|id| &**id)
+    }
+
+    fn get_intents_whitelist<'a: 'c, 'b: 'c, 'c, W, B>(
+        &'c self,
+        intents_whitelist: W,
+        intents_blacklist: B,
+    ) -> Result<Option<Vec<&'c str>>>
+    where
+        W: Into<Option<Vec<&'a str>>>,
+        B: Into<Option<Vec<&'b str>>>,
+    {
+        let all_intents: HashSet<&str> = self
+            .dataset_metadata
+            .slot_name_mappings
+            .keys()
+            .map(|intent| &**intent)
+            .collect();
+        let intents_whitelist = intents_whitelist
+            .into()
+            .map(|intents| self.model_intent_names(intents));
+        let intents_blacklist = intents_blacklist
+            .into()
+            .map(|intents| self.model_intent_names(intents));
+        if let Some(unknown_intent) = vec![intents_whitelist.as_ref(), intents_blacklist.as_ref()]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .find(|intent| !all_intents.contains(*intent))
+        {
+            return Err(format_err!(
+                "Cannot use unknown intent '{}' in intents filter",
+                unknown_intent
+            ));
+        };
+        let reverted_whitelist: Option<Vec<&str>> = intents_blacklist.map(|blacklist| {
             all_intents
                 .iter()
                 .filter(|intent_name| !blacklist.contains(intent_name))
@@ -278,7 +1358,53 @@ impl SnipsNluEngine {
             .or_else(|| reverted_whitelist))
     }
 
+    /// Returns all the intents, including the None intent, ranked by decreasing probability
+    ///
+    /// The probability of each intent is the highest one among the intent parsers, so that an
+    /// intent matched by the deterministic parser has a probability of 1.
     pub fn get_intents(&self, input: &str) -> Result<Vec<IntentClassifierResult>> {
+        let mut intents = self.with_input_entity_cache(input, || self.get_model_intents(input))?;
+        for intent in intents.iter_mut() {
+            self.alias_intent_result(intent);
+        }
+        Ok(intents)
+    }
+
+    /// Returns the `top_n` most probable intents, as ranked by `get_intents`
+    pub fn get_top_intents(
+        &self,
+        input: &str,
+        top_n: usize,
+    ) -> Result<Vec<IntentClassifierResult>> {
+        let mut intents = self.get_intents(input)?;
+        intents.truncate(top_n);
+        Ok(intents)
+    }
+
+    fn get_model_intents(&self, input: &str) -> Result<Vec<IntentClassifierResult>> {
+        if is_blank_input(input) {
+            let none_intent = IntentClassifierResult {
+                intent_name: None,
+                confidence_score: 1.0,
+            };
+            let intents = self
+                .dataset_metadata
+                .slot_name_mappings
+                .keys()
+                .sorted()
+                .map(|intent| IntentClassifierResult {
+                    intent_name: Some(intent.to_string()),
+                    confidence_score: 0.0,
+                });
+            return Ok(Some(none_intent).into_iter().chain(intents).collect());
+        }
+        if let Some(normalized_input) = self.normalization_dictionary.normalize(input) {
+            return self.get_normalized_input_intents(&normalized_input.text);
+        }
+        self.get_normalized_input_intents(input)
+    }
+
+    fn get_normalized_input_intents(&self, input: &str) -> Result<Vec<IntentClassifierResult>> {
         let nb_intents = self.dataset_metadata.slot_name_mappings.len();
         let mut results = HashMap::with_capacity(nb_intents + 1);
         for parser in self.intent_parsers.iter() {
@@ -305,10 +1431,20 @@ impl SnipsNluEngine {
         Ok(results
             .into_iter()
             .map(|(_, res)| res)
-            .sorted_by(|a, b| b.confidence_score.partial_cmp(&a.confidence_score).unwrap())
+            .sorted_by(|a, b| {
+                b.confidence_score
+                    .partial_cmp(&a.confidence_score)
+                    .unwrap()
+                    .then_with(|| a.intent_name.cmp(&b.intent_name))
+            })
             .collect())
     }
 
+    /// Extracts the slots of the provided intent, without classifying the intent of the input
+    ///
+    /// This is meant for inputs whose intent is already known, such as the follow-up turns of
+    /// a dialogue. As in `parse`, the time grains and the slot validators of the engine are
+    /// applied to the slots.
     pub fn get_slots(&self, input: &str, intent: &str) -> Result<Vec<Slot>> {
         self.get_slots_with_alternatives(input, intent, 0)
     }
@@ -318,6 +1454,133 @@ impl SnipsNluEngine {
         input: &str,
         intent: &str,
         slots_alternatives: usize,
+    ) -> Result<Vec<Slot>> {
+        let mut slots = if self.value_weights.is_empty() {
+            self.with_input_entity_cache(input, || {
+                self.get_intent_slots(input, intent, slots_alternatives)
+            })?
+        } else {
+            let candidates = slots_alternatives.max(VALUE_WEIGHTS_CANDIDATES);
+            let mut slots = self.with_input_entity_cache(input, || {
+                self.get_intent_slots(input, intent, candidates)
+            })?;
+            self.value_weights.rank(
+                self.model_intent_name(intent),
+                &mut slots,
+                slots_alternatives,
+            );
+            slots
+        };
+        self.apply_time_grain(intent, &mut slots);
+        self.value_normalizers.normalize(&mut slots);
+        if !self.slot_validators.is_empty() {
+            slots = self.slot_validators.validate(slots).0;
+        }
+        self.convert_ranges(input, &mut slots);
+        Ok(slots)
+    }
+
+    /// Returns at most `n` sets of slots of the provided intent, sorted by decreasing
+    /// probability, the first one being the slots returned by `get_slots`
+    ///
+    /// The sets of slots come from the most probable tag sequences of the probabilistic slot
+    /// filler, so that they can be reranked downstream, for instance against a knowledge
+    /// base. A single set of slots is returned when the slots are extracted by a deterministic
+    /// pattern.
+    pub fn get_slots_nbest(
+        &self,
+        input: &str,
+        intent: &str,
+        n: usize,
+    ) -> Result<Vec<AlternativeSlots>> {
+        let mut alternatives =
+            self.with_input_entity_cache(input, || self.slots_nbest(input, intent, n))?;
+        for alternative in alternatives.iter_mut() {
+            self.convert_ranges(input, &mut alternative.slots);
+        }
+        Ok(alternatives)
+    }
+
+    /// Same as `get_slots_nbest`, with char ranges
+    fn slots_nbest(&self, input: &str, intent: &str, n: usize) -> Result<Vec<AlternativeSlots>> {
+        self.check_intent(intent)?;
+        if n == 0 || is_blank_input(input) {
+            return Ok(vec![]);
+        }
+        let model_intent = self.model_intent_name(intent);
+        let normalized_input = self.normalization_dictionary.normalize(input);
+        let text = normalized_input
+            .as_ref()
+            .map_or(input, |normalized_input| &*normalized_input.text);
+        let mut hypotheses = vec![];
+        for parser in &self.intent_parsers {
+            hypotheses = parser.get_slots_nbest(text, model_intent, n)?;
+            if hypotheses
+                .iter()
+                .any(|hypothesis| !hypothesis.slots.is_empty())
+            {
+                break;
+            }
+        }
+        let mut alternatives: Vec<AlternativeSlots> = Vec::with_capacity(hypotheses.len());
+        let candidates = if self.value_weights.is_empty() {
+            0
+        } else {
+            VALUE_WEIGHTS_CANDIDATES
+        };
+        for hypothesis in hypotheses {
+            let mut slots = self.resolve_slots(text, hypothesis.slots, candidates)?;
+            self.value_weights.rank(model_intent, &mut slots, 0);
+            if let Some(normalized_input) = normalized_input.as_ref() {
+                normalized_input.restore_slots(input, &mut slots);
+            }
+            self.apply_time_grain(intent, &mut slots);
+            self.value_normalizers.normalize(&mut slots);
+            if !self.slot_validators.is_empty() {
+                slots = self.slot_validators.validate(slots).0;
+            }
+            // Distinct tag sequences may lead to the same slots once resolved
+            if alternatives
+                .iter()
+                .all(|alternative| alternative.slots != slots)
+            {
+                alternatives.push(AlternativeSlots {
+                    slots,
+                    probability: hypothesis.probability as f32,
+                });
+            }
+        }
+        Ok(alternatives)
+    }
+
+    fn get_intent_slots(
+        &self,
+        input: &str,
+        intent: &str,
+        slots_alternatives: usize,
+    ) -> Result<Vec<Slot>> {
+        self.check_intent(intent)?;
+        if is_blank_input(input) {
+            return Ok(vec![]);
+        }
+        let intent = self.model_intent_name(intent);
+        if let Some(normalized_input) = self.normalization_dictionary.normalize(input) {
+            let mut slots = self.get_normalized_input_slots(
+                &normalized_input.text,
+                intent,
+                slots_alternatives,
+            )?;
+            normalized_input.restore_slots(input, &mut slots);
+            return Ok(slots);
+        }
+        self.get_normalized_input_slots(input, intent, slots_alternatives)
+    }
+
+    fn get_normalized_input_slots(
+        &self,
+        input: &str,
+        intent: &str,
+        slots_alternatives: usize,
     ) -> Result<Vec<Slot>> {
         for parser in &self.intent_parsers {
             let slots = parser.get_slots(input, intent)?;
@@ -409,6 +1672,7 @@ impl SnipsNluEngine {
         slot_name: &str,
         slot_alternatives: usize,
     ) -> Result<Option<Slot>> {
+        let intent_name = self.model_intent_name(intent_name);
         let entity_name = self
             .dataset_metadata
             .slot_name_mappings
@@ -417,9 +1681,10 @@ impl SnipsNluEngine {
             .get(slot_name)
             .ok_or_else(|| format_err!("Unknown slot: {}", &slot_name))?;
 
-        let slot = if let Some(custom_entity) = self.dataset_metadata.entities.get(entity_name) {
+        let mut slot = if let Some(custom_entity) = self.dataset_metadata.entities.get(entity_name)
+        {
             extract_custom_slot(
-                input,
+                input.clone(),
                 entity_name.to_string(),
                 slot_name.to_string(),
                 custom_entity,
@@ -428,13 +1693,16 @@ impl SnipsNluEngine {
             )?
         } else {
             extract_builtin_slot(
-                input,
+                input.clone(),
                 entity_name.to_string(),
                 slot_name.to_string(),
                 self.shared_resources.builtin_entity_parser.clone(),
                 slot_alternatives,
             )?
         };
+        if let Some(slot) = slot.as_mut() {
+            self.convert_ranges(&input, slice::from_mut(slot));
+        }
         Ok(slot)
     }
 }
@@ -510,83 +1778,867 @@ fn extract_builtin_slot(
         }))
 }
 
+/// Number of alternative resolutions among which time preferences are applied
+const TIME_PREFERENCE_CANDIDATES: usize = 5;
+/// Number of resolutions of the slots of weighted entities which are ranked with their weights
+const VALUE_WEIGHTS_CANDIDATES: usize = 10;
+
+fn current_timestamp() -> Result<i64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64)
+}
+
 #[cfg(test)]
 mod tests {
     use std::iter::FromIterator;
 
+    use maplit::hashmap;
     use snips_nlu_ontology::{NumberValue, StringValue};
 
+    use crate::barge_in::PartialTokenDetection;
+    use crate::cancellation::CancellationToken;
+    use crate::config::{EnsembleConfig, OutputLimits};
     use crate::entity_parser::custom_entity_parser::CustomEntity;
+    use crate::resources::MissingResourcePolicy;
+    use crate::slot_validation::SlotValidation;
     use crate::testutils::*;
+    use crate::text_range::RangeUnit;
+    use crate::thread_pool::ThreadPoolConfig;
+    use crate::time_grain::TimePreference;
+    use crate::value_normalization::custom_value_normalizer;
 
     use super::*;
 
     #[test]
-    fn test_load_from_zip() {
+    fn test_load_from_zip() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage.zip");
+
+        let file = fs::File::open(path).unwrap();
+
+        // When
+        let nlu_engine = SnipsNluEngine::from_zip(file);
+
+        // Then
+        assert!(nlu_engine.is_ok());
+
+        let result = nlu_engine
+            .unwrap()
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+
+        let expected_entity_value = SlotValue::Number(NumberValue { value: 2.0 });
+        let expected_slots = vec![Slot {
+            raw_value: "two".to_string(),
+            value: expected_entity_value,
+            alternatives: vec![],
+            range: 8..11,
+            entity: "snips/number".to_string(),
+            slot_name: "number_of_cups".to_string(),
+            confidence_score: None,
+        }];
+        let expected_intent = Some("MakeCoffee".to_string());
+
+        assert_eq!(expected_intent, result.intent.intent_name);
+        assert_eq!(expected_slots, result.slots);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_load_async() {
+        // Given
+        let models_dir = Path::new("data").join("tests").join("models");
+
+        // When
+        let nlu_engine = futures::executor::block_on(SnipsNluEngine::load_async(
+            models_dir.join("nlu_engine_beverage"),
+        ))
+        .unwrap();
+        let missing_engine =
+            futures::executor::block_on(SnipsNluEngine::load_async(models_dir.join("missing")));
+        let config = EngineConfig {
+            thread_pool: ThreadPoolConfig::Dedicated {
+                nb_threads: 1,
+                pinned_cores: vec![],
+            },
+            ..EngineConfig::default()
+        };
+        let pooled_engine = futures::executor::block_on(SnipsNluEngine::load_async_with_config(
+            models_dir.join("nlu_engine_beverage"),
+            config,
+        ));
+
+        // Then
+        let result = nlu_engine
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+        assert!(missing_engine.is_err());
+        assert!(pooled_engine.is_ok());
+    }
+
+    #[test]
+    fn test_load_from_zip_bytes() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage.zip");
+        let bytes = fs::read(path).unwrap();
+
+        // When
+        let nlu_engine = SnipsNluEngine::from_zip_bytes(&bytes).unwrap();
+
+        // Then
+        let result = nlu_engine
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+    }
+
+    #[test]
+    fn test_update_model() {
+        // Given
+        let models_dir = Path::new("data").join("tests").join("models");
+        let mut nlu_engine =
+            SnipsNluEngine::from_path(models_dir.join("nlu_engine_beverage")).unwrap();
+        let builtin_entity_parser = nlu_engine.shared_resources().builtin_entity_parser.clone();
+
+        // When
+        nlu_engine
+            .update_model(models_dir.join("nlu_engine_beverage"))
+            .unwrap();
+        let update_with_missing_model = nlu_engine.update_model(models_dir.join("missing"));
+
+        // Then
+        let parser_address =
+            |parser: &Arc<dyn BuiltinEntityParser>| Arc::as_ptr(parser) as *const u8;
+        assert_eq!(
+            parser_address(&builtin_entity_parser),
+            parser_address(&nlu_engine.shared_resources().builtin_entity_parser)
+        );
+        assert!(update_with_missing_model.is_err());
+        let result = nlu_engine
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+    }
+
+    #[test]
+    fn test_parse() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+
+        // When
+        let result = nlu_engine
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+
+        // Then
+        let expected_entity_value = SlotValue::Number(NumberValue { value: 2.0 });
+        let expected_slots = vec![Slot {
+            raw_value: "two".to_string(),
+            value: expected_entity_value,
+            alternatives: vec![],
+            range: 8..11,
+            entity: "snips/number".to_string(),
+            slot_name: "number_of_cups".to_string(),
+            confidence_score: None,
+        }];
+        let expected_intent = Some("MakeCoffee".to_string());
+
+        assert_eq!(expected_intent, result.intent.intent_name);
+        assert_eq!(expected_slots, result.slots);
+    }
+
+    #[test]
+    fn test_supported_languages_and_builtin_entities() {
+        // When
+        let languages = SnipsNluEngine::supported_languages();
+        let english_entities = SnipsNluEngine::supported_builtin_entities(Language::EN);
+
+        // Then
+        assert!(languages.contains(&Language::EN));
+        assert!(english_entities.contains(&BuiltinEntityKind::Number));
+        assert!(english_entities.contains(&BuiltinEntityKind::Datetime));
+        for language in languages {
+            for kind in SnipsNluEngine::supported_builtin_entities(*language) {
+                assert!(kind.supported_languages().contains(language));
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_with_memory_budget() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let large_budget_config = EngineConfig {
+            memory_budget: Some(usize::max_value()),
+            ..EngineConfig::default()
+        };
+        let tiny_budget_config = EngineConfig {
+            memory_budget: Some(1),
+            ..EngineConfig::default()
+        };
+
+        // When
+        let nlu_engine = SnipsNluEngine::from_path_with_config(&path, &large_budget_config);
+        let failed_nlu_engine = SnipsNluEngine::from_path_with_config(&path, &tiny_budget_config);
+
+        // Then
+        assert!(nlu_engine.is_ok());
+        assert!(nlu_engine.unwrap().memory_savings().is_empty());
+        assert!(failed_nlu_engine.is_err());
+    }
+
+    #[test]
+    fn test_parse_with_builtin_entity_scopes() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            builtin_entity_scopes: hashmap!["MakeTea".to_string() => vec![]],
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path_with_config(path, &config).unwrap();
+
+        // When
+        let result = nlu_engine
+            .parse("make me two cups of coffee", None, None)
+            .unwrap();
+
+        // Then
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+        assert_eq!(1, result.slots.len());
+        assert_eq!("number_of_cups", result.slots[0].slot_name);
+    }
+
+    #[test]
+    fn test_parse_with_entity_cache() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            entity_cache_capacity: 2,
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path_with_config(path, &config).unwrap();
+
+        // When
+        let result = nlu_engine
+            .parse("make me two cups of coffee", None, None)
+            .unwrap();
+        let repeated_result = nlu_engine
+            .parse("make me two cups of coffee", None, None)
+            .unwrap();
+
+        // Then
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+        assert_eq!(1, result.slots.len());
+        assert_eq!(result, repeated_result);
+    }
+
+    #[test]
+    fn test_load_with_intercept_adjustments() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            intercept_adjustments: hashmap!["MakeTea".to_string() => -0.5],
+            ..EngineConfig::default()
+        };
+        let invalid_config = EngineConfig {
+            intercept_adjustments: hashmap!["MakeChocolate".to_string() => -0.5],
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path(&path).unwrap();
+        let adjusted_nlu_engine = SnipsNluEngine::from_path_with_config(&path, &config).unwrap();
+
+        // When
+        let intercepts = nlu_engine.intent_classifier_intercepts().unwrap();
+        let adjusted_intercepts = adjusted_nlu_engine.intent_classifier_intercepts().unwrap();
+        let invalid_nlu_engine = SnipsNluEngine::from_path_with_config(&path, &invalid_config);
+
+        // Then
+        let tea_intercept = |intercepts: &[ClassIntercept]| {
+            intercepts
+                .iter()
+                .find(|intercept| intercept.intent_name == Some("MakeTea".to_string()))
+                .cloned()
+                .unwrap()
+        };
+        let intercept = tea_intercept(&intercepts);
+        let adjusted_intercept = tea_intercept(&adjusted_intercepts);
+        let adjustments = (intercept.adjustment, adjusted_intercept.adjustment);
+        assert_eq!((0., -0.5), adjustments);
+        assert!((intercept.intercept - 0.5 - adjusted_intercept.intercept).abs() < 1e-6);
+        assert!(invalid_nlu_engine.is_err());
+    }
+
+    #[test]
+    fn test_load_with_aliased_intercept_adjustments() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            intent_aliases: hashmap!["PrepareTea".to_string() => "MakeTea".to_string()],
+            intercept_adjustments: hashmap!["PrepareTea".to_string() => -0.5],
+            builtin_entity_scopes: hashmap!["PrepareTea".to_string() => vec![]],
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path(&path).unwrap();
+
+        // When
+        let aliased_nlu_engine = SnipsNluEngine::from_path_with_config(&path, &config).unwrap();
+        let intercepts = nlu_engine.intent_classifier_intercepts().unwrap();
+        let aliased_intercepts = aliased_nlu_engine.intent_classifier_intercepts().unwrap();
+
+        // Then
+        let tea_intercept = |intercepts: &[ClassIntercept], intent: &str| {
+            intercepts
+                .iter()
+                .find(|intercept| intercept.intent_name == Some(intent.to_string()))
+                .cloned()
+                .unwrap()
+        };
+        let intercept = tea_intercept(&intercepts, "MakeTea");
+        let aliased_intercept = tea_intercept(&aliased_intercepts, "PrepareTea");
+        assert_eq!(-0.5, aliased_intercept.adjustment);
+        assert!((intercept.intercept - 0.5 - aliased_intercept.intercept).abs() < 1e-6);
+    }
+
+    struct ExactMatchParser;
+
+    impl IntentParser for ExactMatchParser {
+        fn parse(
+            &self,
+            input: &str,
+            _intents_whitelist: Option<&[&str]>,
+        ) -> Result<InternalParsingResult> {
+            if input == "the usual please" {
+                Ok(internal_parsing_result(
+                    Some("MakeTea".to_string()),
+                    1.0,
+                    vec![],
+                ))
+            } else {
+                Ok(InternalParsingResult::empty())
+            }
+        }
+
+        fn get_intents(&self, _input: &str) -> Result<Vec<IntentClassifierResult>> {
+            Ok(vec![])
+        }
+
+        fn get_slots(&self, _input: &str, _intent: &str) -> Result<Vec<InternalSlot>> {
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_parse_with_custom_intent_parser() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            custom_intent_parsers: IntentParserRegistry::new().register(
+                "exact_match_parser",
+                CascadePosition::Before("probabilistic_intent_parser".to_string()),
+                |_: &Path, _: Arc<SharedResources>| Ok(Box::new(ExactMatchParser) as _),
+            ),
+            ..EngineConfig::default()
+        };
+        let invalid_config = EngineConfig {
+            custom_intent_parsers: IntentParserRegistry::new().register(
+                "exact_match_parser",
+                CascadePosition::After("unknown_intent_parser".to_string()),
+                |_: &Path, _: Arc<SharedResources>| Ok(Box::new(ExactMatchParser) as _),
+            ),
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path_with_config(&path, &config).unwrap();
+
+        // When
+        let custom_result = nlu_engine.parse("the usual please", None, None).unwrap();
+        let result = nlu_engine
+            .parse("make me two cups of coffee", None, None)
+            .unwrap();
+        let invalid_engine = SnipsNluEngine::from_path_with_config(&path, &invalid_config);
+
+        // Then
+        assert_eq!(
+            Some("MakeTea".to_string()),
+            custom_result.intent.intent_name
+        );
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+        assert!(invalid_engine.is_err());
+    }
+
+    #[test]
+    fn test_parse_with_ensemble() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            ensemble: Some(EnsembleConfig {
+                parsers_weights: hashmap! {
+                    "lookup_intent_parser".to_string() => 0.0,
+                    "deterministic_intent_parser".to_string() => 0.0,
+                },
+                default_weight: 1.0,
+            }),
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path_with_config(path, &config).unwrap();
+
+        // When
+        let result = nlu_engine
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+        let intents = nlu_engine
+            .get_intents("Make me two cups of coffee please")
+            .unwrap();
+
+        // Then
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+        assert_eq!(1, result.slots.len());
+        assert_eq!("number_of_cups", result.slots[0].slot_name);
+        assert_eq!(result.intent, intents[0]);
+    }
+
+    #[test]
+    fn test_parse_with_usage_statistics() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let mut nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        let collector = Arc::new(UsageStatisticsCollector::default());
+        nlu_engine.set_usage_statistics_collector(collector.clone());
+
+        // When
+        nlu_engine
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+        nlu_engine
+            .parse("Make me one cup of coffee", None, None)
+            .unwrap();
+        let statistics = collector.statistics().unwrap();
+
+        // Then
+        assert_eq!(2, statistics.nb_parses);
+        assert_eq!(Some(&2), statistics.intents_counts.get("MakeCoffee"));
+        assert_eq!(0, statistics.none_intent_count);
+    }
+
+    #[test]
+    fn test_parse_blank_inputs() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+
+        for input in &["", "   ", " ?! ...\t"] {
+            // When
+            let result = nlu_engine
+                .parse_with_alternatives(input, None, None, 2, 2)
+                .unwrap();
+            let intents = nlu_engine.get_intents(input).unwrap();
+            let slots = nlu_engine.get_slots(input, "MakeCoffee").unwrap();
+            let unknown_intent_slots = nlu_engine.get_slots(input, "MakeJuice");
+            let unknown_intent_nbest_slots = nlu_engine.get_slots_nbest(input, "MakeJuice", 2);
+
+            // Then
+            let expected_intent = IntentClassifierResult {
+                intent_name: None,
+                confidence_score: 1.0,
+            };
+            assert_eq!(*input, result.input);
+            assert_eq!(expected_intent, result.intent);
+            assert!(result.slots.is_empty());
+            assert!(result.alternatives.is_empty());
+            assert_eq!(expected_intent, intents[0]);
+            assert_eq!(3, intents.len());
+            assert!(slots.is_empty());
+            assert!(unknown_intent_slots.is_err());
+            assert!(unknown_intent_nbest_slots.is_err());
+        }
+    }
+
+    #[test]
+    fn test_load_with_missing_resources() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs_extra::dir::copy(&path, temp_dir.path(), &fs_extra::dir::CopyOptions::new()).unwrap();
+        let engine_dir = temp_dir.path().join("nlu_engine_beverage");
+        let resources_dir = engine_dir.join("resources").join("en");
+        fs::remove_file(
+            resources_dir
+                .join("gazetteers")
+                .join("top_10000_words_stemmed.txt"),
+        )
+        .unwrap();
+        fs::remove_file(resources_dir.join("stemming").join("stems.txt")).unwrap();
+        let degraded_config = EngineConfig {
+            missing_resource_policy: MissingResourcePolicy::Degrade,
+            ..EngineConfig::default()
+        };
+
+        // When
+        let strict_nlu_engine = SnipsNluEngine::from_path(&engine_dir);
+        let degraded_nlu_engine =
+            SnipsNluEngine::from_path_with_config(&engine_dir, &degraded_config);
+
+        // Then
+        assert!(strict_nlu_engine.is_err());
+        let result = degraded_nlu_engine
+            .unwrap()
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+    }
+
+    #[test]
+    fn test_parse_with_normalization_replacements() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            normalization_replacements: vec![("mk".to_string(), "make".to_string())],
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path_with_config(path, &config).unwrap();
+
+        // When
+        let result = nlu_engine
+            .parse("Mk me two cups of coffee please", None, None)
+            .unwrap();
+
+        // Then
+        let expected_slots = vec![Slot {
+            raw_value: "two".to_string(),
+            value: SlotValue::Number(NumberValue { value: 2.0 }),
+            alternatives: vec![],
+            range: 6..9,
+            entity: "snips/number".to_string(),
+            slot_name: "number_of_cups".to_string(),
+            confidence_score: None,
+        }];
+        assert_eq!("Mk me two cups of coffee please", &result.input);
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+        assert_eq!(expected_slots, result.slots);
+    }
+
+    #[test]
+    fn test_parse_with_details_should_report_missing_slots() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+
+        // When
+        let result = nlu_engine
+            .parse_with_details("make me two cups of tea", None, None, 0, 0)
+            .unwrap();
+        let complete_result = nlu_engine
+            .parse_with_details("make me two hot cups of tea", None, None, 0, 0)
+            .unwrap();
+
+        // Then
+        assert_eq!(
+            Some("MakeTea".to_string()),
+            result.result.intent.intent_name
+        );
+        assert_eq!(
+            vec!["beverage_temperature".to_string()],
+            result.missing_slots
+        );
+        assert!(complete_result.missing_slots.is_empty());
+    }
+
+    #[test]
+    fn test_parse_with_slot_validators() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let mut nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        nlu_engine.add_slot_name_validator("number_of_cups", |_: &Slot| {
+            SlotValidation::Rewrite(SlotValue::Number(NumberValue { value: 1.0 }))
+        });
+        nlu_engine.add_entity_validator("Temperature", |_: &Slot| SlotValidation::Reject {
+            reason: "no hot beverage".to_string(),
+        });
+
+        // When
+        let result = nlu_engine
+            .parse_with_details("make me two hot cups of tea", None, None, 0, 0)
+            .unwrap();
+
+        // Then
+        let expected_slots = vec![Slot {
+            raw_value: "two".to_string(),
+            value: SlotValue::Number(NumberValue { value: 1.0 }),
+            alternatives: vec![],
+            range: 8..11,
+            entity: "snips/number".to_string(),
+            slot_name: "number_of_cups".to_string(),
+            confidence_score: None,
+        }];
+        let rejected_slots: Vec<String> = result
+            .rejected_slots
+            .iter()
+            .map(|rejected_slot| rejected_slot.slot.raw_value.clone())
+            .collect();
+        assert_eq!(expected_slots, result.result.slots);
+        assert_eq!(vec!["hot".to_string()], rejected_slots);
+    }
+
+    #[test]
+    fn test_parse_with_value_normalizers() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let mut nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        nlu_engine.add_entity_normalizer(
+            "Temperature",
+            custom_value_normalizer(|value| value.to_uppercase()),
+        );
+
+        // When
+        let result = nlu_engine
+            .parse("make me two hot cups of tea", None, None)
+            .unwrap();
+
+        // Then
+        let temperature_values: Vec<SlotValue> = result
+            .slots
+            .into_iter()
+            .filter(|slot| slot.entity == "Temperature")
+            .map(|slot| slot.value)
+            .collect();
+        assert_eq!(vec![SlotValue::Custom("HOT".into())], temperature_values);
+    }
+
+    #[test]
+    fn test_parse_with_options() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        let options = ParseOptions {
+            intents_whitelist: Some(vec!["MakeTea".to_string()]),
+            time_preference: Some(TimePreference::PreferPast),
+            ..Default::default()
+        };
+
+        // When
+        let result = nlu_engine
+            .parse_with_options("Make me two cups of coffee please", &options)
+            .unwrap();
+
+        // Then
+        assert_eq!(
+            Some("MakeTea".to_string()),
+            result.result.intent.intent_name
+        );
+        assert!(result
+            .result
+            .slots
+            .iter()
+            .all(|slot| slot.alternatives.is_empty()));
+    }
+
+    #[test]
+    fn test_recent_parses() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let mut nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        nlu_engine.set_audit_log(AuditLog::new(1).with_redactor(
+            |result: &mut IntentParserResult| result.input = "<redacted>".to_string(),
+        ));
+
+        // When
+        nlu_engine.parse("Make me a tea", None, None).unwrap();
+        let result = nlu_engine
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+        let recent_parses = nlu_engine.recent_parses().unwrap();
+
+        // Then
+        assert_eq!(1, recent_parses.len());
+        assert_eq!("<redacted>", &recent_parses[0].result.input);
+        assert_eq!(result.intent, recent_parses[0].result.intent);
+        assert_eq!(result.slots, recent_parses[0].result.slots);
+    }
+
+    #[test]
+    fn test_parse_with_output_limits() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            output_limits: OutputLimits {
+                max_input_chars: Some(26),
+                max_slots: Some(0),
+                max_intents_alternatives: Some(1),
+                ..OutputLimits::default()
+            },
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path_with_config(path, &config).unwrap();
+        let options = ParseOptions::new(None, None, 2, 0);
+
+        // When
+        let result = nlu_engine
+            .parse_with_options("Make me two cups of coffee please", &options)
+            .unwrap();
+
+        // Then
+        assert_eq!("Make me two cups of coffee please", &result.result.input);
+        assert!(result.result.slots.is_empty());
+        assert!(result.slots_details.is_empty());
+        assert_eq!(1, result.result.alternatives.len());
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn test_parse_with_excluded_slots() {
         // Given
         let path = Path::new("data")
             .join("tests")
             .join("models")
-            .join("nlu_engine_beverage.zip");
-
-        let file = fs::File::open(path).unwrap();
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        let slots_options = ParseOptions {
+            slots_blacklist: Some(vec!["number_of_cups".to_string()]),
+            intents_alternatives: 1,
+            ..Default::default()
+        };
+        let entities_options = ParseOptions {
+            entities_blacklist: Some(vec!["snips/number".to_string()]),
+            ..Default::default()
+        };
 
         // When
-        let nlu_engine = SnipsNluEngine::from_zip(file);
+        let default_result = nlu_engine
+            .parse("Make me two cups of coffee please", None, None)
+            .unwrap();
+        let slots_result = nlu_engine
+            .parse_with_options("Make me two cups of coffee please", &slots_options)
+            .unwrap()
+            .result;
+        let entities_result = nlu_engine
+            .parse_with_options("Make me two cups of coffee please", &entities_options)
+            .unwrap()
+            .result;
 
         // Then
-        assert!(nlu_engine.is_ok());
+        assert_eq!(1, default_result.slots.len());
+        assert_eq!(default_result.intent, slots_result.intent);
+        assert!(slots_result.slots.is_empty());
+        assert!(slots_result
+            .alternatives
+            .iter()
+            .all(|alternative| alternative.slots.is_empty()));
+        assert_eq!(default_result.intent, entities_result.intent);
+        assert!(entities_result.slots.is_empty());
+    }
 
+    #[test]
+    fn test_parse_with_partial_last_token() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        let options = ParseOptions {
+            partial_token_detection: Some(PartialTokenDetection::Marker("<partial>".to_string())),
+            ..Default::default()
+        };
+        let input = "Make me two cups of coffee please tw<partial>";
+
+        // When
         let result = nlu_engine
+            .parse_with_options(input, &options)
             .unwrap()
-            .parse("Make me two cups of coffee please", None, None)
-            .unwrap();
-
-        let expected_entity_value = SlotValue::Number(NumberValue { value: 2.0 });
-        let expected_slots = vec![Slot {
-            raw_value: "two".to_string(),
-            value: expected_entity_value,
-            alternatives: vec![],
-            range: 8..11,
-            entity: "snips/number".to_string(),
-            slot_name: "number_of_cups".to_string(),
-            confidence_score: None,
-        }];
-        let expected_intent = Some("MakeCoffee".to_string());
+            .result;
+        let partial_slot_result = nlu_engine
+            .parse_with_options("Make me two<partial>", &options)
+            .unwrap()
+            .result;
 
-        assert_eq!(expected_intent, result.intent.intent_name);
-        assert_eq!(expected_slots, result.slots);
+        // Then
+        assert_eq!(input, result.input);
+        assert_eq!(Some("MakeCoffee".to_string()), result.intent.intent_name);
+        assert_eq!(1, result.slots.len());
+        assert_eq!(8..11, result.slots[0].range);
+        assert!(partial_slot_result.slots.is_empty());
     }
 
     #[test]
-    fn test_parse() {
+    fn test_parse_with_cancelled_token() {
         // Given
         let path = Path::new("data")
             .join("tests")
             .join("models")
             .join("nlu_engine_beverage");
         let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        let cancellation_token = CancellationToken::new();
+        let options = ParseOptions {
+            cancellation_token: Some(cancellation_token.clone()),
+            ..Default::default()
+        };
+        cancellation_token.cancel();
 
         // When
-        let result = nlu_engine
-            .parse("Make me two cups of coffee please", None, None)
-            .unwrap();
+        let result = nlu_engine.parse_with_options("Make me two cups of coffee please", &options);
 
         // Then
-        let expected_entity_value = SlotValue::Number(NumberValue { value: 2.0 });
-        let expected_slots = vec![Slot {
-            raw_value: "two".to_string(),
-            value: expected_entity_value,
-            alternatives: vec![],
-            range: 8..11,
-            entity: "snips/number".to_string(),
-            slot_name: "number_of_cups".to_string(),
-            confidence_score: None,
-        }];
-        let expected_intent = Some("MakeCoffee".to_string());
-
-        assert_eq!(expected_intent, result.intent.intent_name);
-        assert_eq!(expected_slots, result.slots);
+        assert!(result.is_err());
     }
 
     #[test]
@@ -647,6 +2699,27 @@ mod tests {
         assert!(failed_result_2.is_err());
     }
 
+    #[test]
+    fn test_parse_with_filter() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+
+        // When
+        let result = nlu_engine
+            .parse_with_filter("Make me two cups of coffee please", &["MakeTea"])
+            .unwrap();
+        let failed_result =
+            nlu_engine.parse_with_filter("Make me two cups of coffee please", &["MakeChocolate"]);
+
+        // Then
+        assert_eq!(Some("MakeTea".to_string()), result.intent.intent_name);
+        assert!(failed_result.is_err());
+    }
+
     #[test]
     fn test_parse_with_intents_alternatives() {
         // Given
@@ -806,6 +2879,37 @@ mod tests {
         assert_eq!(expected_result, result);
     }
 
+    #[test]
+    fn test_parse_with_value_weights() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_game");
+        let mut nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        let weights = EntityValueWeights::default()
+            .weight("Space Invader Limited Edition", 5.0, None)
+            .weight("Invader War Demo", 10.0, Some("PlayGame"));
+        nlu_engine.set_entity_value_weights("game", weights);
+
+        // When
+        let result = nlu_engine
+            .parse_with_alternatives("I want to play to invader", None, None, 0, 1)
+            .unwrap();
+
+        // Then
+        let expected_slots = vec![Slot {
+            raw_value: "invader".to_string(),
+            value: SlotValue::Custom("Invader War Demo".into()),
+            alternatives: vec![SlotValue::Custom("Space Invader Limited Edition".into())],
+            range: 18..25,
+            entity: "game".to_string(),
+            slot_name: "game".to_string(),
+            confidence_score: None,
+        }];
+        assert_eq!(expected_slots, result.slots);
+    }
+
     #[test]
     fn test_get_intents() {
         // Given
@@ -832,6 +2936,168 @@ mod tests {
         assert_eq!(expected_intents, intents);
     }
 
+    #[test]
+    fn test_get_top_intents() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        let input = "Make me two hot cups of tea";
+
+        // When
+        let top_intents = nlu_engine.get_top_intents(input, 2).unwrap();
+
+        // Then
+        let all_intents = nlu_engine.get_intents(input).unwrap();
+        assert_eq!(all_intents[..2].to_vec(), top_intents);
+        assert!(top_intents[0].confidence_score >= top_intents[1].confidence_score);
+    }
+
+    #[test]
+    fn test_intent_aliases() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            intent_aliases: hashmap! {
+                "PrepareTea".to_string() => "MakeTea".to_string(),
+            },
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path_with_config(path, &config).unwrap();
+        let input = "Make me two hot cups of tea";
+
+        // When
+        let result = nlu_engine
+            .parse_with_alternatives(input, vec!["PrepareTea", "MakeCoffee"], None, 1, 0)
+            .unwrap();
+        let blacklisted_result = nlu_engine.parse(input, None, vec!["PrepareTea"]).unwrap();
+        let slots = nlu_engine.get_slots(input, "PrepareTea").unwrap();
+
+        // Then
+        assert_eq!(vec!["MakeCoffee", "PrepareTea"], nlu_engine.intents());
+        assert_eq!(Some("PrepareTea".to_string()), result.intent.intent_name);
+        assert_eq!(
+            Some("MakeCoffee".to_string()),
+            result.alternatives[0].intent.intent_name
+        );
+        assert_ne!(
+            Some("PrepareTea".to_string()),
+            blacklisted_result.intent.intent_name
+        );
+        assert_eq!(2, slots.len());
+    }
+
+    #[test]
+    fn test_invalid_intent_aliases() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let duplicate_aliases_config = EngineConfig {
+            intent_aliases: hashmap! {
+                "PrepareTea".to_string() => "MakeTea".to_string(),
+                "BrewTea".to_string() => "MakeTea".to_string(),
+            },
+            ..EngineConfig::default()
+        };
+        let colliding_alias_config = EngineConfig {
+            intent_aliases: hashmap! {
+                "MakeCoffee".to_string() => "MakeTea".to_string(),
+            },
+            ..EngineConfig::default()
+        };
+
+        // When
+        let duplicate_aliases_error =
+            SnipsNluEngine::from_path_with_config(&path, &duplicate_aliases_config)
+                .err()
+                .unwrap();
+        let colliding_alias_error =
+            SnipsNluEngine::from_path_with_config(&path, &colliding_alias_config)
+                .err()
+                .unwrap();
+
+        // Then
+        assert_eq!(
+            "Invalid engine config key 'intent_aliases.PrepareTea': intent 'MakeTea' is already \
+             aliased as 'BrewTea'",
+            duplicate_aliases_error.to_string()
+        );
+        assert_eq!(
+            "Invalid engine config key 'intent_aliases.MakeCoffee': alias collides with the \
+             intent 'MakeCoffee' of the model",
+            colliding_alias_error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_self_test() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+
+        // When
+        let report = nlu_engine.self_test();
+
+        // Then
+        assert_eq!(Vec::<SelfTestFailure>::new(), report.failures);
+        assert!(report.passed);
+        assert_eq!(SELF_TEST_UTTERANCES.len(), report.nb_utterances);
+        assert!(report.latencies.p50 <= report.latencies.p99);
+        assert!(report.latencies.p99 <= report.latencies.max);
+    }
+
+    #[test]
+    fn test_entity_value_ids() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            entity_value_ids: hashmap! {
+                "Temperature".to_string() => hashmap! {
+                    "hot".to_string() => "temperature-42".to_string(),
+                },
+            },
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path_with_config(path, &config).unwrap();
+
+        // When
+        let result = nlu_engine
+            .parse_with_details("Make me two hot cups of tea", None, None, 0, 0)
+            .unwrap();
+
+        // Then
+        let entity_value_ids: Vec<(&str, Option<&str>)> = result
+            .result
+            .slots
+            .iter()
+            .zip(result.slots_details.iter())
+            .map(|(slot, details)| {
+                (
+                    &*slot.entity,
+                    details.entity_value_id.as_ref().map(|id| &**id),
+                )
+            })
+            .collect();
+        let expected_entity_value_ids = vec![
+            ("snips/number", None),
+            ("Temperature", Some("temperature-42")),
+        ];
+        assert_eq!(expected_entity_value_ids, entity_value_ids);
+    }
+
     #[test]
     fn test_get_slots() {
         // Given
@@ -873,6 +3139,142 @@ mod tests {
         assert_eq!(expected_slots, slots);
     }
 
+    #[test]
+    fn test_get_slots_with_byte_ranges() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            range_unit: RangeUnit::Byte,
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path(&path).unwrap();
+        let byte_ranges_engine = SnipsNluEngine::from_path_with_config(&path, &config).unwrap();
+        let input = "☕ Make me two hot cups of tea";
+
+        // When
+        let slots = nlu_engine.get_slots(input, "MakeTea").unwrap();
+        let byte_ranges_slots = byte_ranges_engine.get_slots(input, "MakeTea").unwrap();
+
+        // Then
+        let expected_ranges: Vec<_> = slots
+            .iter()
+            .map(|slot| RangeUnit::Byte.convert(input, &slot.range))
+            .collect();
+        let byte_ranges: Vec<_> = byte_ranges_slots
+            .iter()
+            .map(|slot| slot.range.clone())
+            .collect();
+        assert!(!slots.is_empty());
+        assert_eq!(expected_ranges, byte_ranges);
+        assert!(byte_ranges_slots
+            .iter()
+            .all(|slot| input[slot.range.clone()] == slot.raw_value));
+    }
+
+    #[test]
+    fn test_get_slots_applies_slot_validators() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let mut nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        nlu_engine.add_entity_validator("snips/number", |_: &Slot| SlotValidation::Reject {
+            reason: "no number expected".to_string(),
+        });
+
+        // When
+        let slots = nlu_engine
+            .get_slots("Make me two hot cups of tea", "MakeTea")
+            .unwrap();
+
+        // Then
+        let slot_names: Vec<&str> = slots.iter().map(|slot| &*slot.slot_name).collect();
+        assert_eq!(vec!["beverage_temperature"], slot_names);
+    }
+
+    #[test]
+    fn test_get_slots_nbest() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let nlu_engine = SnipsNluEngine::from_path(path).unwrap();
+        let input = "Please make me two hot cups of tea right now";
+        let options = ParseOptions {
+            intents_whitelist: Some(vec!["MakeTea".to_string()]),
+            nbest_slots: 2,
+            ..Default::default()
+        };
+
+        // When
+        let alternatives = nlu_engine.get_slots_nbest(input, "MakeTea", 3).unwrap();
+        let best_slots = nlu_engine.get_slots(input, "MakeTea").unwrap();
+        let detailed_result = nlu_engine.parse_with_options(input, &options).unwrap();
+
+        // Then
+        assert!(!alternatives.is_empty() && alternatives.len() <= 3);
+        assert_eq!(best_slots, alternatives[0].slots);
+        for (alternative, next_alternative) in alternatives.iter().tuple_windows() {
+            assert!(alternative.probability >= next_alternative.probability);
+            assert_ne!(alternative.slots, next_alternative.slots);
+        }
+        assert!(detailed_result.alternative_slots.len() <= 2);
+        for alternative in detailed_result.alternative_slots.iter() {
+            assert_ne!(detailed_result.result.slots, alternative.slots);
+        }
+    }
+
+    #[test]
+    fn test_get_slots_nbest_with_output_limits() {
+        // Given
+        let path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            output_limits: OutputLimits {
+                max_input_chars: Some(34),
+                max_slots: Some(1),
+                ..OutputLimits::default()
+            },
+            ..EngineConfig::default()
+        };
+        let nlu_engine = SnipsNluEngine::from_path_with_config(path, &config).unwrap();
+        let input = "Please make me two hot cups of tea right now";
+        let parsed_input = "Please make me two hot cups of tea";
+        let options = ParseOptions {
+            intents_whitelist: Some(vec!["MakeTea".to_string()]),
+            slots_alternatives: 2,
+            nbest_slots: 2,
+            ..Default::default()
+        };
+
+        // When
+        let alternatives = nlu_engine
+            .get_slots_nbest(parsed_input, "MakeTea", 3)
+            .unwrap();
+        let expected_alternatives: Vec<AlternativeSlots> =
+            alternatives.iter().skip(1).cloned().collect();
+        let detailed_result = nlu_engine.parse_with_options(input, &options).unwrap();
+
+        // Then
+        assert!(detailed_result.truncated);
+        assert!(detailed_result.result.slots.len() <= 1);
+        assert!(detailed_result.alternative_slots.len() <= 2);
+        assert_eq!(expected_alternatives, detailed_result.alternative_slots);
+        for alternative in detailed_result.alternative_slots.iter() {
+            assert_ne!(alternatives[0].slots, alternative.slots);
+            for slot in alternative.slots.iter() {
+                assert!(slot.range.end <= parsed_input.chars().count());
+            }
+        }
+    }
+
     #[test]
     fn test_get_slots_with_alternatives() {
         // Given
@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+
+use failure::ResultExt;
+use regex::{Regex, RegexBuilder};
+use snips_nlu_ontology::Slot;
+use snips_nlu_utils::string::substring_with_char_range;
+
+use crate::errors::*;
+use crate::transliteration::Transliterator;
+use crate::unicode::UnicodeNormalization;
+use crate::utils::CharIndex;
+
+pub const NORMALIZATION_DICTIONARY_FILENAME: &str = "normalization_dictionary.json";
+
+/// Literal replacements applied to the input before it is processed by the intent parsers
+///
+/// Replacements are case insensitive and do not apply inside words, e.g. with the `"tv"` →
+/// `"television"` replacement, `"Turn on the TV"` is parsed as `"Turn on the television"`.
+/// The Unicode normalization form of the engine, and the transliteration if any, are applied
+/// to the input beforehand.
+#[derive(Debug, Clone, Default)]
+pub struct NormalizationDictionary {
+    regex: Option<Regex>,
+    replacements: HashMap<String, String>,
+    unicode_normalization: UnicodeNormalization,
+    transliterator: Option<Transliterator>,
+}
+
+impl NormalizationDictionary {
+    /// Builds a dictionary out of (find, replace) pairs, later pairs taking precedence over
+    /// earlier ones when they share the same find value
+    pub fn new(replacements: Vec<(String, String)>) -> Result<Self> {
+        Self::new_with_unicode_normalization(replacements, UnicodeNormalization::default())
+    }
+
+    pub fn new_with_unicode_normalization(
+        replacements: Vec<(String, String)>,
+        unicode_normalization: UnicodeNormalization,
+    ) -> Result<Self> {
+        let replacements: HashMap<String, String> = replacements
+            .into_iter()
+            .filter(|(find, _)| !find.is_empty())
+            .map(|(find, replace)| {
+                let find = unicode_normalization
+                    .apply(&find.to_lowercase())
+                    .into_owned();
+                (find, unicode_normalization.apply(&replace).into_owned())
+            })
+            .collect();
+        if replacements.is_empty() {
+            return Ok(Self {
+                unicode_normalization,
+                ..Self::default()
+            });
+        }
+        let mut keys: Vec<&String> = replacements.keys().collect();
+        // Longest keys first so that they take precedence over their prefixes
+        keys.sort_by(|a, b| b.chars().count().cmp(&a.chars().count()).then(a.cmp(b)));
+        let pattern = keys
+            .iter()
+            .map(|key| {
+                // Word boundaries are only relevant next to word characters
+                let is_word_char =
+                    |c: Option<char>| c.map(|c| c.is_alphanumeric()).unwrap_or(false);
+                let start_boundary = if is_word_char(key.chars().next()) {
+                    r"\b"
+                } else {
+                    ""
+                };
+                let end_boundary = if is_word_char(key.chars().last()) {
+                    r"\b"
+                } else {
+                    ""
+                };
+                format!("{}{}{}", start_boundary, regex::escape(key), end_boundary)
+            })
+            .collect::<Vec<_>>()
+            .join("|");
+        let regex = RegexBuilder::new(&pattern).case_insensitive(true).build()?;
+        Ok(Self {
+            regex: Some(regex),
+            replacements,
+            unicode_normalization,
+            transliterator: None,
+        })
+    }
+
+    /// Transliterates the inputs before applying the replacements
+    pub fn with_transliterator(mut self, transliterator: Transliterator) -> Self {
+        self.transliterator = Some(transliterator);
+        self
+    }
+
+    /// Loads the replacements stored in the engine directory, if any
+    pub fn load_replacements<P: AsRef<Path>>(engine_dir: P) -> Result<Vec<(String, String)>> {
+        let path = engine_dir.as_ref().join(NORMALIZATION_DICTIONARY_FILENAME);
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+        let file = File::open(&path)
+            .with_context(|_| format!("Cannot open normalization dictionary {:?}", path))?;
+        let replacements: HashMap<String, String> = serde_json::from_reader(file)
+            .with_context(|_| format!("Invalid normalization dictionary {:?}", path))?;
+        Ok(replacements.into_iter().collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replacements.is_empty()
+    }
+
+    /// Applies the Unicode normalization form, the transliteration and the replacements to the
+    /// input, returns `None` when the input is left unchanged
+    pub fn normalize(&self, input: &str) -> Option<NormalizedInput> {
+        let mut stages = vec![];
+        let unicode_normalized = self.unicode_normalization.apply_with_segments(input);
+        let text = match unicode_normalized {
+            Some((text, segments)) => {
+                stages.push(segments);
+                text
+            }
+            None => input.to_string(),
+        };
+        let transliterated = self
+            .transliterator
+            .as_ref()
+            .and_then(|transliterator| transliterator.apply_with_segments(&text));
+        let text = match transliterated {
+            Some((transliterated_text, segments)) => {
+                stages.push(segments);
+                transliterated_text
+            }
+            None => text,
+        };
+        let text = match self.replace(&text) {
+            Some((replaced_text, segments)) => {
+                stages.push(segments);
+                replaced_text
+            }
+            None => text,
+        };
+        if stages.is_empty() {
+            None
+        } else {
+            Some(NormalizedInput { text, stages })
+        }
+    }
+
+    fn replace(&self, input: &str) -> Option<(String, Vec<(Range<usize>, Range<usize>)>)> {
+        let regex = self.regex.as_ref()?;
+        let char_index = CharIndex::new(input);
+        let mut text = String::with_capacity(input.len());
+        let mut segments = vec![];
+        let mut last_byte_offset = 0;
+        let mut shift: isize = 0;
+        for a_match in regex.find_iter(input) {
+            let replacement = match self.replacements.get(&a_match.as_str().to_lowercase()) {
+                Some(replacement) => replacement,
+                None => continue,
+            };
+            let original_range = char_index.char_range(&(a_match.start()..a_match.end()));
+            let normalized_start = (original_range.start as isize + shift) as usize;
+            let normalized_range = normalized_start..normalized_start + replacement.chars().count();
+            shift += normalized_range.len() as isize - original_range.len() as isize;
+            text.push_str(&input[last_byte_offset..a_match.start()]);
+            text.push_str(replacement);
+            last_byte_offset = a_match.end();
+            segments.push((normalized_range, original_range));
+        }
+        if segments.is_empty() {
+            return None;
+        }
+        text.push_str(&input[last_byte_offset..]);
+        Some((text, segments))
+    }
+}
+
+/// Input on which replacements have been applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalizedInput {
+    pub text: String,
+    /// Char ranges of the modified segments, in the output and in the input of each successive
+    /// normalization step
+    stages: Vec<Vec<(Range<usize>, Range<usize>)>>,
+}
+
+impl NormalizedInput {
+    /// Input normalized in a single step, whose modified segments are provided
+    pub(crate) fn from_segments(text: String, segments: Vec<(Range<usize>, Range<usize>)>) -> Self {
+        Self {
+            text,
+            stages: vec![segments],
+        }
+    }
+
+    /// Maps a char range of the normalized text to the corresponding range of the original
+    /// input, ranges which partially cover a replaced segment being extended to the whole
+    /// original segment
+    pub fn original_range(&self, range: &Range<usize>) -> Range<usize> {
+        self.stages
+            .iter()
+            .rev()
+            .fold(range.clone(), |range, segments| {
+                original_offset(segments, range.start, false)
+                    ..original_offset(segments, range.end, true)
+            })
+    }
+
+    /// Maps a char range of the original input to the corresponding range of the normalized
+    /// text, ranges which partially cover a replaced segment being extended to the whole
+    /// normalized segment
+    pub fn normalized_range(&self, range: &Range<usize>) -> Range<usize> {
+        self.stages.iter().fold(range.clone(), |range, segments| {
+            normalized_offset(segments, range.start, false)
+                ..normalized_offset(segments, range.end, true)
+        })
+    }
+
+    /// Maps the ranges of the slots back to the original input and updates their raw values
+    /// accordingly
+    pub fn restore_slots(&self, original_input: &str, slots: &mut [Slot]) {
+        for slot in slots.iter_mut() {
+            slot.range = self.original_range(&slot.range);
+            slot.raw_value = substring_with_char_range(original_input.to_string(), &slot.range);
+        }
+    }
+}
+
+fn original_offset(
+    segments: &[(Range<usize>, Range<usize>)],
+    offset: usize,
+    is_end: bool,
+) -> usize {
+    map_offset(
+        segments
+            .iter()
+            .map(|(normalized_range, original_range)| (normalized_range, original_range)),
+        offset,
+        is_end,
+    )
+}
+
+fn normalized_offset(
+    segments: &[(Range<usize>, Range<usize>)],
+    offset: usize,
+    is_end: bool,
+) -> usize {
+    map_offset(
+        segments
+            .iter()
+            .map(|(normalized_range, original_range)| (original_range, normalized_range)),
+        offset,
+        is_end,
+    )
+}
+
+/// Maps an offset of the source side of the segments to their target side
+fn map_offset<'a, I>(segments: I, offset: usize, is_end: bool) -> usize
+where
+    I: Iterator<Item = (&'a Range<usize>, &'a Range<usize>)>,
+{
+    let mut shift: isize = 0;
+    for (source_range, target_range) in segments {
+        if offset <= source_range.start {
+            break;
+        }
+        if offset < source_range.end {
+            return if is_end {
+                target_range.end
+            } else {
+                target_range.start
+            };
+        }
+        shift = target_range.end as isize - source_range.end as isize;
+    }
+    (offset as isize + shift) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_dictionary() -> NormalizationDictionary {
+        NormalizationDictionary::new(vec![
+            ("tv".to_string(), "television".to_string()),
+            ("pls".to_string(), "please".to_string()),
+            ("a.c.".to_string(), "air conditioner".to_string()),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_normalize() {
+        // Given
+        let dictionary = build_dictionary();
+
+        // When
+        let normalized_input = dictionary.normalize("Turn on the TV pls").unwrap();
+        let unchanged_input = dictionary.normalize("Turn on the tvs");
+
+        // Then
+        assert_eq!("Turn on the television please", &normalized_input.text);
+        assert_eq!(None, unchanged_input);
+    }
+
+    #[test]
+    fn test_original_range() {
+        // Given
+        let dictionary = build_dictionary();
+        let normalized_input = dictionary.normalize("set the tv to 5 pls").unwrap();
+        assert_eq!("set the television to 5 please", &normalized_input.text);
+
+        // When
+        let before_replacement = normalized_input.original_range(&(0..3));
+        let replaced = normalized_input.original_range(&(8..18));
+        let between_replacements = normalized_input.original_range(&(22..23));
+        let partially_replaced = normalized_input.original_range(&(22..27));
+
+        // Then
+        assert_eq!(0..3, before_replacement);
+        assert_eq!(8..10, replaced);
+        assert_eq!(14..15, between_replacements);
+        assert_eq!(14..19, partially_replaced);
+    }
+
+    #[test]
+    fn test_original_range_of_right_to_left_input() {
+        // Given
+        let dictionary =
+            NormalizationDictionary::new(vec![("تلفاز".to_string(), "تلفزيون".to_string())])
+                .unwrap();
+        let input = "افتح تلفاز من فضلك";
+
+        // When
+        let normalized_input = dictionary.normalize(input).unwrap();
+        let replaced = normalized_input.original_range(&(5..12));
+        let after_replacement = normalized_input.original_range(&(13..15));
+
+        // Then
+        assert_eq!("افتح تلفزيون من فضلك", &normalized_input.text);
+        assert_eq!(5..10, replaced);
+        assert_eq!(11..13, after_replacement);
+    }
+
+    #[test]
+    fn test_normalize_transliterated_input() {
+        // Given
+        let dictionary = build_dictionary().with_transliterator(Transliterator::default());
+        let input = "play Игорь Стравинский on the tv";
+
+        // When
+        let normalized_input = dictionary.normalize(input).unwrap();
+        let name = normalized_input.original_range(&(5..21));
+        let replaced = normalized_input.original_range(&(29..39));
+
+        // Then
+        assert_eq!(
+            "play Igor Stravinskiy on the television",
+            &normalized_input.text
+        );
+        assert_eq!(5..22, name);
+        assert_eq!(30..32, replaced);
+    }
+
+    #[test]
+    fn test_normalize_decomposed_input() {
+        // Given
+        let dictionary = build_dictionary();
+        let input = "un cafe\u{301} devant la tv";
+
+        // When
+        let normalized_input = dictionary.normalize(input).unwrap();
+        let accented_word = normalized_input.original_range(&(3..7));
+        let replaced = normalized_input.original_range(&(18..28));
+
+        // Then
+        assert_eq!("un caf\u{e9} devant la television", &normalized_input.text);
+        assert_eq!(3..8, accented_word);
+        assert_eq!(19..21, replaced);
+    }
+}
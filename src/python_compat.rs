@@ -0,0 +1,159 @@
+use serde_json::{Map, Value};
+use snips_nlu_ontology::IntentParserResult;
+
+use crate::errors::*;
+
+/// Serializes a parsing result in the exact same format as the `parse` method of the Python
+/// snips-nlu library
+///
+/// The differences with the default serialization are the following:
+/// - the intent score is named `probability` instead of `confidenceScore`
+/// - slots have no `confidenceScore` field
+/// - `alternatives` fields are only present when alternatives have been requested
+pub fn to_python_json(result: &IntentParserResult) -> Result<Value> {
+    let mut value = serde_json::to_value(result)?;
+    if let Value::Object(ref mut result_object) = value {
+        convert_intent_and_slots(result_object);
+        remove_empty_alternatives(result_object);
+        if let Some(Value::Array(alternatives)) = result_object.get_mut("alternatives") {
+            for alternative in alternatives.iter_mut() {
+                if let Value::Object(ref mut alternative_object) = alternative {
+                    convert_intent_and_slots(alternative_object);
+                }
+            }
+        }
+    }
+    Ok(value)
+}
+
+pub fn to_python_json_string(result: &IntentParserResult) -> Result<String> {
+    Ok(serde_json::to_string(&to_python_json(result)?)?)
+}
+
+fn convert_intent_and_slots(object: &mut Map<String, Value>) {
+    if let Some(Value::Object(intent)) = object.get_mut("intent") {
+        if let Some(score) = intent.remove("confidenceScore") {
+            intent.insert("probability".to_string(), score);
+        }
+    }
+    if let Some(Value::Array(slots)) = object.get_mut("slots") {
+        for slot in slots.iter_mut() {
+            if let Value::Object(ref mut slot_object) = slot {
+                slot_object.remove("confidenceScore");
+                remove_empty_alternatives(slot_object);
+            }
+        }
+    }
+}
+
+fn remove_empty_alternatives(object: &mut Map<String, Value>) {
+    let has_no_alternatives = match object.get("alternatives") {
+        Some(Value::Array(alternatives)) => alternatives.is_empty(),
+        Some(Value::Null) => true,
+        _ => false,
+    };
+    if has_no_alternatives {
+        object.remove("alternatives");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::path::Path;
+
+    use snips_nlu_ontology::*;
+
+    use super::*;
+
+    fn load_golden_file(name: &str) -> Value {
+        let path = Path::new("data")
+            .join("tests")
+            .join("python_compat")
+            .join(name);
+        serde_json::from_reader(File::open(path).unwrap()).unwrap()
+    }
+
+    fn make_coffee_slot(alternatives: Vec<SlotValue>) -> Slot {
+        Slot {
+            raw_value: "two".to_string(),
+            value: SlotValue::Number(NumberValue { value: 2.0 }),
+            alternatives,
+            range: 8..11,
+            entity: "snips/number".to_string(),
+            slot_name: "number_of_cups".to_string(),
+            confidence_score: None,
+        }
+    }
+
+    #[test]
+    fn test_to_python_json() {
+        // Given
+        let result = IntentParserResult {
+            input: "make me two cups of coffee".to_string(),
+            intent: IntentClassifierResult {
+                intent_name: Some("MakeCoffee".to_string()),
+                confidence_score: 0.5,
+            },
+            slots: vec![make_coffee_slot(vec![])],
+            alternatives: vec![],
+        };
+
+        // When
+        let python_json = to_python_json(&result).unwrap();
+
+        // Then
+        assert_eq!(load_golden_file("parsing_result.json"), python_json);
+    }
+
+    #[test]
+    fn test_to_python_json_with_none_intent() {
+        // Given
+        let result = IntentParserResult {
+            input: "foo bar".to_string(),
+            intent: IntentClassifierResult {
+                intent_name: None,
+                confidence_score: 0.75,
+            },
+            slots: vec![],
+            alternatives: vec![],
+        };
+
+        // When
+        let python_json = to_python_json(&result).unwrap();
+
+        // Then
+        assert_eq!(load_golden_file("none_parsing_result.json"), python_json);
+    }
+
+    #[test]
+    fn test_to_python_json_with_alternatives() {
+        // Given
+        let result = IntentParserResult {
+            input: "make me two cups of coffee".to_string(),
+            intent: IntentClassifierResult {
+                intent_name: Some("MakeCoffee".to_string()),
+                confidence_score: 0.5,
+            },
+            slots: vec![make_coffee_slot(vec![SlotValue::Number(NumberValue {
+                value: 1.0,
+            })])],
+            alternatives: vec![IntentParserAlternative {
+                intent: IntentClassifierResult {
+                    intent_name: None,
+                    confidence_score: 0.25,
+                },
+                slots: vec![],
+            }],
+        };
+
+        // When
+        let python_json = to_python_json(&result).unwrap();
+
+        // Then
+        assert_eq!(
+            load_golden_file("parsing_result_with_alternatives.json"),
+            python_json
+        );
+    }
+}
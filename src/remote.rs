@@ -0,0 +1,207 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use failure::{bail, format_err, ResultExt};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::errors::*;
+use crate::models::fingerprint;
+use crate::utils::to_hex;
+
+/// Response header in which a model registry may provide the hexadecimal fingerprint of the
+/// served model, as computed by `fingerprint`
+pub const FINGERPRINT_HEADER: &str = "X-Model-Fingerprint";
+
+const CONNECT_TIMEOUT_MS: u64 = 10_000;
+
+/// Cache metadata of a downloaded, or partially downloaded, model archive
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct CachedModelMetadata {
+    etag: Option<String>,
+    fingerprint: Option<String>,
+}
+
+/// Files of the cache which correspond to a model url
+struct CachePaths {
+    model: PathBuf,
+    metadata: PathBuf,
+    partial_model: PathBuf,
+    partial_metadata: PathBuf,
+}
+
+impl CachePaths {
+    fn new(url: &str, cache_dir: &Path) -> Self {
+        let key = to_hex(&Sha256::digest(url.as_bytes())[..16]);
+        Self {
+            model: cache_dir.join(format!("{}.zip", key)),
+            metadata: cache_dir.join(format!("{}.json", key)),
+            partial_model: cache_dir.join(format!("{}.zip.part", key)),
+            partial_metadata: cache_dir.join(format!("{}.part.json", key)),
+        }
+    }
+}
+
+/// Downloads the model archive located at `url` in `cache_dir`, and returns its path
+///
+/// The archive is only downloaded again when its ETag changed, and an interrupted download is
+/// resumed where it stopped. A new archive replaces the cached one only once it is complete
+/// and its fingerprint matches the one provided by the registry, if any. When the registry
+/// cannot be reached, the cached archive is used.
+pub(crate) fn fetch_model(url: &str, cache_dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(cache_dir)
+        .with_context(|_| format!("Cannot create model cache directory {:?}", cache_dir))?;
+    let paths = CachePaths::new(url, cache_dir);
+    let cached_metadata = if paths.model.is_file() {
+        read_metadata(&paths.metadata)
+    } else {
+        None
+    };
+    let partial_metadata = if paths.partial_model.is_file() {
+        read_metadata(&paths.partial_metadata)
+    } else {
+        None
+    };
+
+    let mut request = ureq::get(url);
+    request.timeout_connect(CONNECT_TIMEOUT_MS);
+    if let Some(etag) = cached_metadata.as_ref().and_then(|m| m.etag.as_ref()) {
+        request.set("If-None-Match", etag);
+    }
+    let resume_offset = match partial_metadata.as_ref().and_then(|m| m.etag.as_ref()) {
+        Some(etag) => {
+            let offset = fs::metadata(&paths.partial_model)?.len();
+            request.set("Range", &format!("bytes={}-", offset));
+            request.set("If-Range", etag);
+            offset
+        }
+        None => 0,
+    };
+    let response = request.call();
+    if let Some(error) = response.synthetic_error() {
+        if cached_metadata.is_some() {
+            warn!(
+                "Cannot reach model registry ({}), using cached model {:?}",
+                error, paths.model
+            );
+            return Ok(paths.model);
+        }
+        bail!("Cannot download model from {}: {}", url, error);
+    }
+
+    let append = match response.status() {
+        304 if cached_metadata.is_some() => {
+            info!("Cached model {:?} is up to date", paths.model);
+            return Ok(paths.model);
+        }
+        200 => false,
+        206 if resume_offset > 0 => {
+            info!("Resuming download of {} at byte {}", url, resume_offset);
+            true
+        }
+        status => bail!(
+            "Cannot download model from {}: unexpected status {} {}",
+            url,
+            status,
+            response.status_text()
+        ),
+    };
+    let metadata = CachedModelMetadata {
+        etag: response.header("ETag").map(|etag| etag.to_string()),
+        fingerprint: response
+            .header(FINGERPRINT_HEADER)
+            .map(|fingerprint| fingerprint.trim().to_lowercase()),
+    };
+    // The metadata is written first so that the download can be resumed if interrupted
+    write_metadata(&paths.partial_metadata, &metadata)?;
+    let mut partial_file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(&paths.partial_model)
+        .with_context(|_| format!("Cannot open {:?}", paths.partial_model))?;
+    io::copy(&mut response.into_reader(), &mut partial_file)
+        .with_context(|_| format!("Download of model from {} was interrupted", url))?;
+    drop(partial_file);
+
+    if let Err(error) = validate_fingerprint(&paths.partial_model, &metadata) {
+        let _ = fs::remove_file(&paths.partial_model);
+        let _ = fs::remove_file(&paths.partial_metadata);
+        return Err(error);
+    }
+    fs::rename(&paths.partial_model, &paths.model)?;
+    fs::rename(&paths.partial_metadata, &paths.metadata)?;
+    info!("Downloaded model from {} in {:?}", url, paths.model);
+    Ok(paths.model)
+}
+
+/// Checks that the archive is a valid model archive whose fingerprint is the expected one
+fn validate_fingerprint(model_path: &Path, metadata: &CachedModelMetadata) -> Result<()> {
+    let actual_fingerprint = to_hex(
+        &fingerprint(model_path)
+            .with_context(|_| format!("Downloaded model {:?} is corrupted", model_path))?,
+    );
+    match metadata.fingerprint.as_ref() {
+        Some(expected) if *expected != actual_fingerprint => Err(format_err!(
+            "Fingerprint of downloaded model is {} but {} was expected",
+            actual_fingerprint,
+            expected
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn read_metadata(path: &Path) -> Option<CachedModelMetadata> {
+    let file = fs::File::open(path).ok()?;
+    serde_json::from_reader(file).ok()
+}
+
+fn write_metadata(path: &Path, metadata: &CachedModelMetadata) -> Result<()> {
+    let file = fs::File::create(path).with_context(|_| format!("Cannot create {:?}", path))?;
+    serde_json::to_writer(file, metadata)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::write::{FileOptions, ZipWriter};
+
+    use super::*;
+
+    #[test]
+    fn test_validate_fingerprint() {
+        // Given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let model_path = temp_dir.path().join("model.zip");
+        let mut zip_writer = ZipWriter::new(fs::File::create(&model_path).unwrap());
+        zip_writer
+            .start_file("engine/nlu_engine.json", FileOptions::default())
+            .unwrap();
+        zip_writer.write_all(b"{}").unwrap();
+        zip_writer.finish().unwrap();
+        let expected_fingerprint = to_hex(&fingerprint(&model_path).unwrap());
+        let truncated_path = temp_dir.path().join("truncated.zip");
+        let zip_bytes = fs::read(&model_path).unwrap();
+        fs::write(&truncated_path, &zip_bytes[..zip_bytes.len() / 2]).unwrap();
+
+        let valid_metadata = CachedModelMetadata {
+            etag: Some("\"v1\"".to_string()),
+            fingerprint: Some(expected_fingerprint),
+        };
+        let invalid_metadata = CachedModelMetadata {
+            etag: Some("\"v1\"".to_string()),
+            fingerprint: Some("00".repeat(32)),
+        };
+
+        // When / Then
+        assert!(validate_fingerprint(&model_path, &valid_metadata).is_ok());
+        assert!(validate_fingerprint(&model_path, &CachedModelMetadata::default()).is_ok());
+        assert!(validate_fingerprint(&model_path, &invalid_metadata).is_err());
+        assert!(validate_fingerprint(&truncated_path, &valid_metadata).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Read};
+
+use csv::StringRecord;
+use serde::Serialize;
+
+use crate::errors::*;
+use crate::unicode::UnicodeNormalization;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// Format of a resource file
+///
+/// Line-based resources, such as gazetteers, only use the comment and BOM settings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CsvDialect {
+    /// Separator between the fields of a line
+    pub delimiter: u8,
+    /// Character used to quote the fields, quotes being read literally when `None`
+    pub quote: Option<u8>,
+    /// Lines starting with this character are ignored
+    pub comment: Option<u8>,
+    /// Whether a leading UTF-8 byte order mark must be removed
+    pub strip_bom: bool,
+    /// Normalization form applied to the entries
+    pub unicode_normalization: UnicodeNormalization,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: None,
+            comment: None,
+            strip_bom: true,
+            unicode_normalization: UnicodeNormalization::default(),
+        }
+    }
+}
+
+impl CsvDialect {
+    /// Dialect of the stems files, in which each line lists a stem followed by its inflections
+    pub fn stems() -> Self {
+        Self::default()
+    }
+
+    /// Dialect of the word clusters files, in which each line contains a word and its cluster
+    pub fn word_clusters() -> Self {
+        Self {
+            delimiter: b'\t',
+            ..Self::default()
+        }
+    }
+
+    /// Dialect of the weighted gazetteers, in which each line contains a value, its weight and
+    /// an optional intent
+    pub fn weighted_gazetteer() -> Self {
+        Self {
+            delimiter: b'\t',
+            comment: Some(b'#'),
+            ..Self::default()
+        }
+    }
+
+    /// Reader of the records of a csv resource, whose records may have various lengths when
+    /// `flexible` is true
+    pub(crate) fn csv_reader<'a, R: Read + 'a>(
+        &self,
+        reader: R,
+        flexible: bool,
+    ) -> Result<csv::Reader<Box<dyn BufRead + 'a>>> {
+        let reader = self.prepare(reader)?;
+        Ok(csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .quoting(self.quote.is_some())
+            .quote(self.quote.unwrap_or(b'"'))
+            .comment(self.comment)
+            .flexible(flexible)
+            .has_headers(false)
+            .from_reader(reader))
+    }
+
+    /// Non-empty and non-comment lines of a line-based resource, along with their line number
+    pub(crate) fn lines<'a, R: Read + 'a>(
+        &self,
+        reader: R,
+    ) -> Result<impl Iterator<Item = (u64, Result<String>)> + 'a> {
+        let comment = self.comment;
+        let unicode_normalization = self.unicode_normalization;
+        Ok(self
+            .prepare(reader)?
+            .lines()
+            .enumerate()
+            .map(|(line_idx, line)| (line_idx as u64 + 1, line.map_err(|e| e.into())))
+            .filter(move |(_, line)| match line {
+                Ok(line) => {
+                    !line.is_empty()
+                        && comment.map_or(true, |comment| !line.as_bytes().starts_with(&[comment]))
+                }
+                Err(_) => true,
+            })
+            .map(move |(line_number, line)| {
+                let line = line.map(|line| match unicode_normalization.apply(&line) {
+                    Cow::Borrowed(_) => line,
+                    Cow::Owned(normalized_line) => normalized_line,
+                });
+                (line_number, line)
+            }))
+    }
+
+    /// Applies the normalization form of the dialect to a field of a csv resource
+    pub(crate) fn normalize<'a>(&self, field: &'a str) -> Cow<'a, str> {
+        self.unicode_normalization.apply(field)
+    }
+
+    fn prepare<'a, R: Read + 'a>(&self, reader: R) -> Result<Box<dyn BufRead + 'a>> {
+        let mut reader = BufReader::new(reader);
+        if self.strip_bom && reader.fill_buf()?.starts_with(UTF8_BOM) {
+            reader.consume(UTF8_BOM.len());
+        }
+        Ok(Box::new(reader))
+    }
+}
+
+/// Malformed line skipped when parsing a resource leniently
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsingWarning {
+    /// Line number, starting at 1, or 0 when it is unknown
+    pub line: u64,
+    pub message: String,
+}
+
+/// Either fails on the first malformed line, or skips the malformed lines and collects them
+/// as warnings when parsing leniently
+pub(crate) struct WarningCollector {
+    lenient: bool,
+    pub(crate) warnings: Vec<ParsingWarning>,
+}
+
+impl WarningCollector {
+    pub(crate) fn new(lenient: bool) -> Self {
+        Self {
+            lenient,
+            warnings: vec![],
+        }
+    }
+
+    /// Returns the value of a well-formed line, and `None` for a malformed line which is
+    /// skipped
+    pub(crate) fn check<T>(&mut self, line: u64, result: Result<T>) -> Result<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if self.lenient => {
+                self.warnings.push(ParsingWarning {
+                    line,
+                    message: error.to_string(),
+                });
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    pub(crate) fn check_record(
+        &mut self,
+        record: csv::Result<StringRecord>,
+    ) -> Result<Option<StringRecord>> {
+        let line = match &record {
+            Ok(record) => record.position(),
+            Err(error) => error.position(),
+        }
+        .map(|position| position.line())
+        .unwrap_or(0);
+        self.check(line, record.map_err(|e| e.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_lines_with_dialect() {
+        // Given
+        let content: &[u8] = b"\xEF\xBB\xBFdog\n# animals\n\ncat\n";
+        let dialect = CsvDialect {
+            comment: Some(b'#'),
+            ..CsvDialect::default()
+        };
+
+        // When
+        let lines: Vec<(u64, String)> = dialect
+            .lines(content)
+            .unwrap()
+            .map(|(line_number, line)| (line_number, line.unwrap()))
+            .collect();
+
+        // Then
+        assert_eq!(vec![(1, "dog".to_string()), (4, "cat".to_string())], lines);
+    }
+}
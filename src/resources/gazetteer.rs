@@ -1,29 +1,172 @@
 use crate::errors::*;
-use snips_nlu_utils::string::hash_str_to_i32;
-use std::collections::HashSet;
-use std::io::{BufRead, BufReader, Read};
+use crate::language::FromLanguage;
+use crate::resources::dialect::{CsvDialect, ParsingWarning, WarningCollector};
+use crate::resources::stemmer::Stemmer;
+use failure::{format_err, ResultExt};
+use fst::{Set, SetBuilder};
+use lazy_static::lazy_static;
+use log::info;
+use sha2::{Digest, Sha256};
+use snips_nlu_ontology::Language;
+use snips_nlu_utils::language::Language as NluUtilsLanguage;
+use snips_nlu_utils::string::{hash_str_to_i32, normalize};
+use snips_nlu_utils::token::tokenize_light;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{Read, Write};
 use std::iter::FromIterator;
+use std::path::Path;
+use std::sync::{Arc, Mutex, Weak};
+
+/// Extension of the gazetteer files stored as finite state transducers
+pub const FST_GAZETTEER_EXTENSION: &str = "fst";
+
+/// Suffix of the name of the stemmed version of a gazetteer
+pub const STEMMED_GAZETTEER_SUFFIX: &str = "_stemmed";
+
+lazy_static! {
+    static ref LOADED_GAZETTEERS: Mutex<HashMap<GazetteerKey, Weak<HashSetGazetteer>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// SHA-256 digest of the content of a gazetteer, along with the dialect used to parse it
+type GazetteerKey = (Vec<u8>, CsvDialect);
 
 pub trait Gazetteer: Send + Sync {
     fn contains(&self, value: &str) -> bool;
 }
 
+/// Streaming reader of the entries of a text gazetteer, which reads the file line by line
+/// instead of materializing all its entries
+pub struct GazetteerReader<'a> {
+    lines: Box<dyn Iterator<Item = (u64, Result<String>)> + 'a>,
+}
+
+impl<'a> GazetteerReader<'a> {
+    pub fn new<R: Read + 'a>(reader: R) -> Result<Self> {
+        Self::with_dialect(reader, &CsvDialect::default())
+    }
+
+    pub fn with_dialect<R: Read + 'a>(reader: R, dialect: &CsvDialect) -> Result<Self> {
+        Ok(Self {
+            lines: Box::new(dialect.lines(reader)?),
+        })
+    }
+}
+
+impl<'a> Iterator for GazetteerReader<'a> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        self.lines.next().map(|(line_number, line)| {
+            Ok(line.with_context(|_| format!("Invalid gazetteer line {}", line_number))?)
+        })
+    }
+}
+
 pub struct HashSetGazetteer {
     values: HashSet<i32>,
 }
 
 impl HashSetGazetteer {
     pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
-        let reader = BufReader::new(reader);
+        Self::from_reader_with_dialect(reader, &CsvDialect::default())
+    }
+
+    pub fn from_reader_with_dialect<R: Read>(reader: R, dialect: &CsvDialect) -> Result<Self> {
+        Self::parse(reader, dialect, false).map(|(gazetteer, _)| gazetteer)
+    }
+
+    /// Parses the gazetteer while skipping the malformed lines, which are returned as warnings
+    pub fn from_reader_lenient<R: Read>(
+        reader: R,
+        dialect: &CsvDialect,
+    ) -> Result<(Self, Vec<ParsingWarning>)> {
+        Self::parse(reader, dialect, true)
+    }
+
+    /// Parses a gazetteer written by hand, whose entries are processed like the ones of the
+    /// built-in gazetteers: each entry is tokenized, and its tokens are normalized and stemmed
+    ///
+    /// The stemmer must be the one used by the features looking up the gazetteer, typically
+    /// the stemmer of the engine resources.
+    pub fn from_reader_with_language<R: Read>(
+        reader: R,
+        language: Language,
+        stemmer: Option<&dyn Stemmer>,
+        dialect: &CsvDialect,
+    ) -> Result<Self> {
+        let language = NluUtilsLanguage::from_language(language);
+        let mut values = HashSet::new();
+        for value in GazetteerReader::with_dialect(reader, dialect)? {
+            let processed_value = tokenize_light(&value?, language)
+                .into_iter()
+                .map(|token| {
+                    let normalized_token = normalize(&token);
+                    match stemmer {
+                        Some(stemmer) => stemmer.stem(&normalized_token).into_owned(),
+                        None => normalized_token,
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+            values.insert(hash_str_to_i32(&processed_value));
+        }
+        Ok(Self { values })
+    }
+
+    fn parse<R: Read>(
+        reader: R,
+        dialect: &CsvDialect,
+        lenient: bool,
+    ) -> Result<(Self, Vec<ParsingWarning>)> {
+        let mut collector = WarningCollector::new(lenient);
         let mut values = HashSet::new();
-        for line in reader.lines() {
-            let word = line?;
-            if !word.is_empty() {
+        for (line_number, line) in dialect.lines(reader)? {
+            if let Some(word) = collector.check(line_number, line)? {
                 values.insert(hash_str_to_i32(&*word));
             }
         }
-        Ok(Self { values })
+        Ok((Self { values }, collector.warnings))
     }
+
+    /// Loads the gazetteer, or returns the gazetteer having the same content if one is already
+    /// loaded in the process
+    ///
+    /// Trained engines embed their own copy of the language resources, so hosts running several
+    /// engines of the same language would otherwise keep identical gazetteers in memory. The
+    /// content is identified by its SHA-256 digest, and gazetteers are released once no engine
+    /// uses them.
+    pub fn from_reader_shared<R: Read>(mut reader: R, dialect: &CsvDialect) -> Result<Arc<Self>> {
+        let mut content = vec![];
+        reader.read_to_end(&mut content)?;
+        let key = (Sha256::digest(&content).to_vec(), dialect.clone());
+        if let Some(gazetteer) = loaded_gazetteer(&key)? {
+            info!("Reusing identical gazetteer already loaded");
+            return Ok(gazetteer);
+        }
+
+        // The gazetteer is parsed without holding the lock, so that engines loading different
+        // gazetteers do not wait for each other
+        let gazetteer = Arc::new(Self::from_reader_with_dialect(&content[..], dialect)?);
+        let mut loaded_gazetteers = LOADED_GAZETTEERS
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))?;
+        // Another thread may have loaded the same gazetteer in the meantime
+        if let Some(gazetteer) = loaded_gazetteers.get(&key).and_then(|g| g.upgrade()) {
+            return Ok(gazetteer);
+        }
+        loaded_gazetteers.retain(|_, gazetteer| gazetteer.strong_count() > 0);
+        loaded_gazetteers.insert(key, Arc::downgrade(&gazetteer));
+        Ok(gazetteer)
+    }
+}
+
+fn loaded_gazetteer(key: &GazetteerKey) -> Result<Option<Arc<HashSetGazetteer>>> {
+    Ok(LOADED_GAZETTEERS
+        .lock()
+        .map_err(|e| format_err!("Poisonous mutex: {}", e))?
+        .get(key)
+        .and_then(|gazetteer| gazetteer.upgrade()))
 }
 
 impl FromIterator<String> for HashSetGazetteer {
@@ -43,9 +186,144 @@ impl Gazetteer for HashSetGazetteer {
     }
 }
 
+/// Gazetteer stored as a finite state transducer, which is memory-mapped instead of being
+/// loaded in memory
+///
+/// The loader uses it when a `<name>.fst` file is found next to the `<name>.txt` gazetteer,
+/// which keeps huge gazetteers such as cities_world out of the heap. Like the binary stems
+/// files, the FST format has a versioned header and stores its integers in little-endian order,
+/// so a file built on one platform can be loaded on any other.
+pub struct FstGazetteer {
+    values: Set,
+}
+
+impl FstGazetteer {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        // The mapped file must not be modified while the gazetteer is alive, which is the case
+        // of the resources of a trained engine
+        let values = unsafe { Set::from_path(path)? };
+        Ok(Self { values })
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        Ok(Self {
+            values: Set::from_bytes(bytes)?,
+        })
+    }
+
+    /// Writes the values in the FST format, typically the ones of a `GazetteerReader`
+    ///
+    /// The values are sorted in memory beforehand, as required by the format, so the conversion
+    /// is meant to be done once, offline. They must already be normalized with the Unicode
+    /// normalization form used by the engine.
+    pub fn build<I, W>(values: I, writer: W) -> Result<()>
+    where
+        I: IntoIterator<Item = Result<String>>,
+        W: Write,
+    {
+        let values = values.into_iter().collect::<Result<BTreeSet<String>>>()?;
+        let mut builder = SetBuilder::new(writer)?;
+        builder.extend_iter(values)?;
+        builder.finish()?;
+        Ok(())
+    }
+}
+
+impl Gazetteer for FstGazetteer {
+    fn contains(&self, value: &str) -> bool {
+        self.values.contains(value)
+    }
+}
+
+/// Gazetteer resulting from set operations on other gazetteers
+///
+/// The operations are evaluated lazily, when looking up a value, so composing large gazetteers
+/// such as "world cities minus US cities" does not copy them.
+pub enum ComposedGazetteer {
+    Union(Arc<dyn Gazetteer>, Arc<dyn Gazetteer>),
+    Intersection(Arc<dyn Gazetteer>, Arc<dyn Gazetteer>),
+    Difference(Arc<dyn Gazetteer>, Arc<dyn Gazetteer>),
+    Filter(Arc<dyn Gazetteer>, Box<dyn Fn(&str) -> bool + Send + Sync>),
+}
+
+impl ComposedGazetteer {
+    /// Values contained in any of the two gazetteers
+    pub fn union(gazetteer: Arc<dyn Gazetteer>, other: Arc<dyn Gazetteer>) -> Self {
+        ComposedGazetteer::Union(gazetteer, other)
+    }
+
+    /// Values contained in both gazetteers
+    pub fn intersection(gazetteer: Arc<dyn Gazetteer>, other: Arc<dyn Gazetteer>) -> Self {
+        ComposedGazetteer::Intersection(gazetteer, other)
+    }
+
+    /// Values contained in the first gazetteer but not in the second one
+    pub fn difference(gazetteer: Arc<dyn Gazetteer>, other: Arc<dyn Gazetteer>) -> Self {
+        ComposedGazetteer::Difference(gazetteer, other)
+    }
+
+    /// Values contained in the gazetteer which satisfy the predicate
+    pub fn filter<F>(gazetteer: Arc<dyn Gazetteer>, predicate: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        ComposedGazetteer::Filter(gazetteer, Box::new(predicate))
+    }
+}
+
+impl Gazetteer for ComposedGazetteer {
+    fn contains(&self, value: &str) -> bool {
+        match self {
+            ComposedGazetteer::Union(gazetteer, other) => {
+                gazetteer.contains(value) || other.contains(value)
+            }
+            ComposedGazetteer::Intersection(gazetteer, other) => {
+                gazetteer.contains(value) && other.contains(value)
+            }
+            ComposedGazetteer::Difference(gazetteer, other) => {
+                gazetteer.contains(value) && !other.contains(value)
+            }
+            ComposedGazetteer::Filter(gazetteer, predicate) => {
+                gazetteer.contains(value) && predicate(value)
+            }
+        }
+    }
+}
+
+/// Gazetteer looking up the stems of the values in the stemmed version of a gazetteer
+///
+/// It replaces an unstemmed gazetteer when its stemmed version is loaded as well, in order to
+/// save memory. The lookups are less precise, since distinct words sharing a stem match.
+pub struct StemmingGazetteer {
+    stemmed_gazetteer: Arc<dyn Gazetteer>,
+    stemmer: Arc<dyn Stemmer>,
+}
+
+impl StemmingGazetteer {
+    pub fn new(stemmed_gazetteer: Arc<dyn Gazetteer>, stemmer: Arc<dyn Stemmer>) -> Self {
+        Self {
+            stemmed_gazetteer,
+            stemmer,
+        }
+    }
+}
+
+impl Gazetteer for StemmingGazetteer {
+    fn contains(&self, value: &str) -> bool {
+        let stemmed_value = value
+            .split(' ')
+            .map(|token| self.stemmer.stem(token))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.stemmed_gazetteer.contains(&stemmed_value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Gazetteer, HashSetGazetteer};
+    use crate::resources::stemmer::HashMapStemmer;
+
+    use super::*;
 
     #[test]
     fn test_hashset_gazetteer() {
@@ -67,4 +345,156 @@ crocodile"#
         assert!(gazetteer.contains("crocodile"));
         assert!(!gazetteer.contains("bird"));
     }
+
+    #[test]
+    fn test_shared_gazetteers() {
+        // Given
+        let gazetteer: &[u8] = b"dog\ncat\nbear";
+        let other_gazetteer: &[u8] = b"dog\ncat";
+
+        // When
+        let dialect = CsvDialect::default();
+        let first_gazetteer = HashSetGazetteer::from_reader_shared(gazetteer, &dialect).unwrap();
+        let second_gazetteer = HashSetGazetteer::from_reader_shared(gazetteer, &dialect).unwrap();
+        let third_gazetteer =
+            HashSetGazetteer::from_reader_shared(other_gazetteer, &dialect).unwrap();
+
+        // Then
+        assert!(Arc::ptr_eq(&first_gazetteer, &second_gazetteer));
+        assert!(!Arc::ptr_eq(&first_gazetteer, &third_gazetteer));
+        assert!(third_gazetteer.contains("cat"));
+        assert!(!third_gazetteer.contains("bear"));
+    }
+
+    #[test]
+    fn test_composed_gazetteers() {
+        // Given
+        let world_cities: Arc<dyn Gazetteer> = Arc::new(HashSetGazetteer::from_iter(vec![
+            "paris".to_string(),
+            "new york".to_string(),
+            "boston".to_string(),
+        ]));
+        let us_cities: Arc<dyn Gazetteer> = Arc::new(HashSetGazetteer::from_iter(vec![
+            "new york".to_string(),
+            "boston".to_string(),
+            "chicago".to_string(),
+        ]));
+
+        // When
+        let union = ComposedGazetteer::union(world_cities.clone(), us_cities.clone());
+        let intersection = ComposedGazetteer::intersection(world_cities.clone(), us_cities.clone());
+        let difference: Arc<dyn Gazetteer> = Arc::new(ComposedGazetteer::difference(
+            world_cities.clone(),
+            us_cities.clone(),
+        ));
+        let filtered = ComposedGazetteer::filter(world_cities, |city| city.contains(' '));
+
+        // Then
+        assert!(union.contains("paris"));
+        assert!(union.contains("chicago"));
+        assert!(intersection.contains("boston"));
+        assert!(!intersection.contains("paris"));
+        assert!(difference.contains("paris"));
+        assert!(!difference.contains("new york"));
+        assert!(filtered.contains("new york"));
+        assert!(!filtered.contains("boston"));
+    }
+
+    #[test]
+    fn test_lenient_gazetteer_parsing() {
+        // Given
+        let gazetteer: &[u8] = b"dog\ncat\xFF\nbear";
+
+        // When
+        let strict_result = HashSetGazetteer::from_reader(gazetteer);
+        let (gazetteer, warnings) =
+            HashSetGazetteer::from_reader_lenient(gazetteer, &CsvDialect::default()).unwrap();
+
+        // Then
+        assert!(strict_result.is_err());
+        assert!(gazetteer.contains("dog"));
+        assert!(gazetteer.contains("bear"));
+        assert_eq!(1, warnings.len());
+        assert_eq!(2, warnings[0].line);
+    }
+
+    #[test]
+    fn test_gazetteer_with_language() {
+        // Given
+        let gazetteer: &[u8] = "Smart Speakers\nCafé Maker".as_bytes();
+        let stemmer =
+            HashMapStemmer::from_iter(vec![("speakers".to_string(), "speaker".to_string())]);
+
+        // When
+        let gazetteer = HashSetGazetteer::from_reader_with_language(
+            gazetteer,
+            Language::EN,
+            Some(&stemmer),
+            &CsvDialect::default(),
+        )
+        .unwrap();
+
+        // Then
+        assert!(gazetteer.contains("smart speaker"));
+        assert!(gazetteer.contains("cafe maker"));
+        assert!(!gazetteer.contains("Smart Speakers"));
+    }
+
+    #[test]
+    fn test_fst_gazetteer() {
+        // Given
+        let gazetteer: &[u8] = b"dog\n# animals\ncat\nbear\ncrocodile\n";
+        let dialect = CsvDialect {
+            comment: Some(b'#'),
+            ..CsvDialect::default()
+        };
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fst_path = temp_dir.path().join("animals.fst");
+
+        // When
+        let values = GazetteerReader::with_dialect(gazetteer, &dialect).unwrap();
+        FstGazetteer::build(values, std::fs::File::create(&fst_path).unwrap()).unwrap();
+        let gazetteer = FstGazetteer::from_path(&fst_path).unwrap();
+
+        // Then
+        assert!(gazetteer.contains("dog"));
+        assert!(gazetteer.contains("crocodile"));
+        assert!(!gazetteer.contains("# animals"));
+        assert!(!gazetteer.contains("bird"));
+    }
+
+    #[test]
+    fn test_gazetteer_with_decomposed_entries() {
+        // Given
+        let gazetteer: &[u8] = "cafe\u{301}\ncre\u{300}me bru\u{302}le\u{301}e".as_bytes();
+
+        // When
+        let gazetteer = HashSetGazetteer::from_reader(gazetteer).unwrap();
+
+        // Then
+        assert!(gazetteer.contains("caf\u{e9}"));
+        assert!(gazetteer.contains("cr\u{e8}me br\u{fb}l\u{e9}e"));
+    }
+
+    #[test]
+    fn test_stemming_gazetteer() {
+        // Given
+        let stemmed_gazetteer: Arc<dyn Gazetteer> = Arc::new(HashSetGazetteer::from_iter(vec![
+            "smart speaker".to_string(),
+            "dog".to_string(),
+        ]));
+        let stemmer: Arc<dyn Stemmer> = Arc::new(HashMapStemmer::from_iter(vec![
+            ("speakers".to_string(), "speaker".to_string()),
+            ("dogs".to_string(), "dog".to_string()),
+        ]));
+
+        // When
+        let gazetteer = StemmingGazetteer::new(stemmed_gazetteer, stemmer);
+
+        // Then
+        assert!(gazetteer.contains("smart speakers"));
+        assert!(gazetteer.contains("dogs"));
+        assert!(gazetteer.contains("dog"));
+        assert!(!gazetteer.contains("cats"));
+    }
 }
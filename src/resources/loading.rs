@@ -1,30 +1,156 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use failure::ResultExt;
-use log::info;
-use serde::Deserialize;
+use failure::{format_err, ResultExt};
+use lazy_static::lazy_static;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
 use snips_nlu_ontology::Language;
 
-use crate::entity_parser::{CachingBuiltinEntityParser, CachingCustomEntityParser};
+use crate::config::EngineConfig;
+use crate::entity_parser::{
+    BuiltinEntityParser, BuiltinEntityParserPool, CachingBuiltinEntityParser,
+    CachingCustomEntityParser, EntityCachingBuiltinEntityParser, ParallelBuiltinEntityParser,
+};
 use crate::errors::*;
+use crate::memory_budget::MemoryPlan;
 use crate::models::nlu_engine::NluEngineModel;
-use crate::resources::gazetteer::{Gazetteer, HashSetGazetteer};
-use crate::resources::stemmer::{HashMapStemmer, Stemmer};
+use crate::resources::dialect::CsvDialect;
+use crate::resources::gazetteer::{
+    FstGazetteer, Gazetteer, GazetteerReader, HashSetGazetteer, StemmingGazetteer,
+    FST_GAZETTEER_EXTENSION, STEMMED_GAZETTEER_SUFFIX,
+};
+use crate::resources::pack::ResourcePackManifest;
+use crate::resources::stemmer::{
+    FallbackStemmer, HashMapStemmer, InternedStemmer, SnowballStemmer, Stemmer, StemmerBackend,
+    BINARY_STEMS_EXTENSION,
+};
 use crate::resources::word_clusterer::{HashMapWordClusterer, WordClusterer};
-use crate::resources::SharedResources;
+use crate::resources::{MissingResourcePolicy, SharedResources};
+use crate::unicode::UnicodeNormalization;
+use crate::utils::extract_nlu_engine_zip_archive;
 
-#[derive(Debug, Deserialize, Clone)]
-struct ResourcesMetadata {
-    language: String,
-    gazetteers: Option<Vec<String>>,
-    word_clusters: Option<Vec<String>>,
-    stems: Option<String>,
-    stop_words: Option<String>,
+#[derive(Debug, Serialize, Deserialize, Clone, Hash)]
+pub(crate) struct ResourcesMetadata {
+    pub(crate) language: String,
+    pub(crate) gazetteers: Option<Vec<String>>,
+    pub(crate) word_clusters: Option<Vec<String>>,
+    pub(crate) stems: Option<String>,
+    pub(crate) stop_words: Option<String>,
+    #[serde(default)]
+    pub(crate) stemmer: StemmerBackend,
+}
+
+impl ResourcesMetadata {
+    /// Path of the stems file, its precompiled version being preferred when there is one which
+    /// is not older than the text file
+    fn stems_path(resources_dir: &Path, stems: &str) -> PathBuf {
+        let stems_path = resources_dir.join("stemming").join(stems);
+        let text_path = stems_path.with_extension("txt");
+        let binary_path = stems_path.with_extension(BINARY_STEMS_EXTENSION);
+        if binary_path.exists() && !is_modified_before(&binary_path, &text_path) {
+            binary_path
+        } else {
+            text_path
+        }
+    }
+
+    /// Path of the gazetteer file, its FST version being preferred when there is one
+    pub(crate) fn gazetteer_path(resources_dir: &Path, gazetteer_name: &str) -> PathBuf {
+        let gazetteer_path = resources_dir.join("gazetteers").join(gazetteer_name);
+        let fst_path = gazetteer_path.with_extension(FST_GAZETTEER_EXTENSION);
+        if fst_path.exists() {
+            fst_path
+        } else {
+            gazetteer_path.with_extension("txt")
+        }
+    }
+
+    /// Names of the unstemmed gazetteers whose stemmed version is listed as well
+    pub(crate) fn stemmed_gazetteer_duplicates(&self) -> Vec<&str> {
+        let gazetteers = self.gazetteers.as_ref().map_or(&[][..], |names| &names[..]);
+        gazetteers
+            .iter()
+            .filter(|name| !name.ends_with(STEMMED_GAZETTEER_SUFFIX))
+            .filter(|name| {
+                let stemmed_name = format!("{}{}", name, STEMMED_GAZETTEER_SUFFIX);
+                gazetteers.contains(&stemmed_name)
+            })
+            .map(|name| &**name)
+            .collect()
+    }
+
+    /// Whether a stemmer is loaded with these resources
+    pub(crate) fn has_stemmer(&self) -> bool {
+        self.stems.is_some() || self.stemmer == StemmerBackend::Snowball
+    }
+
+    fn word_clusters_path(resources_dir: &Path, clusters_name: &str) -> PathBuf {
+        resources_dir
+            .join("word_clusters")
+            .join(clusters_name)
+            .with_extension("txt")
+    }
+
+    fn stop_words_path(resources_dir: &Path, stop_words_name: &str) -> PathBuf {
+        resources_dir.join(stop_words_name).with_extension("txt")
+    }
+
+    /// Paths of all the resource files
+    fn paths(&self, resources_dir: &Path) -> Vec<PathBuf> {
+        let mut paths = vec![];
+        if let Some(stems) = self.stems.as_ref() {
+            paths.push(Self::stems_path(resources_dir, stems));
+        }
+        for gazetteer_name in self.gazetteers.iter().flatten() {
+            paths.push(Self::gazetteer_path(resources_dir, gazetteer_name));
+        }
+        for clusters_name in self.word_clusters.iter().flatten() {
+            paths.push(Self::word_clusters_path(resources_dir, clusters_name));
+        }
+        if let Some(stop_words_name) = self.stop_words.as_ref() {
+            paths.push(Self::stop_words_path(resources_dir, stop_words_name));
+        }
+        paths
+    }
+
+    /// Removes the resources whose file is missing, so that they are not loaded
+    fn without_missing_resources(mut self, resources_dir: &Path) -> Self {
+        let is_present = |path: PathBuf| {
+            if path.exists() {
+                true
+            } else {
+                warn!("Resource file {:?} is missing", path);
+                false
+            }
+        };
+        self.stems = self
+            .stems
+            .filter(|stems| is_present(Self::stems_path(resources_dir, stems)));
+        self.gazetteers = self.gazetteers.map(|gazetteers| {
+            gazetteers
+                .into_iter()
+                .filter(|name| is_present(Self::gazetteer_path(resources_dir, name)))
+                .collect()
+        });
+        self.word_clusters = self.word_clusters.map(|word_clusters| {
+            word_clusters
+                .into_iter()
+                .filter(|name| is_present(Self::word_clusters_path(resources_dir, name)))
+                .collect()
+        });
+        self.stop_words = self
+            .stop_words
+            .filter(|name| is_present(Self::stop_words_path(resources_dir, name)));
+        self
+    }
 }
 
 pub fn load_shared_resources<P: AsRef<Path>, Q: AsRef<Path>, R: AsRef<Path>>(
@@ -32,145 +158,1008 @@ pub fn load_shared_resources<P: AsRef<Path>, Q: AsRef<Path>, R: AsRef<Path>>(
     builtin_entity_parser_path: Q,
     custom_entity_parser_path: R,
 ) -> Result<Arc<SharedResources>> {
-    let metadata_file_path = resources_dir.as_ref().join("metadata.json");
-    let metadata_file = File::open(&metadata_file_path)?;
-    let metadata: ResourcesMetadata =
-        serde_json::from_reader(metadata_file).with_context(|_| {
-            format!(
-                "Cannot deserialize resources metadata file '{:?}'",
-                metadata_file_path
-            )
-        })?;
-    let stemmer = load_stemmer(&resources_dir, &metadata)?;
-    let gazetteers = load_gazetteers(&resources_dir, &metadata)?;
-    let word_clusterers = load_word_clusterers(&resources_dir, &metadata)?;
-    let stop_words = load_stop_words(&resources_dir, &metadata)?;
-    let builtin_entity_parser =
-        CachingBuiltinEntityParser::from_path(builtin_entity_parser_path, 1000)?;
-    let custom_entity_parser =
-        CachingCustomEntityParser::from_path(custom_entity_parser_path, 1000)?;
-
-    Ok(Arc::new(SharedResources {
-        builtin_entity_parser: Arc::new(builtin_entity_parser),
-        custom_entity_parser: Arc::new(custom_entity_parser),
-        gazetteers,
-        stemmer,
-        word_clusterers,
-        stop_words,
-    }))
+    load_shared_resources_with_config(
+        resources_dir,
+        builtin_entity_parser_path,
+        custom_entity_parser_path,
+        &EngineConfig::default(),
+    )
+}
+
+pub fn load_shared_resources_with_config<P: AsRef<Path>, Q: AsRef<Path>, R: AsRef<Path>>(
+    resources_dir: P,
+    builtin_entity_parser_path: Q,
+    custom_entity_parser_path: R,
+    config: &EngineConfig,
+) -> Result<Arc<SharedResources>> {
+    ResourceLoader::default().load(
+        resources_dir,
+        builtin_entity_parser_path,
+        custom_entity_parser_path,
+        config,
+    )
 }
 
 pub fn load_engine_shared_resources<P: AsRef<Path>>(engine_dir: P) -> Result<Arc<SharedResources>> {
-    let nlu_engine_file = engine_dir.as_ref().join("nlu_engine.json");
-    let model_file = File::open(&nlu_engine_file)
-        .with_context(|_| format!("Could not open nlu engine file {:?}", nlu_engine_file))?;
-    let model: NluEngineModel = serde_json::from_reader(model_file)
-        .with_context(|_| "Could not deserialize nlu engine json file")?;
-    let language = Language::from_str(&model.dataset_metadata.language_code)?;
-    let resources_path = engine_dir
-        .as_ref()
-        .join("resources")
-        .join(language.to_string());
-    let builtin_parser_path = engine_dir.as_ref().join(&model.builtin_entity_parser);
-    let custom_parser_path = engine_dir.as_ref().join(&model.custom_entity_parser);
-    load_shared_resources(&resources_path, builtin_parser_path, custom_parser_path)
-}
-
-fn load_stemmer<P: AsRef<Path>>(
-    resources_dir: &P,
-    metadata: &ResourcesMetadata,
-) -> Result<Option<Arc<dyn Stemmer>>> {
-    if let Some(stems) = metadata.stems.as_ref() {
-        let stemming_directory = resources_dir.as_ref().join("stemming");
-        let stems_path = stemming_directory.join(stems).with_extension("txt");
-        info!("Loading stemmer ({:?}) ...", stems_path);
-        let stems_reader = File::open(&stems_path)
-            .with_context(|_| format!("Cannot open stems file {:?}", stems_path))?;
-        let stemmer = HashMapStemmer::from_reader(stems_reader)
-            .with_context(|_| format!("Cannot read stems file {:?}", stems_path))?;
-        info!("Stemmer loaded");
-        Ok(Some(Arc::new(stemmer)))
-    } else {
-        Ok(None)
-    }
-}
-
-fn load_gazetteers<P: AsRef<Path>>(
-    resources_dir: &P,
-    metadata: &ResourcesMetadata,
-) -> Result<HashMap<String, Arc<dyn Gazetteer>>> {
-    let mut gazetteers: HashMap<String, Arc<dyn Gazetteer>> = HashMap::new();
-    if let Some(gazetteer_names) = metadata.gazetteers.as_ref() {
-        let gazetteers_directory = resources_dir.as_ref().join("gazetteers");
-        for gazetteer_name in gazetteer_names {
-            let gazetteer_path = gazetteers_directory
-                .join(gazetteer_name.clone())
-                .with_extension("txt");
+    ResourceLoader::default().load_engine_resources(engine_dir)
+}
+
+/// Language resources which do not depend on the training dataset, and which can therefore
+/// be shared between engines
+#[derive(Clone)]
+struct LanguageLayer {
+    gazetteers: HashMap<String, Arc<dyn Gazetteer>>,
+    stemmer: Option<Arc<dyn Stemmer>>,
+    word_clusterers: HashMap<String, Arc<dyn WordClusterer>>,
+    stop_words: HashSet<String>,
+}
+
+/// Language resources loaded by the `ResourceLoader`s using this cache, identified by the
+/// content of their files
+///
+/// The resources are kept in memory as long as the cache is alive, even when no engine uses
+/// them anymore.
+#[derive(Clone, Default)]
+pub(crate) struct LanguageLayers(Arc<Mutex<HashMap<u64, LanguageLayer>>>);
+
+impl LanguageLayers {
+    /// Number of distinct language layers in the cache
+    pub(crate) fn len(&self) -> Result<usize> {
+        Ok(self
+            .0
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))?
+            .len())
+    }
+}
+
+lazy_static! {
+    static ref LANGUAGE_RESOURCES_POOL: LanguageLayers = LanguageLayers::default();
+}
+
+/// Process-wide pool of language resources
+///
+/// Gazetteers, stems and word clusters can weigh tens of megabytes, the pool allows to parse
+/// them once per set of identical resource files and to share them between the engines loaded
+/// with `EngineConfig::share_language_resources`.
+pub struct LanguageResourcesPool;
+
+impl LanguageResourcesPool {
+    /// Removes all the language resources from the pool
+    ///
+    /// The memory of the resources is only released once all the engines using them are
+    /// dropped.
+    pub fn shutdown() -> Result<()> {
+        LANGUAGE_RESOURCES_POOL
+            .0
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))?
+            .clear();
+        info!("Language resources pool shut down");
+        Ok(())
+    }
+
+    /// Number of distinct sets of language resources currently in the pool
+    pub fn len() -> Result<usize> {
+        LANGUAGE_RESOURCES_POOL.len()
+    }
+}
+
+impl fmt::Debug for LanguageLayers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LanguageLayers")
+    }
+}
+
+/// Loader of the language resources and entity parsers shared by the processing units
+#[derive(Debug, Clone, Default)]
+pub struct ResourceLoader {
+    limit: Option<usize>,
+    memory_plan: MemoryPlan,
+    language_layers: Option<LanguageLayers>,
+}
+
+impl ResourceLoader {
+    /// Only loads the first `limit` entries of each gazetteer, stems, word clusters and stop
+    /// words file
+    ///
+    /// The resulting resources are incomplete and must not be used in production, but they
+    /// load much faster, which is convenient in test suites.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            memory_plan: MemoryPlan::default(),
+            language_layers: None,
+        }
+    }
+
+    pub(crate) fn with_memory_plan(memory_plan: MemoryPlan) -> Self {
+        Self {
+            limit: None,
+            memory_plan,
+            language_layers: None,
+        }
+    }
+
+    /// Reuses the language resources of the cache when their files are identical, instead of
+    /// loading them again
+    pub(crate) fn sharing_language_layers(mut self, language_layers: LanguageLayers) -> Self {
+        self.language_layers = Some(language_layers);
+        self
+    }
+
+    pub fn load<P: AsRef<Path>, Q: AsRef<Path>, R: AsRef<Path>>(
+        &self,
+        resources_dir: P,
+        builtin_entity_parser_path: Q,
+        custom_entity_parser_path: R,
+        config: &EngineConfig,
+    ) -> Result<Arc<SharedResources>> {
+        let metadata_file_path = resources_dir.as_ref().join("metadata.json");
+        let metadata_file = File::open(&metadata_file_path)?;
+        let metadata: ResourcesMetadata =
+            serde_json::from_reader(metadata_file).with_context(|_| {
+                format!(
+                    "Cannot deserialize resources metadata file '{:?}'",
+                    metadata_file_path
+                )
+            })?;
+        let metadata = match config.missing_resource_policy {
+            MissingResourcePolicy::Fail => metadata,
+            MissingResourcePolicy::Degrade => {
+                metadata.without_missing_resources(resources_dir.as_ref())
+            }
+        };
+        let language_layers = match self.language_layers.as_ref() {
+            Some(language_layers) => Some(language_layers),
+            None if config.share_language_resources => Some(&*LANGUAGE_RESOURCES_POOL),
+            None => None,
+        };
+        let language_layer = self.load_language_layer(
+            &resources_dir,
+            &metadata,
+            config.unicode_normalization,
+            language_layers,
+        )?;
+        let mut builtin_entity_parser: Arc<dyn BuiltinEntityParser> =
+            if config.share_builtin_entity_parser {
+                BuiltinEntityParserPool::get_or_load(builtin_entity_parser_path)?
+            } else {
+                Arc::new(CachingBuiltinEntityParser::from_path(
+                    builtin_entity_parser_path,
+                    self.memory_plan.entity_parser_cache_capacity(),
+                )?)
+            };
+        if config.parallel_builtin_entities {
+            builtin_entity_parser = Arc::new(ParallelBuiltinEntityParser::new(
+                builtin_entity_parser,
+                config.thread_pool.build()?,
+            ));
+        }
+        let builtin_entity_parser =
+            Arc::new(EntityCachingBuiltinEntityParser::new(builtin_entity_parser));
+        let custom_entity_parser = CachingCustomEntityParser::from_path(
+            custom_entity_parser_path,
+            self.memory_plan.entity_parser_cache_capacity(),
+        )?;
+
+        let mut gazetteers = language_layer.gazetteers;
+        gazetteers.extend(self.load_custom_gazetteers(
+            &metadata,
+            language_layer.stemmer.as_ref(),
+            config,
+        )?);
+        let mut word_clusterers = language_layer.word_clusterers;
+        word_clusterers.extend(self.load_custom_word_clusterers(&metadata, config)?);
+
+        Ok(Arc::new(SharedResources {
+            builtin_entity_parser,
+            custom_entity_parser: Arc::new(custom_entity_parser),
+            gazetteers,
+            stemmer: language_layer.stemmer,
+            word_clusterers,
+            stop_words: language_layer.stop_words,
+            missing_resource_policy: config.missing_resource_policy,
+        }))
+    }
+
+    /// Loads the resources stored in the directory of a trained engine
+    pub fn load_engine_resources<P: AsRef<Path>>(
+        &self,
+        engine_dir: P,
+    ) -> Result<Arc<SharedResources>> {
+        let nlu_engine_file = engine_dir.as_ref().join("nlu_engine.json");
+        let model_file = File::open(&nlu_engine_file)
+            .with_context(|_| format!("Could not open nlu engine file {:?}", nlu_engine_file))?;
+        let model: NluEngineModel = serde_json::from_reader(model_file)
+            .with_context(|_| "Could not deserialize nlu engine json file")?;
+        let language = Language::from_str(&model.dataset_metadata.language_code)?;
+        let resources_path = engine_dir
+            .as_ref()
+            .join("resources")
+            .join(language.to_string());
+        let builtin_parser_path = engine_dir.as_ref().join(&model.builtin_entity_parser);
+        let custom_parser_path = engine_dir.as_ref().join(&model.custom_entity_parser);
+        self.load(
+            &resources_path,
+            builtin_parser_path,
+            custom_parser_path,
+            &EngineConfig::default(),
+        )
+    }
+
+    /// Loads the resources stored in the zip archive of a trained engine
+    pub fn load_engine_resources_from_zip<R: io::Read + io::Seek>(
+        &self,
+        reader: R,
+    ) -> Result<Arc<SharedResources>> {
+        let temp_dir = tempfile::Builder::new()
+            .prefix("temp_dir_nlu_resources_")
+            .tempdir()?;
+        let engine_dir = extract_nlu_engine_zip_archive(reader, temp_dir.path())?;
+        self.load_engine_resources(engine_dir)
+    }
+
+    /// Loads the language resources of a pack built with a `ResourcePackBuilder`, which is
+    /// either a directory or a zip archive, after checking the hashes of its files
+    pub fn load_resource_pack<P: AsRef<Path>, Q: AsRef<Path>, R: AsRef<Path>>(
+        &self,
+        pack_path: P,
+        builtin_entity_parser_path: Q,
+        custom_entity_parser_path: R,
+        config: &EngineConfig,
+    ) -> Result<Arc<SharedResources>> {
+        let temp_dir = tempfile::Builder::new()
+            .prefix("temp_dir_nlu_resource_pack_")
+            .tempdir()?;
+        let pack_dir = if pack_path.as_ref().is_dir() {
+            pack_path.as_ref().to_path_buf()
+        } else {
+            let zip_file = File::open(&pack_path)
+                .with_context(|_| format!("Cannot open resource pack {:?}", pack_path.as_ref()))?;
+            extract_nlu_engine_zip_archive(zip_file, temp_dir.path())?
+        };
+        let manifest = ResourcePackManifest::from_dir(&pack_dir)?;
+        manifest.verify(&pack_dir)?;
+        info!(
+            "Loading resource pack '{}' ({}) ...",
+            manifest.name, manifest.version
+        );
+        self.load(
+            &pack_dir,
+            builtin_entity_parser_path,
+            custom_entity_parser_path,
+            config,
+        )
+    }
+
+    fn load_language_layer<P: AsRef<Path>>(
+        &self,
+        resources_dir: &P,
+        metadata: &ResourcesMetadata,
+        unicode_normalization: UnicodeNormalization,
+        language_layers: Option<&LanguageLayers>,
+    ) -> Result<LanguageLayer> {
+        let language_layers = match language_layers {
+            Some(language_layers) => language_layers,
+            None => {
+                return self.read_language_layer(resources_dir, metadata, unicode_normalization)
+            }
+        };
+        let key = self.language_layer_key(resources_dir, metadata, unicode_normalization)?;
+        let mut language_layers = language_layers
+            .0
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))?;
+        if let Some(language_layer) = language_layers.get(&key) {
+            info!("Reusing language resources already loaded");
+            return Ok(language_layer.clone());
+        }
+        let language_layer =
+            self.read_language_layer(resources_dir, metadata, unicode_normalization)?;
+        language_layers.insert(key, language_layer.clone());
+        Ok(language_layer)
+    }
+
+    fn read_language_layer<P: AsRef<Path>>(
+        &self,
+        resources_dir: &P,
+        metadata: &ResourcesMetadata,
+        unicode_normalization: UnicodeNormalization,
+    ) -> Result<LanguageLayer> {
+        let stemmer = self.load_stemmer(resources_dir, metadata, unicode_normalization)?;
+        Ok(LanguageLayer {
+            gazetteers: self.load_gazetteers(
+                resources_dir,
+                metadata,
+                stemmer.as_ref(),
+                unicode_normalization,
+            )?,
+            stemmer,
+            word_clusterers: self.load_word_clusterers(
+                resources_dir,
+                metadata,
+                unicode_normalization,
+            )?,
+            stop_words: self.load_stop_words(resources_dir, metadata, unicode_normalization)?,
+        })
+    }
+
+    /// Hash of everything the loaded language resources depend on, including the content of
+    /// their files
+    fn language_layer_key<P: AsRef<Path>>(
+        &self,
+        resources_dir: &P,
+        metadata: &ResourcesMetadata,
+        unicode_normalization: UnicodeNormalization,
+    ) -> Result<u64> {
+        let mut hasher = DefaultHasher::new();
+        metadata.hash(&mut hasher);
+        unicode_normalization.hash(&mut hasher);
+        self.memory_plan.savings.hash(&mut hasher);
+        for path in metadata.paths(resources_dir.as_ref()) {
+            let mut content = vec![];
+            self.open(&path)
+                .and_then(|mut reader| Ok(reader.read_to_end(&mut content)?))
+                .with_context(|_| format!("Cannot read resource file {:?}", path))?;
+            content.hash(&mut hasher);
+        }
+        Ok(hasher.finish())
+    }
+
+    /// Opens a resource file, keeping only its first lines when a limit is set
+    fn open(&self, path: &Path) -> Result<Box<dyn Read>> {
+        let file = File::open(path)?;
+        if let (Some(limit), false) = (self.limit, is_binary_file(path)) {
+            let mut content = vec![];
+            for line in BufReader::new(file).lines().take(limit) {
+                content.extend_from_slice(line?.as_bytes());
+                content.push(b'\n');
+            }
+            Ok(Box::new(Cursor::new(content)))
+        } else {
+            Ok(Box::new(file))
+        }
+    }
+
+    fn load_stemmer<P: AsRef<Path>>(
+        &self,
+        resources_dir: &P,
+        metadata: &ResourcesMetadata,
+        unicode_normalization: UnicodeNormalization,
+    ) -> Result<Option<Arc<dyn Stemmer>>> {
+        let dictionary_stemmer =
+            self.load_dictionary_stemmer(resources_dir, metadata, unicode_normalization)?;
+        match metadata.stemmer {
+            StemmerBackend::Dictionary => Ok(dictionary_stemmer),
+            StemmerBackend::Snowball => {
+                let language = Language::from_str(&metadata.language)?;
+                let snowball_stemmer: Arc<dyn Stemmer> = Arc::new(SnowballStemmer::new(language)?);
+                info!(
+                    "Using Snowball stemmer for language '{}'",
+                    metadata.language
+                );
+                let stemmer: Arc<dyn Stemmer> = match dictionary_stemmer {
+                    Some(dictionary_stemmer) => {
+                        Arc::new(FallbackStemmer::new(dictionary_stemmer, snowball_stemmer))
+                    }
+                    None => snowball_stemmer,
+                };
+                Ok(Some(stemmer))
+            }
+        }
+    }
+
+    fn load_dictionary_stemmer<P: AsRef<Path>>(
+        &self,
+        resources_dir: &P,
+        metadata: &ResourcesMetadata,
+        unicode_normalization: UnicodeNormalization,
+    ) -> Result<Option<Arc<dyn Stemmer>>> {
+        if let Some(stems) = metadata.stems.as_ref() {
+            let stems_path = ResourcesMetadata::stems_path(resources_dir.as_ref(), stems);
+            info!("Loading stemmer ({:?}) ...", stems_path);
+            let stems_reader = self
+                .open(&stems_path)
+                .with_context(|_| format!("Cannot open stems file {:?}", stems_path))?;
+            let dialect = CsvDialect {
+                unicode_normalization,
+                ..CsvDialect::stems()
+            };
+            let stemmer: Arc<dyn Stemmer> = if is_binary_stems_file(&stems_path) {
+                // Precompiled stems are always interned, and were normalized when compiled
+                Arc::new(
+                    InternedStemmer::from_binary_reader(
+                        BufReader::new(stems_reader),
+                        unicode_normalization,
+                    )
+                    .with_context(|_| format!("Cannot read stems file {:?}", stems_path))?,
+                )
+            } else if self.memory_plan.interned_stems() {
+                Arc::new(
+                    InternedStemmer::from_reader_with_dialect(stems_reader, &dialect)
+                        .with_context(|_| format!("Cannot read stems file {:?}", stems_path))?,
+                )
+            } else {
+                Arc::new(
+                    HashMapStemmer::from_reader_with_dialect(stems_reader, &dialect)
+                        .with_context(|_| format!("Cannot read stems file {:?}", stems_path))?,
+                )
+            };
+            info!("Stemmer loaded");
+            Ok(Some(stemmer))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn load_gazetteers<P: AsRef<Path>>(
+        &self,
+        resources_dir: &P,
+        metadata: &ResourcesMetadata,
+        stemmer: Option<&Arc<dyn Stemmer>>,
+        unicode_normalization: UnicodeNormalization,
+    ) -> Result<HashMap<String, Arc<dyn Gazetteer>>> {
+        let mut gazetteers: HashMap<String, Arc<dyn Gazetteer>> = HashMap::new();
+        let dialect = CsvDialect {
+            unicode_normalization,
+            ..CsvDialect::default()
+        };
+        let stemmed_duplicates = match stemmer {
+            Some(_) if self.memory_plan.deduplicated_stemmed_gazetteers() => {
+                metadata.stemmed_gazetteer_duplicates()
+            }
+            _ => vec![],
+        };
+        if let Some(gazetteer_names) = metadata.gazetteers.as_ref() {
+            for gazetteer_name in gazetteer_names {
+                if stemmed_duplicates.contains(&gazetteer_name.as_str()) {
+                    continue;
+                }
+                let gazetteer_path =
+                    ResourcesMetadata::gazetteer_path(resources_dir.as_ref(), gazetteer_name);
+                info!(
+                    "Loading gazetteer '{}' ({:?}) ...",
+                    gazetteer_name, gazetteer_path
+                );
+                let gazetteer: Arc<dyn Gazetteer> = if is_fst_file(&gazetteer_path) {
+                    let gazetteer =
+                        FstGazetteer::from_path(&gazetteer_path).with_context(|_| {
+                            format!("Cannot map gazetteer file {:?}", gazetteer_path)
+                        })?;
+                    Arc::new(gazetteer)
+                } else if self.memory_plan.fst_gazetteers() {
+                    let file = self.open(&gazetteer_path).with_context(|_| {
+                        format!("Cannot open gazetteer file {:?}", gazetteer_path)
+                    })?;
+                    let values = GazetteerReader::with_dialect(file, &dialect)?;
+                    let mut fst_bytes = vec![];
+                    FstGazetteer::build(values, &mut fst_bytes).with_context(|_| {
+                        format!("Cannot compile gazetteer file {:?}", gazetteer_path)
+                    })?;
+                    Arc::new(FstGazetteer::from_bytes(fst_bytes)?)
+                } else {
+                    let file = self.open(&gazetteer_path).with_context(|_| {
+                        format!("Cannot open gazetteer file {:?}", gazetteer_path)
+                    })?;
+                    HashSetGazetteer::from_reader_shared(file, &dialect).with_context(|_| {
+                        format!("Cannot read gazetteer file {:?}", gazetteer_path)
+                    })?
+                };
+                gazetteers.insert(gazetteer_name.to_string(), gazetteer);
+                info!("Gazetteer '{}' loaded", gazetteer_name);
+            }
+        }
+        if let Some(stemmer) = stemmer {
+            for gazetteer_name in stemmed_duplicates {
+                let stemmed_name = format!("{}{}", gazetteer_name, STEMMED_GAZETTEER_SUFFIX);
+                let stemmed_gazetteer = gazetteers[&stemmed_name].clone();
+                info!(
+                    "Gazetteer '{}' replaced with lookups in '{}'",
+                    gazetteer_name, stemmed_name
+                );
+                gazetteers.insert(
+                    gazetteer_name.to_string(),
+                    Arc::new(StemmingGazetteer::new(stemmed_gazetteer, stemmer.clone())),
+                );
+            }
+        }
+        Ok(gazetteers)
+    }
+
+    fn load_custom_gazetteers(
+        &self,
+        metadata: &ResourcesMetadata,
+        stemmer: Option<&Arc<dyn Stemmer>>,
+        config: &EngineConfig,
+    ) -> Result<HashMap<String, Arc<dyn Gazetteer>>> {
+        let mut gazetteers: HashMap<String, Arc<dyn Gazetteer>> = HashMap::new();
+        if config.custom_gazetteers.is_empty() {
+            return Ok(gazetteers);
+        }
+        let language = Language::from_str(&metadata.language)?;
+        let dialect = CsvDialect {
+            unicode_normalization: config.unicode_normalization,
+            ..CsvDialect::default()
+        };
+        for (gazetteer_name, gazetteer_path) in config.custom_gazetteers.iter() {
             info!(
-                "Loading gazetteer '{}' ({:?}) ...",
+                "Loading custom gazetteer '{}' ({:?}) ...",
                 gazetteer_name, gazetteer_path
             );
-            let file = File::open(&gazetteer_path)
+            if metadata
+                .gazetteers
+                .iter()
+                .flatten()
+                .any(|name| name == gazetteer_name)
+            {
+                warn!(
+                    "Custom gazetteer '{}' replaces the built-in one",
+                    gazetteer_name
+                );
+            }
+            let file = self
+                .open(gazetteer_path)
                 .with_context(|_| format!("Cannot open gazetteer file {:?}", gazetteer_path))?;
-            let gazetteer = HashSetGazetteer::from_reader(file)
-                .with_context(|_| format!("Cannot read gazetteer file {:?}", gazetteer_path))?;
+            let gazetteer = HashSetGazetteer::from_reader_with_language(
+                file,
+                language,
+                stemmer.map(|stemmer| &**stemmer),
+                &dialect,
+            )
+            .with_context(|_| format!("Cannot read gazetteer file {:?}", gazetteer_path))?;
             gazetteers.insert(gazetteer_name.to_string(), Arc::new(gazetteer));
-            info!("Gazetteer '{}' loaded", gazetteer_name);
+            info!("Custom gazetteer '{}' loaded", gazetteer_name);
         }
+        Ok(gazetteers)
+    }
+
+    fn load_word_clusterers<P: AsRef<Path>>(
+        &self,
+        resources_dir: &P,
+        metadata: &ResourcesMetadata,
+        unicode_normalization: UnicodeNormalization,
+    ) -> Result<HashMap<String, Arc<dyn WordClusterer>>> {
+        let mut word_clusterers: HashMap<String, Arc<dyn WordClusterer>> = HashMap::new();
+        if let Some(word_clusters) = metadata.word_clusters.as_ref() {
+            for clusters_name in word_clusters {
+                let clusters_path =
+                    ResourcesMetadata::word_clusters_path(resources_dir.as_ref(), clusters_name);
+                info!(
+                    "Loading word clusters '{}' ({:?}) ...",
+                    clusters_name, clusters_path
+                );
+                let word_clusters_reader = self.open(&clusters_path).with_context(|_| {
+                    format!("Cannot open word clusters file {:?}", clusters_path)
+                })?;
+                let dialect = CsvDialect {
+                    unicode_normalization,
+                    ..CsvDialect::word_clusters()
+                };
+                let word_clusterer =
+                    HashMapWordClusterer::from_reader_with_dialect(word_clusters_reader, &dialect)
+                        .with_context(|_| {
+                            format!("Cannot read word clusters file {:?}", clusters_path)
+                        })?;
+                word_clusterers.insert(clusters_name.to_string(), Arc::new(word_clusterer));
+                info!("Word clusters '{}' loaded", clusters_name);
+            }
+        }
+        Ok(word_clusterers)
     }
-    Ok(gazetteers)
-}
 
-fn load_word_clusterers<P: AsRef<Path>>(
-    resources_dir: &P,
-    metadata: &ResourcesMetadata,
-) -> Result<HashMap<String, Arc<dyn WordClusterer>>> {
-    let mut word_clusterers: HashMap<String, Arc<dyn WordClusterer>> = HashMap::new();
-    if let Some(word_clusters) = metadata.word_clusters.as_ref() {
-        let word_clusters_directory = resources_dir.as_ref().join("word_clusters");
-        for clusters_name in word_clusters {
-            let clusters_path = word_clusters_directory
-                .join(clusters_name.clone())
-                .with_extension("txt");
-            ;
+    fn load_custom_word_clusterers(
+        &self,
+        metadata: &ResourcesMetadata,
+        config: &EngineConfig,
+    ) -> Result<HashMap<String, Arc<dyn WordClusterer>>> {
+        let mut word_clusterers: HashMap<String, Arc<dyn WordClusterer>> = HashMap::new();
+        let dialect = CsvDialect {
+            unicode_normalization: config.unicode_normalization,
+            ..CsvDialect::word_clusters()
+        };
+        for (clusters_name, clusters_path) in config.custom_word_clusters.iter() {
             info!(
-                "Loading word clusters '{}' ({:?}) ...",
+                "Loading custom word clusters '{}' ({:?}) ...",
                 clusters_name, clusters_path
             );
-            let word_clusters_reader = File::open(&clusters_path)
+            if metadata
+                .word_clusters
+                .iter()
+                .flatten()
+                .any(|name| name == clusters_name)
+            {
+                warn!(
+                    "Custom word clusters '{}' replace the built-in ones",
+                    clusters_name
+                );
+            }
+            let word_clusters_reader = self
+                .open(clusters_path)
                 .with_context(|_| format!("Cannot open word clusters file {:?}", clusters_path))?;
-            let word_clusterer = HashMapWordClusterer::from_reader(word_clusters_reader)
-                .with_context(|_| format!("Cannot read word clusters file {:?}", clusters_path))?;
+            let word_clusterer =
+                HashMapWordClusterer::from_reader_with_dialect(word_clusters_reader, &dialect)
+                    .with_context(|_| {
+                        format!("Cannot read word clusters file {:?}", clusters_path)
+                    })?;
             word_clusterers.insert(clusters_name.to_string(), Arc::new(word_clusterer));
-            info!("Word clusters '{}' loaded", clusters_name);
+            info!("Custom word clusters '{}' loaded", clusters_name);
         }
+        Ok(word_clusterers)
     }
-    Ok(word_clusterers)
-}
 
-fn load_stop_words<P: AsRef<Path>>(
-    resources_dir: &P,
-    metadata: &ResourcesMetadata,
-) -> Result<HashSet<String>> {
-    if let Some(stop_words_name) = metadata.stop_words.as_ref() {
-        let stop_words_path = resources_dir
-            .as_ref()
-            .join(stop_words_name)
-            .with_extension("txt");
-        info!("Loading stop words ({:?}) ...", stop_words_path);
-        let file = File::open(&stop_words_path)
-            .with_context(|_| format!("Cannot open word stop words file {:?}", stop_words_path))?;
-        let reader = BufReader::new(file);
-        let mut stop_words = HashSet::<String>::new();
-        for line in reader.lines() {
-            let stop_word = line?;
-            if !stop_word.is_empty() {
-                stop_words.insert(stop_word);
+    fn load_stop_words<P: AsRef<Path>>(
+        &self,
+        resources_dir: &P,
+        metadata: &ResourcesMetadata,
+        unicode_normalization: UnicodeNormalization,
+    ) -> Result<HashSet<String>> {
+        if let Some(stop_words_name) = metadata.stop_words.as_ref() {
+            let stop_words_path =
+                ResourcesMetadata::stop_words_path(resources_dir.as_ref(), stop_words_name);
+            info!("Loading stop words ({:?}) ...", stop_words_path);
+            let file = self.open(&stop_words_path).with_context(|_| {
+                format!("Cannot open word stop words file {:?}", stop_words_path)
+            })?;
+            let reader = BufReader::new(file);
+            let mut stop_words = HashSet::<String>::new();
+            for line in reader.lines() {
+                let stop_word = line?;
+                if !stop_word.is_empty() {
+                    stop_words.insert(unicode_normalization.apply(&stop_word).into_owned());
+                }
             }
+            info!("Stop words loaded");
+            Ok(stop_words)
+        } else {
+            Ok(HashSet::new())
         }
-        info!("Stop words loaded");
-        Ok(stop_words)
-    } else {
-        Ok(HashSet::new())
+    }
+}
+
+/// FST gazetteers are binary files which are memory-mapped, and thus never truncated to the
+/// loader limit
+fn is_fst_file(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension == FST_GAZETTEER_EXTENSION)
+}
+
+fn is_binary_stems_file(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension == BINARY_STEMS_EXTENSION)
+}
+
+/// Whether the file was modified before the other one, which is not the case when any of them
+/// is missing
+fn is_modified_before(path: &Path, other_path: &Path) -> bool {
+    let modified = |path: &Path| path.metadata().and_then(|metadata| metadata.modified());
+    match (modified(path), modified(other_path)) {
+        (Ok(modified), Ok(other_modified)) => modified < other_modified,
+        _ => false,
+    }
+}
+
+/// Whether the resource file is precompiled, in which case it cannot be truncated
+fn is_binary_file(path: &Path) -> bool {
+    is_fst_file(path) || is_binary_stems_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::memory_budget::MemorySaving;
+    use crate::resources::pack::ResourcePackBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_load_resources_with_limit() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+
+        // When
+        let resources = ResourceLoader::default()
+            .load_engine_resources(&engine_dir)
+            .unwrap();
+        let limited_resources = ResourceLoader::with_limit(3)
+            .load_engine_resources(&engine_dir)
+            .unwrap();
+
+        // Then
+        let gazetteer = &resources.gazetteers["top_10000_words_stemmed"];
+        let limited_gazetteer = &limited_resources.gazetteers["top_10000_words_stemmed"];
+        assert!(gazetteer.contains("aaa"));
+        assert!(gazetteer.contains("changelog"));
+        assert!(limited_gazetteer.contains("aaa"));
+        assert!(!limited_gazetteer.contains("changelog"));
+        assert_eq!(3, limited_resources.stop_words.len());
+        assert!(resources.stop_words.len() > 3);
+    }
+
+    #[test]
+    fn test_load_fst_gazetteer() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs_extra::dir::copy(
+            &engine_dir,
+            temp_dir.path(),
+            &fs_extra::dir::CopyOptions::new(),
+        )
+        .unwrap();
+        let engine_dir = temp_dir.path().join("nlu_engine_beverage");
+        let gazetteer_path = engine_dir
+            .join("resources")
+            .join("en")
+            .join("gazetteers")
+            .join("top_10000_words_stemmed");
+        let values =
+            GazetteerReader::new(File::open(gazetteer_path.with_extension("txt")).unwrap())
+                .unwrap();
+        let fst_file = File::create(gazetteer_path.with_extension("fst")).unwrap();
+        FstGazetteer::build(values, fst_file).unwrap();
+        std::fs::remove_file(gazetteer_path.with_extension("txt")).unwrap();
+
+        // When
+        let resources = ResourceLoader::default()
+            .load_engine_resources(&engine_dir)
+            .unwrap();
+        let limited_resources = ResourceLoader::with_limit(3)
+            .load_engine_resources(&engine_dir)
+            .unwrap();
+
+        // Then
+        assert!(resources.gazetteers["top_10000_words_stemmed"].contains("changelog"));
+        assert!(limited_resources.gazetteers["top_10000_words_stemmed"].contains("changelog"));
+    }
+
+    #[test]
+    fn test_load_gazetteers_with_memory_plan() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs_extra::dir::copy(
+            &engine_dir,
+            temp_dir.path(),
+            &fs_extra::dir::CopyOptions::new(),
+        )
+        .unwrap();
+        let engine_dir = temp_dir.path().join("nlu_engine_beverage");
+        let resources_dir = engine_dir.join("resources").join("en");
+        std::fs::write(
+            resources_dir.join("gazetteers").join("top_10000_words.txt"),
+            "aaa\nchangelog\n",
+        )
+        .unwrap();
+        let metadata_path = resources_dir.join("metadata.json");
+        let mut metadata: ResourcesMetadata =
+            serde_json::from_reader(File::open(&metadata_path).unwrap()).unwrap();
+        metadata.gazetteers = Some(vec![
+            "top_10000_words".to_string(),
+            "top_10000_words_stemmed".to_string(),
+        ]);
+        serde_json::to_writer(File::create(&metadata_path).unwrap(), &metadata).unwrap();
+        let memory_plan = MemoryPlan {
+            savings: vec![
+                MemorySaving::FstGazetteers,
+                MemorySaving::DeduplicatedStemmedGazetteers,
+            ],
+        };
+
+        // When
+        let resources = ResourceLoader::with_memory_plan(memory_plan)
+            .load_engine_resources(&engine_dir)
+            .unwrap();
+
+        // Then
+        let gazetteer = &resources.gazetteers["top_10000_words"];
+        let stemmed_gazetteer = &resources.gazetteers["top_10000_words_stemmed"];
+        assert!(gazetteer.contains("changelog"));
+        assert!(!gazetteer.contains("unknownword"));
+        assert!(stemmed_gazetteer.contains("changelog"));
+        assert!(!stemmed_gazetteer.contains("unknownword"));
+    }
+
+    #[test]
+    fn test_load_custom_gazetteers() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let temp_dir = tempfile::tempdir().unwrap();
+        let gazetteer_path = temp_dir.path().join("products.txt");
+        std::fs::write(&gazetteer_path, "Super Brew 3000\nCafé Deluxe\n").unwrap();
+        let config = EngineConfig {
+            custom_gazetteers: vec![("products".to_string(), gazetteer_path)]
+                .into_iter()
+                .collect(),
+            ..EngineConfig::default()
+        };
+        let model_file = File::open(engine_dir.join("nlu_engine.json")).unwrap();
+        let model: NluEngineModel = serde_json::from_reader(model_file).unwrap();
+
+        // When
+        let resources = ResourceLoader::default()
+            .load(
+                engine_dir.join("resources").join("en"),
+                engine_dir.join(&model.builtin_entity_parser),
+                engine_dir.join(&model.custom_entity_parser),
+                &config,
+            )
+            .unwrap();
+
+        // Then
+        let gazetteer = &resources.gazetteers["products"];
+        assert!(gazetteer.contains("super brew 3000"));
+        assert!(gazetteer.contains("cafe delux"));
+        assert!(!gazetteer.contains("cafe deluxe"));
+        assert!(resources.gazetteers["top_10000_words_stemmed"].contains("changelog"));
+    }
+
+    #[test]
+    fn test_load_custom_word_clusters() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let temp_dir = tempfile::tempdir().unwrap();
+        let clusters_path = temp_dir.path().join("brown_clusters_fr.txt");
+        std::fs::write(&clusters_path, "café\t100\nthé\t101\n").unwrap();
+        let config = EngineConfig {
+            custom_word_clusters: vec![("brown_clusters_fr".to_string(), clusters_path)]
+                .into_iter()
+                .collect(),
+            ..EngineConfig::default()
+        };
+        let model_file = File::open(engine_dir.join("nlu_engine.json")).unwrap();
+        let model: NluEngineModel = serde_json::from_reader(model_file).unwrap();
+
+        // When
+        let resources = ResourceLoader::default()
+            .load(
+                engine_dir.join("resources").join("en"),
+                engine_dir.join(&model.builtin_entity_parser),
+                engine_dir.join(&model.custom_entity_parser),
+                &config,
+            )
+            .unwrap();
+
+        // Then
+        let word_clusterer = &resources.word_clusterers["brown_clusters_fr"];
+        assert_eq!(Some("100".to_string()), word_clusterer.get_cluster("café"));
+        assert_eq!(None, word_clusterer.get_cluster("lait"));
+    }
+
+    #[test]
+    fn test_load_resources_from_zip() {
+        // Given
+        let zip_path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage.zip");
+        let zip_file = File::open(zip_path).unwrap();
+
+        // When
+        let resources = ResourceLoader::default()
+            .load_engine_resources_from_zip(zip_file)
+            .unwrap();
+
+        // Then
+        assert!(resources.gazetteers["top_10000_words_stemmed"].contains("changelog"));
+        assert!(resources.stemmer.is_some());
+        assert!(!resources.stop_words.is_empty());
+    }
+
+    #[test]
+    fn test_load_resource_pack() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let resources_dir = engine_dir.join("resources").join("en");
+        let temp_dir = tempfile::tempdir().unwrap();
+        let pack_path = temp_dir.path().join("beverage_pack.zip");
+        ResourcePackBuilder::new("beverage", "1.0.0", Language::EN)
+            .gazetteer(
+                "top_10000_words_stemmed",
+                resources_dir
+                    .join("gazetteers")
+                    .join("top_10000_words_stemmed.txt"),
+            )
+            .stems(resources_dir.join("stemming").join("stems.txt"))
+            .stop_words(resources_dir.join("stop_words.txt"))
+            .precompile(true)
+            .build_zip(&pack_path)
+            .unwrap();
+        let model_file = File::open(engine_dir.join("nlu_engine.json")).unwrap();
+        let model: NluEngineModel = serde_json::from_reader(model_file).unwrap();
+
+        // When
+        let resources = ResourceLoader::default()
+            .load_resource_pack(
+                &pack_path,
+                engine_dir.join(&model.builtin_entity_parser),
+                engine_dir.join(&model.custom_entity_parser),
+                &EngineConfig::default(),
+            )
+            .unwrap();
+
+        // Then
+        assert!(resources.gazetteers["top_10000_words_stemmed"].contains("changelog"));
+        assert!(resources.stemmer.is_some());
+        assert!(!resources.stop_words.is_empty());
+    }
+
+    #[test]
+    fn test_language_resources_pool() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let config = EngineConfig {
+            share_language_resources: true,
+            ..EngineConfig::default()
+        };
+        let load_resources = || {
+            let model_file = File::open(engine_dir.join("nlu_engine.json")).unwrap();
+            let model: NluEngineModel = serde_json::from_reader(model_file).unwrap();
+            ResourceLoader::default()
+                .load(
+                    engine_dir.join("resources").join("en"),
+                    engine_dir.join(&model.builtin_entity_parser),
+                    engine_dir.join(&model.custom_entity_parser),
+                    &config,
+                )
+                .unwrap()
+        };
+
+        // When
+        let resources = load_resources();
+        let other_resources = load_resources();
+
+        // Then
+        assert!(LanguageResourcesPool::len().unwrap() >= 1);
+        assert!(Arc::ptr_eq(
+            resources.stemmer.as_ref().unwrap(),
+            other_resources.stemmer.as_ref().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_share_language_layers() {
+        // Given
+        let engine_dir = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let language_layers = LanguageLayers::default();
+        let loader = ResourceLoader::default().sharing_language_layers(language_layers.clone());
+
+        // When
+        let resources = loader.load_engine_resources(&engine_dir).unwrap();
+        let other_resources = loader.load_engine_resources(&engine_dir).unwrap();
+
+        // Then
+        assert_eq!(1, language_layers.len().unwrap());
+        assert!(Arc::ptr_eq(
+            resources.stemmer.as_ref().unwrap(),
+            other_resources.stemmer.as_ref().unwrap()
+        ));
+        assert!(!Arc::ptr_eq(
+            &resources.custom_entity_parser,
+            &other_resources.custom_entity_parser
+        ));
     }
 }
@@ -1,15 +1,37 @@
+pub mod dialect;
 pub mod gazetteer;
 pub mod loading;
+pub mod pack;
 pub mod stemmer;
 pub mod word_clusterer;
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use failure::bail;
+use log::warn;
+
 use self::gazetteer::Gazetteer;
 use self::stemmer::Stemmer;
 use self::word_clusterer::WordClusterer;
 use super::entity_parser::{BuiltinEntityParser, CustomEntityParser};
+use crate::errors::*;
+
+/// Behavior when a language resource used by a model is missing, which happens when the
+/// engine is shipped with a trimmed build of the resources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingResourcePolicy {
+    /// Loading fails
+    Fail,
+    /// The features relying on the missing resource are disabled, and a warning is logged
+    Degrade,
+}
+
+impl Default for MissingResourcePolicy {
+    fn default() -> Self {
+        MissingResourcePolicy::Fail
+    }
+}
 
 pub struct SharedResources {
     pub builtin_entity_parser: Arc<dyn BuiltinEntityParser>,
@@ -18,4 +40,29 @@ pub struct SharedResources {
     pub stemmer: Option<Arc<dyn Stemmer>>,
     pub word_clusterers: HashMap<String, Arc<dyn WordClusterer>>,
     pub stop_words: HashSet<String>,
+    pub missing_resource_policy: MissingResourcePolicy,
+}
+
+impl SharedResources {
+    /// Returns the resource if it was found, and otherwise either fails or returns `None` when
+    /// the features using it can be disabled
+    pub(crate) fn check_resource<T>(
+        &self,
+        resource: Option<T>,
+        description: &str,
+    ) -> Result<Option<T>> {
+        match (resource, self.missing_resource_policy) {
+            (Some(resource), _) => Ok(Some(resource)),
+            (None, MissingResourcePolicy::Fail) => {
+                bail!("Cannot find {} in shared resources", description)
+            }
+            (None, MissingResourcePolicy::Degrade) => {
+                warn!(
+                    "Cannot find {} in shared resources, the features using it are disabled",
+                    description
+                );
+                Ok(None)
+            }
+        }
+    }
 }
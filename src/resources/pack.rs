@@ -0,0 +1,481 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use failure::{bail, format_err, ResultExt};
+use log::info;
+use serde::{Deserialize, Serialize};
+use snips_nlu_ontology::Language;
+use zip::write::{FileOptions, ZipWriter};
+use zip::CompressionMethod;
+
+use crate::errors::*;
+use crate::models::fingerprint::digest;
+use crate::resources::dialect::CsvDialect;
+use crate::resources::gazetteer::{FstGazetteer, GazetteerReader, FST_GAZETTEER_EXTENSION};
+use crate::resources::loading::ResourcesMetadata;
+use crate::resources::stemmer::{InternedStemmer, StemmerBackend, BINARY_STEMS_EXTENSION};
+use crate::utils::to_hex;
+
+pub const RESOURCE_PACK_MANIFEST_FILENAME: &str = "manifest.json";
+
+/// Version of the layout of the resource packs, which is increased whenever the packs built by
+/// this version of the library cannot be loaded by the previous ones
+pub const RESOURCE_PACK_FORMAT_VERSION: u32 = 1;
+
+/// Description of a resource pack, stored in its `manifest.json` file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcePackManifest {
+    pub format_version: u32,
+    pub name: String,
+    /// Version of the content of the pack, chosen by its maintainers
+    pub version: String,
+    pub language: String,
+    /// SHA-256 hash, in hexadecimal, of each file of the pack, indexed by its path relative to
+    /// the pack directory with '/' separators
+    pub files: BTreeMap<String, String>,
+}
+
+impl ResourcePackManifest {
+    pub fn from_dir<P: AsRef<Path>>(pack_dir: P) -> Result<Self> {
+        let manifest_path = pack_dir.as_ref().join(RESOURCE_PACK_MANIFEST_FILENAME);
+        let manifest_file = File::open(&manifest_path)
+            .with_context(|_| format!("Cannot open resource pack manifest {:?}", manifest_path))?;
+        let manifest: Self = serde_json::from_reader(manifest_file).with_context(|_| {
+            format!(
+                "Cannot deserialize resource pack manifest {:?}",
+                manifest_path
+            )
+        })?;
+        if manifest.format_version > RESOURCE_PACK_FORMAT_VERSION {
+            bail!(
+                "Resource pack '{}' has format version {}, which is not supported by this \
+                 version of the library (at most {})",
+                manifest.name,
+                manifest.format_version,
+                RESOURCE_PACK_FORMAT_VERSION
+            );
+        }
+        Ok(manifest)
+    }
+
+    /// Checks that all the files of the pack are present and were not modified since it was
+    /// built
+    pub fn verify<P: AsRef<Path>>(&self, pack_dir: P) -> Result<()> {
+        for (relative_path, expected_hash) in self.files.iter() {
+            let path = pack_dir.as_ref().join(relative_path);
+            let mut file = File::open(&path).with_context(|_| {
+                format!(
+                    "Cannot open file '{}' of resource pack '{}'",
+                    relative_path, self.name
+                )
+            })?;
+            if to_hex(&digest(&mut file)?) != *expected_hash {
+                bail!(
+                    "File '{}' of resource pack '{}' does not match its manifest hash",
+                    relative_path,
+                    self.name
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builder of a resource pack, i.e. a language resources directory, loadable with
+/// `ResourceLoader::load_resource_pack`, along with a manifest listing the hashes of its files
+///
+/// The raw word lists and lexeme files are parsed with the provided dialect, so that malformed
+/// files are rejected when the pack is built rather than when an engine loads it, and they
+/// are rewritten in the format expected by the loader.
+pub struct ResourcePackBuilder {
+    name: String,
+    version: String,
+    language: Language,
+    dialect: CsvDialect,
+    gazetteers: Vec<(String, PathBuf)>,
+    word_clusters: Vec<(String, PathBuf)>,
+    stems: Option<PathBuf>,
+    stop_words: Option<PathBuf>,
+    stemmer: StemmerBackend,
+    precompile: bool,
+}
+
+impl ResourcePackBuilder {
+    pub fn new<S: Into<String>, V: Into<String>>(name: S, version: V, language: Language) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            language,
+            dialect: CsvDialect::default(),
+            gazetteers: vec![],
+            word_clusters: vec![],
+            stems: None,
+            stop_words: None,
+            stemmer: StemmerBackend::default(),
+            precompile: false,
+        }
+    }
+
+    /// Format of the input files, whose delimiter only applies to the stems and word clusters
+    pub fn dialect(mut self, dialect: CsvDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Adds a word list, with one value per line
+    pub fn gazetteer<S: Into<String>, P: Into<PathBuf>>(mut self, name: S, path: P) -> Self {
+        self.gazetteers.push((name.into(), path.into()));
+        self
+    }
+
+    /// Adds word clusters, with a word and its cluster per line
+    pub fn word_clusters<S: Into<String>, P: Into<PathBuf>>(mut self, name: S, path: P) -> Self {
+        self.word_clusters.push((name.into(), path.into()));
+        self
+    }
+
+    /// Sets the lexeme file, in which each line lists a stem followed by its inflections
+    pub fn stems<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.stems = Some(path.into());
+        self
+    }
+
+    /// Sets the stop words, with one word per line
+    pub fn stop_words<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.stop_words = Some(path.into());
+        self
+    }
+
+    pub fn stemmer(mut self, stemmer: StemmerBackend) -> Self {
+        self.stemmer = stemmer;
+        self
+    }
+
+    /// Stores the gazetteers in the FST format and the stems in the binary format, which are
+    /// more compact and load faster than the text files
+    ///
+    /// The values are then stored in the normalization form of the dialect, which must be the
+    /// one used by the engines loading the pack.
+    pub fn precompile(mut self, precompile: bool) -> Self {
+        self.precompile = precompile;
+        self
+    }
+
+    /// Builds the pack in the provided directory, which is created if needed, and returns its
+    /// manifest
+    pub fn build<P: AsRef<Path>>(&self, pack_dir: P) -> Result<ResourcePackManifest> {
+        let pack_dir = pack_dir.as_ref();
+        if self.version.trim().is_empty() {
+            bail!("Resource pack '{}' must have a version", self.name);
+        }
+        fs::create_dir_all(pack_dir)
+            .with_context(|_| format!("Cannot create resource pack directory {:?}", pack_dir))?;
+        let mut files = vec![];
+
+        for (name, path) in self.gazetteers.iter() {
+            check_resource_name(name)?;
+            files.push(self.write_gazetteer(pack_dir, name, path)?);
+        }
+        for (name, path) in self.word_clusters.iter() {
+            check_resource_name(name)?;
+            files.push(self.write_word_clusters(pack_dir, name, path)?);
+        }
+        if let Some(path) = self.stems.as_ref() {
+            files.push(self.write_stems(pack_dir, path)?);
+        }
+        if let Some(path) = self.stop_words.as_ref() {
+            files.push(self.write_stop_words(pack_dir, path)?);
+        }
+
+        let metadata = ResourcesMetadata {
+            language: self.language.to_string(),
+            gazetteers: Some(
+                self.gazetteers
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .collect(),
+            ),
+            word_clusters: Some(
+                self.word_clusters
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .collect(),
+            ),
+            stems: self.stems.as_ref().map(|_| "stems".to_string()),
+            stop_words: self.stop_words.as_ref().map(|_| "stop_words".to_string()),
+            stemmer: self.stemmer,
+        };
+        let metadata_file = File::create(pack_dir.join("metadata.json"))?;
+        serde_json::to_writer_pretty(metadata_file, &metadata)?;
+        files.push("metadata.json".to_string());
+
+        let mut manifest = ResourcePackManifest {
+            format_version: RESOURCE_PACK_FORMAT_VERSION,
+            name: self.name.clone(),
+            version: self.version.clone(),
+            language: self.language.to_string(),
+            files: BTreeMap::new(),
+        };
+        for relative_path in files {
+            let mut file = File::open(pack_dir.join(&relative_path))?;
+            manifest
+                .files
+                .insert(relative_path, to_hex(&digest(&mut file)?));
+        }
+        let manifest_file = File::create(pack_dir.join(RESOURCE_PACK_MANIFEST_FILENAME))?;
+        serde_json::to_writer_pretty(manifest_file, &manifest)?;
+        info!(
+            "Resource pack '{}' ({}) built in {:?}",
+            manifest.name, manifest.version, pack_dir
+        );
+        Ok(manifest)
+    }
+
+    /// Builds the pack and stores it in a zip archive, in a directory named after the pack
+    pub fn build_zip<P: AsRef<Path>>(&self, zip_path: P) -> Result<ResourcePackManifest> {
+        let temp_dir = tempfile::Builder::new()
+            .prefix("temp_dir_nlu_resource_pack_")
+            .tempdir()?;
+        let manifest = self.build(temp_dir.path())?;
+        let zip_file = File::create(zip_path.as_ref())
+            .with_context(|_| format!("Cannot create zip archive {:?}", zip_path.as_ref()))?;
+        let mut zip_writer = ZipWriter::new(BufWriter::new(zip_file));
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        let archived_files = manifest
+            .files
+            .keys()
+            .map(|relative_path| &**relative_path)
+            .chain(Some(RESOURCE_PACK_MANIFEST_FILENAME));
+        for relative_path in archived_files {
+            zip_writer.start_file(format!("{}/{}", manifest.name, relative_path), options)?;
+            zip_writer.write_all(&fs::read(temp_dir.path().join(relative_path))?)?;
+        }
+        zip_writer.finish()?;
+        Ok(manifest)
+    }
+
+    fn write_gazetteer(&self, pack_dir: &Path, name: &str, path: &Path) -> Result<String> {
+        let input = open_input(path)?;
+        let values = GazetteerReader::with_dialect(input, &self.dialect)?;
+        let relative_path = if self.precompile {
+            let relative_path = format!("gazetteers/{}.{}", name, FST_GAZETTEER_EXTENSION);
+            let writer = create_output(pack_dir, &relative_path)?;
+            FstGazetteer::build(values, writer)
+                .with_context(|_| format!("Invalid gazetteer file {:?}", path))?;
+            relative_path
+        } else {
+            let relative_path = format!("gazetteers/{}.txt", name);
+            let mut writer = create_output(pack_dir, &relative_path)?;
+            for value in values {
+                let value = value.with_context(|_| format!("Invalid gazetteer file {:?}", path))?;
+                writeln!(writer, "{}", value)?;
+            }
+            relative_path
+        };
+        Ok(relative_path)
+    }
+
+    fn write_word_clusters(&self, pack_dir: &Path, name: &str, path: &Path) -> Result<String> {
+        let relative_path = format!("word_clusters/{}.txt", name);
+        let mut writer = create_output(pack_dir, &relative_path)?;
+        let mut csv_reader = self.dialect.csv_reader(open_input(path)?, true)?;
+        for record in csv_reader.records() {
+            let record =
+                record.with_context(|_| format!("Invalid word clusters file {:?}", path))?;
+            let line = record.position().map(|p| p.line()).unwrap_or(0);
+            if record.len() != 2 {
+                bail!(
+                    "Expected a word and its cluster at line {} of {:?}, found {} fields",
+                    line,
+                    path,
+                    record.len()
+                );
+            }
+            let word = self.dialect.normalize(&record[0]);
+            check_field(&word, '\t', line, path)?;
+            writeln!(writer, "{}\t{}", word, &record[1])?;
+        }
+        Ok(relative_path)
+    }
+
+    fn write_stems(&self, pack_dir: &Path, path: &Path) -> Result<String> {
+        if self.precompile {
+            let relative_path = format!("stemming/stems.{}", BINARY_STEMS_EXTENSION);
+            let stemmer =
+                InternedStemmer::from_reader_with_dialect(open_input(path)?, &self.dialect)
+                    .with_context(|_| format!("Invalid stems file {:?}", path))?;
+            stemmer.write_binary(
+                create_output(pack_dir, &relative_path)?,
+                self.dialect.unicode_normalization,
+            )?;
+            return Ok(relative_path);
+        }
+        let relative_path = "stemming/stems.txt".to_string();
+        let mut writer = create_output(pack_dir, &relative_path)?;
+        let mut csv_reader = self.dialect.csv_reader(open_input(path)?, true)?;
+        for record in csv_reader.records() {
+            let record = record.with_context(|_| format!("Invalid stems file {:?}", path))?;
+            let line = record.position().map(|p| p.line()).unwrap_or(0);
+            if record.len() < 2 {
+                bail!(
+                    "Expected a stem followed by its inflections at line {} of {:?}",
+                    line,
+                    path
+                );
+            }
+            let lexemes = record
+                .iter()
+                .map(|lexeme| {
+                    let lexeme = self.dialect.normalize(lexeme);
+                    check_field(&lexeme, ',', line, path)?;
+                    Ok(lexeme)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            writeln!(writer, "{}", lexemes.join(","))?;
+        }
+        Ok(relative_path)
+    }
+
+    fn write_stop_words(&self, pack_dir: &Path, path: &Path) -> Result<String> {
+        let relative_path = "stop_words.txt".to_string();
+        let mut writer = create_output(pack_dir, &relative_path)?;
+        for (line, stop_word) in self.dialect.lines(open_input(path)?)? {
+            let stop_word =
+                stop_word.with_context(|_| format!("Invalid line {} of {:?}", line, path))?;
+            writeln!(writer, "{}", stop_word)?;
+        }
+        Ok(relative_path)
+    }
+}
+
+fn open_input(path: &Path) -> Result<File> {
+    Ok(File::open(path).with_context(|_| format!("Cannot open resource file {:?}", path))?)
+}
+
+fn create_output(pack_dir: &Path, relative_path: &str) -> Result<BufWriter<File>> {
+    let path = pack_dir.join(relative_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = File::create(&path).with_context(|_| format!("Cannot create file {:?}", path))?;
+    Ok(BufWriter::new(file))
+}
+
+/// Resource names are used as file names in the pack
+fn check_resource_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.starts_with('.') || name.contains(|c| c == '/' || c == '\\') {
+        return Err(format_err!("Invalid resource name '{}'", name));
+    }
+    Ok(())
+}
+
+/// Fields containing the delimiter of the rewritten file would be split when loaded
+fn check_field(field: &str, delimiter: char, line: u64, path: &Path) -> Result<()> {
+    if field.contains(delimiter) {
+        bail!(
+            "Entry '{}' at line {} of {:?} contains the delimiter {:?}",
+            field,
+            line,
+            path,
+            delimiter
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_raw_resources(dir: &Path) -> ResourcePackBuilder {
+        fs::write(dir.join("cities.txt"), "Paris\n# capitals\nBerlin\n").unwrap();
+        fs::write(dir.join("lexemes.csv"), "go;goes;went\nbe;is;was\n").unwrap();
+        fs::write(dir.join("clusters.csv"), "paris;100\nberlin;101\n").unwrap();
+        fs::write(dir.join("stop_words.txt"), "the\na\n").unwrap();
+        let dialect = CsvDialect {
+            delimiter: b';',
+            comment: Some(b'#'),
+            ..CsvDialect::default()
+        };
+        ResourcePackBuilder::new("travel", "1.0.0", Language::EN)
+            .dialect(dialect)
+            .gazetteer("cities", dir.join("cities.txt"))
+            .word_clusters("travel_clusters", dir.join("clusters.csv"))
+            .stems(dir.join("lexemes.csv"))
+            .stop_words(dir.join("stop_words.txt"))
+    }
+
+    #[test]
+    fn test_build_resource_pack() {
+        // Given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let builder = write_raw_resources(temp_dir.path());
+        let pack_dir = temp_dir.path().join("pack");
+
+        // When
+        let manifest = builder.build(&pack_dir).unwrap();
+
+        // Then
+        let expected_files = vec![
+            "gazetteers/cities.txt",
+            "metadata.json",
+            "stemming/stems.txt",
+            "stop_words.txt",
+            "word_clusters/travel_clusters.txt",
+        ];
+        assert_eq!(
+            expected_files,
+            manifest.files.keys().map(|f| &**f).collect::<Vec<_>>()
+        );
+        assert_eq!(manifest, ResourcePackManifest::from_dir(&pack_dir).unwrap());
+        assert!(manifest.verify(&pack_dir).is_ok());
+        assert_eq!(
+            "go,goes,went\nbe,is,was\n",
+            fs::read_to_string(pack_dir.join("stemming").join("stems.txt")).unwrap()
+        );
+        assert_eq!(
+            "Paris\nBerlin\n",
+            fs::read_to_string(pack_dir.join("gazetteers").join("cities.txt")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verify_tampered_resource_pack() {
+        // Given
+        let temp_dir = tempfile::tempdir().unwrap();
+        let pack_dir = temp_dir.path().join("pack");
+        let manifest = write_raw_resources(temp_dir.path())
+            .precompile(true)
+            .build(&pack_dir)
+            .unwrap();
+
+        // When
+        fs::write(pack_dir.join("stop_words.txt"), "the\nan\n").unwrap();
+
+        // Then
+        assert!(manifest.files.contains_key("gazetteers/cities.fst"));
+        assert!(manifest.verify(&pack_dir).is_err());
+    }
+
+    #[test]
+    fn test_reject_malformed_word_clusters() {
+        // Given
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("clusters.csv"), "paris;100;extra\n").unwrap();
+        let builder = ResourcePackBuilder::new("travel", "1.0.0", Language::EN)
+            .dialect(CsvDialect {
+                delimiter: b';',
+                ..CsvDialect::default()
+            })
+            .word_clusters("travel_clusters", temp_dir.path().join("clusters.csv"));
+
+        // When
+        let result = builder.build(temp_dir.path().join("pack"));
+
+        // Then
+        assert!(result.is_err());
+    }
+}
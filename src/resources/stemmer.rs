@@ -1,11 +1,60 @@
 use crate::errors::*;
+use crate::resources::dialect::{CsvDialect, ParsingWarning, WarningCollector};
+use crate::unicode::UnicodeNormalization;
+use bincode::Options;
+use failure::bail;
+use rust_stemmers::Algorithm;
+use serde::{Deserialize, Serialize};
+use snips_nlu_ontology::Language;
 use snips_nlu_utils::string::{hash_str_to_i32, normalize};
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::iter::FromIterator;
+use std::sync::Arc;
 
+/// Extension of the stems files precompiled with `InternedStemmer::write_binary`
+pub const BINARY_STEMS_EXTENSION: &str = "bin";
+
+/// Magic bytes starting the binary stems files
+const BINARY_STEMS_MAGIC: &[u8; 8] = b"SNIPSSTM";
+/// Version of the binary stems format, to be incremented whenever the format changes
+const BINARY_STEMS_VERSION: u32 = 1;
+/// Maximum size of the serialized stems, so that a corrupted length cannot exhaust the memory
+const MAX_BINARY_STEMS_SIZE: u64 = 1 << 30;
+
+/// Dictionary-based stemming of tokens, loaded from the stems file of the language resources
+///
+/// A single stemmer is shared, through the `SharedResources`, by all the processing units of
+/// an engine.
 pub trait Stemmer: Send + Sync {
-    fn stem(&self, value: &str) -> String;
+    /// Returns the stem of the token, or the token itself when it has no known stem
+    fn stem<'a>(&'a self, value: &'a str) -> Cow<'a, str>;
+
+    /// Returns the stem of the token, or `None` when the stemmer does not know the token
+    ///
+    /// Stemmers which always produce a stem, like the rule-based ones, do not need to override
+    /// this method.
+    fn known_stem<'a>(&'a self, value: &'a str) -> Option<Cow<'a, str>> {
+        Some(self.stem(value))
+    }
+}
+
+/// Stemming algorithm selected by the `stemmer` field of the resources metadata
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StemmerBackend {
+    /// Stems file of the language resources only
+    Dictionary,
+    /// Stems file of the language resources, with a Snowball stemmer for the words which are
+    /// not in the file, or a Snowball stemmer alone when there is no stems file
+    Snowball,
+}
+
+impl Default for StemmerBackend {
+    fn default() -> Self {
+        StemmerBackend::Dictionary
+    }
 }
 
 pub struct HashMapStemmer {
@@ -14,22 +63,41 @@ pub struct HashMapStemmer {
 
 impl HashMapStemmer {
     pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        Self::from_reader_with_dialect(reader, &CsvDialect::stems())
+    }
+
+    pub fn from_reader_with_dialect<R: Read>(reader: R, dialect: &CsvDialect) -> Result<Self> {
+        Self::parse(reader, dialect, false).map(|(stemmer, _)| stemmer)
+    }
+
+    /// Parses the stems while skipping the malformed lines, which are returned as warnings
+    pub fn from_reader_lenient<R: Read>(
+        reader: R,
+        dialect: &CsvDialect,
+    ) -> Result<(Self, Vec<ParsingWarning>)> {
+        Self::parse(reader, dialect, true)
+    }
+
+    fn parse<R: Read>(
+        reader: R,
+        dialect: &CsvDialect,
+        lenient: bool,
+    ) -> Result<(Self, Vec<ParsingWarning>)> {
+        let mut collector = WarningCollector::new(lenient);
         let mut values = HashMap::new();
-        let mut csv_reader = csv::ReaderBuilder::new()
-            .delimiter(b',')
-            .quoting(false)
-            .flexible(true)
-            .has_headers(false)
-            .from_reader(reader);
+        let mut csv_reader = dialect.csv_reader(reader, true)?;
 
         for record in csv_reader.records() {
-            let elements = record?;
-            let stem = &elements[0];
+            let elements = match collector.check_record(record)? {
+                Some(elements) => elements,
+                None => continue,
+            };
+            let stem = dialect.normalize(&elements[0]);
             for value in elements.iter().skip(1) {
-                values.insert(hash_str_to_i32(value), stem.to_string());
+                values.insert(hash_str_to_i32(&dialect.normalize(value)), stem.to_string());
             }
         }
-        Ok(Self { values })
+        Ok((Self { values }, collector.warnings))
     }
 }
 
@@ -45,11 +113,214 @@ impl FromIterator<(String, String)> for HashMapStemmer {
 }
 
 impl Stemmer for HashMapStemmer {
-    fn stem(&self, value: &str) -> String {
+    fn stem<'a>(&'a self, value: &'a str) -> Cow<'a, str> {
+        self.known_stem(value).unwrap_or(Cow::Borrowed(value))
+    }
+
+    fn known_stem<'a>(&'a self, value: &'a str) -> Option<Cow<'a, str>> {
         self.values
             .get(&hash_str_to_i32(&*normalize(value)))
-            .map(|v| v.to_string())
-            .unwrap_or_else(|| value.to_string())
+            .map(|stem| Cow::Borrowed(stem.as_str()))
+    }
+}
+
+/// Stemmer storing each distinct stem only once, which uses less memory than the
+/// `HashMapStemmer` at the cost of an additional indirection
+#[derive(Serialize, Deserialize)]
+pub struct InternedStemmer {
+    stem_indices: HashMap<i32, u32>,
+    stems: Vec<String>,
+}
+
+impl InternedStemmer {
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        Self::from_reader_with_dialect(reader, &CsvDialect::stems())
+    }
+
+    pub fn from_reader_with_dialect<R: Read>(reader: R, dialect: &CsvDialect) -> Result<Self> {
+        Self::parse(reader, dialect, false).map(|(stemmer, _)| stemmer)
+    }
+
+    /// Parses the stems while skipping the malformed lines, which are returned as warnings
+    pub fn from_reader_lenient<R: Read>(
+        reader: R,
+        dialect: &CsvDialect,
+    ) -> Result<(Self, Vec<ParsingWarning>)> {
+        Self::parse(reader, dialect, true)
+    }
+
+    /// Loads stems precompiled with `write_binary`, which is much faster than parsing the
+    /// text file
+    ///
+    /// The stems are rejected when they were not normalized with the Unicode normalization form
+    /// used by the engine.
+    pub fn from_binary_reader<R: Read>(
+        mut reader: R,
+        unicode_normalization: UnicodeNormalization,
+    ) -> Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != BINARY_STEMS_MAGIC {
+            bail!("Not a binary stems file");
+        }
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != BINARY_STEMS_VERSION {
+            bail!(
+                "Unsupported binary stems version {}, expected version {}",
+                version,
+                BINARY_STEMS_VERSION
+            );
+        }
+        let mut normalization = [0; 1];
+        reader.read_exact(&mut normalization)?;
+        if normalization[0] != normalization_code(unicode_normalization) {
+            bail!(
+                "Stems were compiled with another Unicode normalization than {:?}",
+                unicode_normalization
+            );
+        }
+        Ok(bincode_options().deserialize_from(reader)?)
+    }
+
+    /// Writes the stems in a compact binary format
+    ///
+    /// The conversion is meant to be done once, offline, the text file remaining the source of
+    /// the stems. The stems are written as normalized when they were parsed, with the Unicode
+    /// normalization form which is recorded in the header of the file. Integers are written in
+    /// little-endian order, so that the file can be loaded on any platform.
+    pub fn write_binary<W: Write>(
+        &self,
+        mut writer: W,
+        unicode_normalization: UnicodeNormalization,
+    ) -> Result<()> {
+        writer.write_all(BINARY_STEMS_MAGIC)?;
+        writer.write_all(&BINARY_STEMS_VERSION.to_le_bytes())?;
+        writer.write_all(&[normalization_code(unicode_normalization)])?;
+        Ok(bincode_options().serialize_into(writer, self)?)
+    }
+
+    fn parse<R: Read>(
+        reader: R,
+        dialect: &CsvDialect,
+        lenient: bool,
+    ) -> Result<(Self, Vec<ParsingWarning>)> {
+        let mut collector = WarningCollector::new(lenient);
+        let mut stem_indices = HashMap::new();
+        let mut stems = vec![];
+        let mut csv_reader = dialect.csv_reader(reader, true)?;
+
+        for record in csv_reader.records() {
+            let elements = match collector.check_record(record)? {
+                Some(elements) => elements,
+                None => continue,
+            };
+            let stem_index = stems.len() as u32;
+            stems.push(dialect.normalize(&elements[0]).into_owned());
+            for value in elements.iter().skip(1) {
+                stem_indices.insert(hash_str_to_i32(&dialect.normalize(value)), stem_index);
+            }
+        }
+        stems.shrink_to_fit();
+        stem_indices.shrink_to_fit();
+        Ok((
+            Self {
+                stem_indices,
+                stems,
+            },
+            collector.warnings,
+        ))
+    }
+}
+
+fn bincode_options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_little_endian()
+        .with_limit(MAX_BINARY_STEMS_SIZE)
+}
+
+fn normalization_code(unicode_normalization: UnicodeNormalization) -> u8 {
+    match unicode_normalization {
+        UnicodeNormalization::None => 0,
+        UnicodeNormalization::Nfc => 1,
+        UnicodeNormalization::Nfd => 2,
+        UnicodeNormalization::Nfkc => 3,
+        UnicodeNormalization::Nfkd => 4,
+    }
+}
+
+impl Stemmer for InternedStemmer {
+    fn stem<'a>(&'a self, value: &'a str) -> Cow<'a, str> {
+        self.known_stem(value).unwrap_or(Cow::Borrowed(value))
+    }
+
+    fn known_stem<'a>(&'a self, value: &'a str) -> Option<Cow<'a, str>> {
+        self.stem_indices
+            .get(&hash_str_to_i32(&*normalize(value)))
+            .map(|index| Cow::Borrowed(self.stems[*index as usize].as_str()))
+    }
+}
+
+/// Rule-based stemmer implementing the Snowball algorithm of a language, which stems any word
+/// including the ones missing from the stems files
+pub struct SnowballStemmer {
+    stemmer: rust_stemmers::Stemmer,
+}
+
+impl SnowballStemmer {
+    pub fn new(language: Language) -> Result<Self> {
+        let algorithm = match language {
+            Language::DE => Algorithm::German,
+            Language::EN => Algorithm::English,
+            Language::ES => Algorithm::Spanish,
+            Language::FR => Algorithm::French,
+            Language::IT => Algorithm::Italian,
+            Language::PT_PT | Language::PT_BR => Algorithm::Portuguese,
+            Language::JA | Language::KO => {
+                bail!(
+                    "No Snowball stemmer available for language '{}'",
+                    language.to_string()
+                )
+            }
+        };
+        Ok(Self {
+            stemmer: rust_stemmers::Stemmer::create(algorithm),
+        })
+    }
+}
+
+impl Stemmer for SnowballStemmer {
+    fn stem<'a>(&'a self, value: &'a str) -> Cow<'a, str> {
+        Cow::Owned(self.stemmer.stem(&normalize(value)).into_owned())
+    }
+}
+
+/// Stemmer looking up the tokens in a primary stemmer first, typically a dictionary-based one,
+/// and using a fallback stemmer for the tokens which the primary one does not know
+pub struct FallbackStemmer {
+    primary: Arc<dyn Stemmer>,
+    fallback: Arc<dyn Stemmer>,
+}
+
+impl FallbackStemmer {
+    pub fn new(primary: Arc<dyn Stemmer>, fallback: Arc<dyn Stemmer>) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+impl Stemmer for FallbackStemmer {
+    fn stem<'a>(&'a self, value: &'a str) -> Cow<'a, str> {
+        self.primary
+            .known_stem(value)
+            .unwrap_or_else(|| self.fallback.stem(value))
+    }
+
+    fn known_stem<'a>(&'a self, value: &'a str) -> Option<Cow<'a, str>> {
+        self.primary
+            .known_stem(value)
+            .or_else(|| self.fallback.known_stem(value))
     }
 }
 
@@ -76,4 +347,111 @@ do,done,don't,doing,did,does"#
         assert_eq!(stemmer.stem("\"investigate"), "investigate".to_string());
         assert_eq!(stemmer.stem("unknown"), "unknown".to_string());
     }
+
+    #[test]
+    fn test_interned_stemmer() {
+        // Given
+        let stems: &[u8] = r#"
+investigate,investigated,investigation,"investigate
+do,done,don't,doing,did,does"#
+            .as_ref();
+
+        // When
+        let stemmer = InternedStemmer::from_reader(stems);
+
+        // Then
+        assert!(stemmer.is_ok());
+        let stemmer = stemmer.unwrap();
+        assert_eq!(stemmer.stem("don't"), "do".to_string());
+        assert_eq!(stemmer.stem("does"), "do".to_string());
+        assert_eq!(stemmer.stem("\"investigate"), "investigate".to_string());
+        assert_eq!(stemmer.stem("unknown"), "unknown".to_string());
+    }
+
+    #[test]
+    fn test_binary_interned_stemmer() {
+        // Given
+        let stems: &[u8] = b"do,done,don't,doing,did,does\nsee,seen,saw";
+        let stemmer = InternedStemmer::from_reader(stems).unwrap();
+        let mut binary_stems = vec![];
+
+        // When
+        stemmer
+            .write_binary(&mut binary_stems, UnicodeNormalization::Nfc)
+            .unwrap();
+        let loaded_stemmer =
+            InternedStemmer::from_binary_reader(&binary_stems[..], UnicodeNormalization::Nfc)
+                .unwrap();
+
+        // Then
+        assert_eq!(loaded_stemmer.stem("did"), "do".to_string());
+        assert_eq!(loaded_stemmer.stem("saw"), "see".to_string());
+        assert_eq!(loaded_stemmer.stem("unknown"), "unknown".to_string());
+    }
+
+    #[test]
+    fn test_reject_incompatible_binary_stems() {
+        // Given
+        let stems: &[u8] = b"do,done,don't,doing,did,does";
+        let stemmer = InternedStemmer::from_reader(stems).unwrap();
+        let mut binary_stems = vec![];
+        stemmer
+            .write_binary(&mut binary_stems, UnicodeNormalization::Nfc)
+            .unwrap();
+        let mut future_binary_stems = binary_stems.clone();
+        future_binary_stems[8] = 2;
+
+        // When
+        let nfd_result =
+            InternedStemmer::from_binary_reader(&binary_stems[..], UnicodeNormalization::Nfd);
+        let future_result = InternedStemmer::from_binary_reader(
+            &future_binary_stems[..],
+            UnicodeNormalization::Nfc,
+        );
+        let raw_result = InternedStemmer::from_binary_reader(
+            &binary_stems[BINARY_STEMS_MAGIC.len()..],
+            UnicodeNormalization::Nfc,
+        );
+
+        // Then
+        assert!(nfd_result.is_err());
+        assert!(future_result.is_err());
+        assert!(raw_result.is_err());
+    }
+
+    #[test]
+    fn test_lenient_stemmer_parsing() {
+        // Given
+        let stems: &[u8] = b"do,done,does\ngo,gone,\xFFgoes\nsee,seen";
+
+        // When
+        let strict_result = HashMapStemmer::from_reader(stems);
+        let (stemmer, warnings) =
+            HashMapStemmer::from_reader_lenient(stems, &CsvDialect::stems()).unwrap();
+
+        // Then
+        assert!(strict_result.is_err());
+        assert_eq!(stemmer.stem("does"), "do".to_string());
+        assert_eq!(stemmer.stem("seen"), "see".to_string());
+        assert_eq!(stemmer.stem("gone"), "gone".to_string());
+        assert_eq!(1, warnings.len());
+        assert_eq!(2, warnings[0].line);
+    }
+
+    #[test]
+    fn test_snowball_fallback_stemmer() {
+        // Given
+        let stems: &[u8] = b"do,done,don't,doing,did,does";
+        let dictionary_stemmer = Arc::new(HashMapStemmer::from_reader(stems).unwrap());
+        let snowball_stemmer = Arc::new(SnowballStemmer::new(Language::EN).unwrap());
+
+        // When
+        let stemmer = FallbackStemmer::new(dictionary_stemmer, snowball_stemmer);
+
+        // Then
+        assert_eq!(stemmer.stem("did"), "do".to_string());
+        assert_eq!(stemmer.stem("Investigated"), "investig".to_string());
+        assert_eq!(stemmer.stem("running"), "run".to_string());
+        assert!(SnowballStemmer::new(Language::JA).is_err());
+    }
 }
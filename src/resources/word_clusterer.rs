@@ -1,4 +1,6 @@
 use crate::errors::*;
+use crate::resources::dialect::{CsvDialect, ParsingWarning, WarningCollector};
+use failure::format_err;
 use itertools::Either;
 use snips_nlu_ontology::Language;
 use snips_nlu_utils::string::hash_str_to_i32;
@@ -18,19 +20,50 @@ pub struct HashMapWordClusterer {
 
 impl HashMapWordClusterer {
     pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
-        let mut csv_reader = csv::ReaderBuilder::new()
-            .delimiter(b'\t')
-            .quoting(false)
-            .has_headers(false)
-            .from_reader(reader);
+        Self::from_reader_with_dialect(reader, &CsvDialect::word_clusters())
+    }
+
+    pub fn from_reader_with_dialect<R: Read>(reader: R, dialect: &CsvDialect) -> Result<Self> {
+        Self::parse(reader, dialect, false).map(|(clusterer, _)| clusterer)
+    }
+
+    /// Parses the word clusters while skipping the malformed lines, which are returned as
+    /// warnings
+    pub fn from_reader_lenient<R: Read>(
+        reader: R,
+        dialect: &CsvDialect,
+    ) -> Result<(Self, Vec<ParsingWarning>)> {
+        Self::parse(reader, dialect, true)
+    }
+
+    fn parse<R: Read>(
+        reader: R,
+        dialect: &CsvDialect,
+        lenient: bool,
+    ) -> Result<(Self, Vec<ParsingWarning>)> {
+        let mut collector = WarningCollector::new(lenient);
+        let mut csv_reader = dialect.csv_reader(reader, true)?;
         // This flag is switched to false as soon as a record is found which cannot
         // be converted to a u16
         let mut u16_casting_ok = true;
         let mut u16_values = HashMap::new();
         let mut str_values = HashMap::new();
         for record in csv_reader.records() {
-            let elements = record?;
-            let hashed_key = hash_str_to_i32(elements[0].as_ref());
+            let elements = match collector.check_record(record)? {
+                Some(elements) => elements,
+                None => continue,
+            };
+            if elements.len() != 2 {
+                let line = elements.position().map(|p| p.line()).unwrap_or(0);
+                let invalid_record: Result<()> = Err(format_err!(
+                    "Expected a word and a cluster at line {}, found {} fields",
+                    line,
+                    elements.len()
+                ));
+                collector.check(line, invalid_record)?;
+                continue;
+            }
+            let hashed_key = hash_str_to_i32(&dialect.normalize(&elements[0]));
             // Casting into u16 is attempted only when all previous clusters were converted
             // successfully
             if u16_casting_ok {
@@ -53,13 +86,14 @@ impl HashMapWordClusterer {
                 str_values.insert(hashed_key, elements[1].to_string());
             }
         }
-        Ok(Self {
+        let clusterer = Self {
             values: if u16_casting_ok {
                 Either::Left(u16_values)
             } else {
                 Either::Right(str_values)
             },
-        })
+        };
+        Ok((clusterer, collector.warnings))
     }
 }
 
@@ -82,6 +116,7 @@ pub struct WordClustererConfiguration {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::unicode::UnicodeNormalization;
 
     #[test]
     fn test_hashmap_word_clusterer_with_non_u16_values() {
@@ -128,4 +163,49 @@ yolo	65500
         assert_eq!(clusterer.get_cluster("yolo"), Some("65500".to_string()));
         assert_eq!(clusterer.get_cluster("unknown"), None);
     }
+
+    #[test]
+    fn test_hashmap_word_clusterer_with_dialect() {
+        // Given
+        let clusters: &[u8] = b"\xEF\xBB\xBF# word;cluster\nhello;42\n\"new;york\";7\n";
+        let dialect = CsvDialect {
+            delimiter: b';',
+            quote: Some(b'"'),
+            comment: Some(b'#'),
+            strip_bom: true,
+            unicode_normalization: UnicodeNormalization::Nfc,
+        };
+
+        // When
+        let clusterer = HashMapWordClusterer::from_reader_with_dialect(clusters, &dialect);
+
+        // Then
+        assert!(clusterer.is_ok());
+        let clusterer = clusterer.unwrap();
+        assert_eq!(clusterer.get_cluster("hello"), Some("42".to_string()));
+        assert_eq!(clusterer.get_cluster("new;york"), Some("7".to_string()));
+        assert_eq!(clusterer.get_cluster("# word"), None);
+    }
+
+    #[test]
+    fn test_lenient_word_clusterer_parsing() {
+        // Given
+        let clusters: &[u8] = b"hello\t42\nworld\nyolo\t12\textra\nbye\t7";
+
+        // When
+        let strict_result = HashMapWordClusterer::from_reader(clusters);
+        let (clusterer, warnings) =
+            HashMapWordClusterer::from_reader_lenient(clusters, &CsvDialect::word_clusters())
+                .unwrap();
+
+        // Then
+        assert!(strict_result.is_err());
+        assert_eq!(clusterer.get_cluster("hello"), Some("42".to_string()));
+        assert_eq!(clusterer.get_cluster("bye"), Some("7".to_string()));
+        assert_eq!(clusterer.get_cluster("world"), None);
+        assert_eq!(
+            vec![2, 3],
+            warnings.iter().map(|w| w.line).collect::<Vec<_>>()
+        );
+    }
 }
@@ -0,0 +1,174 @@
+use std::char::REPLACEMENT_CHARACTER;
+use std::time::Duration;
+
+use serde::Serialize;
+use snips_nlu_ontology::{IntentClassifierResult, IntentParserResult, Slot};
+
+/// Utterances parsed by `SnipsNluEngine::self_test`, which cover the scripts of the supported
+/// languages, punctuation, symbols and edge cases such as blank inputs, whatever the language
+/// of the engine
+pub(crate) const SELF_TEST_UTTERANCES: &[&str] = &[
+    "",
+    "   ",
+    "hello",
+    "make me two cups of coffee at 8pm tomorrow",
+    "set the temperature to 21.5 degrees for three hours",
+    "je voudrais un thé très chaud à 7h30 s'il vous plaît",
+    "¿Puedes poner música de los años 80 en la cocina?",
+    "Mach bitte das Licht im Wohnzimmer für 10 Minuten aus",
+    "accendi la luce della camera alle otto",
+    "quero dois cafés às três da tarde, obrigado",
+    "明日の朝7時にアラームを設定して",
+    "내일 오후 세 시에 알림을 설정해 줘",
+    "ça coûte 3,50 € ou $4.20 ?!",
+    "🎵 play 🎶 some jazz 🎷",
+    "a-b-c d_e_f g.h.i ??? !!! ...",
+    "e\u{301}te\u{301} Ωmega naïve façade",
+];
+
+/// Number of times the battery of utterances is parsed in order to measure latencies
+pub(crate) const SELF_TEST_ROUNDS: usize = 5;
+
+/// Result of `SnipsNluEngine::self_test`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    /// Whether all the utterances were parsed and their results satisfy the invariants
+    pub passed: bool,
+    pub nb_utterances: usize,
+    pub failures: Vec<SelfTestFailure>,
+    pub latencies: LatencyPercentiles,
+}
+
+/// Utterance which failed to be parsed, or whose result breaks an invariant
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestFailure {
+    pub input: String,
+    pub reason: String,
+}
+
+/// Percentiles of the parsing latencies, in microseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyPercentiles {
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+impl LatencyPercentiles {
+    pub(crate) fn from_durations(mut durations: Vec<Duration>) -> Self {
+        durations.sort();
+        let percentile = |ratio: f64| -> u64 {
+            if durations.is_empty() {
+                return 0;
+            }
+            // Nearest-rank method
+            let rank = (ratio * durations.len() as f64).ceil() as usize;
+            let duration = durations[rank.max(1) - 1];
+            duration.as_secs() * 1_000_000 + u64::from(duration.subsec_micros())
+        };
+        Self {
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+            max: percentile(1.0),
+        }
+    }
+}
+
+/// Checks the invariants of a parsing result, and returns the first broken one
+pub(crate) fn check_result(input: &str, result: &IntentParserResult) -> Option<String> {
+    if result.input != input {
+        return Some(format!(
+            "result input {:?} differs from input",
+            result.input
+        ));
+    }
+    let nb_chars = input.chars().count();
+    Some(&result.intent)
+        .into_iter()
+        .chain(
+            result
+                .alternatives
+                .iter()
+                .map(|alternative| &alternative.intent),
+        )
+        .filter_map(check_intent)
+        .chain(
+            result
+                .slots
+                .iter()
+                .chain(result.alternatives.iter().flat_map(|alt| alt.slots.iter()))
+                .filter_map(|slot| check_slot(slot, nb_chars)),
+        )
+        .next()
+        .or_else(|| {
+            serde_json::to_string(result)
+                .err()
+                .map(|e| format!("result cannot be serialized: {}", e))
+        })
+}
+
+fn check_intent(intent: &IntentClassifierResult) -> Option<String> {
+    if !is_probability(intent.confidence_score) {
+        return Some(format!(
+            "invalid confidence score {} for intent {:?}",
+            intent.confidence_score, intent.intent_name
+        ));
+    }
+    None
+}
+
+fn check_slot(slot: &Slot, nb_chars: usize) -> Option<String> {
+    if slot.range.start >= slot.range.end || slot.range.end > nb_chars {
+        return Some(format!(
+            "range {:?} of slot '{}' is out of the bounds of the input",
+            slot.range, slot.slot_name
+        ));
+    }
+    if let Some(score) = slot.confidence_score {
+        if !is_probability(score) {
+            return Some(format!(
+                "invalid confidence score {} for slot '{}'",
+                score, slot.slot_name
+            ));
+        }
+    }
+    if slot.raw_value.contains(REPLACEMENT_CHARACTER) {
+        return Some(format!(
+            "slot '{}' contains invalid UTF-8 sequences",
+            slot.slot_name
+        ));
+    }
+    None
+}
+
+fn is_probability(value: f32) -> bool {
+    (0.0..=1.0).contains(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_percentiles() {
+        // Given
+        let durations = (1..=100).rev().map(Duration::from_millis).collect();
+
+        // When
+        let percentiles = LatencyPercentiles::from_durations(durations);
+
+        // Then
+        let expected_percentiles = LatencyPercentiles {
+            p50: 50_000,
+            p90: 90_000,
+            p99: 99_000,
+            max: 100_000,
+        };
+        assert_eq!(expected_percentiles, percentiles);
+    }
+}
@@ -0,0 +1,297 @@
+use std::ops::Range;
+
+use failure::format_err;
+use snips_nlu_ontology::Language;
+use snips_nlu_utils::language::Language as NluUtilsLanguage;
+use snips_nlu_utils::range::ranges_overlap;
+use snips_nlu_utils::string::substring_with_char_range;
+use snips_nlu_utils::token::Token;
+
+use crate::cancellation::check_cancellation;
+use crate::config::ChunkingConfig;
+use crate::errors::*;
+use crate::language::FromLanguage;
+use crate::slot_filler::crf_utils::TaggingScheme;
+use crate::slot_filler::{SlotFiller, SlotsHypothesis};
+use crate::slot_utils::InternalSlot;
+use crate::tokenization::tokenize_aligned;
+
+/// Slot filler which splits long inputs into overlapping chunks of tokens, extracts slots
+/// in each chunk with the wrapped slot filler, and merges the results
+///
+/// Each chunk is responsible for the slots starting in the part of the input which is closer
+/// to its center than to the center of its neighbours, which avoids duplicated slots in the
+/// overlapping regions.
+pub struct ChunkedSlotFiller {
+    slot_filler: Box<dyn SlotFiller>,
+    /// Language of the wrapped slot filler, used to split the inputs into tokens
+    language: Language,
+    config: ChunkingConfig,
+}
+
+impl ChunkedSlotFiller {
+    pub fn new(
+        slot_filler: Box<dyn SlotFiller>,
+        language: Language,
+        config: ChunkingConfig,
+    ) -> Result<Self> {
+        if config.overlap >= config.max_tokens {
+            return Err(format_err!(
+                "Chunking overlap ({}) must be smaller than the chunks size ({})",
+                config.overlap,
+                config.max_tokens
+            ));
+        }
+        Ok(Self {
+            slot_filler,
+            language,
+            config,
+        })
+    }
+}
+
+impl SlotFiller for ChunkedSlotFiller {
+    fn get_tagging_scheme(&self) -> TaggingScheme {
+        self.slot_filler.get_tagging_scheme()
+    }
+
+    fn get_slots(&self, text: &str) -> Result<Vec<InternalSlot>> {
+        let language = NluUtilsLanguage::from_language(self.language);
+        let tokens = tokenize_aligned(text, language);
+        if tokens.len() <= self.config.max_tokens {
+            return self.slot_filler.get_slots(text);
+        }
+        self.merge_chunks_slots(text, &tokens, |chunk_text| {
+            self.slot_filler.get_slots(chunk_text)
+        })
+    }
+
+    /// Only the best hypothesis is returned for inputs which are split into chunks, its
+    /// probability being the product of the probabilities of the best hypothesis of each chunk
+    fn get_slots_nbest(&self, text: &str, n: usize) -> Result<Vec<SlotsHypothesis>> {
+        let language = NluUtilsLanguage::from_language(self.language);
+        let tokens = tokenize_aligned(text, language);
+        if tokens.len() <= self.config.max_tokens {
+            return self.slot_filler.get_slots_nbest(text, n);
+        }
+        if n == 0 {
+            return Ok(vec![]);
+        }
+        let mut probability = 1.0;
+        let slots = self.merge_chunks_slots(text, &tokens, |chunk_text| {
+            let best_hypothesis = self
+                .slot_filler
+                .get_slots_nbest(chunk_text, 1)?
+                .into_iter()
+                .next();
+            Ok(best_hypothesis
+                .map(|hypothesis| {
+                    probability *= hypothesis.probability;
+                    hypothesis.slots
+                })
+                .unwrap_or_else(|| vec![]))
+        })?;
+        Ok(vec![SlotsHypothesis { slots, probability }])
+    }
+
+    fn get_sequence_probability(&self, tokens: &[Token], tags: Vec<String>) -> Result<f64> {
+        self.slot_filler.get_sequence_probability(tokens, tags)
+    }
+}
+
+impl ChunkedSlotFiller {
+    /// Extracts the slots of each chunk with `extract_slots`, and merges them
+    fn merge_chunks_slots<F>(
+        &self,
+        text: &str,
+        tokens: &[Token],
+        mut extract_slots: F,
+    ) -> Result<Vec<InternalSlot>>
+    where
+        F: FnMut(&str) -> Result<Vec<InternalSlot>>,
+    {
+        let mut slots: Vec<InternalSlot> = vec![];
+        for (chunk_tokens, owned_chars) in self.chunks(tokens, text.chars().count()) {
+            check_cancellation()?;
+            let chunk_start = chunk_tokens[0].char_range.start;
+            let chunk_end = chunk_tokens[chunk_tokens.len() - 1].char_range.end;
+            let chunk_text = substring_with_char_range(text.to_string(), &(chunk_start..chunk_end));
+            for mut slot in extract_slots(&chunk_text)? {
+                slot.char_range =
+                    (slot.char_range.start + chunk_start)..(slot.char_range.end + chunk_start);
+                let is_owned = slot.char_range.start >= owned_chars.start
+                    && slot.char_range.start < owned_chars.end;
+                let is_overlapping = slots
+                    .iter()
+                    .any(|kept_slot| ranges_overlap(&kept_slot.char_range, &slot.char_range));
+                if is_owned && !is_overlapping {
+                    slots.push(slot);
+                }
+            }
+        }
+        Ok(slots)
+    }
+
+    /// Returns the tokens of each chunk along with the char range of the input it owns
+    fn chunks<'a>(&self, tokens: &'a [Token], nb_chars: usize) -> Vec<(&'a [Token], Range<usize>)> {
+        let step = self.config.max_tokens - self.config.overlap;
+        let mut chunks = vec![];
+        let mut owned_start = 0;
+        let mut chunk_start = 0;
+        loop {
+            let chunk_end = (chunk_start + self.config.max_tokens).min(tokens.len());
+            if chunk_end == tokens.len() {
+                chunks.push((&tokens[chunk_start..chunk_end], owned_start..nb_chars));
+                break;
+            }
+            let next_chunk_start = chunk_start + step;
+            // The boundary between two chunks is the middle token of their overlap
+            let boundary_token = (next_chunk_start + chunk_end) / 2;
+            let owned_end = tokens[boundary_token].char_range.start;
+            chunks.push((&tokens[chunk_start..chunk_end], owned_start..owned_end));
+            owned_start = owned_end;
+            chunk_start = next_chunk_start;
+        }
+        chunks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cancellation::{with_cancellation_token, CancellationToken};
+
+    use std::collections::HashMap;
+
+    struct MockedSlotFiller {
+        slots: HashMap<String, Vec<InternalSlot>>,
+    }
+
+    impl SlotFiller for MockedSlotFiller {
+        fn get_tagging_scheme(&self) -> TaggingScheme {
+            TaggingScheme::BIO
+        }
+
+        fn get_slots(&self, text: &str) -> Result<Vec<InternalSlot>> {
+            Ok(self.slots.get(text).cloned().unwrap_or_else(|| vec![]))
+        }
+
+        fn get_sequence_probability(&self, _tokens: &[Token], _tags: Vec<String>) -> Result<f64> {
+            Ok(1.0)
+        }
+    }
+
+    fn number_slot(value: &str, start: usize) -> InternalSlot {
+        InternalSlot {
+            value: value.to_string(),
+            char_range: start..start + value.chars().count(),
+            entity: "snips/number".to_string(),
+            slot_name: "number".to_string(),
+            confidence_score: None,
+        }
+    }
+
+    #[test]
+    fn test_chunked_slot_filler() {
+        // Given
+        let text = "one a two b three c four";
+        let mut slots = HashMap::new();
+        slots.insert(
+            "one a two b".to_string(),
+            vec![number_slot("one", 0), number_slot("two", 6)],
+        );
+        slots.insert(
+            "two b three c".to_string(),
+            vec![number_slot("two", 0), number_slot("three", 6)],
+        );
+        slots.insert(
+            "three c four".to_string(),
+            vec![number_slot("three", 0), number_slot("four", 8)],
+        );
+        let config = ChunkingConfig {
+            max_tokens: 4,
+            overlap: 2,
+        };
+        let slot_filler =
+            ChunkedSlotFiller::new(Box::new(MockedSlotFiller { slots }), Language::EN, config)
+                .unwrap();
+
+        // When
+        let extracted_slots = slot_filler.get_slots(text).unwrap();
+
+        // Then
+        let expected_slots = vec![
+            number_slot("one", 0),
+            number_slot("two", 6),
+            number_slot("three", 12),
+            number_slot("four", 20),
+        ];
+        assert_eq!(expected_slots, extracted_slots);
+    }
+
+    #[test]
+    fn test_chunked_slot_filler_with_short_input() {
+        // Given
+        let text = "one a two";
+        let mut slots = HashMap::new();
+        slots.insert(text.to_string(), vec![number_slot("one", 0)]);
+        let config = ChunkingConfig {
+            max_tokens: 4,
+            overlap: 2,
+        };
+        let slot_filler =
+            ChunkedSlotFiller::new(Box::new(MockedSlotFiller { slots }), Language::EN, config)
+                .unwrap();
+
+        // When
+        let extracted_slots = slot_filler.get_slots(text).unwrap();
+
+        // Then
+        assert_eq!(vec![number_slot("one", 0)], extracted_slots);
+    }
+
+    #[test]
+    fn test_chunked_slot_filler_with_cancelled_token() {
+        // Given
+        let config = ChunkingConfig {
+            max_tokens: 4,
+            overlap: 2,
+        };
+        let slot_filler = ChunkedSlotFiller::new(
+            Box::new(MockedSlotFiller {
+                slots: HashMap::new(),
+            }),
+            Language::EN,
+            config,
+        )
+        .unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+
+        // When
+        let result = with_cancellation_token(Some(&token), || {
+            slot_filler.get_slots("one a two b three c four")
+        });
+
+        // Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_chunked_slot_filler_with_invalid_config() {
+        // Given
+        let slot_filler = MockedSlotFiller {
+            slots: HashMap::new(),
+        };
+        let config = ChunkingConfig {
+            max_tokens: 4,
+            overlap: 4,
+        };
+
+        // When
+        let result = ChunkedSlotFiller::new(Box::new(slot_filler), Language::EN, config);
+
+        // Then
+        assert!(result.is_err());
+    }
+}
@@ -10,7 +10,7 @@ use itertools::Itertools;
 use log::{debug, info};
 use snips_nlu_ontology::Language;
 use snips_nlu_utils::language::Language as NluUtilsLanguage;
-use snips_nlu_utils::token::{tokenize, Token};
+use snips_nlu_utils::token::Token;
 
 use crate::errors::*;
 use crate::language::FromLanguage;
@@ -18,16 +18,22 @@ use crate::models::SlotFillerModel;
 use crate::resources::SharedResources;
 use crate::slot_filler::crf_utils::*;
 use crate::slot_filler::feature_processor::ProbabilisticFeatureProcessor;
-use crate::slot_filler::SlotFiller;
+use crate::slot_filler::{SlotFiller, SlotsHypothesis};
 use crate::slot_utils::*;
+use crate::tokenization::tokenize_aligned;
 use crate::utils::{EntityName, SlotName};
 
+/// Number of candidate tag sequences kept by the beam search of `get_slots_nbest`, for each
+/// requested hypothesis
+const NBEST_BEAM_WIDTH_FACTOR: usize = 4;
+
 pub struct CRFSlotFiller {
     language: Language,
     tagging_scheme: TaggingScheme,
     tagger: Option<Mutex<CRFSuiteTagger>>,
     feature_processor: Option<ProbabilisticFeatureProcessor>,
     slot_name_mapping: HashMap<SlotName, EntityName>,
+    compute_confidence_scores: bool,
 }
 
 impl CRFSlotFiller {
@@ -72,8 +78,20 @@ impl CRFSlotFiller {
             tagger,
             feature_processor,
             slot_name_mapping,
+            compute_confidence_scores: false,
         })
     }
+
+    /// Enables the computation of the confidence score of the slots, which is the lowest
+    /// marginal probability of the tags of their tokens
+    pub fn compute_confidence_scores(mut self, enabled: bool) -> Self {
+        self.compute_confidence_scores = enabled;
+        self
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
 }
 
 impl SlotFiller for CRFSlotFiller {
@@ -82,35 +100,122 @@ impl SlotFiller for CRFSlotFiller {
     }
 
     fn get_slots(&self, text: &str) -> Result<Vec<InternalSlot>> {
-        debug!("Extracting slots...");
-        let slots = if let (Some(ref tagger), Some(ref feature_processor)) =
-            (self.tagger.as_ref(), self.feature_processor.as_ref())
+        self.get_slots_with_disabled_features(text, &[])
+    }
+
+    /// Returns the slots of the `n` most probable tag sequences
+    ///
+    /// The candidate sequences are generated with a beam search on the marginal probabilities
+    /// of the tags, and are then ranked according to their actual probability. The Viterbi
+    /// sequence is always part of the candidates, so that the first hypothesis corresponds to
+    /// `get_slots`. Tag sequences leading to the same slots are merged.
+    fn get_slots_nbest(&self, text: &str, n: usize) -> Result<Vec<SlotsHypothesis>> {
+        if n == 0 {
+            return Ok(vec![]);
+        }
+        let tokens = self.tokenize(text);
+        let (tagger, feature_processor) =
+            match (self.tagger.as_ref(), self.feature_processor.as_ref()) {
+                (Some(tagger), Some(feature_processor)) if !tokens.is_empty() => {
+                    (tagger, feature_processor)
+                }
+                // No tagger defined corresponds to an intent without slots
+                _ => {
+                    return Ok(vec![SlotsHypothesis {
+                        slots: vec![],
+                        probability: 1.0,
+                    }])
+                }
+            };
+        let features = feature_processor.compute_features(&&*tokens)?;
+        let tagger = tagger
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))?;
+        let best_tags = tagger.tag(&features)?;
+        let labels = tagger.labels()?;
+        let marginals = (0..tokens.len())
+            .map(|position| {
+                labels
+                    .iter()
+                    .map(|label| Ok(tagger.marginal(label, position as i32)?))
+                    .collect::<Result<Vec<f64>>>()
+            })
+            .collect::<Result<Vec<Vec<f64>>>>()?;
+
+        let beam_width = n * NBEST_BEAM_WIDTH_FACTOR;
+        let mut beam: Vec<(Vec<usize>, f64)> = vec![(vec![], 0.0)];
+        for position_marginals in marginals.iter() {
+            beam = beam
+                .iter()
+                .flat_map(|(label_indices, log_score)| {
+                    position_marginals
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, marginal)| **marginal > 0.0)
+                        .map(move |(label_index, marginal)| {
+                            let mut extended_indices = label_indices.clone();
+                            extended_indices.push(label_index);
+                            (extended_indices, log_score + marginal.ln())
+                        })
+                })
+                .sorted_by(|(_, score_a), (_, score_b)| score_b.partial_cmp(score_a).unwrap())
+                .take(beam_width)
+                .collect();
+        }
+        let candidates = Some(best_tags)
+            .into_iter()
+            .chain(beam.into_iter().map(|(label_indices, _)| {
+                label_indices
+                    .into_iter()
+                    .map(|label_index| labels[label_index].clone())
+                    .collect()
+            }))
+            .unique()
+            .map(|encoded_tags: Vec<String>| {
+                let probability = tagger.probability(&encoded_tags)?;
+                Ok((encoded_tags, probability))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut hypotheses: Vec<SlotsHypothesis> = vec![];
+        let mut extracted_slots: Vec<Vec<InternalSlot>> = vec![];
+        // The sort is stable, hence the Viterbi sequence stays first in case of ties
+        for (encoded_tags, probability) in candidates
+            .into_iter()
+            .sorted_by(|(_, proba_a), (_, proba_b)| proba_b.partial_cmp(proba_a).unwrap())
         {
-            let tokens = tokenize(text, NluUtilsLanguage::from_language(self.language));
-            if tokens.is_empty() {
-                return Ok(vec![]);
-            }
-            let features = feature_processor.compute_features(&&*tokens)?;
-            let tags = tagger
-                .lock()
-                .map_err(|e| format_err!("Poisonous mutex: {}", e))?
-                .tag(&features)?
-                .into_iter()
+            let tags = encoded_tags
+                .iter()
                 .map(|tag| decode_tag(&*tag))
                 .collect::<Result<Vec<String>>>()?;
-
-            tags_to_slots(
+            let mut slots = tags_to_slots(
                 text,
                 &tokens,
                 &tags,
                 self.tagging_scheme,
                 &self.slot_name_mapping,
-            )?
-        } else {
-            vec![]
-        };
-        debug!("{} slots extracted", slots.len());
-        Ok(slots)
+            )?;
+            if extracted_slots.contains(&slots) {
+                continue;
+            }
+            extracted_slots.push(slots.clone());
+            if self.compute_confidence_scores {
+                let tags_marginals = encoded_tags
+                    .iter()
+                    .enumerate()
+                    .map(|(position, tag)| {
+                        let label_index = labels.iter().position(|label| label == tag).unwrap();
+                        marginals[position][label_index] as f32
+                    })
+                    .collect::<Vec<f32>>();
+                set_confidence_scores(&mut slots, &tokens, &tags_marginals);
+            }
+            hypotheses.push(SlotsHypothesis { slots, probability });
+            if hypotheses.len() == n {
+                break;
+            }
+        }
+        Ok(hypotheses)
     }
 
     fn get_sequence_probability(&self, tokens: &[Token], tags: Vec<String>) -> Result<f64> {
@@ -153,8 +258,72 @@ impl SlotFiller for CRFSlotFiller {
 }
 
 impl CRFSlotFiller {
+    /// Tokens of the text, on which the features of `compute_features` are computed
+    pub fn tokenize(&self, text: &str) -> Vec<Token> {
+        tokenize_aligned(text, NluUtilsLanguage::from_language(self.language))
+    }
+
+    /// Identifiers of the families of features used by the CRF, e.g. `"ngram"` or
+    /// `"entity_match"`
+    pub fn feature_families(&self) -> Vec<&'static str> {
+        self.feature_processor
+            .as_ref()
+            .map(|feature_processor| feature_processor.feature_families())
+            .unwrap_or_else(|| vec![])
+    }
+
+    /// Extracts the slots while ignoring the features of the disabled families, which is how
+    /// the CRF behaves when these features are never fired
+    pub fn get_slots_with_disabled_features(
+        &self,
+        text: &str,
+        disabled_families: &[&str],
+    ) -> Result<Vec<InternalSlot>> {
+        debug!("Extracting slots...");
+        let slots = if let (Some(ref tagger), Some(ref feature_processor)) =
+            (self.tagger.as_ref(), self.feature_processor.as_ref())
+        {
+            let tokens = self.tokenize(text);
+            if tokens.is_empty() {
+                return Ok(vec![]);
+            }
+            let features =
+                feature_processor.compute_ablated_features(&&*tokens, disabled_families)?;
+            let tagger = tagger
+                .lock()
+                .map_err(|e| format_err!("Poisonous mutex: {}", e))?;
+            let encoded_tags = tagger.tag(&features)?;
+            let tags = encoded_tags
+                .iter()
+                .map(|tag| decode_tag(&*tag))
+                .collect::<Result<Vec<String>>>()?;
+
+            let mut slots = tags_to_slots(
+                text,
+                &tokens,
+                &tags,
+                self.tagging_scheme,
+                &self.slot_name_mapping,
+            )?;
+            if self.compute_confidence_scores {
+                // The marginals are computed on the sequence which has just been tagged
+                let marginals = encoded_tags
+                    .iter()
+                    .enumerate()
+                    .map(|(position, tag)| Ok(tagger.marginal(tag, position as i32)? as f32))
+                    .collect::<Result<Vec<f32>>>()?;
+                set_confidence_scores(&mut slots, &tokens, &marginals);
+            }
+            slots
+        } else {
+            vec![]
+        };
+        debug!("{} slots extracted", slots.len());
+        Ok(slots)
+    }
+
     pub fn compute_features(&self, text: &str) -> Result<Vec<Vec<(String, String)>>> {
-        let tokens = tokenize(text, NluUtilsLanguage::from_language(self.language));
+        let tokens = self.tokenize(text);
         if tokens.is_empty() {
             return Ok(vec![]);
         };
@@ -171,6 +340,24 @@ impl CRFSlotFiller {
 // We need to use base64 encoding to ensure ascii encoding because of encoding issues in
 // python-crfsuite
 
+/// Sets the confidence score of each slot to the lowest marginal probability of the tags of
+/// its tokens
+fn set_confidence_scores(slots: &mut [InternalSlot], tokens: &[Token], marginals: &[f32]) {
+    for slot in slots.iter_mut() {
+        slot.confidence_score = tokens
+            .iter()
+            .zip(marginals.iter())
+            .filter(|(token, _)| {
+                token.char_range.start >= slot.char_range.start
+                    && token.char_range.end <= slot.char_range.end
+            })
+            .map(|(_, marginal)| *marginal)
+            .fold(None, |min: Option<f32>, marginal| {
+                Some(min.map_or(marginal, |min| min.min(marginal)))
+            });
+    }
+}
+
 fn decode_tag(tag: &str) -> Result<String> {
     let bytes = base64::decode(tag)?;
     Ok(String::from_utf8(bytes)?)
@@ -210,7 +397,62 @@ mod tests {
             char_range: 8..11,
             entity: "snips/number".to_string(),
             slot_name: "number_of_cups".to_string(),
+            confidence_score: None,
         }];
         assert_eq!(expected_slots, slots);
     }
+
+    #[test]
+    fn test_get_slots_with_confidence_scores() {
+        // Given
+        let trained_engine_path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let slot_filler_path = trained_engine_path
+            .join("probabilistic_intent_parser")
+            .join("slot_filler_0");
+        let resources = load_engine_shared_resources(trained_engine_path).unwrap();
+        let slot_filler = CRFSlotFiller::from_path(slot_filler_path, resources)
+            .unwrap()
+            .compute_confidence_scores(true);
+
+        // When
+        let slots = slot_filler.get_slots("make me two cups of coffee").unwrap();
+
+        // Then
+        assert_eq!(1, slots.len());
+        let confidence_score = slots[0].confidence_score.unwrap();
+        assert!(confidence_score > 0.0 && confidence_score <= 1.0);
+    }
+    #[test]
+    fn test_get_slots_nbest() {
+        // Given
+        let trained_engine_path = Path::new("data")
+            .join("tests")
+            .join("models")
+            .join("nlu_engine_beverage");
+        let slot_filler_path = trained_engine_path
+            .join("probabilistic_intent_parser")
+            .join("slot_filler_0");
+        let resources = load_engine_shared_resources(trained_engine_path).unwrap();
+        let slot_filler = CRFSlotFiller::from_path(slot_filler_path, resources).unwrap();
+
+        // When
+        let hypotheses = slot_filler
+            .get_slots_nbest("make me two cups of coffee", 3)
+            .unwrap();
+
+        // Then
+        let best_slots = slot_filler.get_slots("make me two cups of coffee").unwrap();
+        assert!(hypotheses.len() > 1 && hypotheses.len() <= 3);
+        assert_eq!(best_slots, hypotheses[0].slots);
+        for (hypothesis, next_hypothesis) in hypotheses.iter().tuple_windows() {
+            assert!(hypothesis.probability >= next_hypothesis.probability);
+            assert_ne!(hypothesis.slots, next_hypothesis.slots);
+        }
+        assert!(hypotheses
+            .iter()
+            .all(|hypothesis| hypothesis.probability > 0.0 && hypothesis.probability <= 1.0));
+    }
 }
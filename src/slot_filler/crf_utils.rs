@@ -188,6 +188,7 @@ pub fn tags_to_slots(
                     .to_string(),
                 char_range: s.char_range,
                 slot_name: s.slot_name,
+                confidence_score: None,
             })
         })
         .collect()
@@ -263,6 +264,7 @@ mod tests {
                     value: "blue bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -278,6 +280,7 @@ mod tests {
                     value: "bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -288,6 +291,7 @@ mod tests {
                     value: "bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -301,6 +305,7 @@ mod tests {
                     value: "blue bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -317,6 +322,7 @@ mod tests {
                     value: "light blue bird blue bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -330,6 +336,7 @@ mod tests {
                     value: "bird birdy".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
         ];
@@ -380,6 +387,7 @@ mod tests {
                     value: "blue bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -395,6 +403,7 @@ mod tests {
                     value: "bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -405,6 +414,7 @@ mod tests {
                     value: "bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -418,6 +428,7 @@ mod tests {
                     value: "blue bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -435,12 +446,14 @@ mod tests {
                         value: "light blue bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                     InternalSlot {
                         char_range: 16..25,
                         value: "blue bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                 ],
             },
@@ -456,12 +469,14 @@ mod tests {
                         value: "bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                     InternalSlot {
                         char_range: 5..10,
                         value: "birdy".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                 ],
             },
@@ -480,12 +495,14 @@ mod tests {
                         value: "blue bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                     InternalSlot {
                         char_range: 14..24,
                         value: "white bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                 ],
             },
@@ -537,6 +554,7 @@ mod tests {
                     value: "blue bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -552,6 +570,7 @@ mod tests {
                     value: "bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -562,6 +581,7 @@ mod tests {
                     value: "bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -575,6 +595,7 @@ mod tests {
                     value: "blue bird".to_string(),
                     entity: slot_name.to_string(),
                     slot_name: slot_name.to_string(),
+                    confidence_score: None,
                 }],
             },
             Test {
@@ -592,12 +613,14 @@ mod tests {
                         value: "light blue bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                     InternalSlot {
                         char_range: 16..25,
                         value: "blue bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                 ],
             },
@@ -613,12 +636,14 @@ mod tests {
                         value: "bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                     InternalSlot {
                         char_range: 5..10,
                         value: "birdy".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                 ],
             },
@@ -637,18 +662,21 @@ mod tests {
                         value: "light bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                     InternalSlot {
                         char_range: 11..15,
                         value: "bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                     InternalSlot {
                         char_range: 16..25,
                         value: "blue bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                 ],
             },
@@ -665,18 +693,21 @@ mod tests {
                         value: "bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                     InternalSlot {
                         char_range: 5..9,
                         value: "bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                     InternalSlot {
                         char_range: 10..14,
                         value: "bird".to_string(),
                         entity: slot_name.to_string(),
                         slot_name: slot_name.to_string(),
+                        confidence_score: None,
                     },
                 ],
             },
@@ -33,10 +33,35 @@ impl ProbabilisticFeatureProcessor {
 }
 
 impl ProbabilisticFeatureProcessor {
-    #[rustfmt::skip]
     pub fn compute_features(&self, input: &&[Token]) -> Result<Vec<Vec<(String, String)>>> {
+        self.compute_ablated_features(input, &[])
+    }
+
+    /// Identifiers of the feature factories used by the processor, e.g. `"entity_match"`, in
+    /// the order of the configuration
+    pub fn feature_families(&self) -> Vec<&'static str> {
+        let mut families = vec![];
+        for offsetter in self.features_offsetters.iter() {
+            let family = offsetter.feature.feature_kind().identifier();
+            if !families.contains(&family) {
+                families.push(family);
+            }
+        }
+        families
+    }
+
+    /// Computes the features, except the ones of the disabled feature families
+    #[rustfmt::skip]
+    pub fn compute_ablated_features(
+        &self,
+        input: &&[Token],
+        disabled_families: &[&str],
+    ) -> Result<Vec<Vec<(String, String)>>> {
         let mut features = vec![vec![]; input.len()];
         for offsetter in self.features_offsetters.iter() {
+            if disabled_families.contains(&offsetter.feature.feature_kind().identifier()) {
+                continue;
+            }
             for i in 0..input.len() {
                 if let Some(value) = offsetter.feature.compute(input, i)? {
                     offsetter.offsets_with_name().iter().for_each(|&(offset, ref key)| {
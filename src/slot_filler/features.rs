@@ -114,29 +114,16 @@ impl Feature for NgramFeature {
         let n = parse_as_u64(args, "n")? as usize;
         let common_words_gazetteer_name = parse_as_opt_string(args, "common_words_gazetteer_name")?;
         let opt_common_words_gazetteer = if let Some(gazetteer_name) = common_words_gazetteer_name {
-            Some(
-                shared_resources
-                    .gazetteers
-                    .get(&gazetteer_name)
-                    .cloned()
-                    .ok_or_else(|| {
-                        format_err!(
-                            "Cannot find gazetteer '{}' in shared resources",
-                            gazetteer_name
-                        )
-                    })?,
-            )
+            shared_resources.check_resource(
+                shared_resources.gazetteers.get(&gazetteer_name).cloned(),
+                &format!("gazetteer '{}'", gazetteer_name),
+            )?
         } else {
             None
         };
         let use_stemming = parse_as_bool(args, "use_stemming")?;
         let opt_stemmer = if use_stemming {
-            Some(
-                shared_resources
-                    .stemmer
-                    .clone()
-                    .ok_or_else(|| format_err!("Cannot find stemmer in shared resources"))?,
-            )
+            shared_resources.check_resource(shared_resources.stemmer.clone(), "stemmer")?
         } else {
             None
         };
@@ -158,7 +145,7 @@ impl Feature for NgramFeature {
                 let stemmed_value = self
                     .opt_stemmer
                     .as_ref()
-                    .map(|stemmer| stemmer.stem(&normalize(&token.value)))
+                    .map(|stemmer| stemmer.stem(&normalize(&token.value)).into_owned())
                     .unwrap_or_else(|| normalize(&token.value));
                 if let Some(common_words_gazetteer) = self.opt_common_words_gazetteer.as_ref() {
                     if common_words_gazetteer.contains(&stemmed_value) {
@@ -282,12 +269,7 @@ impl Feature for CustomEntityMatchFeature {
         let tagging_scheme = TaggingScheme::from_u8(tagging_scheme_code)?;
         let use_stemming = parse_as_bool(args, "use_stemming")?;
         let opt_stemmer = if use_stemming {
-            Some(
-                shared_resources
-                    .stemmer
-                    .clone()
-                    .ok_or_else(|| format_err!("Cannot find stemmer in shared resources"))?,
-            )
+            shared_resources.check_resource(shared_resources.stemmer.clone(), "stemmer")?
         } else {
             None
         };
@@ -391,20 +373,20 @@ impl Feature for WordClusterFeature {
         shared_resources: Arc<SharedResources>,
     ) -> Result<Vec<Box<dyn Feature>>> {
         let cluster_name = parse_as_string(args, "cluster_name")?;
-        let word_clusterer = shared_resources
-            .word_clusterers
-            .get(&cluster_name)
-            .cloned()
-            .ok_or_else(|| {
-                format_err!(
-                    "Cannot find word clusters '{}' in shared resources",
-                    cluster_name
-                )
-            })?;
-        Ok(vec![Box::new(Self {
-            cluster_name,
-            word_clusterer,
-        })])
+        let word_clusterer = shared_resources.check_resource(
+            shared_resources.word_clusterers.get(&cluster_name).cloned(),
+            &format!("word clusters '{}'", cluster_name),
+        )?;
+        // The feature is dropped when the word clusters are missing
+        Ok(word_clusterer
+            .map(|word_clusterer| {
+                Box::new(Self {
+                    cluster_name,
+                    word_clusterer,
+                }) as Box<dyn Feature>
+            })
+            .into_iter()
+            .collect())
     }
 
     fn compute(&self, tokens: &[Token], token_index: usize) -> Result<Option<String>> {
@@ -420,9 +402,9 @@ fn transform_tokens(tokens: &[Token], stemmer: Option<Arc<dyn Stemmer>>) -> Vec<
     tokens
         .iter()
         .map(|t| {
-            let normalized_value = stemmer
-                .clone()
-                .map_or(normalize(&t.value), |s| s.stem(&normalize(&t.value)));
+            let normalized_value = stemmer.clone().map_or(normalize(&t.value), |s| {
+                s.stem(&normalize(&t.value)).into_owned()
+            });
             let char_range =
                 current_char_index..(current_char_index + normalized_value.chars().count());
             let byte_range = current_byte_index..(current_byte_index + normalized_value.len());
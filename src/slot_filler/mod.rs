@@ -1,5 +1,6 @@
 #[macro_use]
 mod macros;
+mod chunked_slot_filler;
 pub mod crf_slot_filler;
 mod crf_utils;
 mod feature_processor;
@@ -13,23 +14,51 @@ use std::sync::Arc;
 use failure::{format_err, ResultExt};
 use snips_nlu_utils::token::Token;
 
+use crate::config::ChunkingConfig;
 use crate::errors::*;
 use crate::models::ProcessingUnitMetadata;
 use crate::resources::SharedResources;
 use crate::slot_utils::InternalSlot;
 
+pub use self::chunked_slot_filler::ChunkedSlotFiller;
 pub use self::crf_slot_filler::*;
 use self::crf_utils::TaggingScheme;
 
+/// Set of slots corresponding to one of the most probable tag sequences of an input
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotsHypothesis {
+    pub slots: Vec<InternalSlot>,
+    /// Probability of the tag sequence from which the slots were extracted
+    pub probability: f64,
+}
+
 pub trait SlotFiller: Send + Sync {
     fn get_tagging_scheme(&self) -> TaggingScheme;
     fn get_slots(&self, text: &str) -> Result<Vec<InternalSlot>>;
     fn get_sequence_probability(&self, tokens: &[Token], tags: Vec<String>) -> Result<f64>;
+
+    /// Returns at most `n` hypotheses of slots, sorted by decreasing probability, the first
+    /// one being the slots returned by `get_slots`
+    ///
+    /// Slot fillers which cannot rank several hypotheses only return the best one.
+    fn get_slots_nbest(&self, text: &str, n: usize) -> Result<Vec<SlotsHypothesis>> {
+        if n == 0 {
+            return Ok(vec![]);
+        }
+        Ok(vec![SlotsHypothesis {
+            slots: self.get_slots(text)?,
+            probability: 1.0,
+        }])
+    }
 }
 
+/// Loads the slot filler, which extracts the slots of long inputs chunk by chunk when a
+/// chunking config is provided
 pub fn build_slot_filler<P: AsRef<Path>>(
     path: P,
     shared_resources: Arc<SharedResources>,
+    compute_confidence_scores: bool,
+    chunking: Option<&ChunkingConfig>,
 ) -> Result<Box<dyn SlotFiller>> {
     let metadata_path = path.as_ref().join("metadata.json");
     let metadata_file = File::open(&metadata_path).with_context(|_| {
@@ -42,7 +71,20 @@ pub fn build_slot_filler<P: AsRef<Path>>(
         .with_context(|_| "Cannot deserialize slot filler json data")?;
     match metadata {
         ProcessingUnitMetadata::CrfSlotFiller => {
-            Ok(Box::new(CRFSlotFiller::from_path(path, shared_resources)?) as _)
+            let slot_filler = CRFSlotFiller::from_path(path, shared_resources)?
+                .compute_confidence_scores(compute_confidence_scores);
+            match chunking {
+                Some(chunking_config) => {
+                    let language = slot_filler.language();
+                    let slot_filler = ChunkedSlotFiller::new(
+                        Box::new(slot_filler),
+                        language,
+                        chunking_config.clone(),
+                    )?;
+                    Ok(Box::new(slot_filler) as _)
+                }
+                None => Ok(Box::new(slot_filler) as _),
+            }
         }
         _ => Err(format_err!("{:?} is not a slot filler", metadata)),
     }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use snips_nlu_ontology::{BuiltinEntity, BuiltinEntityKind, Slot, SlotValue};
 
+use crate::config::SlotConflictResolution;
 use crate::entity_parser::{BuiltinEntityParser, CustomEntity, CustomEntityParser};
 use crate::errors::*;
 use crate::models::nlu_engine::Entity;
@@ -14,6 +15,9 @@ pub struct InternalSlot {
     pub char_range: Range<usize>,
     pub entity: EntityName,
     pub slot_name: SlotName,
+    /// Probability of the slot, only computed by the probabilistic slot filler when enabled
+    /// in the `EngineConfig`
+    pub confidence_score: Option<f32>,
 }
 
 pub fn resolve_builtin_slot(
@@ -86,6 +90,51 @@ pub fn resolve_custom_slot(
     Ok(resolved_slot)
 }
 
+/// Returns the resolved string value of a slot, when it corresponds to a custom entity or a
+/// builtin gazetteer entity
+pub fn slot_value_as_str(value: &SlotValue) -> Option<&str> {
+    match value {
+        SlotValue::Custom(string_value)
+        | SlotValue::MusicAlbum(string_value)
+        | SlotValue::MusicArtist(string_value)
+        | SlotValue::MusicTrack(string_value)
+        | SlotValue::City(string_value)
+        | SlotValue::Country(string_value)
+        | SlotValue::Region(string_value) => Some(&*string_value.value),
+        _ => None,
+    }
+}
+
+/// Adds the slots found by another parser to the ones of the parser which matched the intent,
+/// overlapping slots being resolved according to `conflict_resolution`
+pub fn merge_slots(
+    slots: Vec<InternalSlot>,
+    other_slots: Vec<InternalSlot>,
+    conflict_resolution: SlotConflictResolution,
+) -> Vec<InternalSlot> {
+    let overlaps = |a: &InternalSlot, b: &InternalSlot| {
+        a.char_range.start < b.char_range.end && b.char_range.start < a.char_range.end
+    };
+    let mut merged_slots = slots;
+    for other_slot in other_slots {
+        let mut conflicting_slots = merged_slots
+            .iter()
+            .filter(|slot| overlaps(slot, &other_slot));
+        let keeps_other_slot = match conflict_resolution {
+            SlotConflictResolution::MatchingParser => conflicting_slots.next().is_none(),
+            SlotConflictResolution::LongestSlot => {
+                conflicting_slots.all(|slot| slot.char_range.len() < other_slot.char_range.len())
+            }
+        };
+        if keeps_other_slot {
+            merged_slots.retain(|slot| !overlaps(slot, &other_slot));
+            merged_slots.push(other_slot);
+        }
+    }
+    merged_slots.sort_by_key(|slot| slot.char_range.start);
+    merged_slots
+}
+
 fn convert_to_custom_slot(
     slot: InternalSlot,
     resolved_value: String,
@@ -103,7 +152,7 @@ fn convert_to_custom_slot(
         range: slot.char_range,
         entity: slot.entity,
         slot_name: slot.slot_name,
-        confidence_score: None,
+        confidence_score: slot.confidence_score,
     }
 }
 
@@ -115,7 +164,7 @@ fn convert_to_builtin_slot(slot: InternalSlot, builtin_entity: BuiltinEntity) ->
         range: slot.char_range,
         entity: slot.entity,
         slot_name: slot.slot_name,
-        confidence_score: None,
+        confidence_score: slot.confidence_score,
     }
 }
 
@@ -129,6 +178,43 @@ mod tests {
     use std::iter::FromIterator;
     use std::path::Path;
 
+    #[test]
+    fn test_merge_slots() {
+        // Given
+        let slot = |value: &str, char_range: Range<usize>, slot_name: &str| InternalSlot {
+            value: value.to_string(),
+            char_range,
+            entity: "location".to_string(),
+            slot_name: slot_name.to_string(),
+            confidence_score: None,
+        };
+        let slots = vec![slot("paris", 13..18, "destination")];
+        let other_slots = vec![
+            slot("lyon", 5..9, "origin"),
+            slot("paris center", 13..25, "destination"),
+        ];
+
+        // When
+        let matching_parser_slots = merge_slots(
+            slots.clone(),
+            other_slots.clone(),
+            SlotConflictResolution::MatchingParser,
+        );
+        let longest_slots = merge_slots(slots, other_slots, SlotConflictResolution::LongestSlot);
+
+        // Then
+        let expected_matching_parser_slots = vec![
+            slot("lyon", 5..9, "origin"),
+            slot("paris", 13..18, "destination"),
+        ];
+        let expected_longest_slots = vec![
+            slot("lyon", 5..9, "origin"),
+            slot("paris center", 13..25, "destination"),
+        ];
+        assert_eq!(expected_matching_parser_slots, matching_parser_slots);
+        assert_eq!(expected_longest_slots, longest_slots);
+    }
+
     #[test]
     fn test_resolve_builtin_slot() {
         // Given
@@ -137,6 +223,7 @@ mod tests {
             char_range: 22..31,
             slot_name: "amount".to_string(),
             entity: "snips/amountOfMoney".to_string(),
+            confidence_score: None,
         };
         let builtin_entities = vec![
             BuiltinEntity {
@@ -194,6 +281,7 @@ mod tests {
             char_range: 5..14,
             slot_name: "amount".to_string(),
             entity: "snips/amountOfMoney".to_string(),
+            confidence_score: None,
         };
         let builtin_entities = vec![];
         let mocked_entity_parser = Arc::new(MockedBuiltinEntityParser::from_iter(vec![(
@@ -241,6 +329,7 @@ mod tests {
             char_range: 20..30,
             slot_name: "artist".to_string(),
             entity: "snips/musicArtist".to_string(),
+            confidence_score: None,
         };
         let builtin_entities = vec![BuiltinEntity {
             value: "the stones".to_string(),
@@ -280,6 +369,7 @@ mod tests {
             char_range: 27..37,
             entity: "userType".to_string(),
             slot_name: "userType".to_string(),
+            confidence_score: None,
         };
         let custom_entities = vec![
             CustomEntity {
@@ -333,6 +423,7 @@ mod tests {
             char_range: 27..37,
             entity: "userType".to_string(),
             slot_name: "userType".to_string(),
+            confidence_score: None,
         };
         let custom_entities = vec![];
         let mocked_entity_parser = Arc::new(MockedCustomEntityParser::from_iter(vec![(
@@ -380,6 +471,7 @@ mod tests {
             char_range: 27..37,
             entity: "userType".to_string(),
             slot_name: "userType".to_string(),
+            confidence_score: None,
         };
         let custom_entities = vec![];
         let mocked_entity_parser = Arc::new(MockedCustomEntityParser::from_iter(vec![]));
@@ -418,6 +510,7 @@ mod tests {
             char_range: 27..37,
             entity: "userType".to_string(),
             slot_name: "userType".to_string(),
+            confidence_score: None,
         };
         let custom_entities = vec![];
         let mocked_entity_parser = Arc::new(MockedCustomEntityParser::from_iter(vec![]));
@@ -448,6 +541,7 @@ mod tests {
             char_range: 27..37,
             entity: "userType".to_string(),
             slot_name: "userType".to_string(),
+            confidence_score: None,
         };
         let custom_entities = vec![
             CustomEntity {
@@ -501,6 +595,7 @@ mod tests {
             char_range: 10..17,
             entity: "game".to_string(),
             slot_name: "game".to_string(),
+            confidence_score: None,
         };
         let custom_entities = vec![];
         let parser_path = Path::new("data")
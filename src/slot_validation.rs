@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use snips_nlu_ontology::{Slot, SlotValue};
+
+use crate::utils::{EntityName, SlotName};
+
+/// Outcome of the validation of a slot
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlotValidation {
+    /// The slot is kept as is
+    Valid,
+    /// The slot is kept but its value is replaced
+    Rewrite(SlotValue),
+    /// The slot is removed from the parsing result
+    Reject { reason: String },
+}
+
+/// Check applied to the slots once they have been extracted and resolved
+pub trait SlotValidator: Send + Sync {
+    fn validate(&self, slot: &Slot) -> SlotValidation;
+}
+
+impl<F> SlotValidator for F
+where
+    F: Fn(&Slot) -> SlotValidation + Send + Sync,
+{
+    fn validate(&self, slot: &Slot) -> SlotValidation {
+        self(slot)
+    }
+}
+
+/// Slot which was rejected by a `SlotValidator`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedSlot {
+    pub slot: Slot,
+    pub reason: String,
+}
+
+/// Validators registered per slot name and per entity
+///
+/// Validators registered for the slot name of a slot run first, followed by the ones
+/// registered for its entity, and validation stops as soon as a validator rejects the slot.
+#[derive(Default)]
+pub struct SlotValidators {
+    slot_name_validators: HashMap<SlotName, Vec<Box<dyn SlotValidator>>>,
+    entity_validators: HashMap<EntityName, Vec<Box<dyn SlotValidator>>>,
+}
+
+impl SlotValidators {
+    pub fn add_slot_name_validator<V>(&mut self, slot_name: &str, validator: V)
+    where
+        V: SlotValidator + 'static,
+    {
+        self.slot_name_validators
+            .entry(slot_name.to_string())
+            .or_insert_with(|| vec![])
+            .push(Box::new(validator));
+    }
+
+    pub fn add_entity_validator<V>(&mut self, entity: &str, validator: V)
+    where
+        V: SlotValidator + 'static,
+    {
+        self.entity_validators
+            .entry(entity.to_string())
+            .or_insert_with(|| vec![])
+            .push(Box::new(validator));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slot_name_validators.is_empty() && self.entity_validators.is_empty()
+    }
+
+    /// Validates the slots, returning the accepted ones, possibly rewritten, and the rejected
+    /// ones
+    pub fn validate(&self, slots: Vec<Slot>) -> (Vec<Slot>, Vec<RejectedSlot>) {
+        let mut accepted_slots = Vec::with_capacity(slots.len());
+        let mut rejected_slots = vec![];
+        'slots: for mut slot in slots.into_iter() {
+            let validators = self
+                .slot_name_validators
+                .get(&slot.slot_name)
+                .into_iter()
+                .chain(self.entity_validators.get(&slot.entity))
+                .flatten();
+            for validator in validators {
+                match validator.validate(&slot) {
+                    SlotValidation::Valid => {}
+                    SlotValidation::Rewrite(value) => slot.value = value,
+                    SlotValidation::Reject { reason } => {
+                        rejected_slots.push(RejectedSlot { slot, reason });
+                        continue 'slots;
+                    }
+                }
+            }
+            accepted_slots.push(slot);
+        }
+        (accepted_slots, rejected_slots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snips_nlu_ontology::{NumberValue, StringValue};
+
+    fn volume_slot(volume: f64) -> Slot {
+        Slot {
+            raw_value: volume.to_string(),
+            value: SlotValue::Number(NumberValue { value: volume }),
+            alternatives: vec![],
+            range: 0..3,
+            entity: "snips/number".to_string(),
+            slot_name: "volume".to_string(),
+            confidence_score: None,
+        }
+    }
+
+    fn room_slot(room: &str) -> Slot {
+        Slot {
+            raw_value: room.to_string(),
+            value: SlotValue::Custom(StringValue {
+                value: room.to_string(),
+            }),
+            alternatives: vec![],
+            range: 4..(4 + room.len()),
+            entity: "room".to_string(),
+            slot_name: "room".to_string(),
+            confidence_score: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_slots() {
+        // Given
+        let mut validators = SlotValidators::default();
+        validators.add_slot_name_validator("volume", |slot: &Slot| match slot.value {
+            SlotValue::Number(NumberValue { value }) if value > 100.0 => {
+                SlotValidation::Rewrite(SlotValue::Number(NumberValue { value: 100.0 }))
+            }
+            _ => SlotValidation::Valid,
+        });
+        validators.add_entity_validator("room", |slot: &Slot| {
+            if slot.raw_value == "kitchen" {
+                SlotValidation::Valid
+            } else {
+                SlotValidation::Reject {
+                    reason: "unknown room".to_string(),
+                }
+            }
+        });
+        let slots = vec![
+            volume_slot(150.0),
+            room_slot("kitchen"),
+            room_slot("garage"),
+        ];
+
+        // When
+        let (accepted_slots, rejected_slots) = validators.validate(slots);
+
+        // Then
+        let mut expected_volume_slot = volume_slot(150.0);
+        expected_volume_slot.value = SlotValue::Number(NumberValue { value: 100.0 });
+        let expected_accepted_slots = vec![expected_volume_slot, room_slot("kitchen")];
+        let expected_rejected_slots = vec![RejectedSlot {
+            slot: room_slot("garage"),
+            reason: "unknown room".to_string(),
+        }];
+        assert_eq!(expected_accepted_slots, accepted_slots);
+        assert_eq!(expected_rejected_slots, rejected_slots);
+    }
+}
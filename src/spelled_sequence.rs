@@ -0,0 +1,311 @@
+use snips_nlu_ontology::{Language, Slot, SlotValue, StringValue};
+
+use crate::slot_validation::{SlotValidation, SlotValidator};
+
+const NATO_ALPHABET: [&str; 26] = [
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliett",
+    "kilo", "lima", "mike", "november", "oscar", "papa", "quebec", "romeo", "sierra", "tango",
+    "uniform", "victor", "whiskey", "xray", "yankee", "zulu",
+];
+
+/// Captures letter-by-letter spellings and digit sequences, such as "a as in alpha, b, 7, 9",
+/// into a single normalized string, here `AB79`
+///
+/// It is meant to be registered as a validator of the slots holding codes or spellings, whose
+/// values are then rewritten into custom string values. Slots which cannot be read as a spelled
+/// sequence are rejected.
+pub struct SpelledSequence {
+    vocabulary: SpellingVocabulary,
+}
+
+impl SpelledSequence {
+    pub fn new(language: Language) -> Self {
+        Self {
+            vocabulary: SpellingVocabulary::for_language(language),
+        }
+    }
+
+    /// Returns the normalized sequence, or `None` when the text contains words which are not
+    /// part of a spelling
+    pub fn parse(&self, text: &str) -> Option<String> {
+        let text = text.to_lowercase().replace(
+            |c: char| c == ',' || c == ';' || c == '.' || c == '-' || c == '/',
+            " ",
+        );
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let mut sequence = String::new();
+        let mut repetitions = 1;
+        let mut index = 0;
+        while index < words.len() {
+            let word = words[index];
+            index += 1;
+            if let Some(nb_repetitions) = self.vocabulary.repetitions(word) {
+                repetitions = nb_repetitions;
+                continue;
+            }
+            if self.vocabulary.fillers.contains(&word) {
+                continue;
+            }
+            let symbols = self.vocabulary.symbols(word)?;
+            for _ in 0..repetitions {
+                sequence.push_str(&symbols);
+            }
+            repetitions = 1;
+            // Skip the example word in "a as in alpha"
+            if let Some(nb_words) = self.vocabulary.example_marker_length(&words[index..]) {
+                if index + nb_words < words.len() {
+                    index += nb_words + 1;
+                }
+            }
+        }
+        if sequence.is_empty() {
+            None
+        } else {
+            Some(sequence)
+        }
+    }
+}
+
+impl SlotValidator for SpelledSequence {
+    fn validate(&self, slot: &Slot) -> SlotValidation {
+        match self.parse(&slot.raw_value) {
+            Some(sequence) => {
+                SlotValidation::Rewrite(SlotValue::Custom(StringValue::from(sequence.as_str())))
+            }
+            None => SlotValidation::Reject {
+                reason: format!("'{}' is not a spelled sequence", slot.raw_value),
+            },
+        }
+    }
+}
+
+/// Language specific words used when spelling, all in lowercase
+struct SpellingVocabulary {
+    /// Names of the digits from zero to nine, several names being allowed for each digit
+    digits: &'static [(&'static str, char)],
+    /// Words introducing the example word of a letter, e.g. "as in"
+    example_markers: &'static [&'static str],
+    /// Words repeating the following symbol, with the number of repetitions
+    repetition_words: &'static [(&'static str, usize)],
+    /// Words which are ignored, single letters being excluded as they may be spelled
+    fillers: &'static [&'static str],
+}
+
+impl SpellingVocabulary {
+    fn for_language(language: Language) -> Self {
+        match language {
+            Language::EN => Self {
+                digits: &[
+                    ("zero", '0'),
+                    ("oh", '0'),
+                    ("one", '1'),
+                    ("two", '2'),
+                    ("three", '3'),
+                    ("four", '4'),
+                    ("five", '5'),
+                    ("six", '6'),
+                    ("seven", '7'),
+                    ("eight", '8'),
+                    ("nine", '9'),
+                ],
+                example_markers: &["as in", "for", "like"],
+                repetition_words: &[("double", 2), ("triple", 3)],
+                fillers: &["and", "then"],
+            },
+            Language::FR => Self {
+                digits: &[
+                    ("zéro", '0'),
+                    ("un", '1'),
+                    ("deux", '2'),
+                    ("trois", '3'),
+                    ("quatre", '4'),
+                    ("cinq", '5'),
+                    ("six", '6'),
+                    ("sept", '7'),
+                    ("huit", '8'),
+                    ("neuf", '9'),
+                ],
+                example_markers: &["comme"],
+                repetition_words: &[("double", 2), ("triple", 3)],
+                fillers: &["et", "puis"],
+            },
+            Language::DE => Self {
+                digits: &[
+                    ("null", '0'),
+                    ("eins", '1'),
+                    ("zwei", '2'),
+                    ("zwo", '2'),
+                    ("drei", '3'),
+                    ("vier", '4'),
+                    ("fünf", '5'),
+                    ("sechs", '6'),
+                    ("sieben", '7'),
+                    ("acht", '8'),
+                    ("neun", '9'),
+                ],
+                example_markers: &["wie"],
+                repetition_words: &[("doppel", 2), ("doppelt", 2), ("dreifach", 3)],
+                fillers: &["und", "dann"],
+            },
+            Language::ES => Self {
+                digits: &[
+                    ("cero", '0'),
+                    ("uno", '1'),
+                    ("dos", '2'),
+                    ("tres", '3'),
+                    ("cuatro", '4'),
+                    ("cinco", '5'),
+                    ("seis", '6'),
+                    ("siete", '7'),
+                    ("ocho", '8'),
+                    ("nueve", '9'),
+                ],
+                example_markers: &["de", "como"],
+                repetition_words: &[("doble", 2), ("triple", 3)],
+                fillers: &["luego"],
+            },
+            Language::IT => Self {
+                digits: &[
+                    ("zero", '0'),
+                    ("uno", '1'),
+                    ("due", '2'),
+                    ("tre", '3'),
+                    ("quattro", '4'),
+                    ("cinque", '5'),
+                    ("sei", '6'),
+                    ("sette", '7'),
+                    ("otto", '8'),
+                    ("nove", '9'),
+                ],
+                example_markers: &["come"],
+                repetition_words: &[("doppia", 2), ("doppio", 2), ("tripla", 3), ("triplo", 3)],
+                fillers: &["poi"],
+            },
+            Language::PT_PT | Language::PT_BR => Self {
+                digits: &[
+                    ("zero", '0'),
+                    ("um", '1'),
+                    ("dois", '2'),
+                    ("três", '3'),
+                    ("quatro", '4'),
+                    ("cinco", '5'),
+                    ("seis", '6'),
+                    ("sete", '7'),
+                    ("oito", '8'),
+                    ("nove", '9'),
+                ],
+                example_markers: &["de", "como"],
+                repetition_words: &[("duplo", 2), ("triplo", 3)],
+                fillers: &["depois"],
+            },
+            Language::JA | Language::KO => Self {
+                digits: &[],
+                example_markers: &[],
+                repetition_words: &[],
+                fillers: &[],
+            },
+        }
+    }
+
+    fn repetitions(&self, word: &str) -> Option<usize> {
+        self.repetition_words
+            .iter()
+            .find(|(repetition_word, _)| *repetition_word == word)
+            .map(|(_, nb_repetitions)| *nb_repetitions)
+    }
+
+    /// Normalized symbols spelled by the word, which is either a single letter, a sequence of
+    /// digits, the name of a digit or a word of the NATO phonetic alphabet
+    fn symbols(&self, word: &str) -> Option<String> {
+        let mut chars = word.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_alphanumeric() => return Some(c.to_uppercase().collect()),
+            _ => {}
+        }
+        if word.chars().all(|c| c.is_ascii_digit()) {
+            return Some(word.to_string());
+        }
+        if let Some((_, digit)) = self.digits.iter().find(|(name, _)| *name == word) {
+            return Some(digit.to_string());
+        }
+        NATO_ALPHABET
+            .iter()
+            .find(|letter_name| **letter_name == word)
+            .and_then(|letter_name| letter_name.chars().next())
+            .map(|letter| letter.to_uppercase().collect())
+    }
+
+    /// Number of words of the example marker starting the words, if any
+    fn example_marker_length(&self, words: &[&str]) -> Option<usize> {
+        self.example_markers
+            .iter()
+            .map(|marker| marker.split_whitespace().collect::<Vec<_>>())
+            .filter(|marker_words| words.starts_with(marker_words))
+            .map(|marker_words| marker_words.len())
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spelled_sequence() {
+        // Given
+        let spelled_sequence = SpelledSequence::new(Language::EN);
+
+        // When
+        let sequences: Vec<Option<String>> = vec![
+            "a as in alpha, b, 7, 9",
+            "Charlie Delta 42",
+            "double five nine oh three",
+            "b for bravo and c like charlie",
+            "play some music",
+        ]
+        .into_iter()
+        .map(|text| spelled_sequence.parse(text))
+        .collect();
+
+        // Then
+        let expected_sequences = vec![
+            Some("AB79".to_string()),
+            Some("CD42".to_string()),
+            Some("55903".to_string()),
+            Some("BC".to_string()),
+            None,
+        ];
+        assert_eq!(expected_sequences, sequences);
+    }
+
+    #[test]
+    fn test_spelled_sequence_validator() {
+        // Given
+        let spelled_sequence = SpelledSequence::new(Language::FR);
+        let slot = |raw_value: &str| Slot {
+            raw_value: raw_value.to_string(),
+            value: SlotValue::Custom(StringValue::from(raw_value)),
+            alternatives: vec![],
+            range: 0..raw_value.chars().count(),
+            entity: "code".to_string(),
+            slot_name: "pairing_code".to_string(),
+            confidence_score: None,
+        };
+
+        // When
+        let valid_result = spelled_sequence.validate(&slot("x comme xavier, trois, 4"));
+        let invalid_result = spelled_sequence.validate(&slot("bonjour"));
+
+        // Then
+        assert_eq!(
+            SlotValidation::Rewrite(SlotValue::Custom(StringValue::from("X34"))),
+            valid_result
+        );
+        assert_eq!(
+            SlotValidation::Reject {
+                reason: "'bonjour' is not a spelled sequence".to_string()
+            },
+            invalid_result
+        );
+    }
+}
@@ -10,7 +10,7 @@ use crate::errors::*;
 use crate::resources::gazetteer::Gazetteer;
 use crate::resources::stemmer::Stemmer;
 use crate::resources::word_clusterer::WordClusterer;
-use crate::resources::SharedResources;
+use crate::resources::{MissingResourcePolicy, SharedResources};
 
 pub fn assert_epsilon_eq_array1(a: &Array1<f32>, b: &Array1<f32>, epsilon: f32) {
     assert_eq!(a.dim(), b.dim());
@@ -70,6 +70,7 @@ impl SharedResourcesBuilder {
             stemmer: self.stemmer,
             word_clusterers: self.word_clusterers,
             stop_words: self.stop_words,
+            missing_resource_policy: MissingResourcePolicy::Fail,
         }
     }
 }
@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+use serde::Serialize;
+use snips_nlu_ontology::Slot;
+use unicode_normalization::char::is_combining_mark;
+
+/// Unit in which the ranges of the slots are expressed
+///
+/// Ranges are counted in Unicode scalar values by default, which differs from the native
+/// string indices of most languages as soon as the input contains non-ASCII characters:
+/// Rust and C index strings by UTF-8 bytes, whereas Java, JavaScript and C# index them by
+/// UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeUnit {
+    /// Unicode scalar values, i.e. Rust `char`s
+    Char,
+    /// Bytes of the UTF-8 encoding of the input
+    Byte,
+    /// Code units of the UTF-16 encoding of the input
+    Utf16,
+}
+
+impl Default for RangeUnit {
+    fn default() -> Self {
+        RangeUnit::Char
+    }
+}
+
+impl RangeUnit {
+    /// Converts a char range of the text into this unit
+    pub fn convert(&self, text: &str, char_range: &Range<usize>) -> Range<usize> {
+        match self {
+            RangeUnit::Char => char_range.clone(),
+            RangeUnit::Byte => {
+                char_offset_to_unit(text, char_range.start, char::len_utf8)
+                    ..char_offset_to_unit(text, char_range.end, char::len_utf8)
+            }
+            RangeUnit::Utf16 => {
+                char_offset_to_unit(text, char_range.start, char::len_utf16)
+                    ..char_offset_to_unit(text, char_range.end, char::len_utf16)
+            }
+        }
+    }
+}
+
+/// Range of a text fragment expressed in all the supported units, so that every consumer can
+/// use its native string indices
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextRanges {
+    pub chars: Range<usize>,
+    pub bytes: Range<usize>,
+    pub utf16: Range<usize>,
+}
+
+impl TextRanges {
+    pub fn new(text: &str, char_range: &Range<usize>) -> Self {
+        Self {
+            chars: char_range.clone(),
+            bytes: RangeUnit::Byte.convert(text, char_range),
+            utf16: RangeUnit::Utf16.convert(text, char_range),
+        }
+    }
+}
+
+/// Extends a char range so that it does not split a character from its combining marks,
+/// e.g. an "e" followed by a combining acute accent
+pub fn grapheme_char_range(text: &str, char_range: &Range<usize>) -> Range<usize> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut start = char_range.start.min(chars.len());
+    let mut end = char_range.end.min(chars.len()).max(start);
+    while start > 0 && start < chars.len() && is_combining_mark(chars[start]) {
+        start -= 1;
+    }
+    while end < chars.len() && end > 0 && is_combining_mark(chars[end]) {
+        end += 1;
+    }
+    start..end
+}
+
+/// Aligns the ranges of the slots on grapheme boundaries, updating their raw values
+/// accordingly, and expresses them in the provided unit
+pub(crate) fn convert_slots_ranges(text: &str, slots: &mut [Slot], unit: RangeUnit) {
+    for slot in slots.iter_mut() {
+        let char_range = grapheme_char_range(text, &slot.range);
+        if char_range != slot.range {
+            slot.raw_value = text
+                .chars()
+                .skip(char_range.start)
+                .take(char_range.end - char_range.start)
+                .collect();
+        }
+        slot.range = unit.convert(text, &char_range);
+    }
+}
+
+fn char_offset_to_unit<F: Fn(char) -> usize>(text: &str, char_offset: usize, unit_len: F) -> usize {
+    text.chars().take(char_offset).map(unit_len).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use snips_nlu_ontology::SlotValue;
+
+    use super::*;
+
+    #[test]
+    fn test_text_ranges() {
+        // Given
+        let text = "café 🍵 tea";
+
+        // When
+        let ranges = TextRanges::new(text, &(7..10));
+
+        // Then
+        let expected_ranges = TextRanges {
+            chars: 7..10,
+            bytes: 11..14,
+            utf16: 8..11,
+        };
+        assert_eq!(expected_ranges, ranges);
+    }
+
+    #[test]
+    fn test_convert_slots_ranges_on_grapheme_boundaries() {
+        // Given
+        let text = "to cafe\u{301} now";
+        let mut slots = vec![Slot {
+            raw_value: "cafe".to_string(),
+            value: SlotValue::Custom("cafe".into()),
+            alternatives: vec![],
+            range: 3..7,
+            entity: "place".to_string(),
+            slot_name: "place".to_string(),
+            confidence_score: None,
+        }];
+
+        // When
+        convert_slots_ranges(text, &mut slots, RangeUnit::Byte);
+
+        // Then
+        assert_eq!("cafe\u{301}", slots[0].raw_value);
+        assert_eq!(3..9, slots[0].range);
+    }
+}
@@ -0,0 +1,127 @@
+use std::fmt;
+use std::sync::Arc;
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::errors::*;
+
+/// Threads used by the engine for the operations which run in parallel
+///
+/// Voice assistants often share a small number of cores with audio processing, so embedders
+/// can either restrict the engine to a dedicated pool, optionally pinned to specific cores, or
+/// supply a pool of their own.
+#[derive(Clone)]
+pub enum ThreadPoolConfig {
+    /// Parallel operations run on the global rayon pool
+    Global,
+    /// Parallel operations run on a pool created for the engine
+    Dedicated {
+        nb_threads: usize,
+        /// Indices of the cores to which the worker threads are pinned in a round-robin
+        /// fashion, threads are not pinned when empty
+        pinned_cores: Vec<usize>,
+    },
+    /// Parallel operations run on a pool supplied by the embedder
+    Custom(Arc<ThreadPool>),
+}
+
+impl Default for ThreadPoolConfig {
+    fn default() -> Self {
+        ThreadPoolConfig::Global
+    }
+}
+
+impl fmt::Debug for ThreadPoolConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThreadPoolConfig::Global => write!(f, "Global"),
+            ThreadPoolConfig::Dedicated {
+                nb_threads,
+                pinned_cores,
+            } => f
+                .debug_struct("Dedicated")
+                .field("nb_threads", nb_threads)
+                .field("pinned_cores", pinned_cores)
+                .finish(),
+            ThreadPoolConfig::Custom(pool) => {
+                write!(f, "Custom({} threads)", pool.current_num_threads())
+            }
+        }
+    }
+}
+
+impl PartialEq for ThreadPoolConfig {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ThreadPoolConfig::Global, ThreadPoolConfig::Global) => true,
+            (
+                ThreadPoolConfig::Dedicated {
+                    nb_threads,
+                    pinned_cores,
+                },
+                ThreadPoolConfig::Dedicated {
+                    nb_threads: other_nb_threads,
+                    pinned_cores: other_pinned_cores,
+                },
+            ) => nb_threads == other_nb_threads && pinned_cores == other_pinned_cores,
+            (ThreadPoolConfig::Custom(pool), ThreadPoolConfig::Custom(other_pool)) => {
+                Arc::ptr_eq(pool, other_pool)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ThreadPoolConfig {
+    /// Returns the pool to use, or `None` when the global rayon pool must be used
+    pub(crate) fn build(&self) -> Result<Option<Arc<ThreadPool>>> {
+        match self {
+            ThreadPoolConfig::Global => Ok(None),
+            ThreadPoolConfig::Dedicated {
+                nb_threads,
+                pinned_cores,
+            } => {
+                let pinned_cores = pinned_cores.clone();
+                let pool = ThreadPoolBuilder::new()
+                    .num_threads(*nb_threads)
+                    .thread_name(|index| format!("snips-nlu-worker-{}", index))
+                    .start_handler(move |index| {
+                        if !pinned_cores.is_empty() {
+                            let id = pinned_cores[index % pinned_cores.len()];
+                            core_affinity::set_for_current(core_affinity::CoreId { id });
+                        }
+                    })
+                    .build()?;
+                Ok(Some(Arc::new(pool)))
+            }
+            ThreadPoolConfig::Custom(pool) => Ok(Some(pool.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_thread_pool() {
+        // Given
+        let custom_pool = Arc::new(ThreadPoolBuilder::new().num_threads(1).build().unwrap());
+        let dedicated_config = ThreadPoolConfig::Dedicated {
+            nb_threads: 2,
+            pinned_cores: vec![0],
+        };
+
+        // When
+        let global_pool = ThreadPoolConfig::Global.build().unwrap();
+        let dedicated_pool = dedicated_config.build().unwrap();
+        let supplied_pool = ThreadPoolConfig::Custom(custom_pool.clone())
+            .build()
+            .unwrap();
+
+        // Then
+        assert!(global_pool.is_none());
+        assert_eq!(2, dedicated_pool.unwrap().current_num_threads());
+        assert!(Arc::ptr_eq(&custom_pool, &supplied_pool.unwrap()));
+    }
+}
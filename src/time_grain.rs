@@ -0,0 +1,597 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use snips_nlu_ontology::{Grain, InstantTimeValue, Slot, SlotValue, TimeIntervalValue};
+
+/// How instant time values which are finer than the requested grain are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeGrainMode {
+    /// The instant is truncated to the beginning of the grain, e.g. `2019-06-20 18:30:00`
+    /// becomes `2019-06-20 00:00:00` with a day grain
+    Round,
+    /// The instant is replaced by the time interval covering the whole grain, e.g.
+    /// `2019-06-20 18:30:00` becomes `[2019-06-20 00:00:00, 2019-06-21 00:00:00)` with a day
+    /// grain
+    Expand,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeGrainConfig {
+    pub grain: Grain,
+    pub mode: TimeGrainMode,
+}
+
+/// Returns the grain of time slot values
+pub fn time_grain(value: &SlotValue) -> Option<Grain> {
+    match value {
+        SlotValue::InstantTime(instant) => Some(instant.grain),
+        _ => None,
+    }
+}
+
+/// Coarsens the instant time value to the configured grain, returns `None` when the value is
+/// left unchanged
+///
+/// Values which are already coarser than the configured grain, or which cannot be parsed, are
+/// left unchanged.
+pub fn apply_time_grain(value: &SlotValue, config: &TimeGrainConfig) -> Option<SlotValue> {
+    let instant = match value {
+        SlotValue::InstantTime(instant) => instant,
+        _ => return None,
+    };
+    if grain_rank(instant.grain) <= grain_rank(config.grain) {
+        return None;
+    }
+    let start = DateTime::parse(&instant.value)?.truncate(config.grain);
+    Some(match config.mode {
+        TimeGrainMode::Round => SlotValue::InstantTime(InstantTimeValue {
+            value: start.to_string(),
+            grain: config.grain,
+            precision: instant.precision,
+        }),
+        TimeGrainMode::Expand => SlotValue::TimeInterval(TimeIntervalValue {
+            from: Some(start.to_string()),
+            to: Some(start.add_grain(config.grain).to_string()),
+        }),
+    })
+}
+
+/// Time value expressed relatively to a reference time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelativeTime {
+    /// Signed ISO 8601 duration between the reference time and the value, expressed in the
+    /// grain of the value, e.g. `+PT2H` or `-P1D`
+    pub offset: String,
+    /// Day of the week of values having a day grain, e.g. `MON`
+    pub weekday: Option<String>,
+}
+
+/// Expresses an instant time value relatively to the reference unix timestamp
+///
+/// Both times are truncated to the grain of the value before being compared, so that "in two
+/// hours" yields `+PT2H` whatever the current minute is.
+pub fn relative_time(value: &SlotValue, reference_timestamp: i64) -> Option<RelativeTime> {
+    let instant = match value {
+        SlotValue::InstantTime(instant) => instant,
+        _ => return None,
+    };
+    let grain = instant.grain;
+    let value_datetime = DateTime::parse(&instant.value)?.truncate(grain);
+    let reference_datetime =
+        DateTime::from_timestamp(reference_timestamp, &value_datetime.utc_offset)?.truncate(grain);
+    let offset = match grain {
+        Grain::Year | Grain::Quarter | Grain::Month => {
+            let nb_months = (value_datetime.year * 12 + value_datetime.month)
+                - (reference_datetime.year * 12 + reference_datetime.month);
+            match grain {
+                Grain::Year => format_duration(nb_months / 12, "P", "Y"),
+                _ => format_duration(nb_months, "P", "M"),
+            }
+        }
+        Grain::Week | Grain::Day => {
+            let nb_days = value_datetime.days() - reference_datetime.days();
+            match grain {
+                Grain::Week => format_duration(nb_days / 7, "P", "W"),
+                _ => format_duration(nb_days, "P", "D"),
+            }
+        }
+        Grain::Hour | Grain::Minute | Grain::Second => {
+            let nb_seconds = value_datetime.seconds() - reference_datetime.seconds();
+            match grain {
+                Grain::Hour => format_duration(nb_seconds / 3600, "PT", "H"),
+                Grain::Minute => format_duration(nb_seconds / 60, "PT", "M"),
+                _ => format_duration(nb_seconds, "PT", "S"),
+            }
+        }
+    };
+    let weekday = if grain == Grain::Day {
+        let weekdays = ["MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
+        Some(weekdays[(value_datetime.days() + 3).rem_euclid(7) as usize].to_string())
+    } else {
+        None
+    };
+    Some(RelativeTime { offset, weekday })
+}
+
+/// Resolution of ambiguous dates, such as "on monday" or "at 5"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimePreference {
+    /// Resolve to the earliest value which is not over yet
+    PreferFuture,
+    /// Resolve to the latest value which has already started
+    PreferPast,
+    /// Resolve to the value closest to the reference time
+    Nearest,
+}
+
+/// Selects, among the value and the alternative resolutions of an instant time slot, the one
+/// which best matches the preference relatively to the reference unix timestamp
+///
+/// The previous value of the slot takes the place of the selected one in the alternatives.
+/// Slots which are not instant times, or which have no matching resolution, are left
+/// unchanged.
+pub fn apply_time_preference(
+    slot: &mut Slot,
+    preference: TimePreference,
+    reference_timestamp: i64,
+) {
+    let candidates: Vec<Option<(i64, i64)>> = Some(&slot.value)
+        .into_iter()
+        .chain(slot.alternatives.iter())
+        .map(instant_time_bounds)
+        .collect();
+    if candidates[0].is_none() {
+        return;
+    }
+    let best_candidate = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, bounds)| bounds.map(|(start, end)| (index, start, end)))
+        .filter_map(|(index, start, end)| {
+            let score = match preference {
+                TimePreference::PreferFuture if end > reference_timestamp => start,
+                TimePreference::PreferPast if start <= reference_timestamp => -start,
+                TimePreference::Nearest if reference_timestamp < start => {
+                    start - reference_timestamp
+                }
+                TimePreference::Nearest if reference_timestamp >= end => {
+                    reference_timestamp - end + 1
+                }
+                TimePreference::Nearest => 0,
+                _ => return None,
+            };
+            Some((index, score))
+        })
+        .min_by_key(|(_, score)| *score)
+        .map(|(index, _)| index);
+    if let Some(index) = best_candidate {
+        if index > 0 {
+            std::mem::swap(&mut slot.value, &mut slot.alternatives[index - 1]);
+        }
+    }
+}
+
+/// Unix timestamps of the start and the end of an instant time value, given its grain
+fn instant_time_bounds(value: &SlotValue) -> Option<(i64, i64)> {
+    let instant = match value {
+        SlotValue::InstantTime(instant) => instant,
+        _ => return None,
+    };
+    let start = DateTime::parse(&instant.value)?.truncate(instant.grain);
+    let end = start.add_grain(instant.grain);
+    Some((start.timestamp()?, end.timestamp()?))
+}
+
+fn format_duration(amount: i64, prefix: &str, unit: &str) -> String {
+    let sign = if amount < 0 { "-" } else { "+" };
+    format!("{}{}{}{}", sign, prefix, amount.abs(), unit)
+}
+
+fn grain_rank(grain: Grain) -> u8 {
+    match grain {
+        Grain::Year => 0,
+        Grain::Quarter => 1,
+        Grain::Month => 2,
+        Grain::Week => 3,
+        Grain::Day => 4,
+        Grain::Hour => 5,
+        Grain::Minute => 6,
+        Grain::Second => 7,
+    }
+}
+
+/// Datetime in the format used by the builtin entity parser, e.g. `2019-06-20 18:30:00 +02:00`
+#[derive(Debug, Clone, PartialEq)]
+struct DateTime {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    utc_offset: String,
+}
+
+impl DateTime {
+    fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split_whitespace();
+        let date: Vec<i64> = parts
+            .next()?
+            .split('-')
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        let time: Vec<i64> = parts
+            .next()?
+            .split(':')
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        let utc_offset = parts.next()?.to_string();
+        if date.len() != 3 || time.len() != 3 || parts.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            year: date[0],
+            month: date[1],
+            day: date[2],
+            hour: time[0],
+            minute: time[1],
+            second: time[2],
+            utc_offset,
+        })
+    }
+
+    /// Builds the datetime corresponding to the unix timestamp, in the provided UTC offset
+    fn from_timestamp(timestamp: i64, utc_offset: &str) -> Option<Self> {
+        let mut datetime = Self {
+            year: 1970,
+            month: 1,
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            utc_offset: utc_offset.to_string(),
+        };
+        datetime.add_seconds(timestamp + parse_utc_offset(utc_offset)?);
+        Some(datetime)
+    }
+
+    /// Number of days since 1970-01-01, ignoring the UTC offset
+    fn days(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
+    }
+
+    /// Number of seconds since 1970-01-01 00:00:00, ignoring the UTC offset
+    fn seconds(&self) -> i64 {
+        self.days() * 86400 + self.hour * 3600 + self.minute * 60 + self.second
+    }
+
+    /// Unix timestamp of the datetime, taking the UTC offset into account
+    fn timestamp(&self) -> Option<i64> {
+        Some(self.seconds() - parse_utc_offset(&self.utc_offset)?)
+    }
+
+    fn truncate(&self, grain: Grain) -> Self {
+        let mut truncated = self.clone();
+        let rank = grain_rank(grain);
+        if rank < grain_rank(Grain::Second) {
+            truncated.second = 0;
+        }
+        if rank < grain_rank(Grain::Minute) {
+            truncated.minute = 0;
+        }
+        if rank < grain_rank(Grain::Hour) {
+            truncated.hour = 0;
+        }
+        match grain {
+            Grain::Week => {
+                // Weeks start on monday, and 1970-01-01 was a thursday
+                let days = days_from_civil(self.year, self.month, self.day);
+                let days_since_monday = (days + 3).rem_euclid(7);
+                truncated.set_days(days - days_since_monday);
+            }
+            Grain::Month => truncated.day = 1,
+            Grain::Quarter => {
+                truncated.day = 1;
+                truncated.month = (self.month - 1) / 3 * 3 + 1;
+            }
+            Grain::Year => {
+                truncated.day = 1;
+                truncated.month = 1;
+            }
+            _ => {}
+        }
+        truncated
+    }
+
+    fn add_grain(&self, grain: Grain) -> Self {
+        let mut result = self.clone();
+        let days = days_from_civil(self.year, self.month, self.day);
+        match grain {
+            Grain::Year => result.year += 1,
+            Grain::Quarter => result.add_months(3),
+            Grain::Month => result.add_months(1),
+            Grain::Week => result.set_days(days + 7),
+            Grain::Day => result.set_days(days + 1),
+            Grain::Hour => result.add_seconds(3600),
+            Grain::Minute => result.add_seconds(60),
+            Grain::Second => result.add_seconds(1),
+        }
+        result
+    }
+
+    fn set_days(&mut self, days: i64) {
+        let (year, month, day) = civil_from_days(days);
+        self.year = year;
+        self.month = month;
+        self.day = day;
+    }
+
+    fn add_months(&mut self, nb_months: i64) {
+        let months = self.year * 12 + self.month - 1 + nb_months;
+        self.year = months.div_euclid(12);
+        self.month = months.rem_euclid(12) + 1;
+    }
+
+    fn add_seconds(&mut self, nb_seconds: i64) {
+        let seconds = days_from_civil(self.year, self.month, self.day) * 86400
+            + self.hour * 3600
+            + self.minute * 60
+            + self.second
+            + nb_seconds;
+        self.set_days(seconds.div_euclid(86400));
+        let seconds_in_day = seconds.rem_euclid(86400);
+        self.hour = seconds_in_day / 3600;
+        self.minute = seconds_in_day % 3600 / 60;
+        self.second = seconds_in_day % 60;
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02} {}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second, self.utc_offset
+        )
+    }
+}
+
+/// Parses UTC offsets like `+02:00` into a number of seconds
+fn parse_utc_offset(utc_offset: &str) -> Option<i64> {
+    let (sign, offset) = match utc_offset.chars().next()? {
+        '+' => (1, &utc_offset[1..]),
+        '-' => (-1, &utc_offset[1..]),
+        _ => return None,
+    };
+    let mut parts = offset.split(':');
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = parts.next().map(|m| m.parse().ok()).unwrap_or(Some(0))?;
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Number of days since 1970-01-01 in the proleptic gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snips_nlu_ontology::Precision;
+
+    fn instant(value: &str, grain: Grain) -> SlotValue {
+        SlotValue::InstantTime(InstantTimeValue {
+            value: value.to_string(),
+            grain,
+            precision: Precision::Exact,
+        })
+    }
+
+    #[test]
+    fn test_civil_days_conversion() {
+        assert_eq!(0, days_from_civil(1970, 1, 1));
+        assert_eq!(18067, days_from_civil(2019, 6, 20));
+        assert_eq!((2019, 6, 20), civil_from_days(18067));
+        assert_eq!((2020, 2, 29), civil_from_days(days_from_civil(2020, 2, 29)));
+        assert_eq!((1969, 12, 31), civil_from_days(-1));
+    }
+
+    #[test]
+    fn test_round_time_grain() {
+        // Given
+        let value = instant("2019-06-20 18:30:15 +02:00", Grain::Second);
+        let round = |grain| TimeGrainConfig {
+            grain,
+            mode: TimeGrainMode::Round,
+        };
+
+        // When
+        let rounded_values: Vec<Option<SlotValue>> = vec![
+            Grain::Minute,
+            Grain::Day,
+            Grain::Week,
+            Grain::Month,
+            Grain::Quarter,
+            Grain::Year,
+        ]
+        .into_iter()
+        .map(|grain| apply_time_grain(&value, &round(grain)))
+        .collect();
+
+        // Then
+        let expected_values = vec![
+            Some(instant("2019-06-20 18:30:00 +02:00", Grain::Minute)),
+            Some(instant("2019-06-20 00:00:00 +02:00", Grain::Day)),
+            Some(instant("2019-06-17 00:00:00 +02:00", Grain::Week)),
+            Some(instant("2019-06-01 00:00:00 +02:00", Grain::Month)),
+            Some(instant("2019-04-01 00:00:00 +02:00", Grain::Quarter)),
+            Some(instant("2019-01-01 00:00:00 +02:00", Grain::Year)),
+        ];
+        assert_eq!(expected_values, rounded_values);
+    }
+
+    #[test]
+    fn test_expand_time_grain() {
+        // Given
+        let value = instant("2019-12-31 18:30:00 +02:00", Grain::Minute);
+        let expand = |grain| TimeGrainConfig {
+            grain,
+            mode: TimeGrainMode::Expand,
+        };
+
+        // When
+        let day_interval = apply_time_grain(&value, &expand(Grain::Day));
+        let month_interval = apply_time_grain(&value, &expand(Grain::Month));
+        let hour_interval = apply_time_grain(&value, &expand(Grain::Hour));
+
+        // Then
+        let interval = |from: &str, to: &str| {
+            Some(SlotValue::TimeInterval(TimeIntervalValue {
+                from: Some(from.to_string()),
+                to: Some(to.to_string()),
+            }))
+        };
+        assert_eq!(
+            interval("2019-12-31 00:00:00 +02:00", "2020-01-01 00:00:00 +02:00"),
+            day_interval
+        );
+        assert_eq!(
+            interval("2019-12-01 00:00:00 +02:00", "2020-01-01 00:00:00 +02:00"),
+            month_interval
+        );
+        assert_eq!(
+            interval("2019-12-31 18:00:00 +02:00", "2019-12-31 19:00:00 +02:00"),
+            hour_interval
+        );
+    }
+
+    #[test]
+    fn test_apply_time_grain_should_not_refine_values() {
+        // Given
+        let value = instant("2019-06-01 00:00:00 +02:00", Grain::Month);
+        let config = TimeGrainConfig {
+            grain: Grain::Day,
+            mode: TimeGrainMode::Round,
+        };
+
+        // When
+        let result = apply_time_grain(&value, &config);
+
+        // Then
+        assert_eq!(None, result);
+    }
+
+    #[test]
+    fn test_relative_time() {
+        // Given
+        // 2019-06-20 12:34:56 UTC, which is a thursday
+        let reference_timestamp = 1_561_034_096;
+        let in_two_hours = instant("2019-06-20 16:00:00 +02:00", Grain::Hour);
+        let tomorrow = instant("2019-06-21 00:00:00 +02:00", Grain::Day);
+        let next_monday = instant("2019-06-24 00:00:00 +02:00", Grain::Day);
+        let last_month = instant("2019-05-01 00:00:00 +02:00", Grain::Month);
+
+        // When
+        let relative_times: Vec<Option<RelativeTime>> =
+            vec![in_two_hours, tomorrow, next_monday, last_month]
+                .iter()
+                .map(|value| relative_time(value, reference_timestamp))
+                .collect();
+
+        // Then
+        let relative = |offset: &str, weekday: Option<&str>| {
+            Some(RelativeTime {
+                offset: offset.to_string(),
+                weekday: weekday.map(|w| w.to_string()),
+            })
+        };
+        let expected_relative_times = vec![
+            relative("+PT2H", None),
+            relative("+P1D", Some("FRI")),
+            relative("+P4D", Some("MON")),
+            relative("-P1M", None),
+        ];
+        assert_eq!(expected_relative_times, relative_times);
+    }
+
+    #[test]
+    fn test_apply_time_preference() {
+        // Given
+        // 2019-06-20 12:34:56 UTC, which is a thursday
+        let reference_timestamp = 1_561_034_096;
+        let last_monday = instant("2019-06-17 00:00:00 +02:00", Grain::Day);
+        let next_monday = instant("2019-06-24 00:00:00 +02:00", Grain::Day);
+        let monday_slot = Slot {
+            raw_value: "monday".to_string(),
+            value: next_monday.clone(),
+            alternatives: vec![last_monday.clone()],
+            range: 0..6,
+            entity: "snips/datetime".to_string(),
+            slot_name: "date".to_string(),
+            confidence_score: None,
+        };
+        let five_am = instant("2019-06-20 05:00:00 +02:00", Grain::Hour);
+        let five_pm = instant("2019-06-20 17:00:00 +02:00", Grain::Hour);
+        let five_slot = Slot {
+            raw_value: "five".to_string(),
+            value: five_pm.clone(),
+            alternatives: vec![five_am.clone()],
+            range: 0..4,
+            entity: "snips/datetime".to_string(),
+            slot_name: "time".to_string(),
+            confidence_score: None,
+        };
+
+        // When
+        let resolve = |slot: &Slot, preference: TimePreference| {
+            let mut slot = slot.clone();
+            apply_time_preference(&mut slot, preference, reference_timestamp);
+            (slot.value, slot.alternatives)
+        };
+
+        // Then
+        assert_eq!(
+            (next_monday.clone(), vec![last_monday.clone()]),
+            resolve(&monday_slot, TimePreference::PreferFuture)
+        );
+        assert_eq!(
+            (last_monday.clone(), vec![next_monday.clone()]),
+            resolve(&monday_slot, TimePreference::PreferPast)
+        );
+        assert_eq!(
+            (five_am.clone(), vec![five_pm.clone()]),
+            resolve(&five_slot, TimePreference::PreferPast)
+        );
+        assert_eq!(
+            (five_pm.clone(), vec![five_am.clone()]),
+            resolve(&five_slot, TimePreference::Nearest)
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use std::ops::Range;
+
+use snips_nlu_utils::language::Language as NluUtilsLanguage;
+use snips_nlu_utils::range::ranges_overlap;
+use snips_nlu_utils::token::{tokenize, tokenize_light, Token};
+
+/// Mapping between the full tokenization of an input, used by the slot fillers, and its light
+/// tokenization, used by the intent classifier featurizers
+///
+/// Both tokenizations may split the input differently, so a light token can overlap several
+/// full tokens and conversely. Light tokens which cannot be located in the input, because they
+/// are not substrings of it, are aligned with no token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenAlignment {
+    light_token_ranges: Vec<Option<Range<usize>>>,
+    light_to_full: Vec<Vec<usize>>,
+    full_to_light: Vec<Vec<usize>>,
+}
+
+impl TokenAlignment {
+    /// Tokenizes the input both ways, and aligns the two tokenizations
+    pub fn tokenize(
+        input: &str,
+        language: NluUtilsLanguage,
+    ) -> (Vec<Token>, Vec<String>, TokenAlignment) {
+        let tokens = tokenize_aligned(input, language);
+        let light_tokens = tokenize_light(input, language);
+        let alignment = Self::new(input, &tokens, &light_tokens);
+        (tokens, light_tokens, alignment)
+    }
+
+    pub fn new(input: &str, tokens: &[Token], light_tokens: &[String]) -> Self {
+        let input_chars: Vec<char> = input.chars().collect();
+        let mut light_token_ranges = Vec::with_capacity(light_tokens.len());
+        let mut search_start = 0;
+        for light_token in light_tokens {
+            let range = find_chars(&input_chars, light_token, search_start);
+            if let Some(range) = range.as_ref() {
+                search_start = range.end;
+            }
+            light_token_ranges.push(range);
+        }
+
+        let light_to_full: Vec<Vec<usize>> = light_token_ranges
+            .iter()
+            .map(|light_range| match light_range {
+                Some(light_range) => tokens
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, token)| ranges_overlap(&token.char_range, light_range))
+                    .map(|(token_idx, _)| token_idx)
+                    .collect(),
+                None => vec![],
+            })
+            .collect();
+        let mut full_to_light = vec![vec![]; tokens.len()];
+        for (light_idx, full_indices) in light_to_full.iter().enumerate() {
+            for full_idx in full_indices {
+                full_to_light[*full_idx].push(light_idx);
+            }
+        }
+        Self {
+            light_token_ranges,
+            light_to_full,
+            full_to_light,
+        }
+    }
+
+    /// Char range of the light token in the input, if it could be located
+    pub fn light_token_range(&self, light_token_index: usize) -> Option<&Range<usize>> {
+        self.light_token_ranges
+            .get(light_token_index)
+            .and_then(|range| range.as_ref())
+    }
+
+    /// Indices of the full tokens overlapping the light token
+    pub fn full_tokens(&self, light_token_index: usize) -> &[usize] {
+        self.light_to_full
+            .get(light_token_index)
+            .map(|indices| &indices[..])
+            .unwrap_or(&[])
+    }
+
+    /// Indices of the light tokens overlapping the full token
+    pub fn light_tokens(&self, full_token_index: usize) -> &[usize] {
+        self.full_to_light
+            .get(full_token_index)
+            .map(|indices| &indices[..])
+            .unwrap_or(&[])
+    }
+}
+
+/// Tokenizes the input, the ranges of the tokens being anchored on the input itself
+///
+/// The byte and char ranges of the tokens always index the exact provided string, whatever
+/// the whitespaces (tabs, newlines, repeated spaces) and the multi-byte or combining
+/// characters it contains, so that the slots built from these tokens can be located in the
+/// input of the user.
+pub fn tokenize_aligned(input: &str, language: NluUtilsLanguage) -> Vec<Token> {
+    align_tokens(input, tokenize(input, language))
+}
+
+/// Recomputes the ranges of the tokens by locating their values in the input, one after the
+/// other
+///
+/// Tokens whose value cannot be found in the input keep their ranges.
+pub fn align_tokens(input: &str, tokens: Vec<Token>) -> Vec<Token> {
+    let mut byte_offset = 0;
+    let mut char_offset = 0;
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if token.value.is_empty() {
+                return token;
+            }
+            if let Some(relative_start) = input[byte_offset..].find(&*token.value) {
+                let start = byte_offset + relative_start;
+                let end = start + token.value.len();
+                let char_start = char_offset + input[byte_offset..start].chars().count();
+                let char_end = char_start + token.value.chars().count();
+                token.range = start..end;
+                token.char_range = char_start..char_end;
+                byte_offset = end;
+                char_offset = char_end;
+            }
+            token
+        })
+        .collect()
+}
+
+/// Char range of the first occurrence of the value in the chars, starting at `start`
+fn find_chars(chars: &[char], value: &str, start: usize) -> Option<Range<usize>> {
+    let value_chars: Vec<char> = value.chars().collect();
+    if value_chars.is_empty() || start + value_chars.len() > chars.len() {
+        return None;
+    }
+    (start..=chars.len() - value_chars.len())
+        .find(|offset| chars[*offset..*offset + value_chars.len()] == value_chars[..])
+        .map(|offset| offset..offset + value_chars.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use snips_nlu_utils::string::substring_with_char_range;
+
+    use super::*;
+
+    #[test]
+    fn test_token_alignment() {
+        // Given
+        let input = "set the temperature to 21.5 degrees";
+
+        // When
+        let (tokens, light_tokens, alignment) =
+            TokenAlignment::tokenize(input, NluUtilsLanguage::EN);
+
+        // Then
+        for (light_idx, light_token) in light_tokens.iter().enumerate() {
+            let range = alignment.light_token_range(light_idx).unwrap();
+            let light_chars: String = input
+                .chars()
+                .skip(range.start)
+                .take(range.end - range.start)
+                .collect();
+            assert_eq!(light_token, &light_chars);
+            for full_idx in alignment.full_tokens(light_idx) {
+                assert!(ranges_overlap(&tokens[*full_idx].char_range, range));
+                assert!(alignment.light_tokens(*full_idx).contains(&light_idx));
+            }
+        }
+        let temperature_idx = light_tokens
+            .iter()
+            .position(|token| token == "temperature")
+            .unwrap();
+        let full_indices = alignment.full_tokens(temperature_idx);
+        assert_eq!(1, full_indices.len());
+        assert_eq!("temperature", tokens[full_indices[0]].value);
+    }
+
+    #[test]
+    fn test_align_unlocated_light_tokens() {
+        // Given
+        let input = "hello world";
+        let tokens = tokenize(input, NluUtilsLanguage::EN);
+        let light_tokens = vec!["hello".to_string(), "planet".to_string()];
+
+        // When
+        let alignment = TokenAlignment::new(input, &tokens, &light_tokens);
+
+        // Then
+        assert_eq!(Some(&(0..5)), alignment.light_token_range(0));
+        assert_eq!(&[0], alignment.full_tokens(0));
+        assert_eq!(None, alignment.light_token_range(1));
+        assert!(alignment.full_tokens(1).is_empty());
+        assert!(alignment.light_tokens(1).is_empty());
+    }
+
+    #[test]
+    fn test_align_tokens() {
+        // Given
+        let input = "turn\t\ton  the 🔥 light\nin the cafe\u{301}";
+        // Ranges computed as if whitespace runs were single spaces and the emoji was two chars
+        // long
+        let tokens = vec![
+            Token::new("turn".to_string(), 0..4, 0..4),
+            Token::new("on".to_string(), 5..7, 5..7),
+            Token::new("the".to_string(), 8..11, 8..11),
+            Token::new("🔥".to_string(), 12..16, 12..14),
+            Token::new("light".to_string(), 17..22, 15..20),
+            Token::new("in".to_string(), 23..25, 21..23),
+            Token::new("the".to_string(), 26..29, 24..27),
+            Token::new("cafe\u{301}".to_string(), 30..36, 28..33),
+        ];
+
+        // When
+        let aligned_tokens = align_tokens(input, tokens);
+
+        // Then
+        let ranges: Vec<_> = aligned_tokens
+            .iter()
+            .map(|token| (token.range.clone(), token.char_range.clone()))
+            .collect();
+        let expected_ranges = vec![
+            (0..4, 0..4),
+            (6..8, 6..8),
+            (10..13, 10..13),
+            (14..18, 14..15),
+            (19..24, 16..21),
+            (25..27, 22..24),
+            (28..31, 25..28),
+            (32..38, 29..34),
+        ];
+        assert_eq!(expected_ranges, ranges);
+    }
+
+    #[test]
+    fn test_tokenize_aligned() {
+        // Given
+        let input = "  book\ta table\n\nfor  👨‍👩‍👧 at Cafe\u{301} Rouge\r\n";
+
+        // When
+        let tokens = tokenize_aligned(input, NluUtilsLanguage::EN);
+
+        // Then
+        assert!(!tokens.is_empty());
+        for token in tokens {
+            assert_eq!(token.value, &input[token.range.clone()]);
+            assert_eq!(
+                token.value,
+                substring_with_char_range(input.to_string(), &token.char_range)
+            );
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::ops::Range;
+use std::path::Path;
+
+use failure::{bail, ResultExt};
+
+use crate::errors::*;
+
+/// Name of the transliteration table stored in the language resources directory
+pub const TRANSLITERATION_TABLE_FILENAME: &str = "transliteration.txt";
+
+/// Cyrillic and Greek letters, along with their Latin transliterations
+const DEFAULT_TABLE: &[(char, &str)] = &[
+    ('а', "a"),
+    ('б', "b"),
+    ('в', "v"),
+    ('г', "g"),
+    ('ґ', "g"),
+    ('д', "d"),
+    ('е', "e"),
+    ('ё', "e"),
+    ('є', "ye"),
+    ('ж', "zh"),
+    ('з', "z"),
+    ('и', "i"),
+    ('і', "i"),
+    ('ї', "yi"),
+    ('й', "y"),
+    ('к', "k"),
+    ('л', "l"),
+    ('м', "m"),
+    ('н', "n"),
+    ('о', "o"),
+    ('п', "p"),
+    ('р', "r"),
+    ('с', "s"),
+    ('т', "t"),
+    ('у', "u"),
+    ('ф', "f"),
+    ('х', "kh"),
+    ('ц', "ts"),
+    ('ч', "ch"),
+    ('ш', "sh"),
+    ('щ', "shch"),
+    ('ъ', ""),
+    ('ы', "y"),
+    ('ь', ""),
+    ('э', "e"),
+    ('ю', "yu"),
+    ('я', "ya"),
+    ('α', "a"),
+    ('ά', "a"),
+    ('β', "v"),
+    ('γ', "g"),
+    ('δ', "d"),
+    ('ε', "e"),
+    ('έ', "e"),
+    ('ζ', "z"),
+    ('η', "i"),
+    ('ή', "i"),
+    ('θ', "th"),
+    ('ι', "i"),
+    ('ί', "i"),
+    ('ϊ', "i"),
+    ('ΐ', "i"),
+    ('κ', "k"),
+    ('λ', "l"),
+    ('μ', "m"),
+    ('ν', "n"),
+    ('ξ', "x"),
+    ('ο', "o"),
+    ('ό', "o"),
+    ('π', "p"),
+    ('ρ', "r"),
+    ('σ', "s"),
+    ('ς', "s"),
+    ('τ', "t"),
+    ('υ', "y"),
+    ('ύ', "y"),
+    ('ϋ', "y"),
+    ('ΰ', "y"),
+    ('φ', "f"),
+    ('χ', "ch"),
+    ('ψ', "ps"),
+    ('ω', "o"),
+    ('ώ', "o"),
+];
+
+/// Character by character transliteration of inputs typed in another script, so that
+/// borrowed words and names match the ones of a model trained on Latin texts
+///
+/// Uppercase characters are transliterated with their lowercase counterpart, the first letter
+/// of the transliteration being capitalized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transliterator {
+    table: HashMap<char, String>,
+}
+
+impl Default for Transliterator {
+    /// Transliterator of the Cyrillic and Greek alphabets
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_TABLE
+                .iter()
+                .map(|(source, target)| (*source, target.to_string())),
+        )
+    }
+}
+
+impl Transliterator {
+    pub fn new<I: IntoIterator<Item = (char, String)>>(table: I) -> Self {
+        Self {
+            table: table
+                .into_iter()
+                .map(|(source, target)| (to_lowercase_char(source), target.to_lowercase()))
+                .collect(),
+        }
+    }
+
+    /// Loads the table stored in the language resources directory, and falls back to the
+    /// default table when there is none
+    ///
+    /// Each line of the table contains a character and its transliteration, separated by a
+    /// tab.
+    pub fn from_resources_dir<P: AsRef<Path>>(resources_dir: P) -> Result<Self> {
+        let path = resources_dir.as_ref().join(TRANSLITERATION_TABLE_FILENAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let file = File::open(&path)
+            .with_context(|_| format!("Cannot open transliteration table {:?}", path))?;
+        let mut table = vec![];
+        for (line_index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut columns = line.splitn(2, '\t');
+            let mut source_chars = columns.next().unwrap_or("").chars();
+            match (source_chars.next(), source_chars.next(), columns.next()) {
+                (Some(source), None, Some(target)) => table.push((source, target.to_string())),
+                _ => bail!(
+                    "Invalid line {} in transliteration table {:?}",
+                    line_index + 1,
+                    path
+                ),
+            }
+        }
+        Ok(Self::new(table))
+    }
+
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self.apply_with_segments(text) {
+            Some((transliterated_text, _)) => Cow::Owned(transliterated_text),
+            None => Cow::Borrowed(text),
+        }
+    }
+
+    /// Transliterates the text, and returns the char ranges of the modified segments in the
+    /// transliterated text and in the original text, or `None` when no character is
+    /// transliterated
+    ///
+    /// Characters transliterated to nothing, such as the Cyrillic soft sign, are merged with
+    /// the segment of the previous character, so that ranges ending after them still cover
+    /// them.
+    pub(crate) fn apply_with_segments(
+        &self,
+        text: &str,
+    ) -> Option<(String, Vec<(Range<usize>, Range<usize>)>)> {
+        let mut transliterated_text = String::with_capacity(text.len());
+        let mut segments: Vec<(Range<usize>, Range<usize>)> = vec![];
+        let mut transliterated_offset = 0;
+        for (original_offset, c) in text.chars().enumerate() {
+            let target = match self.table.get(&to_lowercase_char(c)) {
+                Some(target) => target,
+                None => {
+                    transliterated_text.push(c);
+                    transliterated_offset += 1;
+                    continue;
+                }
+            };
+            let target_length = target.chars().count();
+            if c.is_uppercase() {
+                let mut target_chars = target.chars();
+                if let Some(first_char) = target_chars.next() {
+                    transliterated_text.extend(first_char.to_uppercase());
+                    transliterated_text.push_str(target_chars.as_str());
+                }
+            } else {
+                transliterated_text.push_str(target);
+            }
+            let follows_last_segment = segments
+                .last()
+                .map(|(_, original_range)| original_range.end == original_offset)
+                .unwrap_or(false);
+            if target_length == 0 && follows_last_segment {
+                segments.last_mut().unwrap().1.end += 1;
+            } else {
+                segments.push((
+                    transliterated_offset..transliterated_offset + target_length,
+                    original_offset..original_offset + 1,
+                ));
+            }
+            transliterated_offset += target_length;
+        }
+        if segments.is_empty() {
+            None
+        } else {
+            Some((transliterated_text, segments))
+        }
+    }
+}
+
+fn to_lowercase_char(c: char) -> char {
+    let mut lowercase = c.to_lowercase();
+    match (lowercase.next(), lowercase.next()) {
+        (Some(lowercase_char), None) => lowercase_char,
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_transliterate() {
+        // Given
+        let transliterator = Transliterator::default();
+
+        // When
+        let cyrillic = transliterator.apply("play Чайковский");
+        let greek = transliterator.apply("play Βαγγέλης");
+        let latin = transliterator.apply("play Tchaikovsky");
+
+        // Then
+        assert_eq!("play Chaykovskiy", cyrillic);
+        assert_eq!("play Vaggelis", greek);
+        assert_eq!(Cow::Borrowed("play Tchaikovsky"), latin);
+    }
+
+    #[test]
+    fn test_transliterate_with_segments() {
+        // Given
+        let transliterator = Transliterator::default();
+
+        // When
+        let (text, segments) = transliterator.apply_with_segments("a Игорь b").unwrap();
+
+        // Then
+        assert_eq!("a Igor b", text);
+        assert_eq!(
+            vec![(2..3, 2..3), (3..4, 3..4), (4..5, 4..5), (5..6, 5..7)],
+            segments
+        );
+    }
+
+    #[test]
+    fn test_load_transliteration_table() {
+        // Given
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join(TRANSLITERATION_TABLE_FILENAME),
+            "й\tj\nщ\tšč\n",
+        )
+        .unwrap();
+
+        // When
+        let transliterator = Transliterator::from_resources_dir(temp_dir.path()).unwrap();
+        let default_transliterator =
+            Transliterator::from_resources_dir(temp_dir.path().join("missing")).unwrap();
+
+        // Then
+        assert_eq!("Jšč", transliterator.apply("Йщ"));
+        assert_eq!(Transliterator::default(), default_transliterator);
+    }
+}
@@ -0,0 +1,228 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use unicode_normalization::char::{canonical_combining_class, is_combining_mark};
+use unicode_normalization::UnicodeNormalization as _;
+
+/// Unicode normalization form applied to the language resources and to the inputs
+///
+/// Accented characters have several representations, e.g. "é" is either a single precomposed
+/// character or an "e" followed by a combining acute accent. Applying the same form to the
+/// resources and to the inputs ensures that both representations match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnicodeNormalization {
+    /// Texts are used as is
+    None,
+    /// Canonical composition
+    Nfc,
+    /// Canonical decomposition
+    Nfd,
+    /// Compatibility composition
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+impl Default for UnicodeNormalization {
+    fn default() -> Self {
+        UnicodeNormalization::Nfc
+    }
+}
+
+impl UnicodeNormalization {
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let normalized: String = match self {
+            UnicodeNormalization::None => return Cow::Borrowed(text),
+            UnicodeNormalization::Nfc => text.nfc().collect(),
+            UnicodeNormalization::Nfd => text.nfd().collect(),
+            UnicodeNormalization::Nfkc => text.nfkc().collect(),
+            UnicodeNormalization::Nfkd => text.nfkd().collect(),
+        };
+        if normalized == text {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(normalized)
+        }
+    }
+
+    /// Normalizes the text, and returns the char ranges of the modified segments in the
+    /// normalized text and in the original text, or `None` when the text is already normalized
+    ///
+    /// Each segment starts with a character which does not combine with the previous ones,
+    /// followed by its combining marks, so that segments are normalized independently.
+    pub(crate) fn apply_with_segments(
+        &self,
+        text: &str,
+    ) -> Option<(String, Vec<(Range<usize>, Range<usize>)>)> {
+        if let Cow::Borrowed(_) = self.apply(text) {
+            return None;
+        }
+        normalize_segments(text, |segment| self.apply(segment))
+    }
+}
+
+/// Folding applied to the patterns of the deterministic intent parser and to the inputs it
+/// parses, so that for instance "reserve" matches a pattern written with "réserve"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PatternNormalization {
+    /// Lowercases the inputs, the patterns being already matched case-insensitively
+    pub lowercase: bool,
+    /// Removes the accents, and the other combining marks, of the compatibility decomposition
+    /// of the inputs and of the patterns
+    pub strip_accents: bool,
+}
+
+impl PatternNormalization {
+    pub fn is_enabled(&self) -> bool {
+        self.lowercase || self.strip_accents
+    }
+
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if !self.is_enabled() {
+            return Cow::Borrowed(text);
+        }
+        let mut normalized = self.apply_to_pattern(text).into_owned();
+        if self.lowercase {
+            normalized = normalized.to_lowercase();
+        }
+        if normalized == text {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(normalized)
+        }
+    }
+
+    /// Strips the accents of a regex pattern, which is not lowercased since this would change
+    /// the meaning of escape sequences such as `\S`
+    pub fn apply_to_pattern<'a>(&self, pattern: &'a str) -> Cow<'a, str> {
+        if !self.strip_accents {
+            return Cow::Borrowed(pattern);
+        }
+        let stripped: String = pattern.nfkd().filter(|c| !is_combining_mark(*c)).collect();
+        if stripped == pattern {
+            Cow::Borrowed(pattern)
+        } else {
+            Cow::Owned(stripped)
+        }
+    }
+
+    /// Normalizes the text, and returns the char ranges of the modified segments in the
+    /// normalized text and in the original text, or `None` when the text is already normalized
+    pub(crate) fn apply_with_segments(
+        &self,
+        text: &str,
+    ) -> Option<(String, Vec<(Range<usize>, Range<usize>)>)> {
+        if let Cow::Borrowed(_) = self.apply(text) {
+            return None;
+        }
+        normalize_segments(text, |segment| self.apply(segment))
+    }
+}
+
+/// Normalizes each segment of the text made of a character and its combining marks, and
+/// returns the normalized text along with the char ranges of the modified segments
+fn normalize_segments<'a, F>(
+    text: &'a str,
+    normalize: F,
+) -> Option<(String, Vec<(Range<usize>, Range<usize>)>)>
+where
+    F: Fn(&'a str) -> Cow<'a, str>,
+{
+    let mut normalized_text = String::with_capacity(text.len());
+    let mut segments = vec![];
+    let mut original_offset = 0;
+    let mut normalized_offset = 0;
+    let mut segment_start = 0;
+    let byte_offsets = text
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .skip(1)
+        .filter(|offset| canonical_combining_class(text[*offset..].chars().next().unwrap()) == 0)
+        .chain(Some(text.len()));
+    for segment_end in byte_offsets {
+        let segment = &text[segment_start..segment_end];
+        let original_length = segment.chars().count();
+        let normalized_segment = normalize(segment);
+        let normalized_length = normalized_segment.chars().count();
+        if let Cow::Owned(_) = normalized_segment {
+            segments.push((
+                normalized_offset..normalized_offset + normalized_length,
+                original_offset..original_offset + original_length,
+            ));
+        }
+        normalized_text.push_str(&normalized_segment);
+        original_offset += original_length;
+        normalized_offset += normalized_length;
+        segment_start = segment_end;
+    }
+    if segments.is_empty() {
+        None
+    } else {
+        Some((normalized_text, segments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_unicode_normalization() {
+        // Given
+        let decomposed = "cafe\u{301}";
+        let composed = "caf\u{e9}";
+
+        // When
+        let nfc = UnicodeNormalization::Nfc.apply(decomposed);
+        let nfd = UnicodeNormalization::Nfd.apply(composed);
+        let unchanged = UnicodeNormalization::None.apply(decomposed);
+
+        // Then
+        assert_eq!(composed, nfc);
+        assert_eq!(decomposed, nfd);
+        assert_eq!(decomposed, unchanged);
+    }
+
+    #[test]
+    fn test_apply_unicode_normalization_with_segments() {
+        // Given
+        let text = "un cafe\u{301} cre\u{300}me";
+
+        // When
+        let (normalized_text, segments) =
+            UnicodeNormalization::Nfc.apply_with_segments(text).unwrap();
+        let already_normalized = UnicodeNormalization::Nfc.apply_with_segments("un café");
+
+        // Then
+        assert_eq!("un caf\u{e9} cr\u{e8}me", normalized_text);
+        assert_eq!(vec![(6..7, 6..8), (10..11, 11..13)], segments);
+        assert_eq!(None, already_normalized);
+    }
+
+    #[test]
+    fn test_apply_pattern_normalization_with_segments() {
+        // Given
+        let normalization = PatternNormalization {
+            lowercase: true,
+            strip_accents: true,
+        };
+        let text = "Re\u{301}serve chez Jos\u{e9}";
+
+        // When
+        let (normalized_text, segments) = normalization.apply_with_segments(text).unwrap();
+        let pattern = normalization.apply_to_pattern(r"^\s*réserve\S*$");
+
+        // Then
+        assert_eq!("reserve chez jose", normalized_text);
+        assert_eq!(
+            vec![
+                (0..1, 0..1),
+                (1..2, 1..3),
+                (13..14, 14..15),
+                (16..17, 17..18)
+            ],
+            segments
+        );
+        assert_eq!(r"^\s*reserve\S*$", pattern);
+    }
+}
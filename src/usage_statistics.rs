@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use failure::format_err;
+use itertools::Itertools;
+use serde::Serialize;
+use snips_nlu_ontology::IntentParserResult;
+use snips_nlu_utils::language::Language as NluUtilsLanguage;
+use snips_nlu_utils::token::tokenize_light;
+
+use crate::errors::*;
+use crate::utils::IntentName;
+
+/// Settings of a `UsageStatisticsCollector`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageStatisticsConfig {
+    /// Parses whose intent has a lower confidence score are counted as low-confidence parses
+    pub low_confidence_threshold: f32,
+    /// Maximum number of tokens of the collected n-grams
+    pub max_ngram_size: usize,
+    /// N-grams occurring fewer times are not exported, so that rare n-grams which could
+    /// identify a user never leave the collector
+    pub min_ngram_count: u64,
+    /// Maximum number of distinct n-grams which are counted, new n-grams being ignored once
+    /// it is reached
+    pub max_tracked_ngrams: usize,
+}
+
+impl Default for UsageStatisticsConfig {
+    fn default() -> Self {
+        Self {
+            low_confidence_threshold: 0.5,
+            max_ngram_size: 2,
+            min_ngram_count: 5,
+            max_tracked_ngrams: 100_000,
+        }
+    }
+}
+
+/// Aggregated statistics about the parses of an engine, which contain no raw utterance
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStatistics {
+    pub nb_parses: u64,
+    pub intents_counts: HashMap<IntentName, u64>,
+    pub none_intent_count: u64,
+    pub none_intent_rate: f32,
+    pub low_confidence_count: u64,
+    /// Frequent n-grams of the inputs for which no intent was found, sorted by decreasing
+    /// count
+    pub unmatched_ngrams: Vec<NgramCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NgramCount {
+    pub ngram: String,
+    pub count: u64,
+}
+
+#[derive(Default)]
+struct Counts {
+    nb_parses: u64,
+    intents_counts: HashMap<IntentName, u64>,
+    none_intent_count: u64,
+    low_confidence_count: u64,
+    unmatched_ngrams: HashMap<String, u64>,
+}
+
+/// Collector of usage statistics, which can be attached to an engine with
+/// `SnipsNluEngine::set_usage_statistics_collector`
+///
+/// Only counts are kept: inputs are never stored, and the n-grams of the unmatched inputs are
+/// only exported once they are frequent enough. N-grams containing digits, which could be
+/// phone numbers or codes, are never collected.
+pub struct UsageStatisticsCollector {
+    config: UsageStatisticsConfig,
+    counts: Mutex<Counts>,
+}
+
+impl Default for UsageStatisticsCollector {
+    fn default() -> Self {
+        Self::new(UsageStatisticsConfig::default())
+    }
+}
+
+impl UsageStatisticsCollector {
+    pub fn new(config: UsageStatisticsConfig) -> Self {
+        Self {
+            config,
+            counts: Mutex::new(Counts::default()),
+        }
+    }
+
+    /// Updates the statistics with the result of a parse
+    pub fn record(&self, result: &IntentParserResult, language: NluUtilsLanguage) -> Result<()> {
+        let ngrams = if result.intent.intent_name.is_none() {
+            self.extract_ngrams(&result.input, language)
+        } else {
+            vec![]
+        };
+        let mut counts = self.lock_counts()?;
+        counts.nb_parses += 1;
+        match result.intent.intent_name.as_ref() {
+            Some(intent) => *counts.intents_counts.entry(intent.clone()).or_insert(0) += 1,
+            None => counts.none_intent_count += 1,
+        }
+        if result.intent.confidence_score < self.config.low_confidence_threshold {
+            counts.low_confidence_count += 1;
+        }
+        for ngram in ngrams {
+            let nb_tracked_ngrams = counts.unmatched_ngrams.len();
+            if let Some(count) = counts.unmatched_ngrams.get_mut(&ngram) {
+                *count += 1;
+            } else if nb_tracked_ngrams < self.config.max_tracked_ngrams {
+                counts.unmatched_ngrams.insert(ngram, 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the statistics collected so far
+    pub fn statistics(&self) -> Result<UsageStatistics> {
+        let counts = self.lock_counts()?;
+        let none_intent_rate = if counts.nb_parses > 0 {
+            counts.none_intent_count as f32 / counts.nb_parses as f32
+        } else {
+            0.0
+        };
+        let unmatched_ngrams = counts
+            .unmatched_ngrams
+            .iter()
+            .filter(|(_, count)| **count >= self.config.min_ngram_count)
+            .map(|(ngram, count)| NgramCount {
+                ngram: ngram.clone(),
+                count: *count,
+            })
+            .sorted_by(|a, b| b.count.cmp(&a.count).then_with(|| a.ngram.cmp(&b.ngram)))
+            .collect();
+        Ok(UsageStatistics {
+            nb_parses: counts.nb_parses,
+            intents_counts: counts.intents_counts.clone(),
+            none_intent_count: counts.none_intent_count,
+            none_intent_rate,
+            low_confidence_count: counts.low_confidence_count,
+            unmatched_ngrams,
+        })
+    }
+
+    /// Returns the statistics collected so far serialized in JSON
+    pub fn export_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&self.statistics()?)?)
+    }
+
+    /// Clears the statistics collected so far
+    pub fn reset(&self) -> Result<()> {
+        *self.lock_counts()? = Counts::default();
+        Ok(())
+    }
+
+    fn extract_ngrams(&self, input: &str, language: NluUtilsLanguage) -> Vec<String> {
+        let tokens: Vec<String> = tokenize_light(&input.to_lowercase(), language);
+        let mut ngrams = vec![];
+        for size in 1..=self.config.max_ngram_size {
+            for window in tokens.windows(size) {
+                if window
+                    .iter()
+                    .any(|token| token.chars().any(|c| c.is_numeric()))
+                {
+                    continue;
+                }
+                ngrams.push(window.join(" "));
+            }
+        }
+        ngrams
+    }
+
+    fn lock_counts(&self) -> Result<MutexGuard<Counts>> {
+        self.counts
+            .lock()
+            .map_err(|e| format_err!("Poisonous mutex: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::float_cmp)]
+
+    use snips_nlu_ontology::IntentClassifierResult;
+
+    use super::*;
+
+    fn parsing_result(input: &str, intent: Option<&str>, score: f32) -> IntentParserResult {
+        IntentParserResult {
+            input: input.to_string(),
+            intent: IntentClassifierResult {
+                intent_name: intent.map(|intent| intent.to_string()),
+                confidence_score: score,
+            },
+            slots: vec![],
+            alternatives: vec![],
+        }
+    }
+
+    #[test]
+    fn test_collect_usage_statistics() {
+        // Given
+        let collector = UsageStatisticsCollector::new(UsageStatisticsConfig {
+            min_ngram_count: 2,
+            ..UsageStatisticsConfig::default()
+        });
+        let results = vec![
+            parsing_result("make me a coffee", Some("MakeCoffee"), 0.9),
+            parsing_result("make me a tea", Some("MakeTea"), 0.3),
+            parsing_result("Order a pizza", None, 0.8),
+            parsing_result("order a pizza at 555 1234", None, 0.6),
+        ];
+
+        // When
+        for result in results.iter() {
+            collector.record(result, NluUtilsLanguage::EN).unwrap();
+        }
+        let statistics = collector.statistics().unwrap();
+
+        // Then
+        assert_eq!(4, statistics.nb_parses);
+        assert_eq!(Some(&1), statistics.intents_counts.get("MakeCoffee"));
+        assert_eq!(2, statistics.none_intent_count);
+        assert_eq!(0.5, statistics.none_intent_rate);
+        assert_eq!(1, statistics.low_confidence_count);
+        let ngrams: Vec<&str> = statistics
+            .unmatched_ngrams
+            .iter()
+            .map(|ngram_count| &*ngram_count.ngram)
+            .collect();
+        assert_eq!(vec!["a", "a pizza", "order", "order a", "pizza"], ngrams);
+    }
+}
@@ -101,6 +101,15 @@ pub fn extract_nlu_engine_zip_archive<R: io::Read + io::Seek>(
     Ok(dest_path.join(engine_dir_name))
 }
 
+/// Returns true when the input contains neither letters nor digits, e.g. when it is empty or
+/// only made of whitespaces and punctuation
+///
+/// Such inputs never have an intent nor slots, so parsing them returns the None intent right
+/// away.
+pub fn is_blank_input(input: &str) -> bool {
+    !input.chars().any(char::is_alphanumeric)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MatchedEntity {
     pub range: Range<usize>,
@@ -176,9 +185,66 @@ pub fn deduplicate_overlapping_entities(entities: Vec<MatchedEntity>) -> Vec<Mat
     deduped
 }
 
+/// Lowercase hexadecimal representation of the bytes, typically of a digest
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Mapping between the byte offsets and the char offsets of a string
+///
+/// It is meant to be built once per string and then shared by all the offset conversions,
+/// for instance when converting the ranges of several regex capture groups.
+pub struct CharIndex {
+    /// Byte offset of each char of the string, followed by the byte length of the string
+    char_byte_offsets: Vec<usize>,
+}
+
+impl CharIndex {
+    pub fn new(text: &str) -> Self {
+        let mut char_byte_offsets: Vec<usize> = text.char_indices().map(|(ix, _)| ix).collect();
+        char_byte_offsets.push(text.len());
+        Self { char_byte_offsets }
+    }
+
+    /// Number of chars in the indexed string
+    pub fn len(&self) -> usize {
+        self.char_byte_offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Converts a byte offset into a char offset
+    ///
+    /// Byte offsets which do not fall on a char boundary are mapped to the char containing
+    /// them, and offsets beyond the end of the string are mapped to its char length.
+    pub fn char_offset(&self, byte_offset: usize) -> usize {
+        match self.char_byte_offsets.binary_search(&byte_offset) {
+            Ok(char_offset) => char_offset,
+            Err(next_char_offset) => next_char_offset - 1,
+        }
+    }
+
+    /// Converts a char offset into a byte offset, offsets beyond the end of the string being
+    /// mapped to its byte length
+    pub fn byte_offset(&self, char_offset: usize) -> usize {
+        self.char_byte_offsets[char_offset.min(self.len())]
+    }
+
+    pub fn char_range(&self, byte_range: &Range<usize>) -> Range<usize> {
+        self.char_offset(byte_range.start)..self.char_offset(byte_range.end)
+    }
+
+    pub fn byte_range(&self, char_range: &Range<usize>) -> Range<usize> {
+        self.byte_offset(char_range.start)..self.byte_offset(char_range.end)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use itertools::Itertools;
     use snips_nlu_utils::range::ranges_overlap;
     use std::ops::Range;
 
@@ -199,4 +265,64 @@ mod tests {
         let expected_items = vec![0..8, 9..13];
         assert_eq!(expected_items, dedup_items);
     }
+
+    #[test]
+    fn test_char_index() {
+        // Given
+        let text = "a€𝄞b😀";
+
+        // When
+        let char_index = CharIndex::new(text);
+
+        // Then
+        assert_eq!(5, char_index.len());
+        assert_eq!(
+            vec![0, 1, 4, 8, 9, 13],
+            (0..=5)
+                .map(|ix| char_index.byte_offset(ix))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2..4, char_index.char_range(&(4..9)));
+        assert_eq!(4..9, char_index.byte_range(&(2..4)));
+        assert_eq!(2, char_index.char_offset(6));
+        assert_eq!(5, char_index.char_offset(20));
+        assert_eq!(13, char_index.byte_offset(20));
+    }
+
+    #[test]
+    fn test_char_index_on_multilingual_inputs() {
+        // Given
+        let alphabet: Vec<char> = "aé ß€日本語한국어𝄞😀🎉\u{301}اЖ\t".chars().collect();
+        let mut seed: u64 = 42;
+        let mut next_random = move |max: usize| {
+            // Linear congruential generator, good enough to build fuzzy inputs
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) as usize) % max
+        };
+
+        for _ in 0..200 {
+            let text: String = (0..next_random(30))
+                .map(|_| alphabet[next_random(alphabet.len())])
+                .collect();
+
+            // When
+            let char_index = CharIndex::new(&text);
+
+            // Then
+            assert_eq!(text.chars().count(), char_index.len());
+            for (char_offset, (byte_offset, _)) in text.char_indices().enumerate() {
+                assert_eq!(char_offset, char_index.char_offset(byte_offset));
+                assert_eq!(byte_offset, char_index.byte_offset(char_offset));
+            }
+            for (start, end) in (0..=char_index.len()).tuple_combinations() {
+                let byte_range = char_index.byte_range(&(start..end));
+                let expected_substring: String =
+                    text.chars().skip(start).take(end - start).collect();
+                assert_eq!(expected_substring, &text[byte_range.clone()]);
+                assert_eq!(start..end, char_index.char_range(&byte_range));
+            }
+        }
+    }
 }
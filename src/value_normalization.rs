@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use snips_nlu_ontology::{Slot, SlotValue, StringValue};
+
+use crate::utils::EntityName;
+
+/// Normalization applied to the resolved values of the slots of an entity, such as formatting
+/// phone numbers or upper-casing airport codes
+pub trait ValueNormalizer: Send + Sync {
+    /// Returns the normalized value, or `None` when the value must be kept as is
+    fn normalize(&self, value: &SlotValue) -> Option<SlotValue>;
+}
+
+impl<F> ValueNormalizer for F
+where
+    F: Fn(&SlotValue) -> Option<SlotValue> + Send + Sync,
+{
+    fn normalize(&self, value: &SlotValue) -> Option<SlotValue> {
+        self(value)
+    }
+}
+
+/// Builds a normalizer which only applies to the string values of custom entities
+pub fn custom_value_normalizer<F>(normalize: F) -> impl ValueNormalizer
+where
+    F: Fn(&str) -> String + Send + Sync,
+{
+    move |value: &SlotValue| match value {
+        SlotValue::Custom(StringValue { value }) => Some(SlotValue::Custom(StringValue {
+            value: normalize(value),
+        })),
+        _ => None,
+    }
+}
+
+/// Normalizers registered per entity, applied in their order of registration
+#[derive(Default)]
+pub struct ValueNormalizers {
+    entity_normalizers: HashMap<EntityName, Vec<Box<dyn ValueNormalizer>>>,
+}
+
+impl ValueNormalizers {
+    pub fn add_entity_normalizer<N>(&mut self, entity: &str, normalizer: N)
+    where
+        N: ValueNormalizer + 'static,
+    {
+        self.entity_normalizers
+            .entry(entity.to_string())
+            .or_insert_with(|| vec![])
+            .push(Box::new(normalizer));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entity_normalizers.is_empty()
+    }
+
+    /// Normalizes the values of the slots, and their alternative values
+    pub fn normalize(&self, slots: &mut [Slot]) {
+        for slot in slots.iter_mut() {
+            if let Some(normalizers) = self.entity_normalizers.get(&slot.entity) {
+                for value in Some(&mut slot.value)
+                    .into_iter()
+                    .chain(slot.alternatives.iter_mut())
+                {
+                    for normalizer in normalizers.iter() {
+                        if let Some(normalized_value) = normalizer.normalize(value) {
+                            *value = normalized_value;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom_slot(entity: &str, value: &str, alternatives: Vec<&str>) -> Slot {
+        let custom_value = |value: &str| {
+            SlotValue::Custom(StringValue {
+                value: value.to_string(),
+            })
+        };
+        Slot {
+            raw_value: value.to_string(),
+            value: custom_value(value),
+            alternatives: alternatives.into_iter().map(custom_value).collect(),
+            range: 0..value.len(),
+            entity: entity.to_string(),
+            slot_name: entity.to_string(),
+            confidence_score: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_values() {
+        // Given
+        let mut normalizers = ValueNormalizers::default();
+        normalizers.add_entity_normalizer(
+            "airport",
+            custom_value_normalizer(|value| value.to_uppercase()),
+        );
+        normalizers.add_entity_normalizer(
+            "phone",
+            custom_value_normalizer(|value| {
+                let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+                format!("+33{}", digits.trim_start_matches('0'))
+            }),
+        );
+        let mut slots = vec![
+            custom_slot("airport", "cdg", vec!["ory"]),
+            custom_slot("phone", "06 12 34 56 78", vec![]),
+            custom_slot("city", "paris", vec![]),
+        ];
+
+        // When
+        normalizers.normalize(&mut slots);
+
+        // Then
+        let expected_slots = vec![
+            Slot {
+                value: SlotValue::Custom("CDG".into()),
+                alternatives: vec![SlotValue::Custom("ORY".into())],
+                ..custom_slot("airport", "cdg", vec![])
+            },
+            Slot {
+                value: SlotValue::Custom("+33612345678".into()),
+                ..custom_slot("phone", "06 12 34 56 78", vec![])
+            },
+            custom_slot("city", "paris", vec![]),
+        ];
+        assert_eq!(expected_slots, slots);
+    }
+}
@@ -0,0 +1,478 @@
+use serde::Serialize;
+use snips_nlu_ontology::{BuiltinEntity, BuiltinEntityKind, Language, Slot, SlotValue};
+
+/// Builtin entities whose values can be expressed as ranges, e.g. "between 3 and 5" or "more
+/// than 20 euros"
+pub const RANGE_ENTITY_KINDS: [BuiltinEntityKind; 3] = [
+    BuiltinEntityKind::Number,
+    BuiltinEntityKind::AmountOfMoney,
+    BuiltinEntityKind::Temperature,
+];
+
+/// Interval of numeric values, a missing bound meaning that the interval is open on this side
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueRange {
+    pub min: Option<RangeBound>,
+    pub max: Option<RangeBound>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RangeBound {
+    pub value: f64,
+    /// Whether or not the bound itself belongs to the range
+    pub inclusive: bool,
+}
+
+/// Detects whether the numeric slot is part of a range expression in the input
+///
+/// The `entities` are the builtin entities of the same kind as the slot which were found in
+/// the input, they are used to retrieve the other bound of closed ranges such as "between 3
+/// and 5". When the slot is the upper bound of a closed range, the same range is returned as
+/// for the lower bound.
+pub fn value_range(
+    input: &str,
+    slot: &Slot,
+    entities: &[BuiltinEntity],
+    language: Language,
+) -> Option<ValueRange> {
+    let value = numeric_value(&slot.value)?;
+    let markers = RangeMarkers::for_language(language);
+    let chars: Vec<char> = input.chars().collect();
+    let substring = |start: usize, end: usize| -> String {
+        chars[start..end]
+            .iter()
+            .collect::<String>()
+            .trim()
+            .to_lowercase()
+    };
+    let before_slot = substring(0, slot.range.start);
+    let after_slot = substring(slot.range.end, chars.len());
+
+    for entity in entities {
+        let other_value = match numeric_value(&entity.entity) {
+            Some(other_value) => other_value,
+            None => continue,
+        };
+        let is_closed_range = if entity.range.start >= slot.range.end {
+            let connector = substring(slot.range.end, entity.range.start);
+            markers.connects(&before_slot, &connector)
+        } else if entity.range.end <= slot.range.start {
+            let connector = substring(entity.range.end, slot.range.start);
+            markers.connects(&substring(0, entity.range.start), &connector)
+        } else {
+            false
+        };
+        if is_closed_range {
+            return Some(ValueRange {
+                min: Some(RangeBound {
+                    value: value.min(other_value),
+                    inclusive: true,
+                }),
+                max: Some(RangeBound {
+                    value: value.max(other_value),
+                    inclusive: true,
+                }),
+            });
+        }
+    }
+
+    let bound = |inclusive: bool| Some(RangeBound { value, inclusive });
+    let prefix_bounds = [
+        (markers.lower_exclusive, true, false),
+        (markers.lower_inclusive, true, true),
+        (markers.upper_exclusive, false, false),
+        (markers.upper_inclusive, false, true),
+    ];
+    let suffix_bounds = [
+        (markers.lower_exclusive_suffixes, true, false),
+        (markers.lower_inclusive_suffixes, true, true),
+        (markers.upper_exclusive_suffixes, false, false),
+        (markers.upper_inclusive_suffixes, false, true),
+    ];
+    let prefix_match = prefix_bounds
+        .iter()
+        .filter_map(|(prefixes, is_min, inclusive)| {
+            markers
+                .longest_suffix_match(&before_slot, prefixes)
+                .map(|length| (length, *is_min, *inclusive))
+        })
+        .max_by_key(|(length, _, _)| *length);
+    let suffix_match = suffix_bounds
+        .iter()
+        .filter_map(|(suffixes, is_min, inclusive)| {
+            markers
+                .longest_prefix_match(&after_slot, suffixes)
+                .map(|length| (length, *is_min, *inclusive))
+        })
+        .max_by_key(|(length, _, _)| *length);
+    prefix_match.or(suffix_match).map(|(_, is_min, inclusive)| {
+        if is_min {
+            ValueRange {
+                min: bound(inclusive),
+                max: None,
+            }
+        } else {
+            ValueRange {
+                min: None,
+                max: bound(inclusive),
+            }
+        }
+    })
+}
+
+fn numeric_value(value: &SlotValue) -> Option<f64> {
+    match value {
+        SlotValue::Number(number) => Some(number.value),
+        SlotValue::AmountOfMoney(amount) => Some(f64::from(amount.value)),
+        SlotValue::Temperature(temperature) => Some(f64::from(temperature.value)),
+        _ => None,
+    }
+}
+
+/// Language specific words introducing ranges, all in lowercase
+struct RangeMarkers {
+    /// Whether or not markers must be separated from numbers by word boundaries
+    word_boundaries: bool,
+    /// Words introducing closed ranges, and the words separating their bounds
+    between: &'static [&'static str],
+    between_connectors: &'static [&'static str],
+    /// Same as above, for ranges like "from 3 to 5"
+    from: &'static [&'static str],
+    from_connectors: &'static [&'static str],
+    /// Words separating the bounds of ranges which are not introduced by any word
+    connectors: &'static [&'static str],
+    lower_exclusive: &'static [&'static str],
+    lower_inclusive: &'static [&'static str],
+    upper_exclusive: &'static [&'static str],
+    upper_inclusive: &'static [&'static str],
+    lower_exclusive_suffixes: &'static [&'static str],
+    lower_inclusive_suffixes: &'static [&'static str],
+    upper_exclusive_suffixes: &'static [&'static str],
+    upper_inclusive_suffixes: &'static [&'static str],
+}
+
+impl RangeMarkers {
+    fn for_language(language: Language) -> Self {
+        match language {
+            Language::EN => Self {
+                word_boundaries: true,
+                between: &["between"],
+                between_connectors: &["and"],
+                from: &["from"],
+                from_connectors: &["to"],
+                connectors: &["-"],
+                lower_exclusive: &["more than", "over", "above", "greater than", "higher than"],
+                lower_inclusive: &["at least", "no less than", "minimum"],
+                upper_exclusive: &["less than", "fewer than", "under", "below", "lower than"],
+                upper_inclusive: &["at most", "up to", "no more than", "maximum"],
+                lower_exclusive_suffixes: &[],
+                lower_inclusive_suffixes: &["or more", "and more", "or above", "minimum"],
+                upper_exclusive_suffixes: &[],
+                upper_inclusive_suffixes: &["or less", "or fewer", "or below", "maximum"],
+            },
+            Language::FR => Self {
+                word_boundaries: true,
+                between: &["entre"],
+                between_connectors: &["et"],
+                from: &["de", "du"],
+                from_connectors: &["à", "a", "au"],
+                connectors: &["-"],
+                lower_exclusive: &["plus de", "plus que", "au-dessus de", "supérieur à"],
+                lower_inclusive: &["au moins", "minimum"],
+                upper_exclusive: &["moins de", "moins que", "en dessous de", "inférieur à"],
+                upper_inclusive: &["au plus", "au maximum", "maximum", "jusqu'à"],
+                lower_exclusive_suffixes: &[],
+                lower_inclusive_suffixes: &["ou plus", "minimum"],
+                upper_exclusive_suffixes: &[],
+                upper_inclusive_suffixes: &["ou moins", "maximum"],
+            },
+            Language::DE => Self {
+                word_boundaries: true,
+                between: &["zwischen"],
+                between_connectors: &["und"],
+                from: &["von"],
+                from_connectors: &["bis"],
+                connectors: &["-"],
+                lower_exclusive: &["mehr als", "über", "größer als"],
+                lower_inclusive: &["mindestens", "wenigstens"],
+                upper_exclusive: &["weniger als", "unter", "kleiner als"],
+                upper_inclusive: &["höchstens", "maximal", "bis zu"],
+                lower_exclusive_suffixes: &[],
+                lower_inclusive_suffixes: &["oder mehr"],
+                upper_exclusive_suffixes: &[],
+                upper_inclusive_suffixes: &["oder weniger"],
+            },
+            Language::ES => Self {
+                word_boundaries: true,
+                between: &["entre"],
+                between_connectors: &["y"],
+                from: &["de", "desde"],
+                from_connectors: &["a", "hasta"],
+                connectors: &["-"],
+                lower_exclusive: &["más de", "más que", "por encima de", "mayor que"],
+                lower_inclusive: &["al menos", "por lo menos", "como mínimo"],
+                upper_exclusive: &["menos de", "menos que", "por debajo de", "menor que"],
+                upper_inclusive: &["como máximo", "hasta"],
+                lower_exclusive_suffixes: &[],
+                lower_inclusive_suffixes: &["o más"],
+                upper_exclusive_suffixes: &[],
+                upper_inclusive_suffixes: &["o menos"],
+            },
+            Language::IT => Self {
+                word_boundaries: true,
+                between: &["tra", "fra"],
+                between_connectors: &["e"],
+                from: &["da"],
+                from_connectors: &["a"],
+                connectors: &["-"],
+                lower_exclusive: &["più di", "oltre", "sopra", "maggiore di"],
+                lower_inclusive: &["almeno", "minimo"],
+                upper_exclusive: &["meno di", "sotto", "minore di"],
+                upper_inclusive: &["al massimo", "fino a"],
+                lower_exclusive_suffixes: &[],
+                lower_inclusive_suffixes: &["o più"],
+                upper_exclusive_suffixes: &[],
+                upper_inclusive_suffixes: &["o meno"],
+            },
+            Language::PT_PT | Language::PT_BR => Self {
+                word_boundaries: true,
+                between: &["entre"],
+                between_connectors: &["e"],
+                from: &["de", "desde"],
+                from_connectors: &["a", "até"],
+                connectors: &["-"],
+                lower_exclusive: &["mais de", "mais que", "acima de", "maior que"],
+                lower_inclusive: &["pelo menos", "no mínimo"],
+                upper_exclusive: &["menos de", "menos que", "abaixo de", "menor que"],
+                upper_inclusive: &["no máximo", "até"],
+                lower_exclusive_suffixes: &[],
+                lower_inclusive_suffixes: &["ou mais"],
+                upper_exclusive_suffixes: &[],
+                upper_inclusive_suffixes: &["ou menos"],
+            },
+            Language::JA => Self {
+                word_boundaries: false,
+                between: &[],
+                between_connectors: &[],
+                from: &[],
+                from_connectors: &[],
+                connectors: &["から", "〜", "~", "-"],
+                lower_exclusive: &[],
+                lower_inclusive: &[],
+                upper_exclusive: &[],
+                upper_inclusive: &[],
+                lower_exclusive_suffixes: &["超", "より多い", "より上"],
+                lower_inclusive_suffixes: &["以上"],
+                upper_exclusive_suffixes: &["未満", "より少ない"],
+                upper_inclusive_suffixes: &["以下", "まで"],
+            },
+            Language::KO => Self {
+                word_boundaries: false,
+                between: &[],
+                between_connectors: &[],
+                from: &[],
+                from_connectors: &[],
+                connectors: &["에서", "부터", "~", "-"],
+                lower_exclusive: &[],
+                lower_inclusive: &[],
+                upper_exclusive: &[],
+                upper_inclusive: &[],
+                lower_exclusive_suffixes: &["초과", "보다 많이"],
+                lower_inclusive_suffixes: &["이상"],
+                upper_exclusive_suffixes: &["미만", "보다 적게"],
+                upper_inclusive_suffixes: &["이하", "까지"],
+            },
+        }
+    }
+
+    /// Checks whether the connector separates the two bounds of a closed range, given the
+    /// text preceding the first bound
+    fn connects(&self, before_first_bound: &str, connector: &str) -> bool {
+        if self.connectors.contains(&connector) {
+            return true;
+        }
+        let introduced_by = |markers: &[&str]| {
+            self.longest_suffix_match(before_first_bound, markers)
+                .is_some()
+        };
+        (self.between_connectors.contains(&connector) && introduced_by(self.between))
+            || (self.from_connectors.contains(&connector) && introduced_by(self.from))
+    }
+
+    /// Length of the longest marker ending the text
+    fn longest_suffix_match(&self, text: &str, markers: &[&str]) -> Option<usize> {
+        markers
+            .iter()
+            .filter(|marker| {
+                text.ends_with(*marker)
+                    && (!self.word_boundaries
+                        || text[..text.len() - marker.len()]
+                            .chars()
+                            .next_back()
+                            .map(|c| !c.is_alphanumeric())
+                            .unwrap_or(true))
+            })
+            .map(|marker| marker.len())
+            .max()
+    }
+
+    /// Length of the longest marker starting the text
+    fn longest_prefix_match(&self, text: &str, markers: &[&str]) -> Option<usize> {
+        markers
+            .iter()
+            .filter(|marker| {
+                text.starts_with(*marker)
+                    && (!self.word_boundaries
+                        || text[marker.len()..]
+                            .chars()
+                            .next()
+                            .map(|c| !c.is_alphanumeric())
+                            .unwrap_or(true))
+            })
+            .map(|marker| marker.len())
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snips_nlu_ontology::{AmountOfMoneyValue, NumberValue, Precision};
+
+    fn number_entity(value: f64, range: std::ops::Range<usize>) -> BuiltinEntity {
+        BuiltinEntity {
+            value: value.to_string(),
+            range,
+            entity: SlotValue::Number(NumberValue { value }),
+            alternatives: vec![],
+            entity_kind: BuiltinEntityKind::Number,
+        }
+    }
+
+    fn slot(entity: &BuiltinEntity) -> Slot {
+        Slot {
+            raw_value: entity.value.clone(),
+            value: entity.entity.clone(),
+            alternatives: vec![],
+            range: entity.range.clone(),
+            entity: "snips/number".to_string(),
+            slot_name: "number".to_string(),
+            confidence_score: None,
+        }
+    }
+
+    fn closed_range(min: f64, max: f64) -> Option<ValueRange> {
+        Some(ValueRange {
+            min: Some(RangeBound {
+                value: min,
+                inclusive: true,
+            }),
+            max: Some(RangeBound {
+                value: max,
+                inclusive: true,
+            }),
+        })
+    }
+
+    #[test]
+    fn test_closed_value_range() {
+        // Given
+        let input = "between 3 and 5 people";
+        let entities = vec![number_entity(3., 8..9), number_entity(5., 14..15)];
+
+        // When
+        let first_bound_range = value_range(input, &slot(&entities[0]), &entities, Language::EN);
+        let second_bound_range = value_range(input, &slot(&entities[1]), &entities, Language::EN);
+
+        // Then
+        assert_eq!(closed_range(3., 5.), first_bound_range);
+        assert_eq!(closed_range(3., 5.), second_bound_range);
+    }
+
+    #[test]
+    fn test_closed_value_range_requires_introducing_word() {
+        // Given
+        let input = "3 and 5";
+        let entities = vec![number_entity(3., 0..1), number_entity(5., 6..7)];
+
+        // When
+        let range = value_range(input, &slot(&entities[0]), &entities, Language::EN);
+
+        // Then
+        assert_eq!(None, range);
+    }
+
+    #[test]
+    fn test_open_value_range() {
+        // Given
+        let amount_slot = |range: std::ops::Range<usize>| Slot {
+            raw_value: "20 euros".to_string(),
+            value: SlotValue::AmountOfMoney(AmountOfMoneyValue {
+                value: 20.,
+                precision: Precision::Exact,
+                unit: Some("€".to_string()),
+            }),
+            alternatives: vec![],
+            range,
+            entity: "snips/amountOfMoney".to_string(),
+            slot_name: "price".to_string(),
+            confidence_score: None,
+        };
+
+        // When
+        let more_than = value_range(
+            "more than 20 euros",
+            &amount_slot(10..18),
+            &[],
+            Language::EN,
+        );
+        let no_more_than = value_range(
+            "no more than 20 euros",
+            &amount_slot(13..21),
+            &[],
+            Language::EN,
+        );
+        let or_less = value_range("20 euros or less", &amount_slot(0..8), &[], Language::EN);
+        let plain = value_range("it costs 20 euros", &amount_slot(9..17), &[], Language::EN);
+
+        // Then
+        let min = Some(ValueRange {
+            min: Some(RangeBound {
+                value: 20.,
+                inclusive: false,
+            }),
+            max: None,
+        });
+        let max = Some(ValueRange {
+            min: None,
+            max: Some(RangeBound {
+                value: 20.,
+                inclusive: true,
+            }),
+        });
+        assert_eq!(min, more_than);
+        assert_eq!(max, no_more_than);
+        assert_eq!(max, or_less);
+        assert_eq!(None, plain);
+    }
+
+    #[test]
+    fn test_value_range_without_word_boundaries() {
+        // Given
+        let input = "3以上";
+        let entities = vec![number_entity(3., 0..1)];
+
+        // When
+        let range = value_range(input, &slot(&entities[0]), &entities, Language::JA);
+
+        // Then
+        let expected_range = Some(ValueRange {
+            min: Some(RangeBound {
+                value: 3.,
+                inclusive: true,
+            }),
+            max: None,
+        });
+        assert_eq!(expected_range, range);
+    }
+}
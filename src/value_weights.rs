@@ -0,0 +1,213 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use failure::{format_err, ResultExt};
+use log::info;
+use snips_nlu_ontology::{Slot, SlotValue};
+
+use crate::errors::*;
+use crate::resources::dialect::CsvDialect;
+use crate::slot_utils::slot_value_as_str;
+use crate::utils::{EntityName, IntentName};
+
+/// Popularity weights of the resolved values of a custom entity, used to rank the resolutions
+/// of an ambiguous value such as "Paris", which may be the city or the person
+///
+/// The weights are read from a weighted gazetteer, a tab-separated file in which each line
+/// contains a resolved value, its weight and, optionally, the intent of the model in which
+/// this weight replaces the default one:
+///
+/// ```text
+/// # value	weight	intent
+/// Paris	10
+/// Paris Hilton	1
+/// Paris Hilton	20	searchCelebrity
+/// ```
+///
+/// Values without weight are ranked after the weighted ones.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityValueWeights {
+    default_weights: HashMap<String, f32>,
+    intents_weights: HashMap<IntentName, HashMap<String, f32>>,
+}
+
+impl EntityValueWeights {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(&path)
+            .with_context(|_| format!("Cannot open weighted gazetteer file {:?}", path.as_ref()))?;
+        Self::from_reader(file, &CsvDialect::weighted_gazetteer())
+    }
+
+    pub fn from_reader<R: Read>(reader: R, dialect: &CsvDialect) -> Result<Self> {
+        let mut weights = Self::default();
+        let mut csv_reader = dialect.csv_reader(reader, true)?;
+        for record in csv_reader.records() {
+            let record = record?;
+            let line = record.position().map(|p| p.line()).unwrap_or(0);
+            if record.len() != 2 && record.len() != 3 {
+                return Err(format_err!(
+                    "Expected a value, a weight and an optional intent at line {}, found {} \
+                     fields",
+                    line,
+                    record.len()
+                ));
+            }
+            let weight = f32::from_str(&record[1])
+                .with_context(|_| format!("Invalid weight at line {}", line))?;
+            weights = weights.weight(dialect.normalize(&record[0]), weight, record.get(2));
+        }
+        Ok(weights)
+    }
+
+    /// Sets the weight of a resolved value, either by default or in the provided intent
+    pub fn weight<S: Into<String>>(mut self, value: S, weight: f32, intent: Option<&str>) -> Self {
+        match intent {
+            Some(intent) => self
+                .intents_weights
+                .entry(intent.to_string())
+                .or_insert_with(HashMap::new)
+                .insert(value.into(), weight),
+            None => self.default_weights.insert(value.into(), weight),
+        };
+        self
+    }
+
+    /// Returns the weight of a resolved value in the provided intent, if any
+    pub fn value_weight(&self, value: &str, intent: &str) -> Option<f32> {
+        self.intents_weights
+            .get(intent)
+            .and_then(|weights| weights.get(value))
+            .or_else(|| self.default_weights.get(value))
+            .cloned()
+    }
+}
+
+/// Weights registered per custom entity
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValueWeights {
+    entity_weights: HashMap<EntityName, EntityValueWeights>,
+}
+
+impl ValueWeights {
+    /// Loads the weighted gazetteers of the entities
+    pub fn from_paths(paths: &HashMap<EntityName, PathBuf>) -> Result<Self> {
+        let mut value_weights = Self::default();
+        for (entity, path) in paths.iter() {
+            info!(
+                "Loading value weights of entity '{}' ({:?}) ...",
+                entity, path
+            );
+            value_weights.set_entity_weights(entity, EntityValueWeights::from_path(path)?);
+        }
+        Ok(value_weights)
+    }
+
+    pub fn set_entity_weights(&mut self, entity: &str, weights: EntityValueWeights) {
+        self.entity_weights.insert(entity.to_string(), weights);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entity_weights.is_empty()
+    }
+
+    /// Ranks the resolved value and the alternatives of the slots of weighted entities by
+    /// decreasing weight in the provided intent, the most popular resolution becoming the
+    /// value of the slot, and keeps at most `max_alternatives` alternatives for all slots
+    pub fn rank(&self, intent: &str, slots: &mut [Slot], max_alternatives: usize) {
+        for slot in slots.iter_mut() {
+            if let Some(weights) = self.entity_weights.get(&slot.entity) {
+                let weight = |value: &SlotValue| {
+                    slot_value_as_str(value).and_then(|value| weights.value_weight(value, intent))
+                };
+                slot.alternatives.insert(0, slot.value.clone());
+                // The sort is stable, so that the resolutions having the same weight keep the
+                // order of the entity parser
+                slot.alternatives
+                    .sort_by(|a, b| match (weight(a), weight(b)) {
+                        (Some(weight_a), Some(weight_b)) => {
+                            weight_b.partial_cmp(&weight_a).unwrap_or(Ordering::Equal)
+                        }
+                        (Some(_), None) => Ordering::Less,
+                        (None, Some(_)) => Ordering::Greater,
+                        (None, None) => Ordering::Equal,
+                    });
+                slot.value = slot.alternatives.remove(0);
+            }
+            slot.alternatives.truncate(max_alternatives);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom_slot(entity: &str, value: &str, alternatives: Vec<&str>) -> Slot {
+        Slot {
+            raw_value: "paris".to_string(),
+            value: SlotValue::Custom(value.into()),
+            alternatives: alternatives
+                .into_iter()
+                .map(|value| SlotValue::Custom(value.into()))
+                .collect(),
+            range: 0..5,
+            entity: entity.to_string(),
+            slot_name: entity.to_string(),
+            confidence_score: None,
+        }
+    }
+
+    #[test]
+    fn test_rank_slots_values() {
+        // Given
+        let weighted_gazetteer: &[u8] = b"# value\tweight\tintent
+Paris\t10
+Paris Hilton\t1
+Paris Hilton\t20\tsearchCelebrity
+";
+        let weights =
+            EntityValueWeights::from_reader(weighted_gazetteer, &CsvDialect::weighted_gazetteer())
+                .unwrap();
+        let mut value_weights = ValueWeights::default();
+        value_weights.set_entity_weights("name", weights);
+        let slots = vec![
+            custom_slot("name", "Paris Hilton", vec!["Paris", "Paris Saint-Germain"]),
+            custom_slot("city", "Paris Hilton", vec!["Paris"]),
+        ];
+        let mut travel_slots = slots.clone();
+        let mut celebrity_slots = slots;
+
+        // When
+        value_weights.rank("bookTrip", &mut travel_slots, 1);
+        value_weights.rank("searchCelebrity", &mut celebrity_slots, 2);
+
+        // Then
+        let expected_travel_slots = vec![
+            custom_slot("name", "Paris", vec!["Paris Hilton"]),
+            custom_slot("city", "Paris Hilton", vec!["Paris"]),
+        ];
+        let expected_celebrity_slots = vec![
+            custom_slot("name", "Paris Hilton", vec!["Paris", "Paris Saint-Germain"]),
+            custom_slot("city", "Paris Hilton", vec!["Paris"]),
+        ];
+        assert_eq!(expected_travel_slots, travel_slots);
+        assert_eq!(expected_celebrity_slots, celebrity_slots);
+    }
+
+    #[test]
+    fn test_invalid_weighted_gazetteer() {
+        // Given
+        let weighted_gazetteer: &[u8] = b"Paris\t10\nParis Hilton\tpopular\n";
+
+        // When
+        let result =
+            EntityValueWeights::from_reader(weighted_gazetteer, &CsvDialect::weighted_gazetteer());
+
+        // Then
+        assert!(result.is_err());
+    }
+}